@@ -1,9 +1,11 @@
+use super::lists::ListBuilder;
+use super::toc::{fill_tables_of_contents, TocTracker};
 use super::AstExtractor;
-use crate::converter::{ConversionContext, ParagraphConverter, TableConverter};
-use crate::core::ast::{BlockNode, DocumentAst};
-use crate::render::escape_html_attr;
-use crate::Result;
-use rs_docx::document::BodyContent;
+use crate::converter::{ConversionContext, ListPlacement, ParagraphConverter, TableConverter};
+use crate::core::ast::{self, BlockNode, ContentControlKind, DocumentAst, InlineNode};
+use crate::localization::{is_toc_heading_style, parse_heading_style, parse_toc_style};
+use crate::{ContentControlMarkup, Result, TocHandling};
+use rs_docx::document::{BodyContent, Paragraph};
 
 #[derive(Debug, Default, Clone, Copy)]
 pub struct DocxExtractor;
@@ -15,8 +17,14 @@ impl AstExtractor for DocxExtractor {
         context: &mut ConversionContext<'a>,
     ) -> Result<DocumentAst> {
         let mut doc = DocumentAst::default();
+        let mut lists = ListBuilder::default();
+        let mut toc = TocTracker::default();
         for content in body {
-            self.extract_content(content, context, &mut doc)?;
+            self.extract_content(content, context, &mut doc, &mut lists, &mut toc)?;
+        }
+        doc.blocks.extend(lists.finish());
+        if context.toc_handling() == TocHandling::Regenerate {
+            fill_tables_of_contents(&mut doc.blocks, context.slug_style());
         }
         Ok(doc)
     }
@@ -28,35 +36,169 @@ impl DocxExtractor {
         content: &BodyContent<'a>,
         context: &mut ConversionContext<'a>,
         output: &mut DocumentAst,
+        lists: &mut ListBuilder,
+        toc: &mut TocTracker,
     ) -> Result<()> {
         match content {
             BodyContent::Paragraph(para) => {
-                let converted = ParagraphConverter::convert(para, context)?;
-                if !converted.is_empty() {
-                    output.blocks.push(BlockNode::Paragraph(converted));
+                let (is_toc_entry, is_toc_title) = Self::toc_role(para, toc);
+                match context.toc_handling() {
+                    TocHandling::Remove if is_toc_entry || is_toc_title => {
+                        context.skip_form_fields(para);
+                        return Ok(());
+                    }
+                    TocHandling::Regenerate if is_toc_entry => {
+                        context.skip_form_fields(para);
+                        if !toc.placed {
+                            toc.placed = true;
+                            Self::push_block(output, lists, BlockNode::TableOfContents(Vec::new()));
+                        }
+                        return Ok(());
+                    }
+                    _ => toc.placed = false,
+                }
+
+                context.set_in_toc_entry(is_toc_entry);
+                let converted = ParagraphConverter::convert_list_item(para, context);
+                context.set_in_toc_entry(false);
+                let (converted, placement) = converted?;
+                match placement {
+                    ListPlacement::Heading(heading) => {
+                        Self::push_block(output, lists, BlockNode::Heading(heading));
+                    }
+                    ListPlacement::Item(marker) => {
+                        output.blocks.extend(lists.push(marker, converted));
+                    }
+                    ListPlacement::Body { .. } if converted.content.is_empty() => {}
+                    ListPlacement::Body {
+                        indent,
+                        numbering_removed,
+                    } => {
+                        let block = BlockNode::Paragraph(converted);
+                        if let Some(block) = lists.continue_item(block, indent, numbering_removed) {
+                            Self::push_block(output, lists, block);
+                        }
+                    }
                 }
             }
             BodyContent::Table(table) => {
-                let converted = TableConverter::convert(table, context)?;
-                output.blocks.push(BlockNode::TableHtml(converted));
+                toc.placed = false;
+                let block = TableConverter::convert_block(table, context)?;
+                let indent = table
+                    .property
+                    .indent
+                    .as_ref()
+                    .and_then(|ind| ind.value)
+                    .map(|v| v as i32);
+                if let Some(block) = lists.continue_item(block, indent, false) {
+                    Self::push_block(output, lists, block);
+                }
             }
             BodyContent::Sdt(sdt) => {
-                if let Some(sdt_content) = &sdt.content {
-                    for child in &sdt_content.content {
-                        self.extract_content(child, context, output)?;
-                    }
+                let control = context.content_control(sdt.property.as_ref());
+                let is_toc = matches!(
+                    &control.kind,
+                    ContentControlKind::DocPart(Some(gallery)) if gallery == "Table of Contents"
+                );
+                if is_toc && context.toc_handling() == TocHandling::Remove {
+                    Self::skip_content(content, context);
+                    return Ok(());
+                }
+                if is_toc {
+                    toc.enter_control();
+                }
+                self.extract_sdt_children(sdt, control, context, output, lists, toc)?;
+                if is_toc {
+                    toc.leave_control();
                 }
             }
             BodyContent::BookmarkStart(bookmark) => {
                 if let Some(name) = &bookmark.name {
-                    output.blocks.push(BlockNode::RawHtml(format!(
-                        "<a id=\"{}\"></a>",
-                        escape_html_attr(name)
-                    )));
+                    let block = BlockNode::Paragraph(ast::Paragraph {
+                        content: vec![InlineNode::Anchor(name.to_string())],
+                        ..Default::default()
+                    });
+                    Self::push_block(output, lists, block);
                 }
             }
             _ => {}
         }
         Ok(())
     }
+
+    /// Moves past the form fields of content that is not converted.
+    fn skip_content(content: &BodyContent<'_>, context: &mut ConversionContext<'_>) {
+        match content {
+            BodyContent::Paragraph(para) => context.skip_form_fields(para),
+            BodyContent::Sdt(sdt) => {
+                for child in sdt.content.iter().flat_map(|c| &c.content) {
+                    Self::skip_content(child, context);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Extracts the content of a content control, as its own container
+    /// block unless controls are flattened.
+    fn extract_sdt_children<'a>(
+        &self,
+        sdt: &rs_docx::document::SDT<'a>,
+        control: crate::core::ast::ContentControl,
+        context: &mut ConversionContext<'a>,
+        output: &mut DocumentAst,
+        lists: &mut ListBuilder,
+        toc: &mut TocTracker,
+    ) -> Result<()> {
+        let children = sdt.content.iter().flat_map(|c| &c.content);
+        if context.content_control_markup() == ContentControlMarkup::Flatten {
+            for child in children {
+                self.extract_content(child, context, output, lists, toc)?;
+            }
+            return Ok(());
+        }
+
+        // The control's content forms its own block sequence
+        let mut inner = DocumentAst::default();
+        let mut inner_lists = ListBuilder::default();
+        for child in children {
+            self.extract_content(child, context, &mut inner, &mut inner_lists, toc)?;
+        }
+        inner.blocks.extend(inner_lists.finish());
+        Self::push_block(
+            output,
+            lists,
+            BlockNode::ContentControl {
+                control,
+                blocks: inner.blocks,
+            },
+        );
+        Ok(())
+    }
+
+    /// Returns whether a paragraph is a table of contents entry (in a `TOC`
+    /// field or table of contents content control, or styled `TOC N`) and
+    /// whether it is the title of one.
+    fn toc_role(para: &Paragraph<'_>, toc: &mut TocTracker) -> (bool, bool) {
+        let in_field = toc.scan_paragraph(para);
+        let style = para
+            .property
+            .as_ref()
+            .and_then(|p| p.style_id.as_ref())
+            .map(|s| s.value.as_ref());
+        let is_title = style.is_some_and(is_toc_heading_style);
+        let is_heading = style.is_some_and(|style| parse_heading_style(style).is_some());
+        let is_entry = !is_title
+            && !is_heading
+            && (in_field
+                || toc.in_control()
+                || style.is_some_and(|style| parse_toc_style(style).is_some()));
+        (is_entry, is_title)
+    }
+
+    /// Adds a non-list block, ending any open list.
+    fn push_block(output: &mut DocumentAst, lists: &mut ListBuilder, block: BlockNode) {
+        output.blocks.extend(lists.finish());
+        output.blocks.push(block);
+    }
 }
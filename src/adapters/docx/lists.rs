@@ -0,0 +1,235 @@
+//! Groups numbered paragraphs into nested lists.
+
+use crate::converter::ListMarker;
+use crate::core::ast::{BlockNode, List, ListItem, ListKind, Paragraph};
+
+/// Text indent step of Word's default list levels, in twips.
+const DEFAULT_LEVEL_INDENT: i32 = 720;
+
+/// Room left of a list's text indent that still counts as under the list, in
+/// twips. Tables sit left of the text by their cell margin.
+const INDENT_TOLERANCE: i32 = 180;
+
+/// Lists still open while extracting, outermost first.
+#[derive(Debug, Default)]
+pub(crate) struct ListBuilder {
+    open: Vec<OpenList>,
+}
+
+#[derive(Debug)]
+struct OpenList {
+    list: List,
+    /// Left indent of the item text in twips
+    indent: i32,
+}
+
+impl ListBuilder {
+    /// Adds a list item. Returns the top-level list the item closed, if any.
+    ///
+    /// An item with a blank label (e.g. a level whose text is a space)
+    /// continues the open item at or above its level instead.
+    pub fn push(&mut self, marker: ListMarker, content: Paragraph) -> Option<BlockNode> {
+        if marker.label.trim().is_empty()
+            && self.open.iter().any(|open| open.list.level <= marker.level)
+        {
+            while self
+                .open
+                .last()
+                .is_some_and(|open| open.list.level > marker.level)
+            {
+                self.close_innermost();
+            }
+            self.append_to_item(self.open.len() - 1, BlockNode::Paragraph(content));
+            return None;
+        }
+
+        let kind = if marker.number.is_some() {
+            ListKind::Ordered
+        } else {
+            ListKind::Bullet
+        };
+
+        // Close deeper lists, and a list of the other kind at this level
+        let mut finished = None;
+        while let Some(top) = self.open.last().map(|open| &open.list) {
+            if top.level > marker.level || (top.level == marker.level && top.kind != kind) {
+                finished = self.close_innermost().or(finished);
+            } else {
+                break;
+            }
+        }
+
+        let item = ListItem {
+            label: marker.label,
+            separator: marker.separator.to_string(),
+            number: marker.number,
+            checked: marker.checked,
+            blocks: vec![BlockNode::Paragraph(content)],
+        };
+        match self.open.last_mut() {
+            Some(open) if open.list.level == marker.level => open.list.items.push(item),
+            _ => self.open.push(OpenList {
+                indent: marker
+                    .indent
+                    .unwrap_or(DEFAULT_LEVEL_INDENT * (marker.level as i32 + 1)),
+                list: List {
+                    kind,
+                    start: marker.number,
+                    level: marker.level,
+                    items: vec![item],
+                },
+            }),
+        }
+        finished
+    }
+
+    /// Adds an unnumbered block to the innermost open item whose text
+    /// `indent` (twips) reaches. With `numbering_removed` the block joins the
+    /// nearest item even when less indented. Returns the block when it does
+    /// not continue a list.
+    pub fn continue_item(
+        &mut self,
+        block: BlockNode,
+        indent: Option<i32>,
+        numbering_removed: bool,
+    ) -> Option<BlockNode> {
+        let under = indent.filter(|&indent| indent > 0).and_then(|indent| {
+            self.open
+                .iter()
+                .rposition(|open| open.indent <= indent + INDENT_TOLERANCE)
+        });
+        let target = match under {
+            Some(target) => target,
+            None if numbering_removed && !self.open.is_empty() => 0,
+            None => return Some(block),
+        };
+
+        while self.open.len() > target + 1 {
+            self.close_innermost();
+        }
+        self.append_to_item(target, block);
+        None
+    }
+
+    /// Closes all open lists, returning the top-level one.
+    pub fn finish(&mut self) -> Option<BlockNode> {
+        let mut finished = None;
+        while !self.open.is_empty() {
+            finished = self.close_innermost().or(finished);
+        }
+        finished
+    }
+
+    /// Closes the innermost list into its parent item, or returns it when it
+    /// is a top-level list.
+    fn close_innermost(&mut self) -> Option<BlockNode> {
+        let list = self.open.pop()?.list;
+        match self
+            .open
+            .last_mut()
+            .and_then(|parent| parent.list.items.last_mut())
+        {
+            Some(item) => {
+                item.blocks.push(BlockNode::List(list));
+                None
+            }
+            None => Some(BlockNode::List(list)),
+        }
+    }
+
+    /// Appends a block to the last item of the open list at `index`.
+    fn append_to_item(&mut self, index: usize, block: BlockNode) {
+        if let Some(item) = self.open[index].list.items.last_mut() {
+            item.blocks.push(block);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::Table;
+
+    fn marker(label: &str, number: Option<i32>, level: usize) -> ListMarker {
+        ListMarker {
+            label: label.to_string(),
+            number,
+            level,
+            separator: " ",
+            indent: Some(720 * (level as i32 + 1)),
+            checked: None,
+        }
+    }
+
+    #[test]
+    fn test_nests_items_by_level_and_splits_by_kind() {
+        let mut builder = ListBuilder::default();
+        assert!(builder
+            .push(marker("1.", Some(1), 0), "one".into())
+            .is_none());
+        assert!(builder.push(marker("a.", Some(1), 1), "a".into()).is_none());
+        assert!(builder
+            .push(marker("2.", Some(2), 0), "two".into())
+            .is_none());
+        let Some(BlockNode::List(ordered)) = builder.push(marker("-", None, 0), "dot".into())
+        else {
+            panic!("ordered list should close");
+        };
+        assert_eq!(ordered.kind, ListKind::Ordered);
+        assert_eq!(ordered.start, Some(1));
+        assert_eq!(ordered.items.len(), 2);
+        assert!(matches!(
+            &ordered.items[0].blocks[1],
+            BlockNode::List(nested) if nested.level == 1 && nested.items[0].label == "a."
+        ));
+
+        let Some(BlockNode::List(bullets)) = builder.finish() else {
+            panic!("bullet list should close");
+        };
+        assert_eq!(bullets.kind, ListKind::Bullet);
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn test_continues_items_by_indent() {
+        let mut builder = ListBuilder::default();
+        builder.push(marker("1.", Some(1), 0), "one".into());
+        builder.push(marker("a.", Some(1), 1), "a".into());
+
+        // Indented under level 0 only: closes the nested list
+        assert!(builder
+            .continue_item(BlockNode::Paragraph("more one".into()), Some(720), false)
+            .is_none());
+        // A blank label continues the item at its level
+        assert!(builder
+            .push(marker(" ", None, 0), "still one".into())
+            .is_none());
+        // A table sits left of the text by its cell margin
+        assert!(builder
+            .continue_item(
+                BlockNode::Table(Table { rows: Vec::new() }),
+                Some(612),
+                false
+            )
+            .is_none());
+        // Unindented text ends the list unless numbering was removed
+        assert!(builder
+            .continue_item(BlockNode::Paragraph("numId 0".into()), None, true)
+            .is_none());
+        let rejected = builder.continue_item(BlockNode::Paragraph("after".into()), None, false);
+        assert!(matches!(rejected, Some(BlockNode::Paragraph(p)) if p == Paragraph::from("after")));
+
+        let Some(BlockNode::List(list)) = builder.finish() else {
+            panic!("list should close");
+        };
+        let blocks = &list.items[0].blocks;
+        assert_eq!(blocks.len(), 6);
+        assert!(matches!(&blocks[1], BlockNode::List(nested) if nested.items.len() == 1));
+        assert!(matches!(&blocks[2], BlockNode::Paragraph(p) if *p == Paragraph::from("more one")));
+        assert!(
+            matches!(&blocks[3], BlockNode::Paragraph(p) if *p == Paragraph::from("still one"))
+        );
+        assert!(matches!(&blocks[4], BlockNode::Table(_)));
+        assert!(matches!(&blocks[5], BlockNode::Paragraph(p) if *p == Paragraph::from("numId 0")));
+    }
+}
@@ -1,4 +1,7 @@
 mod extractor;
+mod lists;
+mod parts;
+mod toc;
 
 use crate::converter::ConversionContext;
 use crate::core::ast::DocumentAst;
@@ -14,3 +17,5 @@ pub trait AstExtractor {
 }
 
 pub use extractor::DocxExtractor;
+pub(crate) use lists::ListBuilder;
+pub(crate) use parts::{replace_part, RawParts};
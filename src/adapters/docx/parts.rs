@@ -0,0 +1,169 @@
+//! Raw XML parts for properties rs-docx does not model.
+
+use crate::Result;
+use std::io::{Cursor, Read, Seek, Write};
+use xmlparser::{Token, Tokenizer};
+use zip::write::SimpleFileOptions;
+
+/// Raw XML of the package parts read alongside the rs-docx model.
+#[derive(Debug, Default, Clone)]
+pub struct RawParts {
+    /// The numbering definitions part (`word/numbering.xml`)
+    pub numbering: Option<String>,
+    /// The main document part (`word/document.xml`)
+    pub document: Option<String>,
+}
+
+impl RawParts {
+    /// Reads the raw parts from a DOCX archive, found through the package
+    /// relationships. Missing parts are `None`.
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        let document_part = relationship_target(&mut archive, "", REL_OFFICE_DOCUMENT)?
+            .unwrap_or_else(|| "word/document.xml".to_string());
+        let numbering_part = relationship_target(&mut archive, &document_part, REL_NUMBERING)?;
+        Ok(Self {
+            numbering: match numbering_part {
+                Some(part) => read_part(&mut archive, &part)?,
+                None => None,
+            },
+            document: read_part(&mut archive, &document_part)?,
+        })
+    }
+}
+
+const REL_OFFICE_DOCUMENT: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument";
+const REL_NUMBERING: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/numbering";
+
+/// Resolves the target of the first relationship of type `ty` of the part
+/// `source` (the package itself when empty) to a part name.
+fn relationship_target<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    source: &str,
+    ty: &str,
+) -> Result<Option<String>> {
+    let (directory, name) = source.rsplit_once('/').unwrap_or(("", source));
+    let rels_part = if directory.is_empty() {
+        format!("_rels/{}.rels", name)
+    } else {
+        format!("{}/_rels/{}.rels", directory, name)
+    };
+    let Some(xml) = read_part(archive, &rels_part)? else {
+        return Ok(None);
+    };
+
+    let mut attrs: Vec<(&str, &str)> = Vec::new();
+    for token in Tokenizer::from(xml.as_str()) {
+        let Ok(token) = token else {
+            break;
+        };
+        match token {
+            Token::ElementStart { .. } => attrs.clear(),
+            Token::Attribute { local, value, .. } => {
+                attrs.push((local.as_str(), value.as_str()));
+            }
+            Token::ElementEnd { .. } => {
+                let attr = |key: &str| attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+                if attr("Type") == Some(ty) && attr("TargetMode") != Some("External") {
+                    if let Some(target) = attr("Target") {
+                        return Ok(Some(resolve_target(directory, target)));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Resolves a relationship target relative to the directory of its source.
+fn resolve_target(directory: &str, target: &str) -> String {
+    let mut segments: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => directory.split('/').filter(|s| !s.is_empty()).collect(),
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
+}
+
+fn read_part<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut xml = String::new();
+    entry.read_to_string(&mut xml)?;
+    Ok(Some(xml))
+}
+
+/// Copies a DOCX archive with the content of the part `name` replaced; the
+/// other parts are copied without recompressing them.
+pub(crate) fn replace_part<R: Read + Seek>(
+    reader: R,
+    name: &str,
+    content: &[u8],
+) -> Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index)?;
+        if entry.name() == name {
+            writer.start_file(name, SimpleFileOptions::default())?;
+            writer.write_all(content)?;
+        } else {
+            writer.raw_copy_file(entry)?;
+        }
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parts_are_found_through_the_package_relationships() {
+        let package = crate::MarkdownToDocx::default()
+            .convert_str("- item\n")
+            .expect("markdown converts to a package");
+
+        let parts =
+            RawParts::from_reader(Cursor::new(package)).expect("package has readable parts");
+
+        assert!(parts.document.is_some_and(|xml| xml.contains("item")));
+        assert!(parts
+            .numbering
+            .is_some_and(|xml| xml.contains("w:abstractNum")));
+    }
+
+    #[test]
+    fn test_relationship_targets_resolve_against_their_source() {
+        assert_eq!(resolve_target("", "word/document.xml"), "word/document.xml");
+        assert_eq!(
+            resolve_target("word", "numbering.xml"),
+            "word/numbering.xml"
+        );
+        assert_eq!(
+            resolve_target("word", "../customXml/item1.xml"),
+            "customXml/item1.xml"
+        );
+        assert_eq!(
+            resolve_target("word", "/word/numbering.xml"),
+            "word/numbering.xml"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Detects tables of contents and regenerates them from headings.
+
+use crate::core::ast::{BlockNode, TocEntry};
+use crate::core::slug::{SlugGenerator, SlugStyle};
+use rs_docx::document::{BodyContent, CharType, Paragraph, ParagraphContent, Run, RunContent};
+
+/// Tracks the table of contents being read while extracting.
+#[derive(Debug, Default)]
+pub(crate) struct TocTracker {
+    /// Instructions of the complex fields open at the current paragraph
+    fields: Vec<String>,
+    /// Depth of table of contents content controls
+    controls: usize,
+    /// Whether the regenerated table of contents was placed for the entries
+    /// being read
+    pub placed: bool,
+}
+
+impl TocTracker {
+    /// Reads the field characters of a paragraph. Returns whether the
+    /// paragraph is inside a `TOC` field: one is open where the paragraph
+    /// starts or ends, or begins and ends within it.
+    pub fn scan_paragraph(&mut self, para: &Paragraph<'_>) -> bool {
+        let mut in_field = self.in_toc_field();
+        for run in paragraph_runs(para) {
+            for content in &run.content {
+                match content {
+                    RunContent::FieldChar(fc) => match fc.ty {
+                        Some(CharType::Begin) => self.fields.push(String::new()),
+                        Some(CharType::End) => {
+                            in_field |= self.in_toc_field();
+                            self.fields.pop();
+                        }
+                        _ => {}
+                    },
+                    RunContent::InstrText(instr) => {
+                        if let Some(field) = self.fields.last_mut() {
+                            field.push_str(&instr.text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        in_field || self.in_toc_field()
+    }
+
+    fn in_toc_field(&self) -> bool {
+        self.fields.iter().any(|instruction| {
+            instruction
+                .split_whitespace()
+                .next()
+                .is_some_and(|name| name.eq_ignore_ascii_case("TOC"))
+        })
+    }
+
+    /// Enters a table of contents content control.
+    pub fn enter_control(&mut self) {
+        self.controls += 1;
+    }
+
+    pub fn leave_control(&mut self) {
+        self.controls = self.controls.saturating_sub(1);
+    }
+
+    /// Whether a table of contents content control is being read.
+    pub fn in_control(&self) -> bool {
+        self.controls > 0
+    }
+}
+
+/// Runs of a paragraph, including those in hyperlinks, insertions and
+/// content controls.
+fn paragraph_runs<'p, 'a>(para: &'p Paragraph<'a>) -> Vec<&'p Run<'a>> {
+    let mut runs = Vec::new();
+    for content in &para.content {
+        match content {
+            ParagraphContent::Run(run) => runs.push(run),
+            ParagraphContent::Link(link) => runs.extend(&link.content),
+            ParagraphContent::Insertion(ins) => runs.extend(&ins.runs),
+            ParagraphContent::SDT(sdt) => {
+                for child in sdt.content.iter().flat_map(|c| &c.content) {
+                    match child {
+                        BodyContent::Run(run) => runs.push(run),
+                        BodyContent::Paragraph(inner) => runs.extend(paragraph_runs(inner)),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// Fills the table of contents placeholders in `blocks` with the headings of
+/// the document. Headings link to their identifier, else to their slug.
+pub(crate) fn fill_tables_of_contents(blocks: &mut [BlockNode], style: SlugStyle) {
+    let mut entries = Vec::new();
+    collect_entries(blocks, &mut SlugGenerator::new(style), &mut entries);
+    fill(blocks, &entries);
+}
+
+fn collect_entries(blocks: &[BlockNode], slugs: &mut SlugGenerator, entries: &mut Vec<TocEntry>) {
+    for block in blocks {
+        match block {
+            BlockNode::Heading(heading) => {
+                let text = heading.plain_text();
+                let slug = slugs.slug(&text);
+                if text.is_empty() {
+                    continue;
+                }
+                entries.push(TocEntry {
+                    level: heading.level,
+                    text,
+                    destination: format!("#{}", heading.id.as_deref().unwrap_or(&slug)),
+                });
+            }
+            BlockNode::ContentControl { blocks, .. } => collect_entries(blocks, slugs, entries),
+            _ => {}
+        }
+    }
+}
+
+fn fill(blocks: &mut [BlockNode], entries: &[TocEntry]) {
+    for block in blocks {
+        match block {
+            BlockNode::TableOfContents(toc) => *toc = entries.to_vec(),
+            BlockNode::ContentControl { blocks, .. } => fill(blocks, entries),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{Heading, InlineNode};
+    use hard_xml::XmlRead;
+
+    fn heading(level: usize, text: &str) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level,
+            label: String::new(),
+            content: vec![InlineNode::Text {
+                text: text.to_string(),
+                style: Default::default(),
+            }],
+            id: None,
+            anchors: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_toc_field_spans_paragraphs() {
+        let paragraphs = [
+            r#"<w:p><w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve"> TOC \o "1-3" \h </w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r><w:r><w:t>Intro</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Scope</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:fldChar w:fldCharType="end"/></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Body</w:t></w:r></w:p>"#,
+        ];
+        let mut tracker = TocTracker::default();
+        let in_toc: Vec<bool> = paragraphs
+            .iter()
+            .map(|xml| tracker.scan_paragraph(&Paragraph::from_str(xml).unwrap()))
+            .collect();
+        assert_eq!(in_toc, vec![true, true, true, false]);
+    }
+
+    #[test]
+    fn test_fill_links_headings_by_slug() {
+        let mut blocks = vec![
+            BlockNode::TableOfContents(Vec::new()),
+            heading(1, "Intro"),
+            heading(2, "Scope & Terms"),
+            heading(1, "Intro"),
+        ];
+        fill_tables_of_contents(&mut blocks, SlugStyle::Github);
+
+        let BlockNode::TableOfContents(entries) = &blocks[0] else {
+            panic!("placeholder replaced");
+        };
+        let destinations: Vec<&str> = entries.iter().map(|e| e.destination.as_str()).collect();
+        assert_eq!(destinations, vec!["#intro", "#scope--terms", "#intro-1"]);
+        assert_eq!(entries[1].level, 2);
+    }
+}
@@ -0,0 +1,447 @@
+//! Heading-aware chunking of a converted document for retrieval pipelines.
+//!
+//! Each heading up to [`ChunkOptions::heading_level`] starts a new chunk, and
+//! sections larger than the target size are split between blocks. Tables and
+//! list items are never split; a single block larger than the target becomes
+//! a chunk of its own.
+//!
+//! ```no_run
+//! use dm2xcod::chunk::ChunkOptions;
+//! use dm2xcod::{ConvertOptions, DocxToMarkdown};
+//!
+//! let converter = DocxToMarkdown::new(ConvertOptions::default());
+//! for chunk in converter.chunk("document.docx", &ChunkOptions::default()).unwrap() {
+//!     println!("{}", chunk.to_json());
+//! }
+//! ```
+
+use crate::core::ast::{for_each_note_reference, BlockNode, DocumentAst, NoteKind};
+use crate::render::Renderer;
+use crate::Result;
+
+/// Target size of a chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkSize {
+    /// Characters of rendered text.
+    Chars(usize),
+    /// Approximate tokens, counted as one token per four characters.
+    Tokens(usize),
+}
+
+impl ChunkSize {
+    /// Size of `text` in this unit.
+    fn measure(self, text: &str) -> usize {
+        let chars = text.chars().count();
+        match self {
+            Self::Chars(_) => chars,
+            Self::Tokens(_) => chars.div_ceil(4),
+        }
+    }
+
+    fn limit(self) -> usize {
+        match self {
+            Self::Chars(limit) | Self::Tokens(limit) => limit,
+        }
+    }
+}
+
+/// Options for splitting a document into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkOptions {
+    /// Size a chunk is kept under where blocks allow.
+    pub target_size: ChunkSize,
+    /// Deepest heading level that starts a new chunk; deeper headings stay in
+    /// the chunk of their parent section while it has room.
+    pub heading_level: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            target_size: ChunkSize::Chars(2000),
+            heading_level: 9,
+        }
+    }
+}
+
+/// Part of a document, rendered on its own.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    /// Text of the enclosing headings, outermost first.
+    pub headings: Vec<String>,
+    /// Indices of the top-level document blocks the chunk was taken from.
+    pub blocks: Vec<usize>,
+    /// Notes and comments referenced in the chunk.
+    pub notes: Vec<ChunkNote>,
+}
+
+/// Note or comment referenced in a chunk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkNote {
+    pub kind: NoteKind,
+    /// Label as in the rendered text, e.g. `en2` for the second endnote.
+    pub label: String,
+    pub text: String,
+}
+
+impl Chunk {
+    /// Serializes the chunk as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        let headings = self
+            .headings
+            .iter()
+            .map(|heading| json_string(heading))
+            .collect::<Vec<_>>();
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|index| index.to_string())
+            .collect::<Vec<_>>();
+        let notes = self
+            .notes
+            .iter()
+            .map(|note| {
+                format!(
+                    "{{\"kind\":{},\"label\":{},\"text\":{}}}",
+                    json_string(note_kind_name(note.kind)),
+                    json_string(&note.label),
+                    json_string(&note.text)
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{{\"text\":{},\"headings\":[{}],\"blocks\":[{}],\"notes\":[{}]}}",
+            json_string(&self.text),
+            headings.join(","),
+            blocks.join(","),
+            notes.join(",")
+        )
+    }
+}
+
+/// Name of a note kind in serialized chunks.
+pub fn note_kind_name(kind: NoteKind) -> &'static str {
+    match kind {
+        NoteKind::Footnote => "footnote",
+        NoteKind::Endnote => "endnote",
+        NoteKind::Comment => "comment",
+    }
+}
+
+/// Splits `document` into chunks rendered with `renderer`.
+pub fn chunk_document<R: Renderer + ?Sized>(
+    document: &DocumentAst,
+    renderer: &R,
+    options: &ChunkOptions,
+) -> Result<Vec<Chunk>> {
+    let mut chunker = Chunker {
+        document,
+        renderer,
+        options,
+        chunks: Vec::new(),
+        path: Vec::new(),
+        current: Vec::new(),
+        current_headings: Vec::new(),
+        current_size: 0,
+        anchors: Vec::new(),
+    };
+    for (index, block) in document.blocks.iter().enumerate() {
+        if block.is_bookmark_only() {
+            chunker.anchors.push((index, block.clone()));
+            continue;
+        }
+        if let BlockNode::Heading(heading) = block {
+            if heading.level <= options.heading_level {
+                chunker.flush()?;
+            }
+            chunker.path.retain(|(level, _)| *level < heading.level);
+            chunker.path.push((heading.level, heading.plain_text()));
+        }
+        match block {
+            BlockNode::List(list) => {
+                for item in &list.items {
+                    let mut unit = list.clone();
+                    unit.items = vec![item.clone()];
+                    chunker.push(index, BlockNode::List(unit))?;
+                }
+            }
+            _ => chunker.push(index, block.clone())?,
+        }
+    }
+    let anchors = std::mem::take(&mut chunker.anchors);
+    chunker.current.extend(anchors);
+    chunker.flush()?;
+    Ok(chunker.chunks)
+}
+
+struct Chunker<'d, R: ?Sized> {
+    document: &'d DocumentAst,
+    renderer: &'d R,
+    options: &'d ChunkOptions,
+    chunks: Vec<Chunk>,
+    /// Levels and text of the headings enclosing the current block.
+    path: Vec<(usize, String)>,
+    /// Blocks of the chunk being filled, with their document indices.
+    current: Vec<(usize, BlockNode)>,
+    current_headings: Vec<String>,
+    current_size: usize,
+    /// Bookmark blocks waiting to go into the chunk of the block after them.
+    anchors: Vec<(usize, BlockNode)>,
+}
+
+impl<R: Renderer + ?Sized> Chunker<'_, R> {
+    /// Adds a block that must not be split, starting a new chunk first when
+    /// it does not fit. Headings and bookmarks at the end of a full chunk
+    /// move along to the new one.
+    fn push(&mut self, index: usize, block: BlockNode) -> Result<()> {
+        let size = self
+            .options
+            .target_size
+            .measure(&self.render(vec![block.clone()])?);
+        let mut carried = Vec::new();
+        if !self.current.is_empty() && self.current_size + size > self.options.target_size.limit() {
+            while self.current.len() > 1
+                && self.current.last().is_some_and(|(_, last)| {
+                    matches!(last, BlockNode::Heading(_)) || last.is_bookmark_only()
+                })
+            {
+                carried.extend(self.current.pop());
+            }
+            self.flush()?;
+        }
+        if self.current.is_empty() && carried.is_empty() {
+            self.current_headings = self.path.iter().map(|(_, text)| text.clone()).collect();
+        }
+        for (carried_index, heading) in carried.into_iter().rev() {
+            self.current_size += self
+                .options
+                .target_size
+                .measure(&self.render(vec![heading.clone()])?);
+            self.current.push((carried_index, heading));
+            self.current_headings = self.path.iter().map(|(_, text)| text.clone()).collect();
+        }
+        let anchors = std::mem::take(&mut self.anchors);
+        self.current.extend(anchors);
+
+        match (self.current.last_mut(), block) {
+            (Some((last, BlockNode::List(list))), BlockNode::List(item)) if *last == index => {
+                list.items.extend(item.items);
+            }
+            (_, block) => self.current.push((index, block)),
+        }
+        self.current_size += size;
+        Ok(())
+    }
+
+    /// Finishes the chunk being filled, if any.
+    fn flush(&mut self) -> Result<()> {
+        if self.current.is_empty() {
+            return Ok(());
+        }
+        let current = std::mem::take(&mut self.current);
+        let mut blocks = Vec::new();
+        let mut notes: Vec<ChunkNote> = Vec::new();
+        for (index, block) in &current {
+            if blocks.last() != Some(index) {
+                blocks.push(*index);
+            }
+            for_each_note_reference(block, &mut |kind, id| {
+                let label = kind.label(id);
+                if notes
+                    .iter()
+                    .any(|note| note.kind == kind && note.label == label)
+                {
+                    return;
+                }
+                if let Some(text) = self.document.references.note_text(kind, id) {
+                    notes.push(ChunkNote {
+                        kind,
+                        label,
+                        text: text.trim().to_string(),
+                    });
+                }
+            });
+        }
+
+        let text = self.render(current.into_iter().map(|(_, block)| block).collect())?;
+        self.chunks.push(Chunk {
+            text: text.trim_end().to_string(),
+            headings: std::mem::take(&mut self.current_headings),
+            blocks,
+            notes,
+        });
+        self.current_size = 0;
+        Ok(())
+    }
+
+    /// Renders blocks without the document's note definitions.
+    fn render(&self, blocks: Vec<BlockNode>) -> Result<String> {
+        self.renderer.render(&DocumentAst {
+            blocks,
+            ..Default::default()
+        })
+    }
+}
+
+/// Quotes and escapes `text` as a JSON string.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{
+        Heading, InlineNode, List, ListItem, ListKind, Paragraph, ReferenceDefinitions, Table,
+        TextStyle,
+    };
+    use crate::render::PlainTextRenderer;
+
+    fn paragraph(text: &str) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content: vec![InlineNode::Text {
+                text: text.to_string(),
+                style: TextStyle::default(),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn heading(level: usize, text: &str) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level,
+            label: String::new(),
+            content: vec![InlineNode::Text {
+                text: text.to_string(),
+                style: TextStyle::default(),
+            }],
+            id: None,
+            anchors: Vec::new(),
+        })
+    }
+
+    fn item(text: &str) -> ListItem {
+        ListItem {
+            label: "-".to_string(),
+            separator: " ".to_string(),
+            number: None,
+            checked: None,
+            blocks: vec![paragraph(text)],
+        }
+    }
+
+    fn chunk(blocks: Vec<BlockNode>, options: ChunkOptions) -> Vec<Chunk> {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["The note.".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        chunk_document(&document, &PlainTextRenderer::default(), &options)
+            .expect("chunking should work")
+    }
+
+    #[test]
+    fn test_chunks_follow_headings_with_breadcrumbs() {
+        let mut noted = paragraph("Noted");
+        if let BlockNode::Paragraph(paragraph) = &mut noted {
+            paragraph.content.push(InlineNode::NoteReference {
+                kind: NoteKind::Footnote,
+                id: "1".to_string(),
+            });
+        }
+        let chunks = chunk(
+            vec![
+                paragraph("Preface"),
+                heading(1, "Guide"),
+                heading(2, "Setup"),
+                noted,
+                heading(1, "Usage"),
+                paragraph("Run it"),
+            ],
+            ChunkOptions::default(),
+        );
+
+        assert_eq!(chunks.len(), 4);
+        assert!(chunks[0].headings.is_empty());
+        assert_eq!(chunks[1].headings, vec!["Guide"]);
+        assert_eq!(chunks[2].headings, vec!["Guide", "Setup"]);
+        assert_eq!(chunks[2].blocks, vec![2, 3]);
+        assert_eq!(chunks[2].text, "Setup\n\nNoted[1]");
+        assert_eq!(
+            chunks[2].notes,
+            vec![ChunkNote {
+                kind: NoteKind::Footnote,
+                label: "1".to_string(),
+                text: "The note.".to_string(),
+            }]
+        );
+        assert_eq!(chunks[3].headings, vec!["Usage"]);
+        assert!(chunks[3].notes.is_empty());
+
+        let merged = chunk(
+            vec![heading(1, "Guide"), heading(2, "Setup"), paragraph("Text")],
+            ChunkOptions {
+                heading_level: 1,
+                ..Default::default()
+            },
+        );
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].blocks, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_chunks_keep_tables_and_list_items_whole() {
+        let list = BlockNode::List(List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 0,
+            items: vec![item("alpha alpha"), item("beta beta"), item("gamma gamma")],
+        });
+        let table = BlockNode::Table(Table { rows: Vec::new() });
+        let chunks = chunk(
+            vec![heading(1, "Items"), list, table],
+            ChunkOptions {
+                target_size: ChunkSize::Chars(35),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].text, "Items\n\n- alpha alpha\n- beta beta");
+        assert_eq!(chunks[1].text, "- gamma gamma");
+        assert_eq!(chunks[1].headings, vec!["Items"]);
+        assert_eq!(chunks[1].blocks, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_chunk_json_escapes_text() {
+        let chunk = Chunk {
+            text: "a \"b\"\n\tc".to_string(),
+            headings: vec!["H".to_string()],
+            blocks: vec![0, 1],
+            notes: Vec::new(),
+        };
+        assert_eq!(
+            chunk.to_json(),
+            r#"{"text":"a \"b\"\n\tc","headings":["H"],"blocks":[0,1],"notes":[]}"#
+        );
+    }
+}
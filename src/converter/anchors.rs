@@ -0,0 +1,331 @@
+//! Links to headings by slug instead of by Word bookmark.
+//!
+//! Word links to a heading through a bookmark around it, usually a hidden one
+//! such as `_Toc12345`. Many Markdown renderers strip the `<a id>` anchors
+//! bookmarks become, while they all give headings an identifier derived from
+//! their text. Links to a bookmark inside a heading are pointed at the
+//! heading's slug instead, and bookmarks Word generates are dropped once
+//! nothing links to them. Only link and anchor nodes are rewritten: raw
+//! markup and the rendered text of notes are left as they are.
+
+use crate::core::ast::{BlockNode, DocumentAst, Heading, InlineNode};
+use crate::core::slug::{SlugGenerator, SlugStyle};
+use std::collections::{HashMap, HashSet};
+
+/// Gives headings unique slugs, points links to their bookmarks at the slugs
+/// and drops generated bookmarks nothing links to.
+pub(crate) fn link_headings_by_slug(document: &mut DocumentAst, style: SlugStyle) {
+    let mut slugs = SlugGenerator::new(style);
+    let mut targets = HashMap::new();
+    // Bookmarks on their own just before a heading belong to it
+    let mut pending = Vec::new();
+    for_each_block(&mut document.blocks, &mut |block| match block {
+        BlockNode::Heading(heading) => {
+            let bookmarks = std::mem::take(&mut pending);
+            assign_slug(heading, bookmarks, &mut slugs, &mut targets);
+        }
+        BlockNode::Paragraph(paragraph) if block_is_bookmarks(&paragraph.content) => {
+            collect_anchors(&paragraph.content, &mut pending)
+        }
+        _ => pending.clear(),
+    });
+
+    let mut referenced = HashSet::new();
+    let mut retarget = |name: &str| {
+        let name = targets.get(name).map_or(name, String::as_str);
+        referenced.insert(name.to_string());
+        format!("#{}", name)
+    };
+    relink(&mut document.blocks, &mut retarget);
+
+    let keep = |name: &str| !is_generated_bookmark(name) || referenced.contains(name);
+    for_each_block(&mut document.blocks, &mut |block| match block {
+        BlockNode::Heading(heading) => {
+            heading.anchors.retain(|name| keep(name));
+            retain_anchors(&mut heading.content, &keep);
+        }
+        BlockNode::Paragraph(paragraph) => retain_anchors(&mut paragraph.content, &keep),
+        _ => {}
+    });
+}
+
+/// Whether Word created the bookmark itself: the last edit position
+/// (`_GoBack`), table of contents entries (`_Toc…`) and cross-reference
+/// links (`_Hlk…`).
+fn is_generated_bookmark(name: &str) -> bool {
+    name == "_GoBack" || name.starts_with("_Toc") || name.starts_with("_Hlk")
+}
+
+/// Calls `f` with every block, including those in list items and content
+/// controls, and with tables before the blocks of their cells.
+pub(crate) fn for_each_block(blocks: &mut [BlockNode], f: &mut dyn FnMut(&mut BlockNode)) {
+    for block in blocks {
+        match block {
+            BlockNode::List(list) => {
+                for item in &mut list.items {
+                    for_each_block(&mut item.blocks, f);
+                }
+            }
+            BlockNode::ContentControl { blocks, .. } => for_each_block(blocks, f),
+            BlockNode::Table(_) => {
+                f(block);
+                if let BlockNode::Table(table) = block {
+                    for cell in table.rows.iter_mut().flatten() {
+                        for_each_block(&mut cell.blocks, f);
+                    }
+                }
+            }
+            block => f(block),
+        }
+    }
+}
+
+/// Gives a heading the next slug and maps its bookmarks, and those before
+/// it, to it. An explicit identifier is replaced by the slug and kept as an
+/// anchor.
+fn assign_slug(
+    heading: &mut Heading,
+    mut bookmarks: Vec<String>,
+    slugs: &mut SlugGenerator,
+    targets: &mut HashMap<String, String>,
+) {
+    let slug = slugs.slug(&heading.plain_text());
+    bookmarks.extend(heading.anchors.iter().cloned());
+    collect_anchors(&heading.content, &mut bookmarks);
+    if let Some(id) = heading.id.as_mut() {
+        let id = std::mem::replace(id, slug.clone());
+        heading.content.insert(0, InlineNode::Anchor(id.clone()));
+        bookmarks.push(id);
+    }
+    for bookmark in bookmarks {
+        targets.insert(bookmark, slug.clone());
+    }
+}
+
+/// Whether inline content is made of bookmarks only.
+fn block_is_bookmarks(nodes: &[InlineNode]) -> bool {
+    !nodes.is_empty()
+        && nodes
+            .iter()
+            .all(|node| matches!(node, InlineNode::Anchor(_)))
+}
+
+fn collect_anchors(nodes: &[InlineNode], anchors: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            InlineNode::Anchor(name) => anchors.push(name.clone()),
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                collect_anchors(content, anchors)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Rewrites the destination of every in-document link (`#name`) in `blocks`
+/// to what `f` returns for the name.
+pub(crate) fn relink(blocks: &mut [BlockNode], f: &mut dyn FnMut(&str) -> String) {
+    for_each_block(blocks, &mut |block| match block {
+        BlockNode::Heading(heading) => relink_nodes(&mut heading.content, f),
+        BlockNode::TableOfContents(entries) => {
+            for entry in entries {
+                if let Some(name) = entry.destination.strip_prefix('#') {
+                    entry.destination = f(name);
+                }
+            }
+        }
+        BlockNode::Paragraph(paragraph) => relink_nodes(&mut paragraph.content, f),
+        _ => {}
+    });
+}
+
+fn relink_nodes(nodes: &mut [InlineNode], f: &mut dyn FnMut(&str) -> String) {
+    for node in nodes {
+        match node {
+            InlineNode::Link {
+                destination,
+                content,
+            } => {
+                if let Some(name) = destination.strip_prefix('#') {
+                    *destination = f(name);
+                }
+                relink_nodes(content, f);
+            }
+            InlineNode::ContentControl { content, .. } => relink_nodes(content, f),
+            _ => {}
+        }
+    }
+}
+
+/// Names of the anchors defined in `blocks`: bookmarks and explicit heading
+/// identifiers.
+pub(crate) fn defined_anchors(blocks: &mut [BlockNode]) -> Vec<String> {
+    let mut names = Vec::new();
+    for_each_block(blocks, &mut |block| match block {
+        BlockNode::Heading(heading) => {
+            names.extend(heading.id.iter().cloned());
+            names.extend(heading.anchors.iter().cloned());
+            collect_anchors(&heading.content, &mut names);
+        }
+        BlockNode::Paragraph(paragraph) => collect_anchors(&paragraph.content, &mut names),
+        _ => {}
+    });
+    names.retain(|name| !name.is_empty());
+    names
+}
+
+fn retain_anchors(nodes: &mut Vec<InlineNode>, keep: &dyn Fn(&str) -> bool) {
+    nodes.retain(|node| !matches!(node, InlineNode::Anchor(name) if !keep(name)));
+    for node in nodes {
+        match node {
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                retain_anchors(content, keep)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{Paragraph, ReferenceDefinitions, TocEntry};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: Default::default(),
+        }
+    }
+
+    fn link(label: &str, destination: &str) -> InlineNode {
+        InlineNode::Link {
+            destination: destination.to_string(),
+            content: vec![text(label)],
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn content(block: &BlockNode) -> &[InlineNode] {
+        match block {
+            BlockNode::Paragraph(paragraph) => &paragraph.content,
+            _ => panic!("not a paragraph: {:?}", block),
+        }
+    }
+
+    fn anchor(name: &str) -> InlineNode {
+        InlineNode::Anchor(name.to_string())
+    }
+
+    fn heading(text: &str, anchors: &[&str]) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level: 1,
+            label: String::new(),
+            content: vec![self::text(text)],
+            id: None,
+            anchors: anchors.iter().map(|a| a.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn test_links_to_heading_bookmarks_use_slugs() {
+        let mut document = DocumentAst {
+            blocks: vec![
+                BlockNode::TableOfContents(vec![TocEntry {
+                    level: 1,
+                    text: "Intro".to_string(),
+                    destination: "#_Toc1".to_string(),
+                }]),
+                paragraph(vec![
+                    text("See "),
+                    link("Intro", "#_Ref7"),
+                    text(", "),
+                    link("again", "#_Toc3"),
+                    text(" and "),
+                    link("x", "#Kept"),
+                ]),
+                heading("Intro", &["_Toc1", "_Ref7"]),
+                heading("Intro", &["_Toc3", "Named"]),
+                paragraph(vec![anchor("_GoBack"), text("Text"), anchor("_Hlk9")]),
+                paragraph(vec![anchor("_Toc5")]),
+                BlockNode::Paragraph(Paragraph::from_markdown("Literal \\](#_Toc1)")),
+            ],
+            references: ReferenceDefinitions {
+                footnotes: vec!["Back to [Intro](#_Toc1)".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        link_headings_by_slug(&mut document, SlugStyle::Github);
+
+        let BlockNode::TableOfContents(entries) = &document.blocks[0] else {
+            panic!("table of contents kept");
+        };
+        assert_eq!(entries[0].destination, "#intro");
+        assert_eq!(
+            content(&document.blocks[1]),
+            vec![
+                text("See "),
+                link("Intro", "#intro"),
+                text(", "),
+                link("again", "#intro-1"),
+                text(" and "),
+                link("x", "#Kept"),
+            ]
+        );
+        assert!(matches!(
+            &document.blocks[2],
+            BlockNode::Heading(h) if h.anchors == vec!["_Ref7".to_string()]
+        ));
+        assert!(matches!(
+            &document.blocks[3],
+            BlockNode::Heading(h) if h.anchors == vec!["Named".to_string()]
+        ));
+        assert_eq!(content(&document.blocks[4]), vec![text("Text")]);
+        assert_eq!(content(&document.blocks[5]), Vec::new());
+        // Raw markup and the text of notes are not links
+        assert_eq!(
+            content(&document.blocks[6]),
+            vec![InlineNode::Raw("Literal \\](#_Toc1)".to_string())]
+        );
+        assert_eq!(document.references.footnotes[0], "Back to [Intro](#_Toc1)");
+    }
+
+    #[test]
+    fn test_bookmarks_before_heading_belong_to_it() {
+        let mut document = DocumentAst {
+            blocks: vec![
+                paragraph(vec![
+                    link("Scope", "#_Toc2"),
+                    text(" and "),
+                    link("Body", "#_Toc3"),
+                ]),
+                paragraph(vec![anchor("_Toc2")]),
+                heading("1.2 Scope", &[]),
+                paragraph(vec![anchor("_Toc3")]),
+                paragraph(vec![text("Body")]),
+            ],
+            ..Default::default()
+        };
+
+        link_headings_by_slug(&mut document, SlugStyle::Pandoc);
+
+        assert_eq!(
+            content(&document.blocks[0]),
+            vec![
+                link("Scope", "#scope"),
+                text(" and "),
+                link("Body", "#_Toc3"),
+            ]
+        );
+        assert_eq!(content(&document.blocks[1]), Vec::new());
+        assert_eq!(content(&document.blocks[3]), vec![anchor("_Toc3")]);
+    }
+}
@@ -1,6 +1,15 @@
-use super::{ImageExtractor, NumberingResolver, StyleResolver};
-use crate::core::ast::ReferenceDefinitions;
-use crate::{ConvertOptions, Result};
+use super::form_fields::{count_form_fields, FormControls, FormField};
+use super::heading_inference::HeadingInference;
+use super::{ImageExtractor, ListMarker, NumberingResolver, StyleResolver};
+use crate::core::ast::{
+    BlockNode, ContentControl, ContentControlKind, InlineNode, NoteKind, ReferenceDefinitions,
+};
+use crate::core::slug::SlugStyle;
+use crate::render::{InlineRenderer, MarkdownRenderer, TextContext};
+use crate::{
+    ContentControlMarkup, ConvertOptions, ListMarkerStyle, MarkdownFlavor, Result, TocHandling,
+};
+use rs_docx::document::SDTProperty;
 use std::collections::{HashMap, HashSet};
 
 /// Context passed through conversion for shared mutable state.
@@ -10,6 +19,7 @@ pub struct ConversionContext<'a> {
     image_extractor: &'a mut ImageExtractor,
     options: &'a ConvertOptions,
     style_resolver: &'a StyleResolver<'a>,
+    inline_renderer: InlineRenderer,
     footnotes: Vec<String>,
     footnote_index_by_id: HashMap<isize, usize>,
     footnote_text_by_id: HashMap<isize, String>,
@@ -20,6 +30,15 @@ pub struct ConversionContext<'a> {
     seen_comment_ids: HashSet<String>,
     comment_text_by_id: HashMap<String, String>,
     missing_references: Vec<String>,
+    heading_inference: Option<HeadingInference>,
+    /// Number of tables the content being converted is nested in
+    table_depth: usize,
+    form_controls: FormControls,
+    /// Position of the next legacy form field
+    form_field_position: usize,
+    /// Current number of each `SEQ` field sequence
+    sequences: HashMap<String, i32>,
+    in_toc_entry: bool,
 }
 
 impl<'a> ConversionContext<'a> {
@@ -104,6 +123,7 @@ impl<'a> ConversionContext<'a> {
             image_extractor,
             options,
             style_resolver,
+            inline_renderer: InlineRenderer::new(options),
             footnotes: Vec::new(),
             footnote_index_by_id: HashMap::new(),
             footnote_text_by_id,
@@ -114,10 +134,32 @@ impl<'a> ConversionContext<'a> {
             seen_comment_ids: HashSet::new(),
             comment_text_by_id,
             missing_references: Vec::new(),
+            heading_inference: None,
+            table_depth: 0,
+            form_controls: FormControls::default(),
+            form_field_position: 0,
+            sequences: HashMap::new(),
+            in_toc_entry: false,
         }
     }
 
     pub fn register_comment_reference(&mut self, id: &str) -> String {
+        let reference = self.comment_reference(id);
+        self.render_inlines(&[reference], TextContext::Block)
+    }
+
+    pub fn register_footnote_reference(&mut self, id: isize) -> String {
+        let reference = self.footnote_reference(id);
+        self.render_inlines(&[reference], TextContext::Block)
+    }
+
+    pub fn register_endnote_reference(&mut self, id: isize) -> String {
+        let reference = self.endnote_reference(id);
+        self.render_inlines(&[reference], TextContext::Block)
+    }
+
+    /// Registers a reference to a comment, returning the reference node.
+    pub fn comment_reference(&mut self, id: &str) -> InlineNode {
         if !self.seen_comment_ids.contains(id) {
             let comment_text = self.comment_text_by_id.get(id).cloned().unwrap_or_else(|| {
                 self.missing_references.push(format!("comment:{id}"));
@@ -128,49 +170,66 @@ impl<'a> ConversionContext<'a> {
             self.seen_comment_ids.insert(id.to_string());
         }
 
-        format!("[^c{}]", id)
+        InlineNode::NoteReference {
+            kind: NoteKind::Comment,
+            id: id.to_string(),
+        }
     }
 
-    pub fn register_footnote_reference(&mut self, id: isize) -> String {
-        if let Some(idx) = self.footnote_index_by_id.get(&id).copied() {
-            return format!("[^{}]", idx);
+    /// Registers a reference to a footnote, returning the reference node.
+    /// Footnotes are numbered in order of first reference.
+    pub fn footnote_reference(&mut self, id: isize) -> InlineNode {
+        let idx = match self.footnote_index_by_id.get(&id).copied() {
+            Some(idx) => idx,
+            None => {
+                let footnote_text =
+                    self.footnote_text_by_id
+                        .get(&id)
+                        .cloned()
+                        .unwrap_or_else(|| {
+                            self.missing_references.push(format!("footnote:{id}"));
+                            String::new()
+                        });
+
+                self.footnotes.push(footnote_text);
+                let idx = self.footnotes.len();
+                self.footnote_index_by_id.insert(id, idx);
+                idx
+            }
+        };
+
+        InlineNode::NoteReference {
+            kind: NoteKind::Footnote,
+            id: idx.to_string(),
         }
-
-        let footnote_text = self
-            .footnote_text_by_id
-            .get(&id)
-            .cloned()
-            .unwrap_or_else(|| {
-                self.missing_references.push(format!("footnote:{id}"));
-                String::new()
-            });
-
-        self.footnotes.push(footnote_text);
-        let idx = self.footnotes.len();
-        self.footnote_index_by_id.insert(id, idx);
-
-        format!("[^{}]", idx)
     }
 
-    pub fn register_endnote_reference(&mut self, id: isize) -> String {
-        if let Some(idx) = self.endnote_index_by_id.get(&id).copied() {
-            return format!("[^en{}]", idx);
+    /// Registers a reference to an endnote, returning the reference node.
+    /// Endnotes are numbered in order of first reference.
+    pub fn endnote_reference(&mut self, id: isize) -> InlineNode {
+        let idx = match self.endnote_index_by_id.get(&id).copied() {
+            Some(idx) => idx,
+            None => {
+                let endnote_text = self
+                    .endnote_text_by_id
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        self.missing_references.push(format!("endnote:{id}"));
+                        String::new()
+                    });
+
+                self.endnotes.push(endnote_text);
+                let idx = self.endnotes.len();
+                self.endnote_index_by_id.insert(id, idx);
+                idx
+            }
+        };
+
+        InlineNode::NoteReference {
+            kind: NoteKind::Endnote,
+            id: idx.to_string(),
         }
-
-        let endnote_text = self
-            .endnote_text_by_id
-            .get(&id)
-            .cloned()
-            .unwrap_or_else(|| {
-                self.missing_references.push(format!("endnote:{id}"));
-                String::new()
-            });
-
-        self.endnotes.push(endnote_text);
-        let idx = self.endnotes.len();
-        self.endnote_index_by_id.insert(id, idx);
-
-        format!("[^en{}]", idx)
     }
 
     pub fn reference_definitions(&self) -> ReferenceDefinitions {
@@ -188,7 +247,7 @@ impl<'a> ConversionContext<'a> {
     pub fn extract_image_from_drawing(
         &mut self,
         drawing: &rs_docx::document::Drawing,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<InlineNode>> {
         self.image_extractor
             .extract_from_drawing(drawing, self.rels)
     }
@@ -196,7 +255,7 @@ impl<'a> ConversionContext<'a> {
     pub fn extract_image_from_pict(
         &mut self,
         pict: &rs_docx::document::Pict,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<InlineNode>> {
         self.image_extractor.extract_from_pict(pict, self.rels)
     }
 
@@ -219,14 +278,169 @@ impl<'a> ConversionContext<'a> {
             .resolve_paragraph_property(direct_props, para_style_id)
     }
 
-    pub fn next_list_marker(&mut self, num_id: i32, ilvl: i32) -> String {
-        self.numbering.next_marker(num_id, ilvl)
+    pub fn next_list_marker(&mut self, num_id: i32, ilvl: i32) -> ListMarker {
+        self.numbering.next_list_marker(num_id, ilvl)
     }
 
     pub fn list_indent_level(&self, num_id: i32, ilvl: i32) -> usize {
         self.numbering.get_indent(num_id, ilvl)
     }
 
+    pub fn list_level_for_style(&self, num_id: i32, style_id: &str) -> Option<i32> {
+        self.numbering.level_for_style(num_id, style_id)
+    }
+
+    /// Sets the check box and form field states read from the raw document.
+    pub(crate) fn set_form_controls(&mut self, controls: FormControls) {
+        self.form_controls = controls;
+    }
+
+    /// Returns the checked state of a `w14:checkbox` content control.
+    pub fn checkbox_state(&self, sdt_id: isize) -> Option<bool> {
+        match self.form_controls.content_controls.get(&sdt_id)?.kind {
+            ContentControlKind::CheckBox { checked } => Some(checked),
+            _ => None,
+        }
+    }
+
+    /// Returns the properties of a content control. Controls missing from the
+    /// raw document fall back to what rs-docx parsed.
+    pub fn content_control(&self, property: Option<&SDTProperty>) -> ContentControl {
+        let id = property.and_then(|p| p.id.as_ref()).and_then(|id| id.id);
+        if let Some(control) = id.and_then(|id| self.form_controls.content_controls.get(&id)) {
+            return control.clone();
+        }
+        let mut control = ContentControl::default();
+        if let Some(doc_part) = property.and_then(|p| p.doc_part_obj.as_ref()) {
+            let gallery = doc_part
+                .doc_part_gallery
+                .as_ref()
+                .and_then(|g| g.name.as_ref())
+                .map(|name| name.to_string());
+            control.kind = ContentControlKind::DocPart(gallery);
+        }
+        control
+    }
+
+    /// Advances a `SEQ` field sequence, returning its new number.
+    pub fn next_sequence(&mut self, identifier: &str) -> i32 {
+        let number = self.sequences.entry(identifier.to_string()).or_insert(0);
+        *number += 1;
+        *number
+    }
+
+    /// Returns the current number of a `SEQ` field sequence (`\c`).
+    pub fn current_sequence(&self, identifier: &str) -> i32 {
+        self.sequences.get(identifier).copied().unwrap_or(0)
+    }
+
+    /// Restarts a `SEQ` field sequence at `number` (`\r`).
+    pub fn reset_sequence(&mut self, identifier: &str, number: i32) -> i32 {
+        self.sequences.insert(identifier.to_string(), number);
+        number
+    }
+
+    /// How tables of contents are written.
+    pub fn toc_handling(&self) -> TocHandling {
+        self.options.toc
+    }
+
+    /// Slugs that link to headings without an identifier: those of the
+    /// anchor style, else those of the Markdown flavor.
+    pub fn slug_style(&self) -> SlugStyle {
+        self.options.slug_style()
+    }
+
+    /// Whether `MERGEFIELD` fields are written as `{{name}}` placeholders.
+    pub fn merge_field_placeholders(&self) -> bool {
+        self.options.merge_field_placeholders
+    }
+
+    /// How list markers, and the labels of numbered headings, are written.
+    pub fn list_markers(&self) -> ListMarkerStyle {
+        self.options.list_markers
+    }
+
+    /// How content controls are marked in the output.
+    pub fn content_control_markup(&self) -> ContentControlMarkup {
+        self.options.content_controls
+    }
+
+    /// Returns the data of the legacy form field at the current position
+    /// and moves past it.
+    pub(crate) fn next_form_field(&mut self) -> Option<FormField> {
+        let field = self
+            .form_controls
+            .fields
+            .get(self.form_field_position)
+            .cloned();
+        self.form_field_position += 1;
+        field
+    }
+
+    /// Moves past the legacy form fields of a paragraph that is not
+    /// converted.
+    pub(crate) fn skip_form_fields(&mut self, para: &rs_docx::document::Paragraph<'_>) {
+        self.form_field_position += count_form_fields(para);
+    }
+
+    /// Collects the document statistics needed to infer heading levels for
+    /// paragraphs without heading styles.
+    pub fn analyze_headings(&mut self, body: &[rs_docx::document::BodyContent<'a>]) {
+        self.heading_inference = Some(HeadingInference::analyze(body, self.style_resolver));
+    }
+
+    /// Returns the inferred heading level for a paragraph outside of tables,
+    /// if heading inference has been enabled via [`Self::analyze_headings`].
+    pub fn inferred_heading_level(&self, para: &rs_docx::document::Paragraph<'a>) -> Option<usize> {
+        if self.in_table() {
+            return None;
+        }
+        self.heading_inference
+            .as_ref()
+            .and_then(|inference| inference.level_for(para, self.style_resolver))
+    }
+
+    /// Marks the start of a table.
+    pub(crate) fn enter_table(&mut self) {
+        self.table_depth += 1;
+    }
+
+    pub(crate) fn leave_table(&mut self) {
+        self.table_depth = self.table_depth.saturating_sub(1);
+    }
+
+    /// Whether the content being converted is in a table cell.
+    pub fn in_table(&self) -> bool {
+        self.table_depth > 0
+    }
+
+    /// Renders inline nodes placed in `text_context` with the configured
+    /// formatting syntax.
+    pub fn render_inlines(&self, nodes: &[InlineNode], text_context: TextContext) -> String {
+        self.inline_renderer.render(nodes, text_context)
+    }
+
+    /// Renders blocks to Markdown with the configured options.
+    pub fn render_markdown(&self, blocks: &[BlockNode]) -> String {
+        MarkdownRenderer::new(self.options).render_blocks(blocks)
+    }
+
+    /// Marks the paragraphs converted next as table of contents entries.
+    pub fn set_in_toc_entry(&mut self, in_toc_entry: bool) {
+        self.in_toc_entry = in_toc_entry;
+    }
+
+    /// Whether the paragraph being converted is a table of contents entry.
+    pub fn in_toc_entry(&self) -> bool {
+        self.in_toc_entry
+    }
+
+    /// Returns the target Markdown flavor, if any.
+    pub fn flavor(&self) -> Option<MarkdownFlavor> {
+        self.options.flavor
+    }
+
     pub fn preserve_whitespace(&self) -> bool {
         self.options.preserve_whitespace
     }
@@ -239,6 +453,26 @@ impl<'a> ConversionContext<'a> {
         self.options.html_strikethrough
     }
 
+    pub fn html_sub_superscript_enabled(&self) -> bool {
+        self.options.html_sub_superscript
+    }
+
+    pub fn html_highlight_enabled(&self) -> bool {
+        self.options.html_highlight
+    }
+
+    pub fn html_caps_enabled(&self) -> bool {
+        self.options.html_caps
+    }
+
+    pub fn html_text_color_enabled(&self) -> bool {
+        self.options.html_text_color
+    }
+
+    pub fn include_hidden_text(&self) -> bool {
+        self.options.include_hidden_text
+    }
+
     pub fn footnote_count(&self) -> usize {
         self.footnotes.len()
     }
@@ -0,0 +1,222 @@
+//! Field codes: parsing field instructions and expanding simple fields.
+//!
+//! rs-docx keeps the `w:instrText` of complex fields but drops `w:fldSimple`
+//! elements along with their runs, so simple fields are rewritten into the
+//! equivalent complex field runs before the document is parsed.
+
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+/// A parsed field instruction such as `HYPERLINK "url" \l "anchor"`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct FieldInstruction {
+    /// Field type in upper case, e.g. `REF`
+    pub name: String,
+    /// Arguments before the first switch
+    pub arguments: Vec<String>,
+    /// Switches (without the backslash) with the argument following them
+    pub switches: Vec<(String, Option<String>)>,
+}
+
+impl FieldInstruction {
+    pub fn parse(instruction: &str) -> Self {
+        let mut tokens = tokenize(instruction).into_iter().peekable();
+        let mut parsed = Self {
+            name: tokens
+                .next()
+                .map(|(token, _)| token.to_uppercase())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+
+        while let Some((token, quoted)) = tokens.next() {
+            match token.strip_prefix('\\').filter(|_| !quoted) {
+                Some(switch) => {
+                    let value = tokens
+                        .next_if(|(next, quoted)| *quoted || !next.starts_with('\\'))
+                        .map(|(value, _)| value);
+                    parsed.switches.push((switch.to_string(), value));
+                }
+                None if parsed.switches.is_empty() => parsed.arguments.push(token),
+                // Stray arguments after switches are ignored
+                None => {}
+            }
+        }
+
+        parsed
+    }
+
+    /// Returns the argument at `index`.
+    pub fn argument(&self, index: usize) -> Option<&str> {
+        self.arguments.get(index).map(String::as_str)
+    }
+
+    /// Whether the switch is present. Switch names are case-insensitive.
+    pub fn has_switch(&self, name: &str) -> bool {
+        self.switches
+            .iter()
+            .any(|(switch, _)| switch.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the argument of a switch.
+    pub fn switch_value(&self, name: &str) -> Option<&str> {
+        self.switches
+            .iter()
+            .find(|(switch, _)| switch.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+/// Splits an instruction into whitespace-separated tokens, keeping quoted
+/// text together. Returns each token with whether it was quoted.
+fn tokenize(instruction: &str) -> Vec<(String, bool)> {
+    let mut tokens = Vec::new();
+    let mut chars = instruction.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut token = String::new();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' if chars.peek() == Some(&'"') => {
+                        token.push('"');
+                        chars.next();
+                    }
+                    _ => token.push(c),
+                }
+            }
+            tokens.push((token, true));
+        } else {
+            let mut token = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || (c == '"' && !token.is_empty()) {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+            tokens.push((token, false));
+        }
+    }
+
+    tokens
+}
+
+/// Rewrites every `w:fldSimple` in `xml` into complex field runs
+/// (`begin`, `instrText`, `separate`, the field result, `end`). Returns `None`
+/// when the document has no simple fields or cannot be tokenized.
+pub(crate) fn expand_simple_fields(xml: &str) -> Option<String> {
+    if !xml.contains("fldSimple") {
+        return None;
+    }
+
+    let mut out = String::with_capacity(xml.len() + 256);
+    let mut copied = 0;
+    // Start offset, prefix and instruction of the `w:fldSimple` start tag being read
+    let mut start_tag: Option<(usize, &str, &str)> = None;
+
+    for token in Tokenizer::from(xml) {
+        match token.ok()? {
+            Token::ElementStart {
+                prefix,
+                local,
+                span,
+            } if local.as_str() == "fldSimple" => {
+                start_tag = Some((span.start(), prefix.as_str(), ""));
+            }
+            Token::Attribute { local, value, .. } if local.as_str() == "instr" => {
+                if let Some((_, _, instr)) = start_tag.as_mut() {
+                    *instr = value.as_str();
+                }
+            }
+            Token::ElementStart { .. } => start_tag = None,
+            Token::ElementEnd { end, span } => match end {
+                ElementEnd::Open | ElementEnd::Empty => {
+                    let Some((start, prefix, instr)) = start_tag.take() else {
+                        continue;
+                    };
+                    out.push_str(&xml[copied..start]);
+                    push_field_char(&mut out, prefix, "begin");
+                    out.push_str(&format!(
+                        "<{p}:r><{p}:instrText xml:space=\"preserve\">{}</{p}:instrText></{p}:r>",
+                        instr,
+                        p = prefix
+                    ));
+                    if matches!(end, ElementEnd::Open) {
+                        push_field_char(&mut out, prefix, "separate");
+                    } else {
+                        push_field_char(&mut out, prefix, "end");
+                    }
+                    copied = span.end();
+                }
+                ElementEnd::Close(prefix, local) if local.as_str() == "fldSimple" => {
+                    out.push_str(&xml[copied..span.start()]);
+                    push_field_char(&mut out, prefix.as_str(), "end");
+                    copied = span.end();
+                }
+                ElementEnd::Close(..) => {}
+            },
+            _ => {}
+        }
+    }
+
+    out.push_str(&xml[copied..]);
+    Some(out)
+}
+
+fn push_field_char(out: &mut String, prefix: &str, ty: &str) {
+    out.push_str(&format!(
+        "<{p}:r><{p}:fldChar {p}:fldCharType=\"{}\"/></{p}:r>",
+        ty,
+        p = prefix
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_field_instructions() {
+        let link =
+            FieldInstruction::parse(r#" HYPERLINK "https://example.com/a b" \l "sec-1" \o "Tip" "#);
+        assert_eq!(link.name, "HYPERLINK");
+        assert_eq!(link.argument(0), Some("https://example.com/a b"));
+        assert_eq!(link.switch_value("l"), Some("sec-1"));
+        assert_eq!(link.switch_value("o"), Some("Tip"));
+
+        let reference = FieldInstruction::parse(r"ref _Ref123 \h \* MERGEFORMAT");
+        assert_eq!(reference.name, "REF");
+        assert_eq!(reference.arguments, vec!["_Ref123".to_string()]);
+        assert!(reference.has_switch("h"));
+        assert_eq!(reference.switch_value("h"), None);
+        assert_eq!(reference.switch_value("*"), Some("MERGEFORMAT"));
+
+        let seq = FieldInstruction::parse(r"SEQ Figure \r 3 \* ROMAN");
+        assert_eq!(seq.switch_value("r"), Some("3"));
+        assert_eq!(seq.switch_value("*"), Some("ROMAN"));
+    }
+
+    #[test]
+    fn test_expand_simple_fields() {
+        let xml = r#"<w:p><w:fldSimple w:instr=" MERGEFIELD Name "><w:r><w:t>«Name»</w:t></w:r></w:fldSimple><w:fldSimple w:instr="REF x"/></w:p>"#;
+
+        assert_eq!(
+            expand_simple_fields(xml).as_deref(),
+            Some(concat!(
+                r#"<w:p><w:r><w:fldChar w:fldCharType="begin"/></w:r>"#,
+                r#"<w:r><w:instrText xml:space="preserve"> MERGEFIELD Name </w:instrText></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="separate"/></w:r>"#,
+                r#"<w:r><w:t>«Name»</w:t></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="end"/></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r>"#,
+                r#"<w:r><w:instrText xml:space="preserve">REF x</w:instrText></w:r>"#,
+                r#"<w:r><w:fldChar w:fldCharType="end"/></w:r></w:p>"#
+            ))
+        );
+        assert_eq!(expand_simple_fields("<w:p/>"), None);
+    }
+}
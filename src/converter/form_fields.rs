@@ -0,0 +1,349 @@
+//! Content controls and form fields that rs-docx does not parse.
+//!
+//! The properties of content controls (tag, alias, type, data binding and
+//! check box state) and the `w:ffData` of legacy form fields are read
+//! straight from `word/document.xml`. Form fields are matched to the fields
+//! of the parsed document by position, which content skipped while
+//! converting (e.g. a removed table of contents) still moves past.
+
+use super::fields::FieldInstruction;
+use crate::core::ast::{ContentControl, ContentControlKind};
+use rs_docx::document::{BodyContent, CharType, Paragraph, ParagraphContent, Run, RunContent};
+use std::collections::HashMap;
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+/// Current value of a legacy form field (`w:ffData`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum FormField {
+    /// `FORMCHECKBOX`: whether the box is checked.
+    CheckBox(bool),
+    /// `FORMDROPDOWN`: the selected entry.
+    DropDown(Option<String>),
+    /// `FORMTEXT`: the value is the field result in the document text.
+    Text,
+}
+
+/// Form controls parsed from raw `word/document.xml`.
+#[derive(Debug, Default)]
+pub(crate) struct FormControls {
+    /// Maps content control `w:id` -> its properties
+    pub content_controls: HashMap<isize, ContentControl>,
+    /// Legacy form fields by position in the document
+    pub fields: Vec<FormField>,
+}
+
+impl FormControls {
+    /// Parses `word/document.xml`. Malformed XML yields what was read so far.
+    pub fn parse(xml: &str) -> Self {
+        let mut parser = Parser::default();
+        let mut element: Option<&str> = None;
+        let mut attrs: Vec<(&str, &str)> = Vec::new();
+
+        for token in Tokenizer::from(xml) {
+            let Ok(token) = token else {
+                break;
+            };
+            match token {
+                Token::ElementStart { local, .. } => {
+                    element = Some(local.as_str());
+                    attrs.clear();
+                }
+                Token::Attribute { local, value, .. } => {
+                    attrs.push((local.as_str(), value.as_str()));
+                }
+                Token::ElementEnd { end, .. } => match end {
+                    ElementEnd::Open => {
+                        if let Some(name) = element.take() {
+                            parser.open(name, &attrs);
+                        }
+                    }
+                    ElementEnd::Empty => {
+                        if let Some(name) = element.take() {
+                            parser.open(name, &attrs);
+                            parser.close(name);
+                        }
+                    }
+                    ElementEnd::Close(_, local) => parser.close(local.as_str()),
+                },
+                _ => {}
+            }
+        }
+
+        parser.result
+    }
+}
+
+/// Counts the legacy form fields of a paragraph where the paragraph
+/// converter reads fields: in its runs and content controls.
+pub(crate) fn count_form_fields(para: &Paragraph<'_>) -> usize {
+    let mut fields = Vec::new();
+    let mut count = 0;
+    for content in &para.content {
+        match content {
+            ParagraphContent::Run(run) => count_run_form_fields(run, &mut fields, &mut count),
+            ParagraphContent::SDT(sdt) => {
+                for child in sdt.content.iter().flat_map(|c| &c.content) {
+                    match child {
+                        BodyContent::Paragraph(inner) => count += count_form_fields(inner),
+                        BodyContent::Run(run) => {
+                            count_run_form_fields(run, &mut fields, &mut count)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    count
+}
+
+/// Counts the form fields a run ends; `fields` holds the instructions of
+/// the fields open before it.
+fn count_run_form_fields(run: &Run<'_>, fields: &mut Vec<String>, count: &mut usize) {
+    for content in &run.content {
+        match content {
+            RunContent::FieldChar(fc) => match fc.ty {
+                Some(CharType::Begin) => fields.push(String::new()),
+                Some(CharType::End) => {
+                    let Some(instruction) = fields.pop() else {
+                        continue;
+                    };
+                    let name = FieldInstruction::parse(&instruction).name;
+                    if matches!(name.as_str(), "FORMCHECKBOX" | "FORMDROPDOWN" | "FORMTEXT") {
+                        *count += 1;
+                    }
+                }
+                _ => {}
+            },
+            RunContent::InstrText(instr) => {
+                if let Some(field) = fields.last_mut() {
+                    field.push_str(&instr.text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Content control properties read so far.
+#[derive(Default)]
+struct SdtState {
+    id: Option<isize>,
+    control: ContentControl,
+}
+
+/// Form field data read so far.
+#[derive(Default)]
+struct FieldState {
+    kind: Option<FieldKind>,
+    checked: Option<bool>,
+    default: Option<i32>,
+    result: Option<i32>,
+    entries: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    CheckBox,
+    DropDown,
+    Text,
+}
+
+#[derive(Default)]
+struct Parser {
+    result: FormControls,
+    sdt: Option<SdtState>,
+    field: Option<FieldState>,
+}
+
+impl Parser {
+    fn open(&mut self, name: &str, attrs: &[(&str, &str)]) {
+        let attr = |key: &str| attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let int_attr = |key: &str| attr(key).and_then(|v| v.trim().parse::<i32>().ok());
+        // On/off values: a missing w:val means on
+        let flag = || !matches!(attr("val"), Some("0" | "false" | "off"));
+
+        if name == "sdtPr" {
+            self.sdt = Some(SdtState::default());
+            return;
+        }
+        if name == "ffData" {
+            self.field = Some(FieldState::default());
+            return;
+        }
+
+        if let Some(sdt) = self.sdt.as_mut() {
+            let control = &mut sdt.control;
+            let kind = match name {
+                "id" => {
+                    sdt.id = attr("val").and_then(|v| v.trim().parse().ok());
+                    None
+                }
+                "tag" => {
+                    control.tag = attr("val").map(str::to_string);
+                    None
+                }
+                "alias" => {
+                    control.alias = attr("val").map(str::to_string);
+                    None
+                }
+                "dataBinding" => {
+                    control.data_binding = attr("xpath").map(str::to_string);
+                    None
+                }
+                "showingPlcHdr" => {
+                    control.showing_placeholder = flag();
+                    None
+                }
+                "checked" => {
+                    if let ContentControlKind::CheckBox { checked } = &mut control.kind {
+                        *checked = flag();
+                    }
+                    None
+                }
+                "docPartGallery" => {
+                    if let ContentControlKind::DocPart(gallery) = &mut control.kind {
+                        *gallery = attr("val").map(str::to_string);
+                    }
+                    None
+                }
+                "richText" => Some(ContentControlKind::RichText),
+                "text" => Some(ContentControlKind::PlainText),
+                "date" => Some(ContentControlKind::Date),
+                "dropDownList" => Some(ContentControlKind::DropDownList),
+                "comboBox" => Some(ContentControlKind::ComboBox),
+                "checkbox" => Some(ContentControlKind::CheckBox { checked: false }),
+                "picture" => Some(ContentControlKind::Picture),
+                "group" => Some(ContentControlKind::Group),
+                "repeatingSection" => Some(ContentControlKind::RepeatingSection),
+                "repeatingSectionItem" => Some(ContentControlKind::RepeatingSectionItem),
+                "docPartObj" | "docPartList" => Some(ContentControlKind::DocPart(None)),
+                "equation" | "citation" | "bibliography" => {
+                    Some(ContentControlKind::Other(name.to_string()))
+                }
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                control.kind = kind;
+            }
+        } else if let Some(field) = self.field.as_mut() {
+            match name {
+                "checkBox" => field.kind = Some(FieldKind::CheckBox),
+                "ddList" => field.kind = Some(FieldKind::DropDown),
+                "textInput" => field.kind = Some(FieldKind::Text),
+                "checked" => field.checked = Some(flag()),
+                "default" => field.default = int_attr("val"),
+                "result" => field.result = int_attr("val"),
+                "listEntry" => {
+                    if let Some(value) = attr("val") {
+                        field.entries.push(value.to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "sdtPr" => {
+                let Some(sdt) = self.sdt.take() else {
+                    return;
+                };
+                if let Some(id) = sdt.id {
+                    self.result.content_controls.insert(id, sdt.control);
+                }
+            }
+            "ffData" => {
+                let Some(field) = self.field.take() else {
+                    return;
+                };
+                let form_field = match field.kind {
+                    Some(FieldKind::CheckBox) => FormField::CheckBox(
+                        field
+                            .checked
+                            .unwrap_or_else(|| field.default.is_some_and(|v| v != 0)),
+                    ),
+                    Some(FieldKind::DropDown) => {
+                        let index = field.result.or(field.default).unwrap_or(0);
+                        FormField::DropDown(
+                            usize::try_from(index)
+                                .ok()
+                                .and_then(|index| field.entries.get(index))
+                                .cloned(),
+                        )
+                    }
+                    Some(FieldKind::Text) | None => FormField::Text,
+                };
+                self.result.fields.push(form_field);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content_controls_and_form_fields() {
+        let xml = r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml">
+<w:body>
+  <w:p>
+    <w:sdt><w:sdtPr><w:id w:val="-1754962"/><w14:checkbox><w14:checked w14:val="1"/></w14:checkbox></w:sdtPr>
+      <w:sdtContent><w:r><w:t>☒</w:t></w:r></w:sdtContent></w:sdt>
+    <w:sdt><w:sdtPr><w:id w:val="42"/><w14:checkbox><w14:checked w14:val="0"/></w14:checkbox></w:sdtPr></w:sdt>
+    <w:sdt><w:sdtPr><w:alias w:val="Client"/><w:tag w:val="client_name"/><w:id w:val="7"/><w:showingPlcHdr/>
+      <w:dataBinding w:xpath="/root[1]/client[1]" w:storeItemID="{1}"/><w:text/></w:sdtPr></w:sdt>
+    <w:sdt><w:sdtPr><w:id w:val="8"/><w:docPartObj><w:docPartGallery w:val="Table of Contents"/><w:docPartUnique/></w:docPartObj></w:sdtPr></w:sdt>
+  </w:p>
+  <w:p>
+    <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:name w:val="Check1"/>
+      <w:checkBox><w:sizeAuto/><w:default w:val="0"/><w:checked/></w:checkBox></w:ffData></w:fldChar></w:r>
+    <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:ddList><w:result w:val="1"/>
+      <w:listEntry w:val="Red"/><w:listEntry w:val="Green"/></w:ddList></w:ffData></w:fldChar></w:r>
+    <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:textInput><w:default w:val="x"/></w:textInput></w:ffData></w:fldChar></w:r>
+    <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:checkBox><w:default w:val="1"/></w:checkBox></w:ffData></w:fldChar></w:r>
+  </w:p>
+</w:body></w:document>"#;
+
+        let parsed = FormControls::parse(xml);
+
+        let kind = |id: isize| parsed.content_controls.get(&id).map(|c| c.kind.clone());
+        assert_eq!(
+            kind(-1754962),
+            Some(ContentControlKind::CheckBox { checked: true })
+        );
+        assert_eq!(
+            kind(42),
+            Some(ContentControlKind::CheckBox { checked: false })
+        );
+        assert_eq!(
+            kind(8),
+            Some(ContentControlKind::DocPart(Some(
+                "Table of Contents".to_string()
+            )))
+        );
+        assert_eq!(
+            parsed.content_controls.get(&7),
+            Some(&ContentControl {
+                tag: Some("client_name".to_string()),
+                alias: Some("Client".to_string()),
+                data_binding: Some("/root[1]/client[1]".to_string()),
+                kind: ContentControlKind::PlainText,
+                showing_placeholder: true,
+            })
+        );
+        assert_eq!(
+            parsed.fields,
+            vec![
+                FormField::CheckBox(true),
+                FormField::DropDown(Some("Green".to_string())),
+                FormField::Text,
+                FormField::CheckBox(true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Heading inference - assigns heading levels to paragraphs without heading styles.
+//!
+//! Many documents format headings as `Normal` paragraphs with bold or larger
+//! runs. This module looks at the font size distribution of the body text and
+//! ranks paragraphs that stand out (larger size, outline levels, bold text
+//! kept with the next paragraph) into heading levels. List paragraphs are
+//! never headings.
+
+use super::StyleResolver;
+use rs_docx::document::{BodyContent, Paragraph, ParagraphContent, Run, RunContent};
+use std::collections::HashMap;
+
+/// Font size (in half-points) assumed when neither runs, styles nor document
+/// defaults specify one.
+const DEFAULT_FONT_SIZE: isize = 20;
+
+/// Minimum ratio (in percent) between a paragraph's font size and the body
+/// font size for the paragraph to be treated as a heading.
+const SIZE_RATIO_PERCENT: isize = 115;
+
+/// Longest text (in characters) that can be inferred as a heading.
+const MAX_HEADING_CHARS: usize = 120;
+
+/// Deepest Markdown heading level.
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// Document-wide statistics used to infer heading levels.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HeadingInference {
+    /// Most common font size of body text, in half-points.
+    body_size: isize,
+    /// Distinct font sizes larger than the body size used by heading
+    /// candidates, largest first. The index maps to the heading level.
+    heading_sizes: Vec<isize>,
+}
+
+/// Per-paragraph signals relevant to heading inference.
+#[derive(Debug, Clone, Default)]
+struct ParagraphFeatures {
+    text: String,
+    /// Character count per font size.
+    size_chars: HashMap<isize, usize>,
+    all_bold: bool,
+    keep_next: bool,
+    outline_level: Option<usize>,
+    /// Whether the paragraph has numbering, i.e. belongs to a list.
+    numbered: bool,
+    has_heading_style: bool,
+}
+
+impl ParagraphFeatures {
+    /// Returns the font size covering the most characters of the paragraph.
+    fn dominant_size(&self) -> Option<isize> {
+        self.size_chars
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(size, _)| *size)
+    }
+}
+
+impl HeadingInference {
+    /// Collects font size statistics from the document body.
+    ///
+    /// Table contents are skipped: headings inside tables are rare and table
+    /// text often uses a smaller font that would skew the body size.
+    pub(crate) fn analyze<'a>(body: &[BodyContent<'a>], resolver: &StyleResolver<'a>) -> Self {
+        let mut paragraphs = Vec::new();
+        collect_features(body, resolver, &mut paragraphs);
+
+        let mut size_totals: HashMap<isize, usize> = HashMap::new();
+        for features in paragraphs.iter().filter(|f| !f.has_heading_style) {
+            for (size, chars) in &features.size_chars {
+                *size_totals.entry(*size).or_default() += chars;
+            }
+        }
+
+        let body_size = size_totals
+            .iter()
+            .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+            .map(|(size, _)| *size)
+            .unwrap_or(DEFAULT_FONT_SIZE);
+
+        let mut heading_sizes: Vec<isize> = paragraphs
+            .iter()
+            .filter(|f| {
+                !f.has_heading_style && !f.numbered && is_heading_text(&f.text, MAX_HEADING_CHARS)
+            })
+            .filter_map(ParagraphFeatures::dominant_size)
+            .filter(|size| is_larger_than_body(*size, body_size))
+            .collect();
+        heading_sizes.sort_unstable_by(|a, b| b.cmp(a));
+        heading_sizes.dedup();
+
+        Self {
+            body_size,
+            heading_sizes,
+        }
+    }
+
+    /// Returns the inferred heading level (1-based) for a paragraph, or `None`
+    /// when the paragraph looks like body text, belongs to a list or already
+    /// has a heading style.
+    ///
+    /// Bold alone is not enough: labels like "Note" are often bold, so bold
+    /// text must also be kept with the next paragraph.
+    pub(crate) fn level_for<'a>(
+        &self,
+        para: &Paragraph<'a>,
+        resolver: &StyleResolver<'a>,
+    ) -> Option<usize> {
+        let features = paragraph_features(para, resolver);
+        if features.has_heading_style || features.numbered || features.text.is_empty() {
+            return None;
+        }
+
+        if let Some(outline) = features.outline_level {
+            return Some((outline + 1).min(MAX_HEADING_LEVEL));
+        }
+
+        if let Some(size) = features.dominant_size() {
+            if is_larger_than_body(size, self.body_size)
+                && is_heading_text(&features.text, MAX_HEADING_CHARS)
+            {
+                let rank = self
+                    .heading_sizes
+                    .iter()
+                    .position(|s| *s == size)
+                    .unwrap_or(self.heading_sizes.len());
+                return Some((rank + 1).min(MAX_HEADING_LEVEL));
+            }
+        }
+
+        if features.all_bold
+            && features.keep_next
+            && is_heading_text(&features.text, MAX_HEADING_CHARS)
+        {
+            let depth = numbered_depth(&features.text).unwrap_or(1);
+            return Some((self.heading_sizes.len() + depth).min(MAX_HEADING_LEVEL));
+        }
+
+        None
+    }
+}
+
+fn collect_features<'a>(
+    body: &[BodyContent<'a>],
+    resolver: &StyleResolver<'a>,
+    output: &mut Vec<ParagraphFeatures>,
+) {
+    for content in body {
+        match content {
+            BodyContent::Paragraph(para) => output.push(paragraph_features(para, resolver)),
+            BodyContent::Sdt(sdt) => {
+                if let Some(sdt_content) = &sdt.content {
+                    collect_features(&sdt_content.content, resolver, output);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn paragraph_features<'a>(para: &Paragraph<'a>, resolver: &StyleResolver<'a>) -> ParagraphFeatures {
+    let para_style_id = para
+        .property
+        .as_ref()
+        .and_then(|p| p.style_id.as_ref())
+        .map(|s| s.value.as_ref());
+    let props = resolver.resolve_paragraph_property(para.property.as_ref(), para_style_id);
+
+    let mut features = ParagraphFeatures {
+        all_bold: true,
+        keep_next: props
+            .keep_next
+            .as_ref()
+            .map(|k| k.value.unwrap_or(true))
+            .unwrap_or(false),
+        outline_level: props
+            .outline_lvl
+            .as_ref()
+            .and_then(|o| usize::try_from(o.value).ok())
+            // Level 9 marks body text.
+            .filter(|lvl| *lvl < 9),
+        // Numbering id 0 removes numbering inherited from the style.
+        numbered: props
+            .numbering
+            .as_ref()
+            .and_then(|n| n.id.as_ref())
+            .is_some_and(|id| id.value != 0),
+        has_heading_style: props
+            .style_id
+            .as_ref()
+            .and_then(|s| crate::localization::parse_heading_style(&s.value))
+            .is_some(),
+        ..Default::default()
+    };
+
+    for content in &para.content {
+        match content {
+            ParagraphContent::Run(run) => add_run(&mut features, run, resolver, para_style_id),
+            ParagraphContent::Link(link) => {
+                for run in &link.content {
+                    add_run(&mut features, run, resolver, para_style_id);
+                }
+            }
+            ParagraphContent::Insertion(ins) => {
+                for run in &ins.runs {
+                    add_run(&mut features, run, resolver, para_style_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    features.text = features.text.trim().to_string();
+    if features.text.is_empty() {
+        features.all_bold = false;
+    }
+    features
+}
+
+fn add_run<'a>(
+    features: &mut ParagraphFeatures,
+    run: &Run<'a>,
+    resolver: &StyleResolver<'a>,
+    para_style_id: Option<&str>,
+) {
+    let mut text = String::new();
+    for content in &run.content {
+        match content {
+            RunContent::Text(t) => text.push_str(&t.text),
+            RunContent::Tab(_) => text.push('\t'),
+            RunContent::Break(_) | RunContent::CarriageReturn(_) => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    let chars = text.chars().filter(|c| !c.is_whitespace()).count();
+    features.text.push_str(&text);
+    if chars == 0 {
+        return;
+    }
+
+    let run_style_id = run
+        .property
+        .as_ref()
+        .and_then(|p| p.style_id.as_ref())
+        .map(|s| s.value.as_ref());
+    let props = resolver.resolve_run_property(run.property.as_ref(), run_style_id, para_style_id);
+
+    let size = props
+        .size
+        .as_ref()
+        .map(|s| s.value)
+        .unwrap_or(DEFAULT_FONT_SIZE);
+    *features.size_chars.entry(size).or_default() += chars;
+
+    let is_bold = props
+        .bold
+        .as_ref()
+        .map(|b| b.value.unwrap_or(true))
+        .unwrap_or(false);
+    features.all_bold &= is_bold;
+}
+
+fn is_larger_than_body(size: isize, body_size: isize) -> bool {
+    size * 100 >= body_size * SIZE_RATIO_PERCENT
+}
+
+/// Checks whether text is short, single-line and not sentence-like.
+fn is_heading_text(text: &str, max_chars: usize) -> bool {
+    !text.is_empty()
+        && !text.contains('\n')
+        && text.chars().count() <= max_chars
+        && !text.ends_with(['.', ',', ';'])
+}
+
+/// Detects outline numbering at the start of the text and returns its depth
+/// ("1 Scope" -> 1, "2.3 Terms" -> 2, "제1장" -> 1, "제2절" -> 2).
+fn numbered_depth(text: &str) -> Option<usize> {
+    let token = text.split_whitespace().next()?;
+
+    let numeric = token.trim_end_matches('.');
+    if !numeric.is_empty()
+        && numeric
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+        && token.len() < text.len()
+    {
+        return Some(numeric.split('.').count());
+    }
+
+    let korean = token.strip_prefix('제')?;
+    let digits_end = korean
+        .char_indices()
+        .find(|(_, c)| !c.is_ascii_digit())
+        .map(|(i, _)| i)?;
+    if digits_end == 0 {
+        return None;
+    }
+    match korean[digits_end..].chars().next()? {
+        '장' => Some(1),
+        '절' => Some(2),
+        '조' => Some(3),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs_docx::formatting::{
+        Bold, CharacterProperty, KeepNext, NumberingProperty, ParagraphProperty,
+    };
+
+    fn sized_para(text: &str, size: isize, bold: bool) -> Paragraph<'static> {
+        let mut props = CharacterProperty::default().size(size);
+        if bold {
+            props = props.bold(Bold::from(true));
+        }
+        Paragraph::default().push(Run::default().property(props).push_text(text.to_string()))
+    }
+
+    fn kept_with_next(para: Paragraph<'static>) -> Paragraph<'static> {
+        para.property(ParagraphProperty {
+            keep_next: Some(KeepNext { value: None }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_larger_font_sizes_rank_into_levels() {
+        let styles = rs_docx::styles::Styles::new();
+        let resolver = StyleResolver::new(&styles);
+        let body = vec![
+            BodyContent::Paragraph(sized_para("Document Title", 40, false)),
+            BodyContent::Paragraph(sized_para("Overview", 32, false)),
+            BodyContent::Paragraph(sized_para(
+                "This is ordinary body text that is long enough to dominate the statistics.",
+                22,
+                false,
+            )),
+        ];
+
+        let inference = HeadingInference::analyze(&body, &resolver);
+        let levels: Vec<_> = body
+            .iter()
+            .map(|b| match b {
+                BodyContent::Paragraph(p) => inference.level_for(p, &resolver),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(levels, vec![Some(1), Some(2), None]);
+    }
+
+    #[test]
+    fn test_bold_only_paragraph_ranks_below_sized_headings() {
+        let styles = rs_docx::styles::Styles::new();
+        let resolver = StyleResolver::new(&styles);
+        let body = vec![
+            BodyContent::Paragraph(sized_para("Title", 36, false)),
+            BodyContent::Paragraph(kept_with_next(sized_para("2.1 Bold Section", 22, true))),
+            BodyContent::Paragraph(sized_para("A bold sentence ends with a period.", 22, true)),
+            BodyContent::Paragraph(sized_para(
+                "Plain body text which is the most common size in this document.",
+                22,
+                false,
+            )),
+        ];
+
+        let inference = HeadingInference::analyze(&body, &resolver);
+        let para = |i: usize| match &body[i] {
+            BodyContent::Paragraph(p) => p,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(inference.level_for(para(1), &resolver), Some(3));
+        assert_eq!(inference.level_for(para(2), &resolver), None);
+    }
+
+    #[test]
+    fn test_keep_next_allows_longer_bold_heading() {
+        let styles = rs_docx::styles::Styles::new();
+        let resolver = StyleResolver::new(&styles);
+        let text =
+            "A rather long bold heading that exceeds the default bold-only limit of eighty chars";
+        let para = kept_with_next(sized_para(text, 22, true));
+        let inference = HeadingInference::analyze(&[], &resolver);
+
+        assert_eq!(inference.level_for(&para, &resolver), Some(1));
+        assert_eq!(
+            inference.level_for(&sized_para(text, 22, true), &resolver),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bold_label_is_not_a_heading() {
+        let styles = rs_docx::styles::Styles::new();
+        let resolver = StyleResolver::new(&styles);
+        let inference = HeadingInference::analyze(&[], &resolver);
+
+        assert_eq!(
+            inference.level_for(&sized_para("Note", 22, true), &resolver),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bold_list_item_is_not_a_heading() {
+        let styles = rs_docx::styles::Styles::new();
+        let resolver = StyleResolver::new(&styles);
+        let item = |size| {
+            sized_para("Bold item", size, true).property(ParagraphProperty {
+                keep_next: Some(KeepNext { value: None }),
+                numbering: Some(NumberingProperty::from((1, 0))),
+                ..Default::default()
+            })
+        };
+        let body = vec![
+            BodyContent::Paragraph(item(22)),
+            BodyContent::Paragraph(item(32)),
+            BodyContent::Paragraph(sized_para(
+                "Plain body text which is the most common size in this document.",
+                22,
+                false,
+            )),
+        ];
+        let inference = HeadingInference::analyze(&body, &resolver);
+
+        assert_eq!(inference.level_for(&item(22), &resolver), None);
+        assert_eq!(inference.level_for(&item(32), &resolver), None);
+    }
+
+    #[test]
+    fn test_numbered_depth() {
+        assert_eq!(numbered_depth("1 Scope"), Some(1));
+        assert_eq!(numbered_depth("2.3. Terms"), Some(2));
+        assert_eq!(numbered_depth("제3절 목적"), Some(2));
+        assert_eq!(numbered_depth("2024"), None);
+        assert_eq!(numbered_depth("Overview"), None);
+    }
+}
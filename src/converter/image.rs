@@ -1,5 +1,6 @@
 //! Image extractor - handles image extraction from DOCX.
 
+use crate::core::ast::InlineNode;
 use crate::{error::Error, Result};
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
@@ -9,6 +10,9 @@ use std::fs::{self, File};
 use std::io::{Cursor, Read, Seek};
 use std::path::{Path, PathBuf};
 
+/// English Metric Units per pixel at 96 dpi.
+const EMUS_PER_PIXEL: isize = 9525;
+
 /// Extractor for images embedded in DOCX.
 pub struct ImageExtractor {
     mode: ImageMode,
@@ -80,12 +84,12 @@ impl ImageExtractor {
         }
     }
 
-    /// Extracts image from a Drawing element and returns Markdown.
+    /// Extracts image from a Drawing element.
     pub fn extract_from_drawing(
         &mut self,
         drawing: &Drawing,
         rels: &HashMap<String, String>,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<InlineNode>> {
         if matches!(self.mode, ImageMode::Skip) {
             return Ok(None);
         }
@@ -103,7 +107,19 @@ impl ImageExtractor {
         };
 
         // Extract and process image
-        self.process_image(image_path)
+        self.process_image(image_path, Self::drawing_width(drawing))
+    }
+
+    /// Displayed width of a drawing in pixels, from its extent in EMUs.
+    fn drawing_width(drawing: &Drawing) -> Option<u32> {
+        let extent = match (&drawing.inline, &drawing.anchor) {
+            (Some(inline), _) => inline.extent.as_ref(),
+            (None, Some(anchor)) => anchor.extent.as_ref(),
+            (None, None) => None,
+        }?;
+        u32::try_from(extent.cx / EMUS_PER_PIXEL)
+            .ok()
+            .filter(|&width| width > 0)
     }
 
     fn find_blip_id(&self, drawing: &Drawing) -> Option<String> {
@@ -139,7 +155,7 @@ impl ImageExtractor {
         &mut self,
         pict: &rs_docx::document::Pict,
         rels: &HashMap<String, String>,
-    ) -> Result<Option<String>> {
+    ) -> Result<Option<InlineNode>> {
         if matches!(self.mode, ImageMode::Skip) {
             return Ok(None);
         }
@@ -157,7 +173,7 @@ impl ImageExtractor {
         };
 
         // Extract and process image
-        self.process_image(image_path)
+        self.process_image(image_path, None)
     }
 
     fn find_pict_blip_id(&self, pict: &rs_docx::document::Pict) -> Option<String> {
@@ -182,7 +198,11 @@ impl ImageExtractor {
         None
     }
 
-    fn process_image(&mut self, image_path: &str) -> Result<Option<String>> {
+    fn process_image(
+        &mut self,
+        image_path: &str,
+        width: Option<u32>,
+    ) -> Result<Option<InlineNode>> {
         // Read image from DOCX archive
         let image_data = self.read_image_from_docx(image_path)?;
 
@@ -202,7 +222,11 @@ impl ImageExtractor {
                 fs::write(&output_path, &image_data)?;
 
                 // Return relative path
-                Ok(Some(format!("![image]({})", output_path.display())))
+                Ok(Some(InlineNode::Image {
+                    source: output_path.display().to_string(),
+                    alt: "image".to_string(),
+                    width,
+                }))
             }
             ImageMode::Inline => {
                 let mime_type = match ext.to_lowercase().as_str() {
@@ -215,10 +239,11 @@ impl ImageExtractor {
                 };
 
                 let b64 = BASE64.encode(&image_data);
-                Ok(Some(format!(
-                    "<img src=\"data:{};base64,{}\" alt=\"image\" />",
-                    mime_type, b64
-                )))
+                Ok(Some(InlineNode::Image {
+                    source: format!("data:{};base64,{}", mime_type, b64),
+                    alt: "image".to_string(),
+                    width,
+                }))
             }
             ImageMode::Skip => Ok(None),
         }
@@ -1,33 +1,55 @@
 //! Converter modules for DOCX to Markdown transformation.
 
+mod heading_inference;
 mod hyperlink;
 mod image;
+mod num_format;
 mod numbering;
+mod numbering_xml;
 mod paragraph;
 mod run;
 
+mod anchors;
 mod context;
+mod fields;
+mod form_fields;
+mod split;
 mod styles;
+mod symbol_fonts;
 mod table;
 mod table_grid;
 
-use crate::adapters::docx::{AstExtractor, DocxExtractor};
+use self::anchors::link_headings_by_slug;
+use self::fields::expand_simple_fields;
+use self::form_fields::FormControls;
+use self::split::split_document;
+use crate::adapters::docx::{AstExtractor, DocxExtractor, RawParts};
+use crate::chunk::{chunk_document, Chunk, ChunkOptions};
+use crate::core::ast::{DocumentAst, DocumentProperties};
 #[cfg(test)]
 use crate::render::escape_html_attr;
 use crate::render::{MarkdownRenderer, Renderer};
 use crate::{error::Error, ConvertOptions, ImageHandling, Result};
+use hard_xml::XmlRead;
+use rs_docx::core::Core;
 #[cfg(test)]
 use rs_docx::document::BodyContent;
-use rs_docx::DocxFile;
+use rs_docx::document::Document;
+use rs_docx::{Docx, DocxFile};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::path::Path;
 
+pub(crate) use self::anchors::for_each_block;
 pub use self::context::ConversionContext;
 pub use self::hyperlink::resolve_hyperlink;
 pub use self::image::ImageExtractor;
-pub use self::numbering::NumberingResolver;
-pub use self::paragraph::ParagraphConverter;
+pub(crate) use self::num_format::format_num;
+pub use self::numbering::{ListMarker, NumberingResolver};
+pub use self::paragraph::{ListPlacement, ParagraphConverter};
 pub use self::run::RunConverter;
+pub use self::split::SplitFile;
 pub use self::styles::StyleResolver;
 pub use self::table::TableConverter;
 
@@ -42,9 +64,9 @@ impl DocxToMarkdown<DocxExtractor, MarkdownRenderer> {
     /// Creates a new converter with the given options.
     pub fn new(options: ConvertOptions) -> Self {
         Self {
+            renderer: MarkdownRenderer::new(&options),
             options,
             extractor: DocxExtractor,
-            renderer: MarkdownRenderer,
         }
     }
 
@@ -54,6 +76,34 @@ impl DocxToMarkdown<DocxExtractor, MarkdownRenderer> {
     }
 }
 
+impl<E: AstExtractor> DocxToMarkdown<E, MarkdownRenderer> {
+    /// Converts a DOCX file to one Markdown file per heading of `level` and
+    /// above (`NN-slug.md`), followed by an `index.md` and an mdBook
+    /// `SUMMARY.md` listing them.
+    ///
+    /// Links between the files point at the file holding their target, and
+    /// each file ends with the notes it references.
+    pub fn split<P: AsRef<Path>>(&self, path: P, level: usize) -> Result<Vec<SplitFile>> {
+        self.split_ast(&self.extract(path)?, level)
+    }
+
+    /// Converts a DOCX file from bytes to one Markdown file per heading of
+    /// `level` and above; see [`DocxToMarkdown::split`].
+    pub fn split_from_bytes(&self, bytes: &[u8], level: usize) -> Result<Vec<SplitFile>> {
+        self.split_ast(&self.extract_from_bytes(bytes)?, level)
+    }
+
+    fn split_ast(&self, document: &DocumentAst, level: usize) -> Result<Vec<SplitFile>> {
+        split_document(
+            document,
+            &self.renderer,
+            level,
+            self.options.slug_style(),
+            self.options.anchors.slug_style().is_some(),
+        )
+    }
+}
+
 impl<E, R> DocxToMarkdown<E, R>
 where
     E: AstExtractor,
@@ -95,14 +145,19 @@ where
     /// # Returns
     /// The converted Markdown content as a String.
     pub fn convert<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        self.renderer.render(&self.extract(path)?)
+    }
+
+    /// Converts a DOCX file to its document AST, before rendering.
+    pub fn extract<P: AsRef<Path>>(&self, path: P) -> Result<DocumentAst> {
         let path = path.as_ref();
 
         // Parse DOCX file
+        let raw_parts = RawParts::from_reader(std::fs::File::open(path)?)?;
         let docx_file =
             DocxFile::from_file(path).map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
-        let docx = docx_file
-            .parse()
-            .map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
+        let expanded = raw_parts.document.as_deref().and_then(expand_simple_fields);
+        let docx = parse_docx(&docx_file, expanded.as_deref())?;
 
         // Initialize image extractor based on options
         let mut image_extractor = match &self.options.image_handling {
@@ -111,7 +166,7 @@ where
             ImageHandling::Skip => ImageExtractor::new_skip(),
         };
 
-        self.convert_inner(&docx, &mut image_extractor)
+        self.extract_inner(&docx, &raw_parts, &mut image_extractor)
     }
 
     /// Converts a DOCX file from bytes to Markdown.
@@ -122,12 +177,16 @@ where
     /// # Returns
     /// The converted Markdown content as a String.
     pub fn convert_from_bytes(&self, bytes: &[u8]) -> Result<String> {
-        let reader = std::io::Cursor::new(bytes);
-        let docx_file =
-            DocxFile::from_reader(reader).map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
-        let docx = docx_file
-            .parse()
+        self.renderer.render(&self.extract_from_bytes(bytes)?)
+    }
+
+    /// Converts a DOCX file from bytes to its document AST, before rendering.
+    pub fn extract_from_bytes(&self, bytes: &[u8]) -> Result<DocumentAst> {
+        let raw_parts = RawParts::from_reader(Cursor::new(bytes))?;
+        let docx_file = DocxFile::from_reader(Cursor::new(bytes))
             .map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
+        let expanded = raw_parts.document.as_deref().and_then(expand_simple_fields);
+        let docx = parse_docx(&docx_file, expanded.as_deref())?;
 
         // Initialize image extractor based on options
         let mut image_extractor = match &self.options.image_handling {
@@ -138,19 +197,34 @@ where
             ImageHandling::Skip => ImageExtractor::new_skip(),
         };
 
-        self.convert_inner(&docx, &mut image_extractor)
+        self.extract_inner(&docx, &raw_parts, &mut image_extractor)
     }
 
-    fn convert_inner<'a>(
+    /// Splits a DOCX file into heading-aware chunks rendered with the
+    /// converter's renderer.
+    pub fn chunk<P: AsRef<Path>>(&self, path: P, options: &ChunkOptions) -> Result<Vec<Chunk>> {
+        chunk_document(&self.extract(path)?, &self.renderer, options)
+    }
+
+    /// Splits a DOCX file given as bytes into heading-aware chunks rendered
+    /// with the converter's renderer.
+    pub fn chunk_from_bytes(&self, bytes: &[u8], options: &ChunkOptions) -> Result<Vec<Chunk>> {
+        chunk_document(&self.extract_from_bytes(bytes)?, &self.renderer, options)
+    }
+
+    fn extract_inner<'a>(
         &'a self,
         docx: &'a rs_docx::Docx,
+        raw_parts: &RawParts,
         image_extractor: &'a mut ImageExtractor,
-    ) -> Result<String> {
+    ) -> Result<DocumentAst> {
         // Build relationship map for hyperlinks
         let rels = self.build_relationship_map(docx);
 
         // Initialize numbering resolver
-        let mut numbering_resolver = NumberingResolver::new(docx);
+        let mut numbering_resolver =
+            NumberingResolver::with_numbering_xml(docx, raw_parts.numbering.as_deref())
+                .keep_bullet_glyphs(self.options.keep_bullet_glyphs);
 
         // Initialize style resolver
         let style_resolver = StyleResolver::new(&docx.styles);
@@ -166,10 +240,22 @@ where
             &style_resolver,
         );
 
+        if let Some(xml) = raw_parts.document.as_deref() {
+            context.set_form_controls(FormControls::parse(xml));
+        }
+
+        if self.options.infer_headings {
+            context.analyze_headings(&docx.document.body.content);
+        }
+
         let mut document = self
             .extractor
             .extract(&docx.document.body.content, &mut context)?;
         document.references = context.reference_definitions();
+        document.properties = self.document_properties(docx);
+        if let Some(style) = self.options.anchors.slug_style() {
+            link_headings_by_slug(&mut document, style);
+        }
 
         if self.options.strict_reference_validation {
             let missing = context.take_missing_references();
@@ -178,7 +264,7 @@ where
             }
         }
 
-        self.renderer.render(&document)
+        Ok(document)
     }
 
     #[cfg(test)]
@@ -228,12 +314,54 @@ where
 
         rels
     }
+
+    /// Reads the document properties from `docProps/core.xml`.
+    fn document_properties(&self, docx: &rs_docx::Docx) -> DocumentProperties {
+        let owned = |value: &Option<Cow<str>>| value.as_ref().map(|value| value.to_string());
+        // Both variants have the same fields, with and without namespaces
+        macro_rules! properties {
+            ($core:expr) => {
+                DocumentProperties {
+                    title: owned(&$core.title),
+                    subject: owned(&$core.subject),
+                    author: owned(&$core.creator),
+                    keywords: owned(&$core.keywords),
+                    description: owned(&$core.description),
+                    category: owned(&$core.category),
+                    language: owned(&$core.language),
+                    created: owned(&$core.created),
+                    modified: owned(&$core.modified),
+                }
+            };
+        }
+        match &docx.core {
+            Some(Core::CoreNamespace(core)) => properties!(core),
+            Some(Core::CoreNoNamespace(core)) => properties!(core),
+            None => DocumentProperties::default(),
+        }
+    }
+}
+
+/// Parses a DOCX package. rs-docx drops the simple fields it does not
+/// model, so when the main document has some, it is parsed again from
+/// `expanded`, its XML with the simple fields rewritten as complex fields.
+fn parse_docx<'a>(docx_file: &'a DocxFile, expanded: Option<&'a str>) -> Result<Docx<'a>> {
+    let mut docx = docx_file
+        .parse()
+        .map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
+    if let Some(xml) = expanded {
+        docx.document =
+            Document::from_str(xml).map_err(|e| Error::DocxParse(format!("{:?}", e)))?;
+    }
+    Ok(docx)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::adapters::docx::replace_part;
     use crate::core::ast::{BlockNode, DocumentAst};
+    use crate::TocHandling;
     use rs_docx::document::{
         BodyContent, BookmarkStart, EndNote, EndNotes, FootNote, FootNotes, Paragraph, SDTContent,
         SDT,
@@ -267,8 +395,8 @@ mod tests {
         ) -> Result<DocumentAst> {
             let _ = context.register_footnote_reference(1);
             Ok(DocumentAst {
-                blocks: vec![BlockNode::Paragraph("custom block".to_string())],
-                references: Default::default(),
+                blocks: vec![BlockNode::Paragraph("custom block".into())],
+                ..Default::default()
             })
         }
     }
@@ -452,7 +580,8 @@ mod tests {
         let mut image_extractor = ImageExtractor::new_skip();
 
         let rendered = converter
-            .convert_inner(&docx, &mut image_extractor)
+            .extract_inner(&docx, &RawParts::default(), &mut image_extractor)
+            .and_then(|document| converter.renderer.render(&document))
             .expect("conversion should succeed");
 
         assert_eq!(rendered, "blocks=1;footnotes=1;first=Injected note");
@@ -469,7 +598,7 @@ mod tests {
         let mut image_extractor = ImageExtractor::new_skip();
 
         let err = converter
-            .convert_inner(&docx, &mut image_extractor)
+            .extract_inner(&docx, &RawParts::default(), &mut image_extractor)
             .expect_err("strict validation should fail on missing references");
 
         match err {
@@ -508,7 +637,7 @@ mod tests {
         let mut image_extractor = ImageExtractor::new_skip();
 
         let err = converter
-            .convert_inner(&docx, &mut image_extractor)
+            .extract_inner(&docx, &RawParts::default(), &mut image_extractor)
             .expect_err("strict validation should fail on missing comment");
 
         match err {
@@ -540,7 +669,7 @@ mod tests {
         let mut image_extractor = ImageExtractor::new_skip();
 
         let err = converter
-            .convert_inner(&docx, &mut image_extractor)
+            .extract_inner(&docx, &RawParts::default(), &mut image_extractor)
             .expect_err("strict validation should fail on missing endnote");
 
         match err {
@@ -548,4 +677,71 @@ mod tests {
             other => panic!("unexpected error: {:?}", other),
         }
     }
+
+    #[test]
+    fn test_simple_fields_are_expanded_in_the_parsed_document() {
+        let package = crate::MarkdownToDocx::default()
+            .convert_str("Dear FIELD,\n")
+            .expect("Markdown converts to DOCX");
+        let document = RawParts::from_reader(Cursor::new(&package))
+            .expect("package has readable parts")
+            .document
+            .expect("package has a main document part")
+            .replace(
+                "FIELD",
+                r#"</w:t></w:r><w:fldSimple w:instr=" MERGEFIELD Name "><w:r><w:t>«Name»</w:t></w:r></w:fldSimple><w:r><w:t>"#,
+            );
+        let package = replace_part(
+            Cursor::new(package),
+            "word/document.xml",
+            document.as_bytes(),
+        )
+        .expect("main document part is replaced");
+
+        let markdown = DocxToMarkdown::new(ConvertOptions::default())
+            .convert_from_bytes(&package)
+            .expect("DOCX converts to Markdown");
+        assert_eq!(markdown.trim(), "Dear «Name»,");
+    }
+
+    #[test]
+    fn test_form_fields_after_a_removed_toc_keep_their_own_data() {
+        let checkbox = |checked: &str| {
+            format!(
+                r#"<w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:checkBox><w:default w:val="{}"/></w:checkBox></w:ffData></w:fldChar></w:r><w:r><w:instrText> FORMCHECKBOX </w:instrText></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r>"#,
+                checked
+            )
+        };
+        let package = crate::MarkdownToDocx::default()
+            .convert_str("TOC\n\nAgreed FIELD\n")
+            .expect("markdown converts to a package");
+        let document = RawParts::from_reader(Cursor::new(&package))
+            .expect("package has readable parts")
+            .document
+            .expect("package has a main document")
+            .replacen(
+                "TOC",
+                &format!(
+                    r#"</w:t></w:r><w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText> TOC \o "1-3" </w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r>{}<w:r><w:fldChar w:fldCharType="end"/></w:r><w:r><w:t>"#,
+                    checkbox("1")
+                ),
+                1,
+            )
+            .replace("FIELD", &format!("</w:t></w:r>{}<w:r><w:t>", checkbox("0")));
+        let package = replace_part(
+            Cursor::new(package),
+            "word/document.xml",
+            document.as_bytes(),
+        )
+        .expect("main document is replaced");
+
+        let options = ConvertOptions {
+            toc: TocHandling::Remove,
+            ..ConvertOptions::default()
+        };
+        let markdown = DocxToMarkdown::new(options)
+            .convert_from_bytes(&package)
+            .expect("document converts");
+        assert_eq!(markdown.trim(), "Agreed ☐");
+    }
 }
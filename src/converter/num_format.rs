@@ -0,0 +1,550 @@
+//! Number formats for list markers (`w:numFmt`, ST_NumberFormat).
+
+const LATIN_LOWER: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+/// Russian letters as Word counts them (ё, й, ъ, ы and ь are skipped).
+const RUSSIAN_LOWER: &[char] = &[
+    'а', 'б', 'в', 'г', 'д', 'е', 'ж', 'з', 'и', 'к', 'л', 'м', 'н', 'о', 'п', 'р', 'с', 'т', 'у',
+    'ф', 'х', 'ц', 'ч', 'ш', 'щ', 'э', 'ю', 'я',
+];
+
+const HEBREW_LETTERS: &[char] = &[
+    'א', 'ב', 'ג', 'ד', 'ה', 'ו', 'ז', 'ח', 'ט', 'י', 'כ', 'ל', 'מ', 'נ', 'ס', 'ע', 'פ', 'צ', 'ק',
+    'ר', 'ש', 'ת',
+];
+
+const ARABIC_ALPHA: &[char] = &[
+    'أ', 'ب', 'ت', 'ث', 'ج', 'ح', 'خ', 'د', 'ذ', 'ر', 'ز', 'س', 'ش', 'ص', 'ض', 'ط', 'ظ', 'ع', 'غ',
+    'ف', 'ق', 'ك', 'ل', 'م', 'ن', 'ه', 'و', 'ي',
+];
+
+const ARABIC_ABJAD: &[char] = &[
+    'أ', 'ب', 'ج', 'د', 'ه', 'و', 'ز', 'ح', 'ط', 'ي', 'ك', 'ل', 'م', 'ن', 'س', 'ع', 'ف', 'ص', 'ق',
+    'ر', 'ش', 'ت', 'ث', 'خ', 'ذ', 'ض', 'ظ', 'غ',
+];
+
+/// Thai consonants as Word counts them (the obsolete ฃ and ฅ are skipped).
+const THAI_LETTERS: &[char] = &[
+    'ก', 'ข', 'ค', 'ง', 'จ', 'ฉ', 'ช', 'ซ', 'ฌ', 'ญ', 'ฎ', 'ฏ', 'ฐ', 'ฑ', 'ฒ', 'ณ', 'ด', 'ต', 'ถ',
+    'ท', 'ธ', 'น', 'บ', 'ป', 'ผ', 'ฝ', 'พ', 'ฟ', 'ภ', 'ม', 'ย', 'ร', 'ล', 'ว', 'ศ', 'ษ', 'ส', 'ห',
+    'ฬ', 'อ', 'ฮ',
+];
+
+const AIUEO_FULL_WIDTH: &[char] = &[
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ヲ', 'ン',
+];
+
+const AIUEO_HALF_WIDTH: &[char] = &[
+    'ｱ', 'ｲ', 'ｳ', 'ｴ', 'ｵ', 'ｶ', 'ｷ', 'ｸ', 'ｹ', 'ｺ', 'ｻ', 'ｼ', 'ｽ', 'ｾ', 'ｿ', 'ﾀ', 'ﾁ', 'ﾂ', 'ﾃ',
+    'ﾄ', 'ﾅ', 'ﾆ', 'ﾇ', 'ﾈ', 'ﾉ', 'ﾊ', 'ﾋ', 'ﾌ', 'ﾍ', 'ﾎ', 'ﾏ', 'ﾐ', 'ﾑ', 'ﾒ', 'ﾓ', 'ﾔ', 'ﾕ', 'ﾖ',
+    'ﾗ', 'ﾘ', 'ﾙ', 'ﾚ', 'ﾛ', 'ﾜ', 'ｦ', 'ﾝ',
+];
+
+const IROHA_FULL_WIDTH: &[char] = &[
+    'イ', 'ロ', 'ハ', 'ニ', 'ホ', 'ヘ', 'ト', 'チ', 'リ', 'ヌ', 'ル', 'ヲ', 'ワ', 'カ', 'ヨ', 'タ',
+    'レ', 'ソ', 'ツ', 'ネ', 'ナ', 'ラ', 'ム', 'ウ', 'ヰ', 'ノ', 'オ', 'ク', 'ヤ', 'マ', 'ケ', 'フ',
+    'コ', 'エ', 'テ', 'ア', 'サ', 'キ', 'ユ', 'メ', 'ミ', 'シ', 'ヱ', 'ヒ', 'モ', 'セ', 'ス',
+];
+
+/// Half-width iroha; ヰ and ヱ have no half-width forms, so Word uses ｲ and ｴ.
+const IROHA_HALF_WIDTH: &[char] = &[
+    'ｲ', 'ﾛ', 'ﾊ', 'ﾆ', 'ﾎ', 'ﾍ', 'ﾄ', 'ﾁ', 'ﾘ', 'ﾇ', 'ﾙ', 'ｦ', 'ﾜ', 'ｶ', 'ﾖ', 'ﾀ', 'ﾚ', 'ｿ', 'ﾂ',
+    'ﾈ', 'ﾅ', 'ﾗ', 'ﾑ', 'ｳ', 'ｲ', 'ﾉ', 'ｵ', 'ｸ', 'ﾔ', 'ﾏ', 'ｹ', 'ﾌ', 'ｺ', 'ｴ', 'ﾃ', 'ｱ', 'ｻ', 'ｷ',
+    'ﾕ', 'ﾒ', 'ﾐ', 'ｼ', 'ｴ', 'ﾋ', 'ﾓ', 'ｾ', 'ｽ',
+];
+
+/// Heavenly stems (甲, 乙, 丙...).
+const HEAVENLY_STEMS: &[char] = &['甲', '乙', '丙', '丁', '戊', '己', '庚', '辛', '壬', '癸'];
+
+/// Earthly branches (子, 丑, 寅...).
+const EARTHLY_BRANCHES: &[char] = &[
+    '子', '丑', '寅', '卯', '辰', '巳', '午', '未', '申', '酉', '戌', '亥',
+];
+
+/// Digits and unit characters for CJK counting systems.
+struct CjkCounting {
+    digits: [char; 10],
+    /// Units for tens, hundreds and thousands.
+    units: [char; 3],
+    ten_thousand: char,
+    /// Marks skipped positions ("一百〇一"); `None` drops them ("百一").
+    zero: Option<char>,
+    /// Drops "一" before every unit ("百十一" rather than "一百一十一").
+    omit_one: bool,
+    /// Drops "一" before a leading ten ("十一" rather than "一十一").
+    short_teens: bool,
+}
+
+const JAPANESE_COUNTING: CjkCounting = CjkCounting {
+    digits: ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'],
+    units: ['十', '百', '千'],
+    ten_thousand: '万',
+    zero: None,
+    omit_one: true,
+    short_teens: true,
+};
+
+const CHINESE_COUNTING: CjkCounting = CjkCounting {
+    digits: ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'],
+    units: ['十', '百', '千'],
+    ten_thousand: '万',
+    zero: Some('〇'),
+    omit_one: false,
+    short_teens: true,
+};
+
+const CHINESE_LEGAL_SIMPLIFIED: CjkCounting = CjkCounting {
+    digits: ['零', '壹', '贰', '叁', '肆', '伍', '陆', '柒', '捌', '玖'],
+    units: ['拾', '佰', '仟'],
+    ten_thousand: '万',
+    zero: Some('零'),
+    omit_one: false,
+    short_teens: false,
+};
+
+/// Formats a number according to a `w:numFmt` value. Unknown formats and
+/// values a format cannot express fall back to decimal.
+pub(crate) fn format_num(fmt: &str, val: i32) -> String {
+    match fmt {
+        "bullet" | "none" => "-".to_string(),
+        "decimal" => format!("{}", val),
+        "decimalZero" => {
+            if (0..10).contains(&val) {
+                format!("0{}", val)
+            } else {
+                format!("{}", val)
+            }
+        }
+        "decimalFullWidth" | "decimalFullWidth2" => map_digits(val, '０'),
+        "hindiNumbers" => map_digits(val, '٠'),
+        "thaiNumbers" => map_digits(val, '๐'),
+        "decimalEnclosedCircle" | "decimalEnclosedCircleChinese" => format_circle_number(val),
+        "decimalEnclosedParen" => enclosed(val, '\u{2474}'),
+        "decimalEnclosedFullstop" => enclosed(val, '\u{2488}'),
+        "lowerLetter" => repeat_letter(val, LATIN_LOWER),
+        "upperLetter" => repeat_letter(val, LATIN_LOWER).to_uppercase(),
+        "russianLower" => repeat_letter(val, RUSSIAN_LOWER),
+        "russianUpper" => repeat_letter(val, RUSSIAN_LOWER).to_uppercase(),
+        "hebrew1" => format_hebrew_numeral(val),
+        "hebrew2" => repeat_letter(val, HEBREW_LETTERS),
+        "arabicAlpha" => repeat_letter(val, ARABIC_ALPHA),
+        "arabicAbjad" => repeat_letter(val, ARABIC_ABJAD),
+        "thaiLetters" => repeat_letter(val, THAI_LETTERS),
+        "aiueo" => repeat_letter(val, AIUEO_HALF_WIDTH),
+        "aiueoFullWidth" => repeat_letter(val, AIUEO_FULL_WIDTH),
+        "iroha" => repeat_letter(val, IROHA_HALF_WIDTH),
+        "irohaFullWidth" => repeat_letter(val, IROHA_FULL_WIDTH),
+        "lowerRoman" => to_roman(val).to_lowercase(),
+        "upperRoman" => to_roman(val),
+        "ordinal" => format_ordinal(val),
+        "cardinalText" => capitalize(&english_cardinal(val)),
+        "ordinalText" => capitalize(&english_ordinal(val)),
+        "japaneseCounting" => cjk_counting(val, &JAPANESE_COUNTING),
+        "chineseCounting" | "chineseCountingThousand" => cjk_counting(val, &CHINESE_COUNTING),
+        "chineseLegalSimplified" => cjk_counting(val, &CHINESE_LEGAL_SIMPLIFIED),
+        "ideographTraditional" => cycle(val, HEAVENLY_STEMS),
+        "ideographZodiac" => cycle(val, EARTHLY_BRANCHES),
+        "ideographZodiacTraditional" => {
+            if val >= 1 {
+                let index = (val - 1) as usize;
+                format!(
+                    "{}{}",
+                    HEAVENLY_STEMS[index % HEAVENLY_STEMS.len()],
+                    EARTHLY_BRANCHES[index % EARTHLY_BRANCHES.len()]
+                )
+            } else {
+                format!("{}", val)
+            }
+        }
+        "koreanCounting" | "korean" | "ganada" => format_ganada(val),
+        "chosung" => format_chosung(val),
+        "geonodeo" => format_geonodeo(val),
+        _ => format!("{}", val),
+    }
+}
+
+/// Letter sequence that repeats the letter once the alphabet runs out
+/// (a..z, aa..zz, aaa...), as Word does.
+fn repeat_letter(val: i32, letters: &[char]) -> String {
+    if val < 1 {
+        return format!("{}", val);
+    }
+    let index = (val - 1) as usize;
+    letters[index % letters.len()]
+        .to_string()
+        .repeat(index / letters.len() + 1)
+}
+
+/// Symbol sequence that starts over once exhausted.
+fn cycle(val: i32, symbols: &[char]) -> String {
+    if val < 1 {
+        return format!("{}", val);
+    }
+    symbols[(val - 1) as usize % symbols.len()].to_string()
+}
+
+/// Decimal number written with another script's digits, given its zero.
+fn map_digits(val: i32, zero: char) -> String {
+    val.to_string()
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(d) => char::from_u32(zero as u32 + d).unwrap_or(c),
+            None => c,
+        })
+        .collect()
+}
+
+/// Enclosed numbers 1-20 (⑴, ⒈...), starting at the character for 1.
+fn enclosed(val: i32, first: char) -> String {
+    if (1..=20).contains(&val) {
+        char::from_u32(first as u32 + (val - 1) as u32)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| format!("{}", val))
+    } else {
+        format!("{}", val)
+    }
+}
+
+/// Converts a number to circled number (①②③...).
+fn format_circle_number(val: i32) -> String {
+    // Unicode circled numbers: ① = U+2460, ② = U+2461, ... ⑳ = U+2473
+    // Extended: ㉑ = U+3251, ㉒ = U+3252, ... ㊿ = U+32BF (21-50)
+    if (1..=20).contains(&val) {
+        char::from_u32(0x245F + val as u32)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| format!("{}", val))
+    } else if (21..=50).contains(&val) {
+        char::from_u32(0x3250 + (val - 20) as u32)
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| format!("{}", val))
+    } else {
+        format!("{}", val) // Fallback for numbers outside supported range
+    }
+}
+
+/// Converts a number to Korean Ganada (가, 나, 다...).
+fn format_ganada(val: i32) -> String {
+    let chars = [
+        '가', '나', '다', '라', '마', '바', '사', '아', '자', '차', '카', '타', '파', '하',
+    ];
+    if val >= 1 && val as usize <= chars.len() {
+        chars[(val - 1) as usize].to_string()
+    } else {
+        format!("{}", val) // Fallback
+    }
+}
+
+/// Converts a number to Korean Geonodeo (거, 너, 더...).
+fn format_geonodeo(val: i32) -> String {
+    let chars = [
+        '거', '너', '더', '러', '머', '버', '서', '어', '저', '처', '커', '터', '퍼', '허',
+    ];
+    if val >= 1 && val as usize <= chars.len() {
+        chars[(val - 1) as usize].to_string()
+    } else {
+        format!("{}", val)
+    }
+}
+
+/// Converts a number to Korean Chosung (ㄱ, ㄴ, ㄷ...).
+fn format_chosung(val: i32) -> String {
+    let chars = [
+        'ㄱ', 'ㄴ', 'ㄷ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅅ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+    ];
+    if val >= 1 && val as usize <= chars.len() {
+        chars[(val - 1) as usize].to_string()
+    } else {
+        format!("{}", val) // Fallback
+    }
+}
+
+/// Converts a number to Roman numeral.
+fn to_roman(mut num: i32) -> String {
+    const ROMAN_TABLE: &[(i32, &str)] = &[
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    if num <= 0 {
+        return num.to_string();
+    }
+    let mut result = String::new();
+    for &(v, s) in ROMAN_TABLE {
+        while num >= v {
+            result.push_str(s);
+            num -= v;
+        }
+    }
+    result
+}
+
+/// Converts a number to Hebrew numerals (א, ב... י, יא... ק), with the
+/// conventional טו/טז for 15 and 16.
+fn format_hebrew_numeral(val: i32) -> String {
+    const HUNDREDS: &[(i32, char)] = &[(400, 'ת'), (300, 'ש'), (200, 'ר'), (100, 'ק')];
+    const TENS: &[char] = &['י', 'כ', 'ל', 'מ', 'נ', 'ס', 'ע', 'פ', 'צ'];
+    const ONES: &[char] = &['א', 'ב', 'ג', 'ד', 'ה', 'ו', 'ז', 'ח', 'ט'];
+
+    if val < 1 {
+        return format!("{}", val);
+    }
+    let mut rest = val;
+    let mut result = String::new();
+    for &(value, letter) in HUNDREDS {
+        while rest >= value {
+            result.push(letter);
+            rest -= value;
+        }
+    }
+    match rest {
+        15 => result.push_str("טו"),
+        16 => result.push_str("טז"),
+        _ => {
+            if rest >= 10 {
+                result.push(TENS[(rest / 10 - 1) as usize]);
+            }
+            if rest % 10 > 0 {
+                result.push(ONES[(rest % 10 - 1) as usize]);
+            }
+        }
+    }
+    result
+}
+
+/// Converts a number to an English ordinal (1st, 2nd, 3rd, 11th...).
+fn format_ordinal(val: i32) -> String {
+    let suffix = match (val % 100, val % 10) {
+        (11..=13, _) => "th",
+        (_, 1) => "st",
+        (_, 2) => "nd",
+        (_, 3) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", val, suffix)
+}
+
+/// Spells out a number in English ("twenty-one").
+fn english_cardinal(val: i32) -> String {
+    const ONES: &[&str] = &[
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: &[&str] = &[
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    if val < 0 {
+        return format!("{}", val);
+    }
+    let n = val as usize;
+    match n {
+        0..=19 => ONES[n].to_string(),
+        20..=99 if n % 10 == 0 => TENS[n / 10].to_string(),
+        20..=99 => format!("{}-{}", TENS[n / 10], ONES[n % 10]),
+        100..=999 => join_scale(n / 100, "hundred", n % 100),
+        1_000..=999_999 => join_scale(n / 1_000, "thousand", n % 1_000),
+        _ => join_scale(n / 1_000_000, "million", n % 1_000_000),
+    }
+}
+
+fn join_scale(count: usize, scale: &str, rest: usize) -> String {
+    let head = format!("{} {}", english_cardinal(count as i32), scale);
+    if rest == 0 {
+        head
+    } else {
+        format!("{} {}", head, english_cardinal(rest as i32))
+    }
+}
+
+/// Spells out an English ordinal ("twenty-first").
+fn english_ordinal(val: i32) -> String {
+    let cardinal = english_cardinal(val);
+    let split = cardinal.rfind(['-', ' ']).map(|i| i + 1).unwrap_or(0);
+    let (head, last) = cardinal.split_at(split);
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        word if word.ends_with('y') => format!("{}ieth", &word[..word.len() - 1]),
+        word => format!("{}th", word),
+    };
+    format!("{}{}", head, last)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Writes a number with CJK digits and units, grouping by 万 (10^4).
+fn cjk_counting(val: i32, system: &CjkCounting) -> String {
+    if val < 1 {
+        return format!("{}", val);
+    }
+    let high = val / 10_000;
+    let low = val % 10_000;
+    let mut result = String::new();
+    if high > 0 {
+        result.push_str(&cjk_counting(high, system));
+        result.push(system.ten_thousand);
+        if low > 0 && low < 1_000 {
+            if let Some(zero) = system.zero {
+                result.push(zero);
+            }
+        }
+    }
+    if low > 0 {
+        result.push_str(&cjk_section(low, system, high == 0));
+    }
+    result
+}
+
+/// Writes 1..=9999 with CJK digits and units.
+fn cjk_section(val: i32, system: &CjkCounting, leading: bool) -> String {
+    let digits = [val / 1_000, val / 100 % 10, val / 10 % 10, val % 10];
+    let mut result = String::new();
+    let mut pending_zero = false;
+    for (position, &digit) in digits.iter().enumerate() {
+        let unit = 3 - position;
+        if digit == 0 {
+            pending_zero = !result.is_empty();
+            continue;
+        }
+        if pending_zero {
+            if let Some(zero) = system.zero {
+                result.push(zero);
+            }
+            pending_zero = false;
+        }
+        let is_first = result.is_empty() && leading;
+        let skip_one = digit == 1
+            && unit > 0
+            && (system.omit_one || (system.short_teens && unit == 1 && is_first));
+        if !skip_one {
+            result.push(system.digits[digit as usize]);
+        }
+        if unit > 0 {
+            result.push(system.units[unit - 1]);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters_repeat_after_alphabet() {
+        assert_eq!(format_num("lowerLetter", 1), "a");
+        assert_eq!(format_num("lowerLetter", 26), "z");
+        assert_eq!(format_num("lowerLetter", 27), "aa");
+        assert_eq!(format_num("upperLetter", 54), "BBB");
+        assert_eq!(format_num("russianLower", 29), "аа");
+        assert_eq!(format_num("russianUpper", 3), "В");
+    }
+
+    #[test]
+    fn test_decimal_variants() {
+        assert_eq!(format_num("decimalZero", 7), "07");
+        assert_eq!(format_num("decimalZero", 12), "12");
+        assert_eq!(format_num("decimalFullWidth", 12), "１２");
+        assert_eq!(format_num("hindiNumbers", 30), "٣٠");
+        assert_eq!(format_num("decimalEnclosedParen", 3), "⑶");
+        assert_eq!(format_num("decimalEnclosedFullstop", 20), "⒛");
+        assert_eq!(format_num("decimalEnclosedParen", 21), "21");
+    }
+
+    #[test]
+    fn test_english_text_formats() {
+        assert_eq!(format_num("ordinal", 1), "1st");
+        assert_eq!(format_num("ordinal", 12), "12th");
+        assert_eq!(format_num("ordinal", 23), "23rd");
+        assert_eq!(format_num("cardinalText", 21), "Twenty-one");
+        assert_eq!(format_num("cardinalText", 105), "One hundred five");
+        assert_eq!(format_num("ordinalText", 1), "First");
+        assert_eq!(format_num("ordinalText", 20), "Twentieth");
+        assert_eq!(format_num("ordinalText", 42), "Forty-second");
+    }
+
+    #[test]
+    fn test_cjk_counting() {
+        assert_eq!(format_num("japaneseCounting", 10), "十");
+        assert_eq!(format_num("japaneseCounting", 111), "百十一");
+        assert_eq!(format_num("japaneseCounting", 2_305), "二千三百五");
+        assert_eq!(format_num("chineseCounting", 10), "十");
+        assert_eq!(format_num("chineseCounting", 110), "一百一十");
+        assert_eq!(format_num("chineseCounting", 101), "一百〇一");
+        assert_eq!(format_num("chineseLegalSimplified", 25), "贰拾伍");
+        assert_eq!(format_num("chineseLegalSimplified", 10_001), "壹万零壹");
+    }
+
+    #[test]
+    fn test_ideograph_cycles() {
+        assert_eq!(format_num("ideographTraditional", 1), "甲");
+        assert_eq!(format_num("ideographTraditional", 11), "甲");
+        assert_eq!(format_num("ideographZodiac", 12), "亥");
+        assert_eq!(format_num("ideographZodiacTraditional", 11), "甲戌");
+    }
+
+    #[test]
+    fn test_alphabetic_scripts() {
+        assert_eq!(format_num("aiueoFullWidth", 6), "カ");
+        assert_eq!(format_num("aiueo", 1), "ｱ");
+        assert_eq!(format_num("irohaFullWidth", 3), "ハ");
+        assert_eq!(format_num("iroha", 2), "ﾛ");
+        assert_eq!(format_num("hebrew1", 15), "טו");
+        assert_eq!(format_num("hebrew1", 123), "קכג");
+        assert_eq!(format_num("hebrew2", 23), "אא");
+        assert_eq!(format_num("arabicAlpha", 2), "ب");
+        assert_eq!(format_num("arabicAbjad", 3), "ج");
+        assert_eq!(format_num("thaiLetters", 3), "ค");
+    }
+
+    #[test]
+    fn test_unknown_format_falls_back_to_decimal() {
+        assert_eq!(format_num("someFutureFormat", 4), "4");
+        assert_eq!(format_num("lowerRoman", 14), "xiv");
+    }
+}
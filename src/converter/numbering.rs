@@ -1,11 +1,15 @@
 //! Numbering resolver - handles list numbering and indentation.
 
+use super::num_format::format_num;
+use super::numbering_xml::{LevelExtras, LevelSuffix, NumberingXml};
+use super::symbol_fonts::{is_symbol_font, map_symbol_text};
+use rs_docx::document::Level;
 use rs_docx::Docx;
 use std::collections::HashMap;
 
 /// Resolver for DOCX numbering definitions.
 pub struct NumberingResolver<'a> {
-    /// Maps numId -> abstractNumId
+    /// Maps numId -> abstractNumId (after following `w:numStyleLink`)
     num_instances: HashMap<i32, i32>,
     /// Maps abstractNumId -> level definitions
     abstract_nums: HashMap<i32, Vec<LevelDef>>,
@@ -14,73 +18,113 @@ pub struct NumberingResolver<'a> {
     /// Maps abstractNumId -> current counters (one counter per level 0..9)
     /// Using abstractNumId allows continuous numbering even if numId changes (e.g. broken lists)
     counters: HashMap<i32, Vec<i32>>,
-    /// Maps abstractNumId -> base indentation level (shift)
-    /// Used to normalize indentation for lists that start at high levels (e.g., Article at Level 4)
-    level_shifts: HashMap<i32, i32>,
     /// Maps (numId, ilvl) -> override LevelDef (style change)
     style_overrides: HashMap<(i32, i32), LevelDef>,
+    /// Whether bullets keep their symbol-font glyphs instead of Unicode
+    keep_bullet_glyphs: bool,
     _phantom: std::marker::PhantomData<&'a ()>,
 }
 
+/// Marker of a list item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListMarker {
+    /// Label as Word displays it (e.g. "1.", "(a)", "제3조").
+    pub label: String,
+    /// Counter value; `None` for bullets.
+    pub number: Option<i32>,
+    /// Nesting depth (see [`NumberingResolver::get_indent`]).
+    pub level: usize,
+    /// Text between the label and the paragraph text.
+    pub separator: &'static str,
+    /// Left indent of the item text in twips, when the level defines one.
+    pub indent: Option<i32>,
+    /// Task state from a check box at the start of the paragraph.
+    pub checked: Option<bool>,
+}
+
 #[derive(Clone, Debug)]
 struct LevelDef {
     ilvl: i32,
     start: i32,
     num_fmt: String,
     lvl_text: Option<String>,
+    /// Left indentation in twips from the level's `w:pPr`
+    indent: Option<i32>,
+    /// Font of the level text from the level's `w:rPr`
+    font: Option<String>,
+    extras: LevelExtras,
+}
+
+impl LevelDef {
+    fn from_level(level: &Level<'_>, ilvl: i32, extras: Option<&LevelExtras>) -> Self {
+        let start = level
+            .start
+            .as_ref()
+            .and_then(|s| s.value)
+            .map(|v| v as i32)
+            .unwrap_or(1);
+        let num_fmt = level
+            .number_format
+            .as_ref()
+            .map(|f| f.value.to_string())
+            .unwrap_or_else(|| "decimal".to_string());
+        let lvl_text = level
+            .level_text
+            .as_ref()
+            .and_then(|t| t.value.as_ref())
+            .map(|v| v.to_string());
+        let indent = level
+            .p_pr
+            .as_ref()
+            .and_then(|p| p.indent.as_ref())
+            .and_then(|ind| ind.left.or(ind.start))
+            .map(|v| v as i32);
+        let font = level
+            .r_pr
+            .iter()
+            .find_map(|r_pr| r_pr.fonts.as_ref())
+            .and_then(|fonts| fonts.ascii.clone().or_else(|| fonts.h_ansi.clone()));
+
+        Self {
+            ilvl,
+            start,
+            num_fmt,
+            lvl_text,
+            indent,
+            font,
+            extras: extras.cloned().unwrap_or_default(),
+        }
+    }
 }
 
 impl<'a> NumberingResolver<'a> {
     /// Creates a new numbering resolver from a parsed DOCX.
     pub fn new(docx: &'a Docx) -> Self {
+        Self::with_numbering_xml(docx, None)
+    }
+
+    /// Creates a numbering resolver that also reads the raw
+    /// `word/numbering.xml` for properties rs-docx does not model
+    /// (`w:lvlRestart`, `w:isLgl`, `w:pStyle`, `w:suff`, `w:numStyleLink`).
+    pub fn with_numbering_xml(docx: &'a Docx, numbering_xml: Option<&str>) -> Self {
+        let extras = numbering_xml.map(NumberingXml::parse).unwrap_or_default();
         let mut num_instances = HashMap::new();
         let mut abstract_nums = HashMap::new();
         let mut overrides = HashMap::new();
         let mut style_overrides = HashMap::new();
-        let mut level_shifts = HashMap::new();
 
         if let Some(numbering) = &docx.numbering {
             // Parse abstract numbering definitions
             for abs_num in &numbering.abstract_numberings {
                 let abs_id = abs_num.abstract_num_id.map(|id| id as i32).unwrap_or(0);
-                let mut levels = Vec::new();
-
-                for lvl in &abs_num.levels {
-                    let ilvl = lvl.i_level.map(|i| i as i32).unwrap_or(0);
-                    let start = lvl
-                        .start
-                        .as_ref()
-                        .and_then(|s| s.value)
-                        .map(|v| v as i32)
-                        .unwrap_or(1);
-                    let num_fmt = lvl
-                        .number_format
-                        .as_ref()
-                        .map(|f| f.value.to_string())
-                        .unwrap_or_else(|| "decimal".to_string());
-                    let lvl_text = lvl
-                        .level_text
-                        .as_ref()
-                        .and_then(|t| t.value.as_ref())
-                        .map(|v| v.to_string());
-
-                    // Heuristic: If this level looks like an "Article" heading (제%1조),
-                    // treat it as a base level (Level 0 equivalent) for indentation.
-                    if let Some(text) = &lvl_text {
-                        if text.contains("제") && text.contains("조") && text.contains("%") {
-                            // Only set if not already set (prefer higher levels if multiple? No, prefer shallowest)
-                            // But Article usually wraps everything.
-                            level_shifts.entry(abs_id).or_insert(ilvl);
-                        }
-                    }
-
-                    levels.push(LevelDef {
-                        ilvl,
-                        start,
-                        num_fmt,
-                        lvl_text,
-                    });
-                }
+                let mut levels: Vec<LevelDef> = abs_num
+                    .levels
+                    .iter()
+                    .map(|lvl| {
+                        let ilvl = lvl.i_level.map(|i| i as i32).unwrap_or(0);
+                        LevelDef::from_level(lvl, ilvl, extras.levels.get(&(abs_id, ilvl)))
+                    })
+                    .collect();
 
                 levels.sort_by_key(|l| l.ilvl);
                 abstract_nums.insert(abs_id, levels);
@@ -109,36 +153,13 @@ impl<'a> NumberingResolver<'a> {
                             (override_def.i_level, &override_def.level)
                         {
                             let ilvl = ilvl as i32;
-                            let start = level
-                                .start
-                                .as_ref()
-                                .and_then(|s| s.value)
-                                .map(|v| v as i32)
-                                .unwrap_or(1); // Default to 1 if not specified in override? Or inherit?
-                                               // In strict XML, if start not present in override, it might inherit.
-                                               // But here we construct a full LevelDef.
-                                               // Ideally we should merge with abstract level.
-                                               // For now, let's take what's in the override or default.
-
-                            let num_fmt = level
-                                .number_format
-                                .as_ref()
-                                .map(|f| f.value.to_string())
-                                .unwrap_or_else(|| "decimal".to_string());
-                            let lvl_text = level
-                                .level_text
-                                .as_ref()
-                                .and_then(|t| t.value.as_ref())
-                                .map(|v| v.to_string());
-
                             style_overrides.insert(
                                 (nid, ilvl),
-                                LevelDef {
+                                LevelDef::from_level(
+                                    level,
                                     ilvl,
-                                    start,
-                                    num_fmt,
-                                    lvl_text,
-                                },
+                                    extras.override_levels.get(&(nid, ilvl)),
+                                ),
                             );
                         }
                     }
@@ -146,58 +167,168 @@ impl<'a> NumberingResolver<'a> {
             }
         }
 
+        // An abstract numbering with w:numStyleLink takes its levels from the
+        // numbering style it names: the style's numId leads to the abstract
+        // numbering that defines the levels (and carries w:styleLink).
+        let linked: Vec<(i32, i32)> = num_instances
+            .iter()
+            .filter_map(|(&num_id, abs_id)| {
+                let style_id = extras.num_style_links.get(abs_id)?;
+                let target = docx
+                    .styles
+                    .styles
+                    .iter()
+                    .find(|style| style.style_id == style_id.as_str())
+                    .and_then(|style| style.paragraph.as_ref())
+                    .and_then(|p| p.numbering.as_ref())
+                    .and_then(|n| n.id.as_ref())
+                    .and_then(|id| num_instances.get(&(id.value as i32)))
+                    .copied()
+                    .filter(|target| !extras.num_style_links.contains_key(target))
+                    .or_else(|| {
+                        extras
+                            .style_links
+                            .iter()
+                            .find(|(_, link)| *link == style_id)
+                            .map(|(&id, _)| id)
+                    })?;
+                Some((num_id, target))
+            })
+            .collect();
+        num_instances.extend(linked);
+
         Self {
             num_instances,
             abstract_nums,
             overrides,
             style_overrides,
             counters: HashMap::new(),
-            level_shifts,
+            keep_bullet_glyphs: false,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    /// Keeps symbol-font bullet glyphs (e.g. U+F0B7 in Symbol) and picture
+    /// bullets' fallback text as they are, instead of mapping them to Unicode.
+    pub fn keep_bullet_glyphs(mut self, keep: bool) -> Self {
+        self.keep_bullet_glyphs = keep;
+        self
+    }
+
+    /// Finds the definition of a level, preferring the instance's override.
+    fn level_def(&self, num_id: i32, ilvl: i32) -> Option<&LevelDef> {
+        self.style_overrides.get(&(num_id, ilvl)).or_else(|| {
+            let abs_id = self.num_instances.get(&num_id)?;
+            self.abstract_nums
+                .get(abs_id)?
+                .iter()
+                .find(|l| l.ilvl == ilvl)
+        })
+    }
+
     /// Gets the indentation level for a list item.
+    ///
+    /// When the level defines a left indent, the depth is the number of
+    /// distinct smaller indents among the levels above it, so lists whose
+    /// first used level sits at the margin (e.g. articles at level 4) start
+    /// flush. Otherwise the depth is `ilvl`.
     pub fn get_indent(&self, num_id: i32, ilvl: i32) -> usize {
-        let mut indent = ilvl;
+        let Some(own) = self.level_def(num_id, ilvl).and_then(|l| l.indent) else {
+            return ilvl.max(0) as usize;
+        };
 
-        // Apply shift if exists for this abstract numbering
-        if let Some(&abs_id) = self.num_instances.get(&num_id) {
-            if let Some(&base_level) = self.level_shifts.get(&abs_id) {
-                indent = indent.saturating_sub(base_level);
-            }
-        }
+        let mut smaller: Vec<i32> = (0..ilvl)
+            .filter_map(|i| self.level_def(num_id, i).and_then(|l| l.indent))
+            .filter(|&indent| indent < own)
+            .collect();
+        smaller.sort_unstable();
+        smaller.dedup();
+        smaller.len()
+    }
 
-        indent as usize
+    /// Finds the level of a numbering linked to a paragraph style (`w:pStyle`).
+    pub fn level_for_style(&self, num_id: i32, style_id: &str) -> Option<i32> {
+        let abs_id = self.num_instances.get(&num_id)?;
+        self.abstract_nums
+            .get(abs_id)?
+            .iter()
+            .find(|l| l.extras.p_style.as_deref() == Some(style_id))
+            .map(|l| l.ilvl)
+    }
+
+    /// Gets the text between a list marker and the paragraph text (`w:suff`).
+    /// Tabs become a single space.
+    pub fn marker_separator(&self, num_id: i32, ilvl: i32) -> &'static str {
+        match self.level_def(num_id, ilvl).map(|l| l.extras.suffix) {
+            Some(LevelSuffix::Nothing) => "",
+            _ => " ",
+        }
     }
 
     /// Gets the marker for a list item (e.g., "1.", "-", "a)").
     /// Updates the internal counter state.
     pub fn next_marker(&mut self, num_id: i32, ilvl: i32) -> String {
+        self.next_list_marker(num_id, ilvl).label
+    }
+
+    /// Gets the marker of the next list item with its counter value, depth
+    /// and separator. Updates the internal counter state.
+    pub fn next_list_marker(&mut self, num_id: i32, ilvl: i32) -> ListMarker {
+        let depth = self.get_indent(num_id, ilvl);
+        let separator = self.marker_separator(num_id, ilvl);
+        let indent = self.level_def(num_id, ilvl).and_then(|l| l.indent);
+        let bullet = |label: &str| ListMarker {
+            label: label.to_string(),
+            number: None,
+            level: depth,
+            separator,
+            indent,
+            checked: None,
+        };
+
         let Some(&abs_id) = self.num_instances.get(&num_id) else {
-            return "-".to_string();
+            return bullet("-");
         };
 
         let Some(levels) = self.abstract_nums.get(&abs_id) else {
-            return "-".to_string();
+            return bullet("-");
         };
 
-        // Initialize counters for this abstract_num_id if not present
-        // Use abstract_id as key to share state across different num_ids for same style
-        let counters = self.counters.entry(abs_id).or_insert_with(|| vec![0; 10]);
-
         // Find level definition
         // Check for style override first
         let level_def = self
-            .style_overrides
-            .get(&(num_id, ilvl))
-            .or_else(|| levels.iter().find(|l| l.ilvl == ilvl))
-            .or_else(|| levels.first());
+            .level_def(num_id, ilvl)
+            .or_else(|| levels.first())
+            .cloned();
 
         let Some(level) = level_def else {
-            return "-".to_string();
+            return bullet("-");
         };
 
+        // w:lvlRestart of each lower level: None restarts after any higher
+        // level, Some(0) never restarts, Some(n) restarts after levels 1..=n.
+        let restarts: Vec<Option<i32>> = (0..10)
+            .map(|i| self.level_def(num_id, i).and_then(|l| l.extras.restart))
+            .collect();
+        // Number formats of every level, as shown in this level's text
+        let formats: Vec<String> = (0..10)
+            .map(|i| {
+                if level.extras.is_legal {
+                    "decimal".to_string()
+                } else {
+                    self.level_def(num_id, i)
+                        .map(|l| l.num_fmt.clone())
+                        .unwrap_or_else(|| "decimal".to_string())
+                }
+            })
+            .collect();
+
+        let keep_bullet_glyphs = self.keep_bullet_glyphs;
+
+        // Initialize counters for this abstract_num_id if not present
+        // Use abstract_id as key to share state across different num_ids for same style
+        let counters = self.counters.entry(abs_id).or_insert_with(|| vec![0; 10]);
+
         // Increment current level
         let ilvl_idx = ilvl as usize;
         if counters.len() <= ilvl_idx {
@@ -217,162 +348,62 @@ impl<'a> NumberingResolver<'a> {
         }
 
         // Reset lower levels
-        for counter in counters.iter_mut().skip(ilvl_idx + 1) {
-            *counter = 0;
+        for (i, counter) in counters.iter_mut().enumerate().skip(ilvl_idx + 1) {
+            match restarts.get(i).copied().flatten() {
+                Some(0) => {}
+                Some(restart_after) if ilvl >= restart_after => {}
+                _ => *counter = 0,
+            }
         }
 
+        let number = match level.num_fmt.as_str() {
+            "bullet" | "none" => None,
+            _ => Some(counters[ilvl_idx]),
+        };
+        let marker = |label: String| ListMarker {
+            label,
+            number,
+            level: depth,
+            separator,
+            indent,
+            checked: None,
+        };
+
         // Use level text if available (substituting placeholders)
         if let Some(text) = &level.lvl_text {
-            let mut marker = text.clone();
+            let mut label = text.clone();
             // Replace %1, %2, etc. with formatted numbers
             for (i, count) in counters.iter().enumerate() {
                 let level_num = i + 1; // %1 is index 0
                 let placeholder = format!("%{}", level_num);
-                if marker.contains(&placeholder) {
-                    // Find formatting for this level
-                    let fmt = self
-                        .style_overrides
-                        .get(&(num_id, i as i32))
-                        .map(|l| l.num_fmt.as_str())
-                        .or_else(|| {
-                            levels
-                                .iter()
-                                .find(|l| l.ilvl == i as i32)
-                                .map(|l| l.num_fmt.as_str())
-                        })
-                        .unwrap_or("decimal");
+                if label.contains(&placeholder) {
+                    let fmt = formats.get(i).map(String::as_str).unwrap_or("decimal");
 
                     // If count is 0, it means it hasn't been initialized/incremented yet, so use start value
                     let val = if *count == 0 { 1 } else { *count };
 
-                    let formatted_num = Self::format_num(fmt, val);
-                    marker = marker.replace(&placeholder, &formatted_num);
+                    let formatted_num = format_num(fmt, val);
+                    label = label.replace(&placeholder, &formatted_num);
+                }
+            }
+            if !keep_bullet_glyphs {
+                if level.extras.picture_bullet {
+                    label = "•".to_string();
+                } else if let Some(font) = level.font.as_deref().filter(|f| is_symbol_font(f)) {
+                    label = map_symbol_text(font, &label);
                 }
             }
-            return marker;
+            return marker(label);
         }
 
         // Fallback: if no lvlText, add dot for standard types
-        let raw_num = Self::format_num(&level.num_fmt, counters[ilvl_idx]);
-        match level.num_fmt.as_str() {
+        let raw_num = format_num(&level.num_fmt, counters[ilvl_idx]);
+        marker(match level.num_fmt.as_str() {
             "decimal" | "lowerLetter" | "upperLetter" | "lowerRoman" | "upperRoman" => {
                 format!("{}.", raw_num)
             }
             _ => raw_num,
-        }
-    }
-
-    /// Formats a number according to the format string.
-    fn format_num(fmt: &str, val: i32) -> String {
-        match fmt {
-            "bullet" | "none" => "-".to_string(),
-            "decimal" => format!("{}", val),
-            "lowerLetter" => {
-                if (1..=26).contains(&val) {
-                    char::from(b'a' + (val - 1) as u8).to_string()
-                } else {
-                    format!("{}", val)
-                }
-            }
-            "upperLetter" => {
-                if (1..=26).contains(&val) {
-                    char::from(b'A' + (val - 1) as u8).to_string()
-                } else {
-                    format!("{}", val)
-                }
-            }
-            "lowerRoman" => Self::to_roman(val).to_lowercase(),
-            "upperRoman" => Self::to_roman(val),
-            "koreanCounting" | "korean" | "ganada" => Self::format_ganada(val),
-            "chosung" => Self::format_chosung(val),
-            "geonodeo" => Self::format_geonodeo(val),
-            "decimalEnclosedCircle" => Self::format_circle_number(val),
-            _ => format!("{}", val),
-        }
-    }
-
-    /// Converts a number to circled number (①②③...).
-    fn format_circle_number(val: i32) -> String {
-        // Unicode circled numbers: ① = U+2460, ② = U+2461, ... ⑳ = U+2473
-        // Extended: ㉑ = U+3251, ㉒ = U+3252, ... ㊿ = U+32BF (21-50)
-        if (1..=20).contains(&val) {
-            char::from_u32(0x245F + val as u32)
-                .map(|c| c.to_string())
-                .unwrap_or_else(|| format!("{}", val))
-        } else if (21..=50).contains(&val) {
-            char::from_u32(0x3250 + (val - 20) as u32)
-                .map(|c| c.to_string())
-                .unwrap_or_else(|| format!("{}", val))
-        } else {
-            format!("{}", val) // Fallback for numbers outside supported range
-        }
-    }
-
-    /// Converts a number to Korean Ganada (가, 나, 다...).
-    fn format_ganada(val: i32) -> String {
-        let chars = [
-            '가', '나', '다', '라', '마', '바', '사', '아', '자', '차', '카', '타', '파', '하',
-        ];
-        if val >= 1 && val as usize <= chars.len() {
-            chars[(val - 1) as usize].to_string()
-        } else {
-            format!("{}", val) // Fallback
-        }
-    }
-
-    /// Converts a number to Korean Geonodeo (거, 너, 더...).
-    fn format_geonodeo(val: i32) -> String {
-        let chars = [
-            '거', '너', '더', '러', '머', '버', '서', '어', '저', '처', '커', '터', '퍼', '허',
-        ];
-        if val >= 1 && val as usize <= chars.len() {
-            chars[(val - 1) as usize].to_string()
-        } else {
-            format!("{}", val)
-        }
-    }
-
-    /// Converts a number to Korean Chosung (ㄱ, ㄴ, ㄷ...).
-    fn format_chosung(val: i32) -> String {
-        let chars = [
-            'ㄱ', 'ㄴ', 'ㄷ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅅ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
-        ];
-        if val >= 1 && val as usize <= chars.len() {
-            chars[(val - 1) as usize].to_string()
-        } else {
-            format!("{}", val) // Fallback
-        }
-    }
-
-    /// Converts a number to Roman numeral.
-    fn to_roman(mut num: i32) -> String {
-        const ROMAN_TABLE: &[(i32, &str)] = &[
-            (1000, "M"),
-            (900, "CM"),
-            (500, "D"),
-            (400, "CD"),
-            (100, "C"),
-            (90, "XC"),
-            (50, "L"),
-            (40, "XL"),
-            (10, "X"),
-            (9, "IX"),
-            (5, "V"),
-            (4, "IV"),
-            (1, "I"),
-        ];
-
-        if num <= 0 {
-            return num.to_string();
-        }
-        let mut result = String::new();
-        for &(v, s) in ROMAN_TABLE {
-            while num >= v {
-                result.push_str(s);
-                num -= v;
-            }
-        }
-        result
+        })
     }
 }
 
@@ -441,4 +472,220 @@ mod tests {
         let marker = resolver.next_marker(2, 0);
         assert_eq!(marker, "A)");
     }
+
+    fn level(ilvl: isize, fmt: &'static str, text: &'static str) -> Level<'static> {
+        Level {
+            i_level: Some(ilvl),
+            start: Some(LevelStart { value: Some(1) }),
+            number_format: Some(NumFmt {
+                value: Cow::Borrowed(fmt),
+            }),
+            level_text: Some(LevelText {
+                value: Some(Cow::Borrowed(text)),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn docx_with(
+        abstract_nums: Vec<AbstractNum<'static>>,
+        nums: Vec<(isize, isize)>,
+    ) -> Docx<'static> {
+        Docx {
+            numbering: Some(Numbering {
+                abstract_numberings: abstract_nums,
+                numberings: nums
+                    .into_iter()
+                    .map(|(num_id, abs_id)| Num {
+                        num_id: Some(num_id),
+                        abstract_num_id: Some(AbstractNumId {
+                            value: Some(abs_id),
+                        }),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn legal_docx() -> Docx<'static> {
+        docx_with(
+            vec![AbstractNum {
+                abstract_num_id: Some(1),
+                levels: vec![
+                    level(0, "upperRoman", "Article %1"),
+                    level(1, "decimal", "%1.%2"),
+                    level(2, "lowerLetter", "(%3)"),
+                ],
+                ..Default::default()
+            }],
+            vec![(1, 1)],
+        )
+    }
+
+    #[test]
+    fn test_is_legal_forces_decimal_in_level_text() {
+        let docx = legal_docx();
+        let xml = r#"<w:numbering><w:abstractNum w:abstractNumId="1">
+            <w:lvl w:ilvl="1"><w:isLgl/></w:lvl>
+        </w:abstractNum></w:numbering>"#;
+        let mut resolver = NumberingResolver::with_numbering_xml(&docx, Some(xml));
+
+        assert_eq!(resolver.next_marker(1, 0), "Article I");
+        assert_eq!(resolver.next_marker(1, 1), "1.1");
+        assert_eq!(resolver.next_marker(1, 0), "Article II");
+        assert_eq!(resolver.next_marker(1, 1), "2.1");
+    }
+
+    #[test]
+    fn test_lvl_restart_controls_lower_level_reset() {
+        let docx = legal_docx();
+        // Level 2 never restarts; level 1 restarts only after level 1 (ilvl 0)
+        let xml = r#"<w:numbering><w:abstractNum w:abstractNumId="1">
+            <w:lvl w:ilvl="2"><w:lvlRestart w:val="0"/></w:lvl>
+        </w:abstractNum></w:numbering>"#;
+        let mut resolver = NumberingResolver::with_numbering_xml(&docx, Some(xml));
+
+        resolver.next_marker(1, 0);
+        resolver.next_marker(1, 1);
+        assert_eq!(resolver.next_marker(1, 2), "(a)");
+        assert_eq!(resolver.next_marker(1, 2), "(b)");
+        resolver.next_marker(1, 1);
+        assert_eq!(resolver.next_marker(1, 2), "(c)");
+        resolver.next_marker(1, 0);
+        assert_eq!(resolver.next_marker(1, 2), "(d)");
+    }
+
+    #[test]
+    fn test_num_style_link_uses_linked_definition() {
+        use rs_docx::formatting::{NumberingId, NumberingProperty, ParagraphProperty};
+        use rs_docx::styles::{Style, StyleType};
+
+        let mut docx = docx_with(
+            vec![
+                AbstractNum {
+                    abstract_num_id: Some(1),
+                    levels: vec![level(0, "upperLetter", "%1)")],
+                    ..Default::default()
+                },
+                AbstractNum {
+                    abstract_num_id: Some(2),
+                    levels: vec![level(0, "decimal", "%1.")],
+                    ..Default::default()
+                },
+            ],
+            vec![(1, 1), (2, 2)],
+        );
+        let mut style = Style::new(StyleType::Numbering, "LegalList");
+        style.paragraph = Some(ParagraphProperty {
+            numbering: Some(NumberingProperty {
+                id: Some(NumberingId { value: 1 }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        docx.styles.push(style);
+        let xml = r#"<w:numbering>
+            <w:abstractNum w:abstractNumId="1"><w:styleLink w:val="LegalList"/></w:abstractNum>
+            <w:abstractNum w:abstractNumId="2"><w:numStyleLink w:val="LegalList"/></w:abstractNum>
+        </w:numbering>"#;
+        let mut resolver = NumberingResolver::with_numbering_xml(&docx, Some(xml));
+
+        assert_eq!(resolver.next_marker(2, 0), "A)");
+        // Both instances share the linked definition's counters
+        assert_eq!(resolver.next_marker(1, 0), "B)");
+    }
+
+    #[test]
+    fn test_pstyle_level_and_suffix() {
+        let docx = legal_docx();
+        let xml = r#"<w:numbering><w:abstractNum w:abstractNumId="1">
+            <w:lvl w:ilvl="1"><w:pStyle w:val="Heading2"/><w:suff w:val="nothing"/></w:lvl>
+        </w:abstractNum></w:numbering>"#;
+        let resolver = NumberingResolver::with_numbering_xml(&docx, Some(xml));
+
+        assert_eq!(resolver.level_for_style(1, "Heading2"), Some(1));
+        assert_eq!(resolver.level_for_style(1, "Heading3"), None);
+        assert_eq!(resolver.marker_separator(1, 1), "");
+        assert_eq!(resolver.marker_separator(1, 0), " ");
+    }
+
+    #[test]
+    fn test_indent_follows_level_indentation() {
+        use rs_docx::document::PPr;
+        use rs_docx::formatting::Indent;
+
+        let indented = |ilvl: isize, left: isize| Level {
+            p_pr: Some(PPr {
+                indent: Some(Indent {
+                    left: Some(left),
+                    ..Default::default()
+                }),
+            }),
+            ..level(ilvl, "decimal", "%1.")
+        };
+        // Articles at level 4 sit at the margin, their clauses below them
+        let docx = docx_with(
+            vec![AbstractNum {
+                abstract_num_id: Some(1),
+                levels: vec![
+                    indented(0, 720),
+                    indented(1, 1440),
+                    indented(4, 0),
+                    indented(5, 400),
+                    level(6, "decimal", "%7."),
+                ],
+                ..Default::default()
+            }],
+            vec![(1, 1)],
+        );
+        let resolver = NumberingResolver::new(&docx);
+
+        assert_eq!(resolver.get_indent(1, 0), 0);
+        assert_eq!(resolver.get_indent(1, 1), 1);
+        assert_eq!(resolver.get_indent(1, 4), 0);
+        assert_eq!(resolver.get_indent(1, 5), 1);
+        // Without an indent the level number is the depth
+        assert_eq!(resolver.get_indent(1, 6), 6);
+    }
+
+    #[test]
+    fn test_symbol_font_bullets_map_to_unicode() {
+        use rs_docx::formatting::{CharacterProperty, Fonts};
+
+        let symbol_bullet = |ilvl: isize, font: &str, glyph: &'static str| Level {
+            r_pr: vec![CharacterProperty {
+                fonts: Some(Fonts::default().ascii(font)),
+                ..Default::default()
+            }],
+            ..level(ilvl, "bullet", glyph)
+        };
+        let docx = docx_with(
+            vec![AbstractNum {
+                abstract_num_id: Some(1),
+                levels: vec![
+                    symbol_bullet(0, "Symbol", "\u{F0B7}"),
+                    symbol_bullet(1, "Courier New", "o"),
+                    symbol_bullet(2, "Wingdings", "\u{F0A7}"),
+                ],
+                ..Default::default()
+            }],
+            vec![(1, 1)],
+        );
+
+        let mut resolver = NumberingResolver::new(&docx);
+        assert_eq!(resolver.next_marker(1, 0), "•");
+        assert_eq!(resolver.next_marker(1, 1), "o");
+        assert_eq!(resolver.next_marker(1, 2), "▪");
+
+        let mut resolver = NumberingResolver::new(&docx).keep_bullet_glyphs(true);
+        assert_eq!(resolver.next_marker(1, 0), "\u{F0B7}");
+
+        let xml = r#"<w:numbering><w:abstractNum w:abstractNumId="1">
+            <w:lvl w:ilvl="1"><w:lvlPicBulletId w:val="0"/></w:lvl>
+        </w:abstractNum></w:numbering>"#;
+        let mut resolver = NumberingResolver::with_numbering_xml(&docx, Some(xml));
+        assert_eq!(resolver.next_marker(1, 1), "•");
+    }
 }
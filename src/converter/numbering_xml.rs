@@ -0,0 +1,192 @@
+//! Numbering definitions that rs-docx does not parse.
+//!
+//! `w:lvlRestart`, `w:isLgl`, `w:pStyle`, `w:suff`, `w:lvlPicBulletId`,
+//! `w:numStyleLink` and `w:styleLink` are read straight from
+//! `word/numbering.xml`.
+
+use std::collections::HashMap;
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+/// Content between a list marker and the paragraph text (`w:suff`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum LevelSuffix {
+    #[default]
+    Tab,
+    Space,
+    Nothing,
+}
+
+/// Level properties missing from the rs-docx model.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LevelExtras {
+    /// `w:lvlRestart`: restart after this 1-based level; 0 never restarts.
+    pub restart: Option<i32>,
+    /// `w:isLgl`: show every level number in this level's text as decimal.
+    pub is_legal: bool,
+    /// `w:pStyle`: paragraph style linked to this level.
+    pub p_style: Option<String>,
+    pub suffix: LevelSuffix,
+    /// `w:lvlPicBulletId`: the bullet is a picture.
+    pub picture_bullet: bool,
+}
+
+/// Numbering properties parsed from raw `word/numbering.xml`.
+#[derive(Debug, Default)]
+pub(crate) struct NumberingXml {
+    /// Maps (abstractNumId, ilvl) -> level extras
+    pub levels: HashMap<(i32, i32), LevelExtras>,
+    /// Maps (numId, ilvl) -> level extras from `w:lvlOverride`
+    pub override_levels: HashMap<(i32, i32), LevelExtras>,
+    /// Maps abstractNumId -> numbering style it defers to (`w:numStyleLink`)
+    pub num_style_links: HashMap<i32, String>,
+    /// Maps abstractNumId -> numbering style it defines (`w:styleLink`)
+    pub style_links: HashMap<i32, String>,
+}
+
+impl NumberingXml {
+    /// Parses `word/numbering.xml`. Malformed XML yields what was read so far.
+    pub fn parse(xml: &str) -> Self {
+        let mut parser = Parser::default();
+        let mut element: Option<&str> = None;
+        let mut attrs: Vec<(&str, &str)> = Vec::new();
+
+        for token in Tokenizer::from(xml) {
+            let Ok(token) = token else {
+                break;
+            };
+            match token {
+                Token::ElementStart { local, .. } => {
+                    element = Some(local.as_str());
+                    attrs.clear();
+                }
+                Token::Attribute { local, value, .. } => {
+                    attrs.push((local.as_str(), value.as_str()));
+                }
+                Token::ElementEnd { end, .. } => match end {
+                    ElementEnd::Open => {
+                        if let Some(name) = element.take() {
+                            parser.open(name, &attrs);
+                        }
+                    }
+                    ElementEnd::Empty => {
+                        if let Some(name) = element.take() {
+                            parser.open(name, &attrs);
+                            parser.close(name);
+                        }
+                    }
+                    ElementEnd::Close(_, local) => parser.close(local.as_str()),
+                },
+                _ => {}
+            }
+        }
+
+        parser.result
+    }
+}
+
+#[derive(Default)]
+struct Parser {
+    result: NumberingXml,
+    abstract_id: Option<i32>,
+    num_id: Option<i32>,
+    level: Option<(i32, LevelExtras)>,
+}
+
+impl Parser {
+    fn open(&mut self, name: &str, attrs: &[(&str, &str)]) {
+        let attr = |key: &str| attrs.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+        let int_attr = |key: &str| attr(key).and_then(|v| v.trim().parse::<i32>().ok());
+
+        match name {
+            "abstractNum" => self.abstract_id = int_attr("abstractNumId"),
+            "num" => self.num_id = int_attr("numId"),
+            "lvl" => self.level = Some((int_attr("ilvl").unwrap_or(0), LevelExtras::default())),
+            "numStyleLink" | "styleLink" if self.level.is_none() => {
+                if let (Some(abs_id), Some(style)) = (self.abstract_id, attr("val")) {
+                    let links = if name == "numStyleLink" {
+                        &mut self.result.num_style_links
+                    } else {
+                        &mut self.result.style_links
+                    };
+                    links.insert(abs_id, style.to_string());
+                }
+            }
+            _ => {
+                let Some((_, level)) = self.level.as_mut() else {
+                    return;
+                };
+                match name {
+                    "lvlRestart" => level.restart = int_attr("val"),
+                    "isLgl" => level.is_legal = !matches!(attr("val"), Some("0" | "false" | "off")),
+                    "pStyle" => level.p_style = attr("val").map(str::to_string),
+                    "lvlPicBulletId" => level.picture_bullet = true,
+                    "suff" => {
+                        level.suffix = match attr("val") {
+                            Some("space") => LevelSuffix::Space,
+                            Some("nothing") => LevelSuffix::Nothing,
+                            _ => LevelSuffix::Tab,
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "lvl" => {
+                let Some((ilvl, level)) = self.level.take() else {
+                    return;
+                };
+                // Levels inside w:num belong to a w:lvlOverride
+                if let Some(num_id) = self.num_id {
+                    self.result.override_levels.insert((num_id, ilvl), level);
+                } else if let Some(abs_id) = self.abstract_id {
+                    self.result.levels.insert((abs_id, ilvl), level);
+                }
+            }
+            "abstractNum" => self.abstract_id = None,
+            "num" => self.num_id = None,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level_extras_and_style_links() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:abstractNum w:abstractNumId="1">
+    <w:styleLink w:val="LegalList"/>
+    <w:lvl w:ilvl="0"><w:start w:val="1"/><w:pStyle w:val="Heading1"/><w:suff w:val="space"/></w:lvl>
+    <w:lvl w:ilvl="1"><w:lvlRestart w:val="0"/><w:isLgl/><w:lvlPicBulletId w:val="0"/></w:lvl>
+  </w:abstractNum>
+  <w:abstractNum w:abstractNumId="2">
+    <w:numStyleLink w:val="LegalList"/>
+  </w:abstractNum>
+  <w:num w:numId="3">
+    <w:abstractNumId w:val="1"/>
+    <w:lvlOverride w:ilvl="0"><w:lvl w:ilvl="0"><w:suff w:val="nothing"/></w:lvl></w:lvlOverride>
+  </w:num>
+</w:numbering>"#;
+
+        let parsed = NumberingXml::parse(xml);
+
+        let first = &parsed.levels[&(1, 0)];
+        assert_eq!(first.p_style.as_deref(), Some("Heading1"));
+        assert_eq!(first.suffix, LevelSuffix::Space);
+        let second = &parsed.levels[&(1, 1)];
+        assert_eq!(second.restart, Some(0));
+        assert!(second.is_legal);
+        assert!(second.picture_bullet);
+        assert!(!first.picture_bullet);
+        assert_eq!(parsed.style_links[&1], "LegalList");
+        assert_eq!(parsed.num_style_links[&2], "LegalList");
+        assert_eq!(parsed.override_levels[&(3, 0)].suffix, LevelSuffix::Nothing);
+    }
+}
@@ -1,26 +1,31 @@
 //! Paragraph converter - handles paragraph elements and their structure.
 
-use super::{ConversionContext, RunConverter};
-use crate::render::{
-    escape_html_attr, escape_markdown_link_destination, escape_markdown_link_text,
-};
-use crate::Result;
-use rs_docx::document::{Hyperlink, Paragraph, ParagraphContent};
+use super::fields::FieldInstruction;
+use super::form_fields::FormField;
+use super::num_format::format_num;
+use super::{ConversionContext, ListMarker, RunConverter};
+use crate::adapters::docx::ListBuilder;
+use crate::core::ast::{self, plain_text, Alignment, BlockNode, Heading, InlineNode, TextStyle};
+use crate::{ContentControlMarkup, ListMarkerStyle, Result};
+use rs_docx::document::{CharType, Hyperlink, Paragraph, ParagraphContent, Run, RunContent};
 
 /// Converter for Paragraph elements.
 pub struct ParagraphConverter;
 
-/// Segment of formatted text with consistent styling.
-#[derive(Debug, Clone, PartialEq, Default)]
-struct FormattedSegment {
-    text: String,
-    is_bold: bool,
-    is_italic: bool,
-    has_underline: bool,
-    has_strike: bool,
-    is_insertion: bool,
-    is_deletion: bool,
-    anchor: Option<String>,
+/// How a paragraph relates to the lists around it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ListPlacement {
+    /// A numbered list item.
+    Item(ListMarker),
+    /// An unnumbered paragraph, continuing the open list item it is indented
+    /// under (`indent` in twips) or any open item when `w:numId="0"` removed
+    /// its numbering.
+    Body {
+        indent: Option<i32>,
+        numbering_removed: bool,
+    },
+    /// A heading, which never belongs to a list.
+    Heading(Heading),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,47 +34,223 @@ enum FieldPhase {
     Result,
 }
 
+/// A complex field (`w:fldChar` begin .. end) being read.
+#[derive(Debug)]
+struct OpenField {
+    phase: FieldPhase,
+    instruction: String,
+    /// Inline nodes of the field result read so far
+    result: Vec<InlineNode>,
+}
+
 impl ParagraphConverter {
-    /// Filters a run so only field-visible content remains, updating field stack.
-    fn filter_run_by_field_state<'a>(
-        run: &rs_docx::document::Run<'a>,
-        field_stack: &mut Vec<FieldPhase>,
-    ) -> rs_docx::document::Run<'a> {
-        let mut filtered = run.clone();
-        filtered.content.clear();
+    /// Collects the inline nodes of a run, tracking the complex fields it
+    /// begins, continues or ends. Field results are buffered until their
+    /// field ends and then interpreted (see [`Self::field_result`]).
+    fn collect_run_inlines<'a>(
+        run: &Run<'a>,
+        context: &mut ConversionContext<'a>,
+        para_style_id: Option<&str>,
+        fields: &mut Vec<OpenField>,
+        nodes: &mut Vec<InlineNode>,
+    ) {
+        let mut segment = run.clone();
+        segment.content.clear();
 
         for content in &run.content {
             match content {
-                rs_docx::document::RunContent::FieldChar(fc) => {
-                    if let Some(char_type) = &fc.ty {
-                        match char_type {
-                            rs_docx::document::CharType::Begin => {
-                                field_stack.push(FieldPhase::Instruction)
+                RunContent::FieldChar(fc) => {
+                    Self::flush_segment(&mut segment, context, para_style_id, fields, nodes);
+                    match fc.ty {
+                        Some(CharType::Begin) => fields.push(OpenField {
+                            phase: FieldPhase::Instruction,
+                            instruction: String::new(),
+                            result: Vec::new(),
+                        }),
+                        Some(CharType::Separate) => {
+                            if let Some(field) = fields.last_mut() {
+                                field.phase = FieldPhase::Result;
                             }
-                            rs_docx::document::CharType::Separate => {
-                                if let Some(last) = field_stack.last_mut() {
-                                    *last = FieldPhase::Result;
-                                }
-                            }
-                            rs_docx::document::CharType::End => {
-                                let _ = field_stack.pop();
+                        }
+                        Some(CharType::End) => {
+                            if let Some(field) = fields.pop() {
+                                Self::end_field(field, context, fields, nodes);
                             }
                         }
+                        None => {}
                     }
                 }
-                // Keep existing behavior: field instructions are never rendered.
-                rs_docx::document::RunContent::InstrText(_)
-                | rs_docx::document::RunContent::DelInstrText(_) => {}
-                _ => {
-                    // Skip non-instruction payload while inside field instruction section.
-                    if field_stack.last() != Some(&FieldPhase::Instruction) {
-                        filtered.content.push(content.clone());
+                RunContent::InstrText(instr) => {
+                    if let Some(field) = fields.last_mut() {
+                        field.instruction.push_str(&instr.text);
                     }
                 }
+                RunContent::DelInstrText(_) => {}
+                // Content between an instruction and its separator is not shown
+                _ if fields.last().map(|f| f.phase) == Some(FieldPhase::Instruction) => {}
+                _ => segment.content.push(content.clone()),
             }
         }
 
-        filtered
+        Self::flush_segment(&mut segment, context, para_style_id, fields, nodes);
+    }
+
+    /// Converts the run content collected so far into the innermost field
+    /// result, or `nodes` outside fields.
+    fn flush_segment<'a>(
+        segment: &mut Run<'a>,
+        context: &mut ConversionContext<'a>,
+        para_style_id: Option<&str>,
+        fields: &mut [OpenField],
+        nodes: &mut Vec<InlineNode>,
+    ) {
+        if segment.content.is_empty() {
+            return;
+        }
+        let inlines = RunConverter::to_inlines(segment, context, para_style_id);
+        segment.content.clear();
+        match fields.last_mut() {
+            Some(field) => field.result.extend(inlines),
+            None => nodes.extend(inlines),
+        }
+    }
+
+    /// Interprets an ended field, adding its result to the enclosing field or
+    /// to `nodes`. A field nested in another field's instruction contributes
+    /// its text to that instruction.
+    fn end_field(
+        field: OpenField,
+        context: &mut ConversionContext<'_>,
+        fields: &mut [OpenField],
+        nodes: &mut Vec<InlineNode>,
+    ) {
+        let result = Self::field_result(field, context);
+        match fields.last_mut() {
+            Some(parent) if parent.phase == FieldPhase::Instruction => {
+                for node in &result {
+                    if let InlineNode::Text { text, .. } = node {
+                        parent.instruction.push_str(text);
+                    }
+                }
+            }
+            Some(parent) => parent.result.extend(result),
+            None => nodes.extend(result),
+        }
+    }
+
+    /// Ends the fields still open at the end of a paragraph, e.g. a table of
+    /// contents field spanning several paragraphs.
+    fn end_open_fields(
+        fields: &mut Vec<OpenField>,
+        context: &mut ConversionContext<'_>,
+        nodes: &mut Vec<InlineNode>,
+    ) {
+        while let Some(field) = fields.pop() {
+            Self::end_field(field, context, fields, nodes);
+        }
+    }
+
+    /// Returns the inline nodes shown for a field:
+    ///
+    /// - `HYPERLINK`, `REF` and `PAGEREF` link their result to the URL or
+    ///   bookmark they point to.
+    /// - `SEQ` shows its number, counted through the document.
+    /// - `MERGEFIELD` becomes a `{{name}}` placeholder when requested.
+    /// - Legacy check box and dropdown form fields show their current value.
+    /// - Other fields, such as `DATE`, keep the result Word last computed.
+    fn field_result(field: OpenField, context: &mut ConversionContext<'_>) -> Vec<InlineNode> {
+        let instruction = FieldInstruction::parse(&field.instruction);
+        let mut result = field.result;
+        // Replacement text keeps the formatting of the cached result
+        let text = |text: String, result: &[InlineNode]| InlineNode::Text {
+            text,
+            style: result
+                .iter()
+                .find_map(|node| match node {
+                    InlineNode::Text { style, .. } => Some(style.clone()),
+                    _ => None,
+                })
+                .unwrap_or_default(),
+        };
+
+        match instruction.name.as_str() {
+            "HYPERLINK" => {
+                let destination = match (instruction.argument(0), instruction.switch_value("l")) {
+                    (Some(url), Some(anchor)) => format!("{}#{}", url, anchor),
+                    (Some(url), None) => url.to_string(),
+                    (None, Some(anchor)) => format!("#{}", anchor),
+                    (None, None) => return result,
+                };
+                Self::link_field(destination, result)
+            }
+            "REF" | "PAGEREF" => match instruction.argument(0) {
+                Some(bookmark) => Self::link_field(format!("#{}", bookmark), result),
+                None => result,
+            },
+            "SEQ" => {
+                let Some(identifier) = instruction.argument(0) else {
+                    return result;
+                };
+                let number = if let Some(value) = instruction.switch_value("r") {
+                    context.reset_sequence(identifier, value.trim().parse().unwrap_or(1))
+                } else if instruction.has_switch("c") {
+                    context.current_sequence(identifier)
+                } else {
+                    context.next_sequence(identifier)
+                };
+                if instruction.has_switch("h") {
+                    return Vec::new();
+                }
+                let format = match instruction.switch_value("*") {
+                    Some("ALPHABETIC") => "upperLetter",
+                    Some("alphabetic") => "lowerLetter",
+                    Some("ROMAN") => "upperRoman",
+                    Some("roman") => "lowerRoman",
+                    _ => "decimal",
+                };
+                vec![text(format_num(format, number), &result)]
+            }
+            "MERGEFIELD" if context.merge_field_placeholders() => match instruction.argument(0) {
+                Some(name) => vec![text(format!("{{{{{}}}}}", name), &result)],
+                None => result,
+            },
+            "FORMCHECKBOX" | "FORMDROPDOWN" | "FORMTEXT" => {
+                match context.next_form_field() {
+                    Some(FormField::CheckBox(checked)) => {
+                        result.insert(0, InlineNode::CheckBox { checked })
+                    }
+                    Some(FormField::DropDown(Some(value))) => result.insert(0, text(value, &[])),
+                    // Text fields show their value as the field result
+                    Some(FormField::DropDown(None) | FormField::Text) | None => {}
+                }
+                result
+            }
+            _ => result,
+        }
+    }
+
+    /// Links a field result, unless it is empty. Links inside the result,
+    /// such as page references in a table of contents entry, are unwrapped.
+    fn link_field(destination: String, result: Vec<InlineNode>) -> Vec<InlineNode> {
+        if result.is_empty() {
+            return result;
+        }
+        vec![InlineNode::Link {
+            destination,
+            content: Self::unwrap_links(result),
+        }]
+    }
+
+    /// Replaces links with their content.
+    fn unwrap_links(nodes: Vec<InlineNode>) -> Vec<InlineNode> {
+        let mut unwrapped = Vec::with_capacity(nodes.len());
+        for node in nodes {
+            match node {
+                InlineNode::Link { content, .. } => unwrapped.extend(Self::unwrap_links(content)),
+                node => unwrapped.push(node),
+            }
+        }
+        unwrapped
     }
 
     /// Converts a Paragraph to Markdown.
@@ -77,65 +258,218 @@ impl ParagraphConverter {
         para: &Paragraph<'a>,
         context: &mut ConversionContext<'a>,
     ) -> Result<String> {
-        // Collect all formatted segments from runs
-        let segments = Self::collect_segments(para, context)?;
+        let block = Self::convert_block(para, context)?;
+        Ok(context.render_markdown(std::slice::from_ref(&block)))
+    }
 
-        // Merge adjacent segments with same formatting
-        let merged = Self::merge_segments(segments);
+    /// Converts a Paragraph to a block: a heading, a list of one item for a
+    /// numbered paragraph, or a paragraph.
+    pub fn convert_block<'a>(
+        para: &Paragraph<'a>,
+        context: &mut ConversionContext<'a>,
+    ) -> Result<BlockNode> {
+        let (paragraph, placement) = Self::convert_list_item(para, context)?;
+        Ok(match placement {
+            ListPlacement::Heading(heading) => BlockNode::Heading(heading),
+            ListPlacement::Item(marker) => {
+                let mut lists = ListBuilder::default();
+                lists.push(marker, paragraph);
+                lists.finish().expect("the item opens a list")
+            }
+            ListPlacement::Body { .. } => BlockNode::Paragraph(paragraph),
+        })
+    }
+
+    /// Converts a Paragraph, returning how it relates to the lists around
+    /// it: the list marker of a numbered paragraph, or the heading it is.
+    pub fn convert_list_item<'a>(
+        para: &Paragraph<'a>,
+        context: &mut ConversionContext<'a>,
+    ) -> Result<(ast::Paragraph, ListPlacement)> {
+        let (paragraph, marker, heading) = Self::convert_paragraph(para, context)?;
+        let placement = match (marker, heading) {
+            (_, Some(heading)) => ListPlacement::Heading(heading),
+            (Some(marker), None) => ListPlacement::Item(marker),
+            (None, None) => Self::body_placement(para, context),
+        };
+        Ok((paragraph, placement))
+    }
 
-        // Separate leading anchors (anchors at the start with empty text) from the rest
+    /// Returns the indentation an unnumbered paragraph may continue a list
+    /// item with. Headings never continue one.
+    fn body_placement<'a>(para: &Paragraph<'a>, context: &ConversionContext<'a>) -> ListPlacement {
+        let para_style_id = para
+            .property
+            .as_ref()
+            .and_then(|p| p.style_id.as_ref())
+            .map(|s| s.value.as_ref());
+        let props = context.resolve_paragraph_property(para.property.as_ref(), para_style_id);
+        if Self::heading_level(&props, context.inferred_heading_level(para)).is_some() {
+            return ListPlacement::Body {
+                indent: None,
+                numbering_removed: false,
+            };
+        }
+
+        let indent = props
+            .indent
+            .as_ref()
+            .and_then(|ind| ind.left.or(ind.start))
+            .map(|v| v as i32);
+        let numbering_removed = props
+            .numbering
+            .as_ref()
+            .and_then(|n| n.id.as_ref())
+            .is_some_and(|id| id.value == 0);
+        ListPlacement::Body {
+            indent,
+            numbering_removed,
+        }
+    }
+
+    fn convert_paragraph<'a>(
+        para: &Paragraph<'a>,
+        context: &mut ConversionContext<'a>,
+    ) -> Result<(ast::Paragraph, Option<ListMarker>, Option<Heading>)> {
+        // Collect inline nodes from runs, merging adjacent text with the same style
+        let nodes = Self::merge_text_nodes(Self::collect_inlines(para, context)?);
+
+        // Separate leading anchors from the rest
         let mut leading_anchors = Vec::new();
-        let mut content_segments = Vec::new();
-        let mut looking_for_anchors = true;
-
-        for seg in merged {
-            if looking_for_anchors && seg.text.is_empty() && seg.anchor.is_some() {
-                if let Some(anchor) = &seg.anchor {
-                    // Use id attribute instead of name for better compatibility (VS Code etc.)
-                    leading_anchors.push(format!("<a id=\"{}\"></a>", escape_html_attr(anchor)));
+        let mut content_nodes = Vec::new();
+
+        for node in nodes {
+            match node {
+                InlineNode::Anchor(anchor) if content_nodes.is_empty() => {
+                    leading_anchors.push(anchor);
                 }
-            } else {
-                looking_for_anchors = false;
-                content_segments.push(seg);
+                node => content_nodes.push(node),
             }
         }
 
-        // Convert merged segments to markdown
-        let text = Self::segments_to_markdown(&content_segments, context);
+        // Page numbers of a table of contents entry are meaningless here
+        if context.in_toc_entry() {
+            Self::strip_toc_page_number(&mut content_nodes);
+        }
 
-        let anchor_tags = leading_anchors.join("");
+        // Inferred headings are usually bold body text; the heading marker
+        // already conveys the emphasis.
+        let inferred_heading = context.inferred_heading_level(para);
+        if inferred_heading.is_some() {
+            Self::clear_bold(&mut content_nodes);
+        }
 
-        let is_effectively_empty = if context.preserve_whitespace() {
-            text.is_empty()
-        } else {
-            text.trim().is_empty()
-        };
+        let para_style_id = para
+            .property
+            .as_ref()
+            .and_then(|p| p.style_id.as_ref())
+            .map(|s| s.value.as_ref());
+        let style = para_style_id.map(str::to_string);
+        let effective_props =
+            context.resolve_paragraph_property(para.property.as_ref(), para_style_id);
+        let heading_level = Self::heading_level(&effective_props, inferred_heading);
+
+        if let Some(level) = heading_level {
+            // With attribute syntax, a heading takes its first anchor as
+            // `{#id}` and other anchors stay inline (`[]{#id}`).
+            let uses_attributes = context
+                .flavor()
+                .is_some_and(|flavor| flavor.supports_attributes());
+            let mut heading_id = None;
+            if uses_attributes && !leading_anchors.is_empty() {
+                let mut anchors = std::mem::take(&mut leading_anchors).into_iter();
+                heading_id = anchors.next();
+                let mut nodes: Vec<InlineNode> = anchors.map(InlineNode::Anchor).collect();
+                nodes.append(&mut content_nodes);
+                content_nodes = nodes;
+            }
 
-        if is_effectively_empty {
-            // If there is no content but there are anchors, return just the anchors
-            return Ok(anchor_tags);
+            if plain_text(&content_nodes).trim().is_empty() {
+                // Don't generate heading for empty text
+                let content = heading_id
+                    .into_iter()
+                    .chain(leading_anchors)
+                    .map(InlineNode::Anchor)
+                    .collect();
+                let paragraph = ast::Paragraph {
+                    content,
+                    ..Default::default()
+                };
+                return Ok((paragraph, None, None));
+            }
+            let label = Self::next_list_marker(&effective_props, context)
+                .map(|marker| Self::heading_label(marker, context.list_markers()))
+                .unwrap_or_default();
+            let heading = Heading {
+                level,
+                label,
+                content: content_nodes,
+                id: heading_id,
+                anchors: leading_anchors,
+            };
+            let paragraph = ast::Paragraph {
+                content: heading.content.clone(),
+                style,
+                alignment: None,
+            };
+            return Ok((paragraph, None, Some(heading)));
         }
 
-        // Apply paragraph-level formatting
-        let formatted_text = Self::apply_paragraph_formatting(para, text, context)?;
+        let alignment = Self::alignment(&effective_props);
 
-        if !anchor_tags.is_empty() {
-            // Place anchors on the line BEFORE the paragraph
-            // This ensures scrolling lands above the header/list item
-            // and maintains valid Markdown syntax for headers (e.g. ### Title)
-            Ok(format!("{}\n{}", anchor_tags, formatted_text))
+        // A check box at the start of a paragraph makes it a task list item
+        let task = if alignment.is_none() && !context.in_table() {
+            Self::take_leading_checkbox(&mut content_nodes)
         } else {
-            Ok(formatted_text)
+            None
+        };
+
+        let mut content: Vec<InlineNode> = leading_anchors
+            .into_iter()
+            .map(InlineNode::Anchor)
+            .collect();
+        if task.is_none() && Self::is_blank(&content_nodes, context.preserve_whitespace()) {
+            // If there is no content but there are anchors, keep just the anchors
+            let paragraph = ast::Paragraph {
+                content,
+                style,
+                alignment: None,
+            };
+            return Ok((paragraph, None, None));
         }
+        content.append(&mut content_nodes);
+
+        let list_marker = Self::list_marker(&effective_props, context, task);
+        let paragraph = ast::Paragraph {
+            content,
+            style,
+            alignment,
+        };
+        Ok((paragraph, list_marker, None))
+    }
+
+    /// Whether inline nodes show no text: only blank text, or empty text
+    /// when whitespace is preserved.
+    fn is_blank(nodes: &[InlineNode], preserve_whitespace: bool) -> bool {
+        nodes.iter().all(|node| match node {
+            InlineNode::Text { text, .. } | InlineNode::Raw(text) => {
+                if preserve_whitespace {
+                    text.is_empty()
+                } else {
+                    text.trim().is_empty()
+                }
+            }
+            _ => false,
+        })
     }
 
-    /// Collects formatted segments from paragraph content.
-    fn collect_segments<'a>(
+    /// Collects inline nodes from paragraph content.
+    fn collect_inlines<'a>(
         para: &Paragraph<'a>,
         context: &mut ConversionContext<'a>,
-    ) -> Result<Vec<FormattedSegment>> {
-        let mut segments = Vec::new();
-        let mut field_stack = Vec::new();
+    ) -> Result<Vec<InlineNode>> {
+        let mut nodes = Vec::new();
+        let mut fields = Vec::new();
 
         // Get paragraph style ID for inheritance
         let para_style_id = para
@@ -147,58 +481,65 @@ impl ParagraphConverter {
         for content in &para.content {
             match content {
                 ParagraphContent::Run(run) => {
-                    let filtered_run = Self::filter_run_by_field_state(run, &mut field_stack);
-                    if filtered_run.content.is_empty() {
-                        continue;
-                    }
-
-                    // Extract visible text only (field instructions already filtered out).
-                    let text = Self::extract_text(&filtered_run, context);
-                    if !text.is_empty() {
-                        let segs =
-                            Self::run_to_segment(&filtered_run, &text, context, para_style_id);
-                        segments.extend(segs);
-                    }
+                    Self::collect_run_inlines(run, context, para_style_id, &mut fields, &mut nodes);
                 }
                 ParagraphContent::Link(hyperlink) => {
-                    let link_md = Self::convert_hyperlink(hyperlink, context, para_style_id)?;
-                    if !link_md.is_empty() {
-                        // Hyperlinks are treated as plain text segments
-                        segments.push(FormattedSegment {
-                            text: link_md,
-                            ..Default::default()
-                        });
-                    }
+                    nodes.push(Self::convert_hyperlink(hyperlink, context, para_style_id));
                 }
                 ParagraphContent::BookmarkStart(bookmark) => {
                     if let Some(name) = &bookmark.name {
-                        segments.push(FormattedSegment {
-                            anchor: Some(name.to_string()),
-                            ..Default::default()
-                        });
+                        nodes.push(InlineNode::Anchor(name.to_string()));
                     }
                 }
                 ParagraphContent::SDT(sdt) => {
-                    // Structured document tags (TOC, etc.) - extract inner content
-                    if let Some(sdt_content) = &sdt.content {
+                    let mut content = Vec::new();
+                    // Check box content controls show their state, not their glyph
+                    let checkbox = sdt
+                        .property
+                        .as_ref()
+                        .and_then(|p| p.id.as_ref())
+                        .and_then(|id| id.id)
+                        .and_then(|id| context.checkbox_state(id));
+                    if let Some(checked) = checkbox {
+                        content.push(InlineNode::CheckBox { checked });
+                    } else if let Some(sdt_content) = &sdt.content {
+                        // Structured document tags (TOC, dropdowns, etc.) - extract inner content
                         for bc in &sdt_content.content {
-                            if let rs_docx::document::BodyContent::Paragraph(inner_para) = bc {
-                                let inner_segs = Self::collect_segments(inner_para, context)?;
-                                segments.extend(inner_segs);
+                            match bc {
+                                rs_docx::document::BodyContent::Paragraph(inner_para) => {
+                                    content.extend(Self::collect_inlines(inner_para, context)?);
+                                }
+                                rs_docx::document::BodyContent::Run(run) => {
+                                    Self::collect_run_inlines(
+                                        run,
+                                        context,
+                                        para_style_id,
+                                        &mut fields,
+                                        &mut content,
+                                    );
+                                }
+                                _ => {}
                             }
                         }
                     }
+
+                    if context.content_control_markup() == ContentControlMarkup::Flatten {
+                        nodes.extend(content);
+                    } else {
+                        nodes.push(InlineNode::ContentControl {
+                            control: context.content_control(sdt.property.as_ref()),
+                            content,
+                        });
+                    }
                 }
                 ParagraphContent::Insertion(ins) => {
                     // Handle inserted content (track changes)
                     for run in &ins.runs {
-                        let text = Self::extract_text(run, context);
-                        if !text.is_empty() {
-                            let mut segs = Self::run_to_segment(run, &text, context, para_style_id);
-                            for seg in &mut segs {
-                                seg.is_insertion = true;
+                        for mut node in RunConverter::to_inlines(run, context, para_style_id) {
+                            if let InlineNode::Text { style, .. } = &mut node {
+                                style.insertion = true;
                             }
-                            segments.extend(segs);
+                            nodes.push(node);
                         }
                     }
                 }
@@ -206,18 +547,21 @@ impl ParagraphConverter {
                     // Handle deleted content (track changes)
                     let text = Self::extract_deleted_text(del);
                     if !text.is_empty() {
-                        segments.push(FormattedSegment {
+                        nodes.push(InlineNode::Text {
                             text,
-                            is_deletion: true,
-                            ..Default::default()
+                            style: TextStyle {
+                                deletion: true,
+                                ..Default::default()
+                            },
                         });
                     }
                 }
                 _ => {}
             }
         }
+        Self::end_open_fields(&mut fields, context, &mut nodes);
 
-        Ok(segments)
+        Ok(nodes)
     }
 
     /// Extracts deleted text from a Deletion element.
@@ -233,399 +577,228 @@ impl ParagraphConverter {
         text
     }
 
-    /// Extracts text from a run, excluding field codes.
-    fn extract_text<'a>(
-        run: &rs_docx::document::Run<'a>,
-        context: &mut ConversionContext<'a>,
-    ) -> String {
-        let mut text = String::new();
-        for content in &run.content {
-            match content {
-                rs_docx::document::RunContent::Text(t) => {
-                    text.push_str(&t.text);
-                }
-                rs_docx::document::RunContent::Tab(_) => {
-                    text.push('\t');
-                }
-                rs_docx::document::RunContent::Break(br) => match br.ty {
-                    Some(rs_docx::document::BreakType::Page) => text.push_str("\n\n---\n\n"),
-                    _ => text.push('\n'),
-                },
-                rs_docx::document::RunContent::CarriageReturn(_) => {
-                    text.push('\n');
-                }
-                rs_docx::document::RunContent::Drawing(drawing) => {
-                    if let Ok(Some(img_md)) = context.extract_image_from_drawing(drawing) {
-                        text.push_str(&img_md);
-                    }
-                }
-                rs_docx::document::RunContent::Pict(pict) => {
-                    if let Ok(Some(img_md)) = context.extract_image_from_pict(pict) {
-                        text.push_str(&img_md);
-                    }
-                }
-                // Skip InstrText (field codes like TOC, PAGEREF)
-                rs_docx::document::RunContent::InstrText(_) => {}
-                rs_docx::document::RunContent::DelInstrText(_) => {}
-                rs_docx::document::RunContent::CommentReference(cref) => {
-                    // Extract comment ID and look up comment text
-                    if let Some(id) = &cref.id {
-                        let marker = context.register_comment_reference(id.as_ref());
-                        text.push_str(&marker);
-                    }
-                }
-                rs_docx::document::RunContent::FootnoteReference(fnref) => {
-                    // Extract footnote ID and look up footnote text
-                    if let Some(ref id_str) = fnref.id {
-                        if let Ok(id_num) = id_str.parse::<isize>() {
-                            let marker = context.register_footnote_reference(id_num);
-                            text.push_str(&marker);
-                        }
-                    }
-                }
-                rs_docx::document::RunContent::EndnoteReference(enref) => {
-                    // Extract endnote ID and look up endnote text
-                    if let Some(ref id_str) = enref.id {
-                        if let Ok(id_num) = id_str.parse::<isize>() {
-                            let marker = context.register_endnote_reference(id_num);
-                            text.push_str(&marker);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-        text
-    }
-
-    /// Creates formatted segments from a run, splitting on page breaks.
-    fn run_to_segment<'a>(
-        run: &rs_docx::document::Run<'a>,
-        text: &str,
-        context: &mut ConversionContext<'a>,
-        para_style_id: Option<&str>,
-    ) -> Vec<FormattedSegment> {
-        // Resolve run style ID
-        let mut run_style_id = None;
-        if let Some(props) = &run.property {
-            if let Some(style) = &props.style_id {
-                run_style_id = Some(style.value.as_ref());
-            }
-        }
-
-        // Resolve effective properties
-        let props =
-            context.resolve_run_property(run.property.as_ref(), run_style_id, para_style_id);
-
-        let is_bold = props
-            .bold
-            .as_ref()
-            .map(|b| b.value.unwrap_or(true))
-            .unwrap_or(false);
-        let is_italic = props
-            .italics
-            .as_ref()
-            .map(|i| i.value.unwrap_or(true))
-            .unwrap_or(false);
-        let has_underline = props.underline.is_some();
-        let has_strike = props
-            .strike
-            .as_ref()
-            .map(|s| s.value.unwrap_or(true))
-            .unwrap_or(false);
-
-        let delimiter = "\n\n---\n\n";
-        let parts: Vec<&str> = text.split(delimiter).collect();
-        let mut segments = Vec::new();
-
-        for (i, part) in parts.iter().enumerate() {
-            if i > 0 {
-                // Add the break segment with no formatting
-                segments.push(FormattedSegment {
-                    text: delimiter.to_string(),
-                    is_bold: false,
-                    is_italic: false,
-                    has_underline: false,
-                    has_strike: false,
-                    is_insertion: false,
-                    is_deletion: false,
-                    anchor: None,
-                });
-            }
-            if !part.is_empty() {
-                segments.push(FormattedSegment {
-                    text: part.to_string(),
-                    is_bold,
-                    is_italic,
-                    has_underline,
-                    has_strike,
-                    is_insertion: false,
-                    is_deletion: false,
-                    anchor: None,
-                });
-            }
-        }
-
-        segments
-    }
-
-    /// Merges adjacent segments with identical formatting.
-    fn merge_segments(segments: Vec<FormattedSegment>) -> Vec<FormattedSegment> {
-        let mut merged: Vec<FormattedSegment> = Vec::new();
+    /// Merges adjacent text nodes with identical formatting.
+    fn merge_text_nodes(nodes: Vec<InlineNode>) -> Vec<InlineNode> {
+        let mut merged: Vec<InlineNode> = Vec::new();
 
-        for seg in segments {
-            if let Some(last) = merged.last_mut() {
+        for node in nodes {
+            if let (
+                Some(InlineNode::Text {
+                    text: last_text,
+                    style: last_style,
+                }),
+                InlineNode::Text { text, style },
+            ) = (merged.last_mut(), &node)
+            {
                 // Check if formatting matches (including track changes flags)
-                if last.is_bold == seg.is_bold
-                    && last.is_italic == seg.is_italic
-                    && last.has_underline == seg.has_underline
-                    && last.has_strike == seg.has_strike
-                    && last.is_insertion == seg.is_insertion
-                    && last.is_deletion == seg.is_deletion
-                    && last.anchor == seg.anchor
-                {
-                    // Merge text
-                    last.text.push_str(&seg.text);
+                if last_style == style {
+                    last_text.push_str(text);
                     continue;
                 }
             }
-            merged.push(seg);
+            merged.push(node);
         }
 
         merged
     }
 
-    /// Applies markdown formatting markers safely, handling edge cases.
-    ///
-    /// Handles:
-    /// - Empty or whitespace-only text (skips formatting)
-    /// - Text with newlines (applies formatting per line)
-    /// - Leading/trailing whitespace (preserves outside markers)
-    fn apply_format_safely(text: &str, open: &str, close: &str) -> String {
-        // Skip if text is empty or whitespace-only
-        if text.trim().is_empty() {
-            return text.to_string();
+    /// Removes a check box that starts the paragraph (after any blank text)
+    /// along with the space following it, returning its state.
+    fn take_leading_checkbox(nodes: &mut Vec<InlineNode>) -> Option<bool> {
+        let index = nodes.iter().position(
+            |node| !matches!(node, InlineNode::Text { text, .. } if text.trim().is_empty()),
+        )?;
+        let checked = match &nodes[index] {
+            InlineNode::CheckBox { checked } => *checked,
+            InlineNode::ContentControl { content, .. } => match content.as_slice() {
+                [InlineNode::CheckBox { checked }] => *checked,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        nodes.drain(..=index);
+        if let Some(InlineNode::Text { text, .. }) = nodes.first_mut() {
+            *text = text.trim_start().to_string();
+            if text.is_empty() {
+                nodes.remove(0);
+            }
         }
+        Some(checked)
+    }
 
-        // Handle leading/trailing whitespace - preserve it outside the markers
-        let leading_ws: String = text
-            .chars()
-            .take_while(|c| c.is_whitespace() && *c != '\n')
-            .collect();
-        let trailing_ws: String = text
-            .chars()
-            .rev()
-            .take_while(|c| c.is_whitespace() && *c != '\n')
-            .collect::<String>()
+    /// Removes the page number after the last tab of a table of contents
+    /// entry, along with the tab.
+    fn strip_toc_page_number(nodes: &mut Vec<InlineNode>) {
+        let text = plain_text(nodes);
+        let Some((_, page)) = text.rsplit_once('\t') else {
+            return;
+        };
+        let is_page_number = page
+            .trim()
             .chars()
-            .rev()
-            .collect();
-
-        let content_start = leading_ws.len();
-        let content_end = text.len() - trailing_ws.len();
-        let content = &text[content_start..content_end];
-
-        // If content contains newlines, apply formatting to each non-empty line
-        if content.contains('\n') {
-            let formatted: Vec<String> = content
-                .split('\n')
-                .map(|line| {
-                    let line_trimmed = line.trim();
-                    if line_trimmed.is_empty() {
-                        line.to_string()
-                    } else {
-                        // Preserve line's own leading/trailing whitespace
-                        let line_leading: String =
-                            line.chars().take_while(|c| c.is_whitespace()).collect();
-                        let line_trailing: String = line
-                            .chars()
-                            .rev()
-                            .take_while(|c| c.is_whitespace())
-                            .collect::<String>()
-                            .chars()
-                            .rev()
-                            .collect();
-                        format!(
-                            "{}{}{}{}{}",
-                            line_leading, open, line_trimmed, close, line_trailing
-                        )
-                    }
-                })
-                .collect();
-            return format!("{}{}{}", leading_ws, formatted.join("\n"), trailing_ws);
+            .all(|c| c.is_ascii_digit() || c == '-' || "ivxlcdmIVXLCDM".contains(c));
+        if is_page_number {
+            Self::remove_trailing_chars(nodes, page.chars().count() + 1);
         }
-
-        // Normal case: wrap content with markers, preserve outer whitespace
-        format!(
-            "{}{}{}{}{}",
-            leading_ws,
-            open,
-            content.trim(),
-            close,
-            trailing_ws
-        )
     }
 
-    /// Converts segments to markdown text.
-    fn segments_to_markdown(
-        segments: &[FormattedSegment],
-        context: &ConversionContext<'_>,
-    ) -> String {
-        let mut result = String::new();
-
-        for seg in segments {
-            // Render anchor if present
-            if let Some(anchor) = &seg.anchor {
-                result.push_str(&format!("<a id=\"{}\"></a>", anchor));
-            }
-
-            let mut text = seg.text.clone();
-
-            // Apply track changes formatting first
-            if seg.is_deletion {
-                // Deleted text: strikethrough
-                text = Self::apply_format_safely(&text, "~~", "~~");
-            }
-            if seg.is_insertion {
-                // Inserted text: HTML ins tag or underline
-                text = format!("<ins>{}</ins>", text);
-            }
-
-            // Apply regular formatting
-            if seg.has_underline && context.html_underline_enabled() && !seg.is_insertion {
-                text = format!("<u>{}</u>", text);
-            }
-
-            if seg.has_strike && !seg.is_deletion {
-                if context.html_strikethrough_enabled() {
-                    text = format!("<s>{}</s>", text);
-                } else {
-                    text = Self::apply_format_safely(&text, "~~", "~~");
+    /// Removes `count` characters of text from the end of `nodes`, looking
+    /// into links and content controls. Returns how many were left to remove.
+    fn remove_trailing_chars(nodes: &mut Vec<InlineNode>, mut count: usize) -> usize {
+        let mut index = nodes.len();
+        while count > 0 && index > 0 {
+            index -= 1;
+            match &mut nodes[index] {
+                InlineNode::Text { text, .. } => {
+                    let len = text.chars().count();
+                    if len <= count {
+                        count -= len;
+                        nodes.remove(index);
+                    } else {
+                        let end = text
+                            .char_indices()
+                            .nth(len - count)
+                            .map_or(text.len(), |(i, _)| i);
+                        text.truncate(end);
+                        count = 0;
+                    }
                 }
+                InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                    count = Self::remove_trailing_chars(content, count);
+                    if content.is_empty() {
+                        nodes.remove(index);
+                    }
+                }
+                _ => {}
             }
-
-            if seg.is_bold && seg.is_italic {
-                text = format!("<strong><em>{}</em></strong>", text);
-            } else if seg.is_bold {
-                text = format!("<strong>{}</strong>", text);
-            } else if seg.is_italic {
-                text = format!("<em>{}</em>", text);
-            }
-
-            result.push_str(&text);
         }
-
-        result
+        count
     }
 
-    /// Applies paragraph-level formatting (heading, list, alignment).
-    fn apply_paragraph_formatting<'a>(
-        para: &Paragraph<'a>,
-        text: String,
-        context: &mut ConversionContext<'a>,
-    ) -> Result<String> {
-        let para_style_id = para
-            .property
-            .as_ref()
-            .and_then(|p| p.style_id.as_ref())
-            .map(|s| s.value.as_ref());
-
-        // Resolve effective paragraph properties
-        let effective_props =
-            context.resolve_paragraph_property(para.property.as_ref(), para_style_id);
-
-        let mut prefix = String::new();
-        let mut is_heading = false;
-
-        // Check for heading via pStyle
-        if let Some(style) = &effective_props.style_id {
-            if let Some(heading_level) = crate::localization::parse_heading_style(&style.value) {
-                // Don't generate heading for empty text
-                if text.trim().is_empty() {
-                    return Ok(String::new());
+    /// Removes bold formatting from text, including link labels.
+    fn clear_bold(nodes: &mut [InlineNode]) {
+        for node in nodes {
+            match node {
+                InlineNode::Text { style, .. } => style.bold = false,
+                InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                    Self::clear_bold(content)
                 }
-                prefix.push_str(&"#".repeat(heading_level));
-                prefix.push(' ');
-                is_heading = true;
+                _ => {}
             }
         }
+    }
 
-        // Check for numbering (list items)
-        if let Some(num_pr) = &effective_props.numbering {
-            if let (Some(num_id), Some(ilvl)) = (&num_pr.id, &num_pr.level) {
-                let num_id_val = num_id.value as i32;
-                let ilvl_val = ilvl.value as i32;
-                let marker = context.next_list_marker(num_id_val, ilvl_val);
-
-                if is_heading {
-                    prefix.push_str(&marker);
-                    if !marker.is_empty() {
-                        prefix.push(' ');
-                    }
-                } else {
-                    let indent = context.list_indent_level(num_id_val, ilvl_val);
-                    let indent_str = "  ".repeat(indent);
-                    prefix.push_str(&indent_str);
-                    prefix.push_str(&marker);
-                    prefix.push(' ');
-                }
-            }
+    /// Returns the list marker of a numbered paragraph, advancing its
+    /// numbering. A `task` state marks it as a task item, making a bullet
+    /// item of an unnumbered paragraph.
+    fn list_marker(
+        effective_props: &rs_docx::formatting::ParagraphProperty<'_>,
+        context: &mut ConversionContext<'_>,
+        task: Option<bool>,
+    ) -> Option<ListMarker> {
+        match Self::next_list_marker(effective_props, context) {
+            Some(marker) => Some(ListMarker {
+                checked: task,
+                ..marker
+            }),
+            None => task.map(|checked| ListMarker {
+                label: "-".to_string(),
+                number: None,
+                level: 0,
+                separator: " ",
+                indent: None,
+                checked: Some(checked),
+            }),
         }
+    }
 
-        let text_for_output = if context.preserve_whitespace() {
-            text.as_str()
-        } else {
-            text.trim()
+    /// Label of a numbered heading followed by its separator: the number
+    /// alone (`3. `) with Markdown markers, else the label Word displays.
+    /// Bullets are left out.
+    fn heading_label(marker: ListMarker, style: ListMarkerStyle) -> String {
+        let Some(number) = marker.number else {
+            return String::new();
         };
-        let final_text = format!("{}{}", prefix, text_for_output);
-
-        // Check for text alignment (only if not heading)
-        if !is_heading {
-            if let Some(jc) = &effective_props.justification {
-                match &jc.value {
-                    rs_docx::formatting::JustificationVal::Center => {
-                        return Ok(format!(
-                            "<div style=\"text-align: center;\">{}</div>",
-                            final_text
-                        ));
-                    }
-                    rs_docx::formatting::JustificationVal::Right => {
-                        return Ok(format!(
-                            "<div style=\"text-align: right;\">{}</div>",
-                            final_text
-                        ));
-                    }
-                    _ => {}
-                }
+        match style {
+            ListMarkerStyle::Markdown => format!("{}. ", number),
+            ListMarkerStyle::Original | ListMarkerStyle::MarkdownWithLabel
+                if !marker.label.is_empty() =>
+            {
+                format!("{}{}", marker.label, marker.separator)
             }
+            _ => String::new(),
         }
+    }
 
-        Ok(final_text)
+    /// Advances the numbering of a numbered paragraph, returning its marker.
+    /// `w:numId="0"` removes numbering; a missing ilvl comes from the level
+    /// linked to the paragraph style, else level 0.
+    fn next_list_marker(
+        effective_props: &rs_docx::formatting::ParagraphProperty<'_>,
+        context: &mut ConversionContext<'_>,
+    ) -> Option<ListMarker> {
+        let num_pr = effective_props.numbering.as_ref()?;
+        let num_id = num_pr
+            .id
+            .as_ref()
+            .map(|id| id.value as i32)
+            .filter(|&id| id != 0)?;
+        let ilvl = num_pr
+            .level
+            .as_ref()
+            .map(|ilvl| ilvl.value as i32)
+            .or_else(|| {
+                effective_props
+                    .style_id
+                    .as_ref()
+                    .and_then(|style| context.list_level_for_style(num_id, &style.value))
+            })
+            .unwrap_or(0);
+        Some(context.next_list_marker(num_id, ilvl))
     }
 
-    /// Converts a hyperlink to Markdown format.
+    /// Returns the heading level from the paragraph style, falling back to
+    /// the inferred level.
+    fn heading_level(
+        props: &rs_docx::formatting::ParagraphProperty<'_>,
+        inferred_heading: Option<usize>,
+    ) -> Option<usize> {
+        props
+            .style_id
+            .as_ref()
+            .and_then(|style| crate::localization::parse_heading_style(&style.value))
+            .or(inferred_heading)
+    }
+
+    /// Returns the alignment of centered and right aligned paragraphs.
+    fn alignment(props: &rs_docx::formatting::ParagraphProperty<'_>) -> Option<Alignment> {
+        match props.justification.as_ref().map(|jc| &jc.value) {
+            Some(rs_docx::formatting::JustificationVal::Center) => Some(Alignment::Center),
+            Some(rs_docx::formatting::JustificationVal::Right) => Some(Alignment::Right),
+            _ => None,
+        }
+    }
+
+    /// Converts a hyperlink to a link node.
     fn convert_hyperlink<'a>(
         hyperlink: &Hyperlink<'a>,
         context: &mut ConversionContext<'a>,
         para_style_id: Option<&str>,
-    ) -> Result<String> {
-        let mut link_text = String::new();
-        let mut field_stack = Vec::new();
+    ) -> InlineNode {
+        let mut content = Vec::new();
+        let mut fields = Vec::new();
 
         for run in &hyperlink.content {
-            let filtered_run = Self::filter_run_by_field_state(run, &mut field_stack);
-            if filtered_run.content.is_empty() {
-                continue;
-            }
-
-            let text = RunConverter::convert(&filtered_run, context, para_style_id)?;
-            link_text.push_str(&text);
+            Self::collect_run_inlines(run, context, para_style_id, &mut fields, &mut content);
         }
+        Self::end_open_fields(&mut fields, context, &mut content);
+        // Links may not nest
+        let content = Self::unwrap_links(content);
 
         // Get target URL from relationship or anchor
-        let url = if let Some(anchor) = &hyperlink.anchor {
+        let destination = if let Some(anchor) = &hyperlink.anchor {
             // Internal bookmark link (used in TOC entries)
-            format!("#{}", escape_markdown_link_destination(anchor))
+            format!("#{}", anchor)
         } else if let Some(id) = &hyperlink.id {
             // External link via relationship
             context
@@ -636,14 +809,9 @@ impl ParagraphConverter {
             "#".to_string()
         };
 
-        if link_text.is_empty() {
-            Ok(url)
-        } else {
-            Ok(format!(
-                "[{}]({})",
-                escape_markdown_link_text(&link_text),
-                escape_markdown_link_destination(&url)
-            ))
+        InlineNode::Link {
+            destination,
+            content: Self::merge_text_nodes(content),
         }
     }
 }
@@ -655,6 +823,29 @@ mod tests {
     use std::borrow::Cow;
     use std::collections::HashMap;
 
+    /// Builds the context converting `docx` with `options`, without
+    /// relationships or images. Its resolvers are leaked so that it can
+    /// outlive this function.
+    fn context<'a>(
+        docx: &'a rs_docx::Docx,
+        options: &'a crate::ConvertOptions,
+    ) -> ConversionContext<'a> {
+        let rels = Box::leak(Box::new(HashMap::new()));
+        let numbering_resolver = Box::leak(Box::new(super::super::NumberingResolver::new(docx)));
+        let image_extractor = Box::leak(Box::new(super::super::ImageExtractor::new_skip()));
+        let style_resolver = Box::leak(Box::new(super::super::StyleResolver::new(&docx.styles)));
+        ConversionContext::new(
+            rels,
+            numbering_resolver,
+            image_extractor,
+            options,
+            docx.comments.as_ref(),
+            docx.footnotes.as_ref(),
+            docx.endnotes.as_ref(),
+            style_resolver,
+        )
+    }
+
     #[test]
     fn test_toc_anchor_link() {
         // Create a paragraph with a hyperlink having an anchor
@@ -677,22 +868,9 @@ mod tests {
 
         // Setup minimal context
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         // Convert
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
@@ -724,22 +902,9 @@ mod tests {
 
         // Setup minimal context
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         // Convert
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
@@ -778,22 +943,9 @@ mod tests {
 
         // Setup mock context
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         // Convert
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
@@ -831,22 +983,9 @@ mod tests {
 
         // Setup minimal context
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         // Convert
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
@@ -866,32 +1005,19 @@ mod tests {
         para.content.push(ParagraphContent::Run(run));
 
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions {
             preserve_whitespace: true,
             ..Default::default()
         };
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
         assert_eq!(md, "  Keep Surrounding Spaces  ");
     }
 
     #[test]
-    fn test_deep_list_indentation_not_clamped() {
+    fn test_deep_list_keeps_level_and_starts_at_first_column() {
         use rs_docx::document::{
             AbstractNum, AbstractNumId, Level, LevelStart, LevelText, Num, NumFmt, Numbering,
         };
@@ -940,25 +1066,127 @@ mod tests {
             ..Default::default()
         };
 
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
-        );
 
-        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
-        assert_eq!(md, "      1. Deep Item");
+        let mut context = context(&docx, &options);
+
+        let block =
+            ParagraphConverter::convert_block(&para, &mut context).expect("Conversion failed");
+        assert!(matches!(&block, BlockNode::List(list) if list.level == 3));
+        assert_eq!(context.render_markdown(&[block]), "1. Deep Item");
+    }
+
+    #[test]
+    fn test_numbered_heading_labels_follow_list_markers() {
+        use rs_docx::document::{
+            AbstractNum, AbstractNumId, Level, LevelStart, LevelText, Num, NumFmt, Numbering,
+        };
+        use rs_docx::formatting::{NumberingProperty, ParagraphProperty, ParagraphStyleId};
+
+        let level = |format: &'static str, text: &'static str| Level {
+            i_level: Some(0),
+            start: Some(LevelStart { value: Some(3) }),
+            number_format: Some(NumFmt {
+                value: Cow::Borrowed(format),
+            }),
+            level_text: Some(LevelText {
+                value: Some(Cow::Borrowed(text)),
+            }),
+            ..Default::default()
+        };
+        let docx = rs_docx::Docx {
+            numbering: Some(Numbering {
+                abstract_numberings: vec![
+                    AbstractNum {
+                        abstract_num_id: Some(1),
+                        levels: vec![level("bullet", "•")],
+                        ..Default::default()
+                    },
+                    AbstractNum {
+                        abstract_num_id: Some(2),
+                        levels: vec![level("decimal", "Article %1")],
+                        ..Default::default()
+                    },
+                ],
+                numberings: (1..=2)
+                    .map(|id| Num {
+                        num_id: Some(id),
+                        abstract_num_id: Some(AbstractNumId { value: Some(id) }),
+                        ..Default::default()
+                    })
+                    .collect(),
+            }),
+            ..Default::default()
+        };
+        let heading = |num_id: isize, text: &'static str| {
+            Paragraph::default()
+                .property(ParagraphProperty {
+                    style_id: Some(ParagraphStyleId {
+                        value: "Heading1".into(),
+                    }),
+                    numbering: Some(NumberingProperty::from((num_id, 0isize))),
+                    ..Default::default()
+                })
+                .push_text(text)
+        };
+
+        for (list_markers, expected) in [
+            (crate::ListMarkerStyle::Markdown, "# 3. Scope"),
+            (
+                crate::ListMarkerStyle::MarkdownWithLabel,
+                "# Article 3 Scope",
+            ),
+            (crate::ListMarkerStyle::Original, "# Article 3 Scope"),
+        ] {
+            let options = crate::ConvertOptions {
+                list_markers,
+                ..Default::default()
+            };
+            let mut context = context(&docx, &options);
+
+            let bullet = ParagraphConverter::convert(&heading(1, "Purpose"), &mut context)
+                .expect("Conversion failed");
+            assert_eq!(bullet, "# Purpose");
+            let numbered = ParagraphConverter::convert(&heading(2, "Scope"), &mut context)
+                .expect("Conversion failed");
+            assert_eq!(numbered, expected);
+        }
+    }
+
+    #[test]
+    fn test_inferred_heading_drops_bold_markup() {
+        use rs_docx::document::BodyContent;
+        use rs_docx::formatting::{Bold, CharacterProperty, KeepNext, ParagraphProperty};
+
+        let heading = Paragraph::default()
+            .property(ParagraphProperty {
+                keep_next: Some(KeepNext { value: None }),
+                ..Default::default()
+            })
+            .push(
+                Run::default()
+                    .property(CharacterProperty::default().bold(Bold::from(true)))
+                    .push_text("Scope"),
+            );
+        let body_para = Paragraph::default().push_text("Regular body text of the document.");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            infer_headings: true,
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+        let body = vec![
+            BodyContent::Paragraph(heading.clone()),
+            BodyContent::Paragraph(body_para.clone()),
+        ];
+        context.analyze_headings(&body);
+
+        let md = ParagraphConverter::convert(&heading, &mut context).expect("Conversion failed");
+        assert_eq!(md, "# Scope");
+        let md = ParagraphConverter::convert(&body_para, &mut context).expect("Conversion failed");
+        assert_eq!(md, "Regular body text of the document.");
     }
 
     #[test]
@@ -1000,22 +1228,9 @@ mod tests {
             }));
         para.content.push(ParagraphContent::Run(run2));
 
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            docx.footnotes.as_ref(),
-            None,
-            &style_resolver,
-        );
+
+        let mut context = context(&docx, &options);
 
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
         assert_eq!(md, "[^1][^1]");
@@ -1061,22 +1276,9 @@ mod tests {
             }));
         para.content.push(ParagraphContent::Run(run2));
 
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
 
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            docx.comments.as_ref(),
-            None,
-            None,
-            &style_resolver,
-        );
+        let mut context = context(&docx, &options);
 
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
         assert_eq!(md, "[^c9][^c9]");
@@ -1105,24 +1307,362 @@ mod tests {
         para.content.push(ParagraphContent::Run(run));
 
         let docx = rs_docx::Docx::default();
-        let rels = HashMap::new();
-        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
-        let mut image_extractor = super::super::ImageExtractor::new_skip();
         let options = crate::ConvertOptions::default();
-        let style_resolver = super::super::StyleResolver::new(&docx.styles);
-
-        let mut context = super::ConversionContext::new(
-            &rels,
-            &mut numbering_resolver,
-            &mut image_extractor,
-            &options,
-            None,
-            None,
-            None,
-            &style_resolver,
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(md, "prefix [Visible](#_Ref) suffix");
+    }
+
+    #[test]
+    fn test_field_codes_become_links_and_numbers() {
+        use hard_xml::XmlRead;
+
+        let field = |instr: &str, result: &str| {
+            format!(
+                r#"<w:r><w:fldChar w:fldCharType="begin"/></w:r><w:r><w:instrText xml:space="preserve">{}</w:instrText></w:r><w:r><w:fldChar w:fldCharType="separate"/></w:r>{}<w:r><w:fldChar w:fldCharType="end"/></w:r>"#,
+                instr, result
+            )
+        };
+        let text = |t: &str| format!(r#"<w:r><w:t xml:space="preserve">{}</w:t></w:r>"#, t);
+        let paragraph = |runs: Vec<String>| format!("<w:p>{}</w:p>", runs.concat());
+
+        let links = paragraph(vec![
+            field(r#" HYPERLINK "https://example.com" "#, &text("site")),
+            text(", "),
+            field(
+                r#" HYPERLINK \l "_Toc1" "#,
+                &(text("Intro ") + &field(r" PAGEREF _Toc1 \h ", &text("3"))),
+            ),
+            text(", see "),
+            field(r" REF _Ref42 \h ", &text("Table 1")),
+        ]);
+        let captions = paragraph(vec![
+            text("Figure "),
+            field(r" SEQ Figure \* ARABIC ", &text("9")),
+            text(", Figure "),
+            field(r" SEQ Figure ", &text("9")),
+            text(", Table "),
+            field(r" SEQ Table \r 4 \* ROMAN ", &text("1")),
+        ]);
+        let merge = paragraph(vec![
+            text("Dear "),
+            field(" MERGEFIELD FirstName ", &text("«FirstName»")),
+            // Fields nested in an instruction are not shown
+            r#"<w:r><w:fldChar w:fldCharType="begin"/><w:instrText xml:space="preserve"> IF </w:instrText></w:r>"#.to_string(),
+            field(" MERGEFIELD Title ", &text("Dr")),
+            r#"<w:r><w:instrText xml:space="preserve"> = "Dr" "Doctor" "" </w:instrText><w:fldChar w:fldCharType="separate"/><w:fldChar w:fldCharType="end"/></w:r>"#.to_string(),
+        ]);
+        let links = Paragraph::from_str(&links).expect("valid paragraph");
+        let captions = Paragraph::from_str(&captions).expect("valid paragraph");
+        let merge = Paragraph::from_str(&merge).expect("valid paragraph");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            merge_field_placeholders: true,
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&links, &mut context).expect("Conversion failed");
+        assert_eq!(
+            md,
+            "[site](https://example.com), [Intro 3](#_Toc1), see [Table 1](#_Ref42)"
+        );
+        let md = ParagraphConverter::convert(&captions, &mut context).expect("Conversion failed");
+        assert_eq!(md, "Figure 1, Figure 2, Table IV");
+        let md = ParagraphConverter::convert(&merge, &mut context).expect("Conversion failed");
+        assert_eq!(md, "Dear {{FirstName}}");
+    }
+
+    #[test]
+    fn test_extended_character_formatting() {
+        use hard_xml::XmlRead;
+
+        let mut para = Paragraph::default();
+        for xml in [
+            r#"<w:r><w:t xml:space="preserve">E=mc</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:vertAlign w:val="superscript"/></w:rPr><w:t>2</w:t></w:r>"#,
+            r#"<w:r><w:t xml:space="preserve"> H</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:vertAlign w:val="subscript"/></w:rPr><w:t>2</w:t></w:r>"#,
+            r#"<w:r><w:t xml:space="preserve">O </w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:highlight w:val="yellow"/></w:rPr><w:t>marked</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:vanish/></w:rPr><w:t>secret</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:smallCaps/></w:rPr><w:t xml:space="preserve"> Small Caps</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:caps/></w:rPr><w:t xml:space="preserve"> caps</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:dstrike/></w:rPr><w:t xml:space="preserve"> gone</w:t></w:r>"#,
+        ] {
+            para.content.push(ParagraphContent::Run(
+                Run::from_str(xml).expect("valid run"),
+            ));
+        }
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions::default();
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(
+            md,
+            "E=mc<sup>2</sup> H<sub>2</sub>O <mark>marked</mark> Small Caps CAPS ~~gone~~"
+        );
+    }
+
+    #[test]
+    fn test_extended_character_formatting_markdown_syntax() {
+        use hard_xml::XmlRead;
+
+        let mut para = Paragraph::default();
+        for xml in [
+            r#"<w:r><w:rPr><w:vertAlign w:val="superscript"/></w:rPr><w:t>th</w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:highlight w:val="green"/></w:rPr><w:t xml:space="preserve"> key </w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:color w:val="FF0000"/><w:vanish/></w:rPr><w:t>shown</w:t></w:r>"#,
+        ] {
+            para.content.push(ParagraphContent::Run(
+                Run::from_str(xml).expect("valid run"),
+            ));
+        }
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            html_sub_superscript: false,
+            html_highlight: false,
+            html_text_color: true,
+            include_hidden_text: true,
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(
+            md,
+            "^th^ ==key== <span style=\"color: #FF0000;\">shown</span>"
+        );
+    }
+
+    #[test]
+    fn test_hyperlink_text_shares_inline_formatting() {
+        use hard_xml::XmlRead;
+
+        let mut para = Paragraph::default();
+        para.content.push(ParagraphContent::Run(
+            Run::from_str(
+                r#"<w:r><w:rPr><w:i/></w:rPr><w:t xml:space="preserve">Read </w:t></w:r>"#,
+            )
+            .expect("valid run"),
+        ));
+        para.content.push(ParagraphContent::Link(
+            Hyperlink::from_str(
+                r#"<w:hyperlink w:anchor="intro"><w:r><w:rPr><w:b/></w:rPr><w:t>the intro</w:t></w:r></w:hyperlink>"#,
+            )
+            .expect("valid hyperlink"),
+        ));
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            emphasis_style: crate::EmphasisStyle::Asterisk,
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(md, "*Read* [**the intro**](#intro)");
+    }
+
+    #[test]
+    fn test_literal_text_is_escaped_by_context() {
+        use rs_docx::formatting::{Justification, JustificationVal, ParagraphProperty};
+
+        let heading_like = Paragraph::default().push_text("# 1. not *bold* <b>");
+        let centered = Paragraph::default()
+            .property(ParagraphProperty::default().justification(Justification {
+                value: JustificationVal::Center,
+            }))
+            .push_text("a < b & <c>");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions::default();
+
+        let mut context = context(&docx, &options);
+
+        let md =
+            ParagraphConverter::convert(&heading_like, &mut context).expect("Conversion failed");
+        assert_eq!(md, "\\# 1. not \\*bold\\* \\<b>");
+        let md = ParagraphConverter::convert(&centered, &mut context).expect("Conversion failed");
+        assert_eq!(
+            md,
+            "<div style=\"text-align: center;\">a &lt; b &amp; &lt;c&gt;</div>"
+        );
+    }
+
+    #[test]
+    fn test_escaping_can_be_disabled() {
+        let para = Paragraph::default().push_text("# raw *markdown*");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            escape_markdown: false,
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(md, "# raw *markdown*");
+    }
+
+    #[test]
+    fn test_pandoc_definition_list_and_heading_id() {
+        use rs_docx::document::BookmarkStart;
+        use rs_docx::formatting::{ParagraphProperty, ParagraphStyleId};
+
+        let styled = |style: &'static str, text: &'static str| {
+            Paragraph::default()
+                .property(ParagraphProperty {
+                    style_id: Some(ParagraphStyleId {
+                        value: style.into(),
+                    }),
+                    ..Default::default()
+                })
+                .push_text(text)
+        };
+        let mut heading = styled("Heading2", "Terms");
+        heading.content.insert(
+            0,
+            ParagraphContent::BookmarkStart(BookmarkStart {
+                name: Some(Cow::Borrowed("terms")),
+                ..Default::default()
+            }),
+        );
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions {
+            flavor: Some(crate::MarkdownFlavor::Pandoc),
+            ..Default::default()
+        };
+
+        let mut context = context(&docx, &options);
+
+        let blocks = [
+            heading,
+            styled("DefinitionTerm", "Apple"),
+            styled("Definition", "A fruit."),
+            styled("Definition", "Grows on trees."),
+        ]
+        .iter()
+        .map(|para| ParagraphConverter::convert_block(para, &mut context))
+        .collect::<Result<Vec<_>>>()
+        .expect("Conversion failed");
+        assert_eq!(
+            context.render_markdown(&blocks),
+            "## Terms {#terms}\n\nApple\n\n:   A fruit.\n\n    Grows on trees."
+        );
+    }
+
+    #[test]
+    fn test_symbol_font_characters_map_to_unicode() {
+        use hard_xml::XmlRead;
+
+        let mut para = Paragraph::default();
+        for xml in [
+            r#"<w:r><w:sym w:font="Wingdings" w:char="F0FC"/></w:r>"#,
+            r#"<w:r><w:t xml:space="preserve"> done </w:t></w:r>"#,
+            r#"<w:r><w:rPr><w:rFonts w:ascii="Symbol" w:hAnsi="Symbol"/></w:rPr><w:t>a</w:t></w:r>"#,
+            r#"<w:r><w:sym w:font="Calibri" w:char="2192"/></w:r>"#,
+        ] {
+            para.content.push(ParagraphContent::Run(
+                Run::from_str(xml).expect("valid run"),
+            ));
+        }
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions::default();
+
+        let mut context = context(&docx, &options);
+
+        let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
+        assert_eq!(md, "✔ done α→");
+    }
+
+    #[test]
+    fn test_checkboxes_and_form_fields() {
+        use super::super::form_fields::FormControls;
+        use hard_xml::XmlRead;
+
+        let task_xml = r#"<w:p><w:sdt><w:sdtPr><w:id w:val="5"/><w14:checkbox><w14:checked w14:val="1"/></w14:checkbox></w:sdtPr><w:sdtContent><w:r><w:t>☒</w:t></w:r></w:sdtContent></w:sdt><w:r><w:t xml:space="preserve"> Buy milk</w:t></w:r></w:p>"#;
+        let form_xml = r#"<w:p><w:r><w:t xml:space="preserve">Color: </w:t></w:r><w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:ddList><w:result w:val="1"/><w:listEntry w:val="Red"/><w:listEntry w:val="Green"/></w:ddList></w:ffData></w:fldChar></w:r><w:r><w:instrText xml:space="preserve"> FORMDROPDOWN </w:instrText></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r><w:r><w:t xml:space="preserve">, agreed </w:t></w:r><w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:checkBox><w:default w:val="0"/></w:checkBox></w:ffData></w:fldChar></w:r><w:r><w:instrText xml:space="preserve"> FORMCHECKBOX </w:instrText></w:r><w:r><w:fldChar w:fldCharType="end"/></w:r></w:p>"#;
+        let task = Paragraph::from_str(task_xml).expect("valid paragraph");
+        let form = Paragraph::from_str(form_xml).expect("valid paragraph");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions::default();
+
+        let mut context = context(&docx, &options);
+        context.set_form_controls(FormControls::parse(&format!(
+            "<w:body>{}{}</w:body>",
+            task_xml, form_xml
+        )));
+
+        let (text, placement) =
+            ParagraphConverter::convert_list_item(&task, &mut context).expect("Conversion failed");
+        assert_eq!(plain_text(&text.content), "Buy milk");
+        assert!(matches!(
+            placement,
+            ListPlacement::Item(ListMarker { checked: Some(true), ref label, .. }) if label == "-"
+        ));
+
+        let md = ParagraphConverter::convert(&form, &mut context).expect("Conversion failed");
+        assert_eq!(md, "Color: Green, agreed ☐");
+    }
+
+    #[test]
+    fn test_content_control_metadata_markup() {
+        use super::super::form_fields::FormControls;
+        use hard_xml::XmlRead;
+
+        let xml = r#"<w:p><w:r><w:t xml:space="preserve">Client: </w:t></w:r><w:sdt><w:sdtPr><w:alias w:val="Client"/><w:tag w:val="client_name"/><w:id w:val="9"/><w:text/></w:sdtPr><w:sdtContent><w:r><w:t>Acme</w:t></w:r></w:sdtContent></w:sdt></w:p>"#;
+        let para = Paragraph::from_str(xml).expect("valid paragraph");
+
+        let docx = rs_docx::Docx::default();
+        let convert = |content_controls| {
+            let options = crate::ConvertOptions {
+                content_controls,
+                ..Default::default()
+            };
+            let mut context = context(&docx, &options);
+            context.set_form_controls(FormControls::parse(xml));
+            ParagraphConverter::convert(&para, &mut context).expect("Conversion failed")
+        };
+
+        assert_eq!(convert(ContentControlMarkup::Flatten), "Client: Acme");
+        assert_eq!(
+            convert(ContentControlMarkup::Comments),
+            "Client: <!-- sdt tag=\"client_name\" alias=\"Client\" type=\"text\" -->Acme<!-- /sdt -->"
         );
+        assert_eq!(
+            convert(ContentControlMarkup::Spans),
+            "Client: <span data-tag=\"client_name\" data-alias=\"Client\" data-type=\"text\">Acme</span>"
+        );
+    }
+
+    #[test]
+    fn test_toc_entries_drop_page_numbers() {
+        use hard_xml::XmlRead;
+
+        let xml = r#"<w:p><w:hyperlink w:anchor="_Toc1"><w:r><w:t>1 Intro</w:t></w:r><w:r><w:tab/></w:r><w:r><w:t>iv</w:t></w:r></w:hyperlink></w:p>"#;
+        let para = Paragraph::from_str(xml).expect("valid paragraph");
+
+        let docx = rs_docx::Docx::default();
+        let options = crate::ConvertOptions::default();
+        let mut context = context(&docx, &options);
 
+        context.set_in_toc_entry(true);
         let md = ParagraphConverter::convert(&para, &mut context).expect("Conversion failed");
-        assert_eq!(md, "prefix Visible suffix");
+        assert_eq!(md, "[1 Intro](#_Toc1)");
     }
 }
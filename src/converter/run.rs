@@ -1,8 +1,12 @@
 //! Run element converter - handles text runs with formatting.
 
+use super::symbol_fonts::{is_symbol_font, map_symbol_char, map_symbol_text};
 use super::ConversionContext;
+use crate::core::ast::{InlineNode, TextStyle};
+use crate::render::TextContext;
 use crate::Result;
 use rs_docx::document::{BreakType, Run, RunContent};
+use rs_docx::formatting::{CharacterProperty, HighlightType, VertAlignType};
 
 /// Converter for Run elements.
 pub struct RunConverter;
@@ -14,17 +18,64 @@ impl RunConverter {
         context: &mut ConversionContext<'a>,
         para_style_id: Option<&str>,
     ) -> Result<String> {
+        let nodes = Self::to_inlines(run, context, para_style_id);
+        Ok(context.render_inlines(&nodes, TextContext::Block))
+    }
+
+    /// Converts a Run to inline nodes. Hidden runs yield no nodes unless
+    /// hidden text is kept.
+    pub fn to_inlines<'a>(
+        run: &Run<'a>,
+        context: &mut ConversionContext<'a>,
+        para_style_id: Option<&str>,
+    ) -> Vec<InlineNode> {
+        // Run Style ID
+        let run_style_id = run
+            .property
+            .as_ref()
+            .and_then(|props| props.style_id.as_ref())
+            .map(|style| style.value.as_ref());
+
+        // Check formatting via resolver
+        let effective_props =
+            context.resolve_run_property(run.property.as_ref(), run_style_id, para_style_id);
+
+        let is_hidden = effective_props
+            .vanish
+            .as_ref()
+            .map(|v| v.value.unwrap_or(true))
+            .unwrap_or(false);
+        if is_hidden && !context.include_hidden_text() {
+            return Vec::new();
+        }
+
+        let style = Self::text_style(&effective_props);
+        let symbol_font = effective_props
+            .fonts
+            .as_ref()
+            .and_then(|fonts| fonts.ascii.as_deref().or(fonts.h_ansi.as_deref()))
+            .filter(|font| is_symbol_font(font));
+        let mut nodes = Vec::new();
         let mut text = String::new();
+        let mut push_node = |text: &mut String, node: InlineNode| {
+            if !text.is_empty() {
+                nodes.push(InlineNode::Text {
+                    text: std::mem::take(text),
+                    style: style.clone(),
+                });
+            }
+            nodes.push(node);
+        };
 
-        // Extract text from run content
+        // Extract text from run content; field instructions are never rendered
         for content in &run.content {
             match content {
-                RunContent::Text(t) => {
-                    text.push_str(&t.text);
-                }
+                RunContent::Text(t) => match symbol_font {
+                    Some(font) => text.push_str(&map_symbol_text(font, &t.text)),
+                    None => text.push_str(&t.text),
+                },
                 RunContent::Break(br) => match br.ty {
-                    Some(BreakType::Page) => text.push_str("\n\n---\n\n"),
-                    Some(BreakType::Column) => text.push_str("\n\n"),
+                    Some(BreakType::Page) => push_node(&mut text, InlineNode::PageBreak),
                     _ => text.push('\n'),
                 },
                 RunContent::Tab(_) => {
@@ -35,129 +86,95 @@ impl RunConverter {
                 }
                 RunContent::Drawing(drawing) => {
                     // Handle inline images (DrawingML)
-                    if let Some(img_md) = context.extract_image_from_drawing(drawing)? {
-                        text.push_str(&img_md);
+                    if let Ok(Some(image)) = context.extract_image_from_drawing(drawing) {
+                        push_node(&mut text, image);
                     }
                 }
                 RunContent::Pict(pict) => {
                     // Handle legacy images (VML)
-                    if let Some(img_md) = context.extract_image_from_pict(pict)? {
-                        text.push_str(&img_md);
+                    if let Ok(Some(image)) = context.extract_image_from_pict(pict) {
+                        push_node(&mut text, image);
                     }
                 }
                 RunContent::Sym(sym) => {
-                    // Symbol character - use Unicode if possible
-                    if let Some(char_code) = &sym.char {
-                        // Try to decode hex char code
-                        if let Ok(code) = u32::from_str_radix(char_code, 16) {
-                            if let Some(c) = char::from_u32(code) {
-                                text.push(c);
-                            }
-                        }
+                    // Symbol character - map symbol fonts to Unicode, else use the code as is
+                    let code = sym
+                        .char
+                        .as_ref()
+                        .and_then(|code| u32::from_str_radix(code, 16).ok());
+                    if let Some(c) = code.and_then(|code| {
+                        sym.font
+                            .as_ref()
+                            .and_then(|font| map_symbol_char(font, code))
+                            .or_else(|| char::from_u32(code))
+                    }) {
+                        text.push(c);
                     }
                 }
                 RunContent::FootnoteReference(fnref) => {
-                    if let Some(id_str) = &fnref.id {
-                        if let Ok(id_num) = id_str.parse::<isize>() {
-                            let marker = context.register_footnote_reference(id_num);
-                            text.push_str(&marker);
-                        }
+                    if let Some(id_num) = fnref.id.as_ref().and_then(|id| id.parse::<isize>().ok())
+                    {
+                        let reference = context.footnote_reference(id_num);
+                        push_node(&mut text, reference);
                     }
                 }
                 RunContent::EndnoteReference(enref) => {
-                    if let Some(id_str) = &enref.id {
-                        if let Ok(id_num) = id_str.parse::<isize>() {
-                            let marker = context.register_endnote_reference(id_num);
-                            text.push_str(&marker);
-                        }
+                    if let Some(id_num) = enref.id.as_ref().and_then(|id| id.parse::<isize>().ok())
+                    {
+                        let reference = context.endnote_reference(id_num);
+                        push_node(&mut text, reference);
                     }
                 }
                 RunContent::CommentReference(cref) => {
                     // Extract comment ID and look up comment text
                     if let Some(id) = &cref.id {
-                        let marker = context.register_comment_reference(id.as_ref());
-                        text.push_str(&marker);
+                        let reference = context.comment_reference(id.as_ref());
+                        push_node(&mut text, reference);
                     }
                 }
                 _ => {}
             }
         }
-
-        // Apply formatting if text is not empty
-        if text.is_empty() {
-            return Ok(text);
-        }
-
-        // Run Style ID
-        let mut run_style_id = None;
-        if let Some(props) = &run.property {
-            if let Some(style) = &props.style_id {
-                run_style_id = Some(style.value.as_ref());
-            }
+        if !text.is_empty() {
+            nodes.push(InlineNode::Text { text, style });
         }
 
-        // Check formatting via resolver
-        let effective_props =
-            context.resolve_run_property(run.property.as_ref(), run_style_id, para_style_id);
-
-        text = Self::apply_formatting(&text, &effective_props, context);
-
-        Ok(text)
+        nodes
     }
 
-    /// Applies text formatting based on run properties.
-    fn apply_formatting(
-        text: &str,
-        props: &rs_docx::formatting::CharacterProperty<'_>,
-        context: &ConversionContext<'_>,
-    ) -> String {
-        let mut result = text.to_string();
-
-        // Check for bold
-        let is_bold = props
-            .bold
-            .as_ref()
-            .map(|b| b.value.unwrap_or(true))
-            .unwrap_or(false);
-
-        // Check for italic
-        let is_italic = props
-            .italics
-            .as_ref()
-            .map(|i| i.value.unwrap_or(true))
-            .unwrap_or(false);
-
-        // Check for underline
-        let has_underline = props.underline.is_some();
-
-        // Check for strikethrough
-        let has_strike = props
-            .strike
-            .as_ref()
-            .map(|s| s.value.unwrap_or(true))
-            .unwrap_or(false);
-
-        // Apply formatting in order: underline (HTML), strike, bold, italic
-        if has_underline && context.html_underline_enabled() {
-            result = format!("<u>{}</u>", result);
-        }
-
-        if has_strike {
-            if context.html_strikethrough_enabled() {
-                result = format!("<s>{}</s>", result);
-            } else {
-                result = format!("~~{}~~", result);
-            }
-        }
-
-        if is_bold && is_italic {
-            result = format!("<strong>*{}*</strong>", result);
-        } else if is_bold {
-            result = format!("<strong>{}</strong>", result);
-        } else if is_italic {
-            result = format!("*{}*", result);
+    /// Builds the text style for effective run properties.
+    fn text_style(props: &CharacterProperty<'_>) -> TextStyle {
+        let flag = |value: Option<Option<bool>>| value.map(|v| v.unwrap_or(true)).unwrap_or(false);
+        let vert_align = props.vertical_align.as_ref().and_then(|v| v.value.as_ref());
+
+        TextStyle {
+            bold: flag(props.bold.as_ref().map(|b| b.value)),
+            italic: flag(props.italics.as_ref().map(|i| i.value)),
+            underline: props.underline.is_some(),
+            strike: flag(props.strike.as_ref().map(|s| s.value))
+                || flag(props.dstrike.as_ref().map(|s| s.value)),
+            superscript: matches!(vert_align, Some(VertAlignType::Superscript)),
+            subscript: matches!(vert_align, Some(VertAlignType::Subscript)),
+            small_caps: flag(props.small_caps.as_ref().map(|c| c.value)),
+            all_caps: flag(props.caps.as_ref().map(|c| c.value)),
+            highlight: props
+                .highlight
+                .as_ref()
+                .and_then(|h| h.value.as_ref())
+                .is_some_and(|h| !matches!(h, HighlightType::None)),
+            color: props
+                .color
+                .as_ref()
+                .map(|c| c.value.as_ref())
+                .filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case("auto"))
+                .map(|c| {
+                    if c.len() == 6 && c.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                        format!("#{}", c)
+                    } else {
+                        c.to_string()
+                    }
+                }),
+            ..Default::default()
         }
-
-        result
     }
 }
@@ -0,0 +1,342 @@
+//! Splits a converted document into one Markdown file per section.
+//!
+//! Every heading up to the split level starts a file named `NN-slug.md`,
+//! content before the first one goes to `00-front-matter.md`. Links between
+//! files point at the file holding their target, notes go to the files that
+//! reference them, and `index.md` and `SUMMARY.md` (mdBook) list the files.
+
+use super::anchors::{defined_anchors, for_each_block, relink};
+use crate::core::ast::{
+    for_each_note_reference, BlockNode, DocumentAst, NoteKind, ReferenceDefinitions,
+};
+use crate::core::slug::{SlugGenerator, SlugStyle};
+use crate::render::{escape_markdown_link_text, MarkdownRenderer, Renderer};
+use crate::Result;
+use std::collections::HashMap;
+
+/// Markdown file written when splitting a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitFile {
+    /// File name, e.g. `01-introduction.md`.
+    pub name: String,
+    pub content: String,
+}
+
+struct Section {
+    title: String,
+    /// Level of the heading starting the section; 0 for front matter.
+    level: usize,
+    blocks: Vec<BlockNode>,
+}
+
+/// Splits `document` at headings of level `level` and above, followed by
+/// `index.md` and `SUMMARY.md`. `slug_style` names the files; `linked_slugs`
+/// is set when links target headings by slug, so that those links follow
+/// the slugs headings get in their own file.
+pub(crate) fn split_document(
+    document: &DocumentAst,
+    renderer: &MarkdownRenderer,
+    level: usize,
+    slug_style: SlugStyle,
+    linked_slugs: bool,
+) -> Result<Vec<SplitFile>> {
+    let mut sections = sections(document, level);
+    let width = sections.len().to_string().len().max(2);
+    let front_matter = sections.first().is_some_and(|section| section.level == 0);
+    let names = sections
+        .iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let number = if front_matter { i } else { i + 1 };
+            let slug = match section.level {
+                0 => "front-matter".to_string(),
+                _ => slug_style.slugify(&section.title),
+            };
+            let slug = if slug.is_empty() { "section" } else { &slug };
+            format!("{:0width$}-{}.md", number, slug, width = width)
+        })
+        .collect::<Vec<_>>();
+
+    // Anchor names, as linked to, mapped to their file and name there
+    let mut targets = HashMap::new();
+    let mut document_slugs = SlugGenerator::new(slug_style);
+    for (i, section) in sections.iter_mut().enumerate() {
+        let mut file_slugs = SlugGenerator::new(slug_style);
+        for_each_block(&mut section.blocks, &mut |block| {
+            if let BlockNode::Heading(heading) = block {
+                let text = heading.plain_text();
+                let slug = document_slugs.slug(&text);
+                let file_slug = file_slugs.slug(&text);
+                if linked_slugs && heading.id.is_none() {
+                    targets.entry(slug).or_insert((i, file_slug));
+                }
+            }
+        });
+        for name in defined_anchors(&mut section.blocks) {
+            targets.entry(name.clone()).or_insert((i, name));
+        }
+    }
+
+    let mut files = Vec::with_capacity(sections.len() + 2);
+    for (i, section) in sections.iter_mut().enumerate() {
+        let mut retarget = |name: &str| match targets.get(name) {
+            Some((file, name)) if *file == i => format!("#{}", name),
+            Some((file, name)) => format!("{}#{}", names[*file], name),
+            None => format!("#{}", name),
+        };
+        relink(&mut section.blocks, &mut retarget);
+
+        let notes = section_notes(&section.blocks, &document.references);
+        let mut content = renderer.render(&DocumentAst {
+            blocks: std::mem::take(&mut section.blocks),
+            ..Default::default()
+        })?;
+        renderer.push_notes(
+            &mut content,
+            notes
+                .iter()
+                .map(|(label, text)| (label.clone(), text.as_str())),
+        );
+        files.push(SplitFile {
+            name: names[i].clone(),
+            content,
+        });
+    }
+
+    let entries = sections
+        .iter()
+        .zip(&names)
+        .filter(|(section, _)| section.level > 0)
+        .map(|(section, name)| (section.level, section.title.as_str(), name.as_str()))
+        .collect::<Vec<_>>();
+    let mut index = String::from("# Contents\n\n");
+    index.push_str(&nav(&entries));
+    let mut summary = String::from("# Summary\n\n");
+    if front_matter {
+        summary.push_str(&format!("[Front matter]({})\n\n", names[0]));
+    }
+    summary.push_str(&nav(&entries));
+    files.push(SplitFile {
+        name: "index.md".to_string(),
+        content: index,
+    });
+    files.push(SplitFile {
+        name: "SUMMARY.md".to_string(),
+        content: summary,
+    });
+    Ok(files)
+}
+
+/// Splits the top-level blocks before each heading of `level` and above.
+/// Bookmarks just before such a heading go with it.
+fn sections(document: &DocumentAst, level: usize) -> Vec<Section> {
+    let mut sections = vec![Section {
+        title: String::new(),
+        level: 0,
+        blocks: Vec::new(),
+    }];
+    let mut bookmarks = Vec::new();
+    for block in &document.blocks {
+        match block {
+            BlockNode::Heading(heading) if heading.level <= level => {
+                sections.push(Section {
+                    title: heading.plain_text(),
+                    level: heading.level,
+                    blocks: std::mem::take(&mut bookmarks),
+                });
+            }
+            block if block.is_bookmark_only() => {
+                bookmarks.push(block.clone());
+                continue;
+            }
+            _ => {}
+        }
+        let section = sections.last_mut().expect("there is always a section");
+        section.blocks.append(&mut bookmarks);
+        section.blocks.push(block.clone());
+    }
+    if let Some(section) = sections.last_mut() {
+        section.blocks.append(&mut bookmarks);
+    }
+    if sections[0].blocks.is_empty() && sections.len() > 1 {
+        sections.remove(0);
+    }
+    sections
+}
+
+/// Labels and text of the notes referenced in `blocks`, in document order.
+fn section_notes(blocks: &[BlockNode], references: &ReferenceDefinitions) -> Vec<(String, String)> {
+    let mut referenced = Vec::new();
+    for block in blocks {
+        for_each_note_reference(block, &mut |kind, id| referenced.push(kind.label(id)));
+    }
+    let footnotes = references
+        .footnotes
+        .iter()
+        .enumerate()
+        .map(|(i, text)| (NoteKind::Footnote.label(&(i + 1).to_string()), text));
+    let endnotes = references
+        .endnotes
+        .iter()
+        .enumerate()
+        .map(|(i, text)| (NoteKind::Endnote.label(&(i + 1).to_string()), text));
+    let comments = references
+        .comments
+        .iter()
+        .map(|(id, text)| (NoteKind::Comment.label(id), text));
+    footnotes
+        .chain(endnotes)
+        .chain(comments)
+        .filter(|(label, _)| referenced.contains(label))
+        .map(|(label, text)| (label, text.clone()))
+        .collect()
+}
+
+/// Nested list of links to the files of `entries` (level, title, file name).
+fn nav(entries: &[(usize, &str, &str)]) -> String {
+    let top = entries
+        .iter()
+        .map(|(level, _, _)| *level)
+        .min()
+        .unwrap_or(1);
+    let mut out = String::new();
+    let mut depth = 0;
+    for (i, (level, title, name)) in entries.iter().enumerate() {
+        depth = if i == 0 {
+            0
+        } else {
+            (level - top).min(depth + 1)
+        };
+        out.push_str(&format!(
+            "{}- [{}]({})\n",
+            "  ".repeat(depth),
+            escape_markdown_link_text(title),
+            name
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{Heading, InlineNode, Paragraph, TextStyle};
+
+    fn heading(level: usize, text: &str) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level,
+            label: String::new(),
+            content: vec![InlineNode::Text {
+                text: text.to_string(),
+                style: TextStyle::default(),
+            }],
+            id: None,
+            anchors: Vec::new(),
+        })
+    }
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn link(label: &str, destination: &str) -> InlineNode {
+        InlineNode::Link {
+            destination: destination.to_string(),
+            content: vec![text(label)],
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn note(note: usize) -> InlineNode {
+        InlineNode::NoteReference {
+            kind: NoteKind::Footnote,
+            id: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_split_links_across_files_with_their_notes() {
+        let document = DocumentAst {
+            blocks: vec![
+                BlockNode::Paragraph("Title page".into()),
+                paragraph(vec![InlineNode::Anchor("_Toc1".to_string())]),
+                heading(1, "Install"),
+                heading(2, "Overview"),
+                paragraph(vec![
+                    text("See "),
+                    link("usage", "#_Toc2"),
+                    text(" and "),
+                    link("its overview", "#overview-1"),
+                    text("."),
+                    note(1),
+                ]),
+                heading(1, "Usage"),
+                paragraph(vec![
+                    InlineNode::Anchor("_Toc2".to_string()),
+                    text("Run it."),
+                ]),
+                heading(2, "Overview"),
+                paragraph(vec![
+                    text("Back to "),
+                    link("install", "#_Toc1"),
+                    text("."),
+                    note(2),
+                ]),
+            ],
+            references: ReferenceDefinitions {
+                footnotes: vec!["First.".to_string(), "Second.".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let files = split_document(
+            &document,
+            &MarkdownRenderer::default(),
+            1,
+            SlugStyle::Github,
+            true,
+        )
+        .expect("split should work");
+
+        let names = files
+            .iter()
+            .map(|file| file.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                "00-front-matter.md",
+                "01-install.md",
+                "02-usage.md",
+                "index.md",
+                "SUMMARY.md"
+            ]
+        );
+        assert_eq!(files[0].content, "Title page\n\n");
+        assert_eq!(
+            files[1].content,
+            "<a id=\"_Toc1\"></a>\n\n# Install\n\n## Overview\n\n\
+             See [usage](02-usage.md#_Toc2) and [its overview](02-usage.md#overview).[^1]\n\n\
+             ---\n\n[^1]: First.\n"
+        );
+        assert!(files[2]
+            .content
+            .ends_with("Back to [install](01-install.md#_Toc1).[^2]\n\n---\n\n[^2]: Second.\n"));
+        assert_eq!(
+            files[3].content,
+            "# Contents\n\n- [Install](01-install.md)\n- [Usage](02-usage.md)\n"
+        );
+        assert!(files[4]
+            .content
+            .starts_with("# Summary\n\n[Front matter](00-front-matter.md)\n\n- [Install]"));
+    }
+}
@@ -156,6 +156,41 @@ fn merge_char_props_mut<'a>(target: &mut CharacterProperty<'a>, overlay: &Charac
     if overlay.underline.is_some() {
         target.underline = overlay.underline.clone();
     }
+    if overlay.size.is_some() {
+        target.size = overlay.size.clone();
+    }
+    if overlay.dstrike.is_some() {
+        target.dstrike = overlay.dstrike.clone();
+    }
+    if overlay.caps.is_some() {
+        target.caps = overlay.caps.clone();
+    }
+    if overlay.small_caps.is_some() {
+        target.small_caps = overlay.small_caps.clone();
+    }
+    if overlay.vertical_align.is_some() {
+        target.vertical_align = overlay.vertical_align.clone();
+    }
+    if overlay.highlight.is_some() {
+        target.highlight = overlay.highlight.clone();
+    }
+    if overlay.color.is_some() {
+        target.color = overlay.color.clone();
+    }
+    if overlay.vanish.is_some() {
+        target.vanish = overlay.vanish.clone();
+    }
+    if let Some(fonts) = &overlay.fonts {
+        // Each w:rFonts attribute inherits separately
+        let mut merged = fonts.clone();
+        if let Some(base) = &target.fonts {
+            merged.ascii = merged.ascii.or_else(|| base.ascii.clone());
+            merged.h_ansi = merged.h_ansi.or_else(|| base.h_ansi.clone());
+            merged.east_asia = merged.east_asia.or_else(|| base.east_asia.clone());
+            merged.custom = merged.custom.or_else(|| base.custom.clone());
+        }
+        target.fonts = Some(merged);
+    }
 }
 
 // Helper to merge paragraph properties (in-place mutation)
@@ -163,10 +198,37 @@ fn merge_para_props_mut<'a>(target: &mut ParagraphProperty<'a>, overlay: &Paragr
     if overlay.justification.is_some() {
         target.justification = overlay.justification.clone();
     }
-    if overlay.numbering.is_some() {
-        target.numbering = overlay.numbering.clone();
+    if let Some(numbering) = &overlay.numbering {
+        // numId and ilvl inherit separately
+        let mut merged = numbering.clone();
+        if let Some(base) = &target.numbering {
+            if merged.id.is_none() {
+                merged.id = base.id.clone();
+            }
+            if merged.level.is_none() {
+                merged.level = base.level.clone();
+            }
+        }
+        target.numbering = Some(merged);
     }
     if overlay.style_id.is_some() {
         target.style_id = overlay.style_id.clone();
     }
+    if overlay.keep_next.is_some() {
+        target.keep_next = overlay.keep_next.clone();
+    }
+    if overlay.outline_lvl.is_some() {
+        target.outline_lvl = overlay.outline_lvl.clone();
+    }
+    if let Some(indent) = &overlay.indent {
+        // Each w:ind attribute inherits separately
+        let mut merged = indent.clone();
+        if let Some(base) = &target.indent {
+            merged.left = merged.left.or(base.left);
+            merged.start = merged.start.or(base.start);
+            merged.hanging = merged.hanging.or(base.hanging);
+            merged.first_line = merged.first_line.or(base.first_line);
+        }
+        target.indent = Some(merged);
+    }
 }
@@ -1,7 +1,9 @@
-//! Table converter - converts tables to HTML with merge support.
+//! Table converter - converts tables to table blocks with merge support.
 
-use super::table_grid;
-use super::{ConversionContext, ParagraphConverter};
+use super::table_grid::{self, CellStatus};
+use super::{ConversionContext, ListPlacement, ParagraphConverter};
+use crate::adapters::docx::ListBuilder;
+use crate::core::ast::{self, BlockNode};
 use crate::Result;
 use rs_docx::document::{Table, TableCell, TableCellContent};
 
@@ -9,35 +11,92 @@ use rs_docx::document::{Table, TableCell, TableCellContent};
 pub struct TableConverter;
 
 impl TableConverter {
-    /// Converts a Table to HTML format with correct merge handling.
+    /// Converts a Table to Markdown: a pipe table when the target flavor
+    /// supports it and the table has a simple grid, HTML otherwise.
     pub fn convert<'a>(table: &Table<'a>, context: &mut ConversionContext<'a>) -> Result<String> {
-        let grid = table_grid::build_grid(table, |cell| Self::convert_cell_content(cell, context))?;
-        Ok(table_grid::render_grid(grid))
+        let block = Self::convert_block(table, context)?;
+        Ok(context.render_markdown(std::slice::from_ref(&block)))
     }
 
+    /// Converts a Table to a table block, resolving merged cells.
+    pub fn convert_block<'a>(
+        table: &Table<'a>,
+        context: &mut ConversionContext<'a>,
+    ) -> Result<BlockNode> {
+        Self::convert_table(table, context).map(BlockNode::Table)
+    }
+
+    fn convert_table<'a>(
+        table: &Table<'a>,
+        context: &mut ConversionContext<'a>,
+    ) -> Result<ast::Table> {
+        context.enter_table();
+        let grid = table_grid::build_grid(table, |cell| Self::convert_cell_content(cell, context));
+        context.leave_table();
+
+        let rows = grid?
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .filter_map(|cell| match cell {
+                        CellStatus::Occupied {
+                            content: blocks,
+                            rowspan,
+                            colspan,
+                        } => Some(ast::TableCell {
+                            blocks,
+                            colspan,
+                            rowspan,
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(ast::Table { rows })
+    }
+
+    /// Converts the content of a cell to blocks, grouping numbered
+    /// paragraphs into lists.
     fn convert_cell_content<'a>(
         cell: &TableCell<'a>,
         context: &mut ConversionContext<'a>,
-    ) -> Result<String> {
-        let mut content = String::new();
+    ) -> Result<Vec<BlockNode>> {
+        let mut blocks = Vec::new();
+        let mut lists = ListBuilder::default();
         for item in &cell.content {
-            match item {
+            let block = match item {
                 TableCellContent::Paragraph(para) => {
-                    let para_content = ParagraphConverter::convert(para, context)?;
-                    if !para_content.is_empty() {
-                        if !content.is_empty() {
-                            content.push_str("<br/>");
+                    let (paragraph, placement) =
+                        ParagraphConverter::convert_list_item(para, context)?;
+                    match placement {
+                        ListPlacement::Heading(heading) => BlockNode::Heading(heading),
+                        ListPlacement::Item(marker) => {
+                            blocks.extend(lists.push(marker, paragraph));
+                            continue;
+                        }
+                        ListPlacement::Body { .. } if paragraph.content.is_empty() => continue,
+                        ListPlacement::Body {
+                            indent,
+                            numbering_removed,
+                        } => {
+                            let block = BlockNode::Paragraph(paragraph);
+                            match lists.continue_item(block, indent, numbering_removed) {
+                                Some(block) => block,
+                                None => continue,
+                            }
                         }
-                        content.push_str(&para_content);
                     }
                 }
                 TableCellContent::Table(table) => {
-                    let table_content = TableConverter::convert(table, context)?;
-                    content.push_str(&table_content);
+                    BlockNode::Table(Self::convert_table(table, context)?)
                 }
-            }
+            };
+            blocks.extend(lists.finish());
+            blocks.push(block);
         }
-        Ok(content)
+        blocks.extend(lists.finish());
+        Ok(blocks)
     }
 }
 
@@ -90,4 +149,50 @@ mod tests {
         assert!(html.contains("<td rowspan=\"2\" colspan=\"2\">TOP</td>"));
         assert!(html.contains("<td>L</td>"));
     }
+
+    #[test]
+    fn test_pipe_table_for_flavor_with_simple_grid() {
+        let table = Table::default()
+            .push_row(
+                TableRow::default()
+                    .push_cell(TableCell::paragraph(Paragraph::default().push_text("Name")))
+                    .push_cell(TableCell::paragraph(
+                        Paragraph::default().push_text("Value"),
+                    )),
+            )
+            .push_row(
+                TableRow::default()
+                    .push_cell(TableCell::paragraph(Paragraph::default().push_text("a|b")))
+                    .push_cell(TableCell::paragraph(
+                        Paragraph::default().push_text("1 < 2"),
+                    )),
+            );
+
+        let docx = rs_docx::Docx::default();
+        let rels = HashMap::new();
+        let mut numbering_resolver = super::super::NumberingResolver::new(&docx);
+        let mut image_extractor = super::super::ImageExtractor::new_skip();
+        let options = ConvertOptions {
+            flavor: Some(crate::MarkdownFlavor::Gfm),
+            ..Default::default()
+        };
+        let style_resolver = super::super::StyleResolver::new(&docx.styles);
+        let mut context = super::super::ConversionContext::new(
+            &rels,
+            &mut numbering_resolver,
+            &mut image_extractor,
+            &options,
+            None,
+            None,
+            None,
+            &style_resolver,
+        );
+
+        let markdown =
+            TableConverter::convert(&table, &mut context).expect("table conversion failed");
+        assert_eq!(
+            markdown,
+            "| Name | Value |\n| --- | --- |\n| a\\|b | 1 < 2 |"
+        );
+    }
 }
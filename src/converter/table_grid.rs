@@ -2,9 +2,9 @@ use crate::Result;
 use rs_docx::document::{Table, TableCell};
 
 #[derive(Clone, Debug)]
-pub(crate) enum CellStatus {
+pub(crate) enum CellStatus<T> {
     Occupied {
-        content: String,
+        content: T,
         rowspan: usize,
         colspan: usize,
     },
@@ -13,14 +13,15 @@ pub(crate) enum CellStatus {
     Empty,
 }
 
-pub(crate) fn build_grid<'a, F>(
+pub(crate) fn build_grid<'a, T, F>(
     table: &Table<'a>,
     mut convert_cell: F,
-) -> Result<Vec<Vec<CellStatus>>>
+) -> Result<Vec<Vec<CellStatus<T>>>>
 where
-    F: FnMut(&TableCell<'a>) -> Result<String>,
+    T: Clone,
+    F: FnMut(&TableCell<'a>) -> Result<T>,
 {
-    let mut grid: Vec<Vec<CellStatus>> = Vec::new();
+    let mut grid: Vec<Vec<CellStatus<T>>> = Vec::new();
 
     for (row_idx, row) in table.rows.iter().enumerate() {
         if grid.len() <= row_idx {
@@ -54,18 +55,13 @@ where
                     let is_v_merge_continue =
                         cell.property.v_merge.is_some() && !is_v_merge_restart;
 
-                    let content = if is_v_merge_continue {
-                        String::new()
-                    } else {
-                        convert_cell(cell)?
-                    };
-
                     if is_v_merge_continue {
                         increment_rowspan(&mut grid, row_idx, col_idx);
                         for i in 0..grid_span {
                             set_grid_cell(&mut grid, row_idx, col_idx + i, CellStatus::MergedUp);
                         }
                     } else {
+                        let content = convert_cell(cell)?;
                         set_grid_cell(
                             &mut grid,
                             row_idx,
@@ -91,39 +87,12 @@ where
     Ok(grid)
 }
 
-pub(crate) fn render_grid(grid: Vec<Vec<CellStatus>>) -> String {
-    let mut html = String::from("<table>\n");
-    for row in grid {
-        html.push_str("  <tr>\n");
-        for cell in row {
-            match cell {
-                CellStatus::Occupied {
-                    content,
-                    rowspan,
-                    colspan,
-                } => {
-                    let mut attrs = String::new();
-                    if rowspan > 1 {
-                        attrs.push_str(&format!(" rowspan=\"{}\"", rowspan));
-                    }
-                    if colspan > 1 {
-                        attrs.push_str(&format!(" colspan=\"{}\"", colspan));
-                    }
-                    html.push_str(&format!("    <td{}>{}</td>\n", attrs, content));
-                }
-                CellStatus::MergedLeft | CellStatus::MergedUp => {}
-                CellStatus::Empty => {
-                    html.push_str("    <td></td>\n");
-                }
-            }
-        }
-        html.push_str("  </tr>\n");
-    }
-    html.push_str("</table>");
-    html
-}
-
-fn set_grid_cell(grid: &mut Vec<Vec<CellStatus>>, row: usize, col: usize, status: CellStatus) {
+fn set_grid_cell<T: Clone>(
+    grid: &mut Vec<Vec<CellStatus<T>>>,
+    row: usize,
+    col: usize,
+    status: CellStatus<T>,
+) {
     if grid.len() <= row {
         grid.resize(row + 1, Vec::new());
     }
@@ -133,7 +102,7 @@ fn set_grid_cell(grid: &mut Vec<Vec<CellStatus>>, row: usize, col: usize, status
     grid[row][col] = status;
 }
 
-fn increment_rowspan(grid: &mut [Vec<CellStatus>], current_row: usize, col: usize) {
+fn increment_rowspan<T>(grid: &mut [Vec<CellStatus<T>>], current_row: usize, col: usize) {
     if current_row == 0 {
         return;
     }
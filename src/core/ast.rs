@@ -2,13 +2,440 @@
 pub struct DocumentAst {
     pub blocks: Vec<BlockNode>,
     pub references: ReferenceDefinitions,
+    pub properties: DocumentProperties,
+}
+
+/// Document properties from `docProps/core.xml`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentProperties {
+    pub title: Option<String>,
+    pub subject: Option<String>,
+    pub author: Option<String>,
+    pub keywords: Option<String>,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub language: Option<String>,
+    /// Creation time as written, e.g. `2024-01-31T09:00:00Z`.
+    pub created: Option<String>,
+    /// Last modification time as written.
+    pub modified: Option<String>,
+}
+
+impl DocumentProperties {
+    /// Names and values of the properties that are set and not blank, in
+    /// declaration order.
+    pub fn fields(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Title", &self.title),
+            ("Subject", &self.subject),
+            ("Author", &self.author),
+            ("Keywords", &self.keywords),
+            ("Description", &self.description),
+            ("Category", &self.category),
+            ("Language", &self.language),
+            ("Created", &self.created),
+            ("Modified", &self.modified),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = value.as_deref()?.trim();
+            (!value.is_empty()).then_some((name, value))
+        })
+        .collect()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum BlockNode {
-    Paragraph(String),
-    TableHtml(String),
+    Paragraph(Paragraph),
+    Heading(Heading),
+    /// Table of contents regenerated from the document's headings.
+    TableOfContents(Vec<TocEntry>),
+    Table(Table),
     RawHtml(String),
+    List(List),
+    /// Blocks inside a structured document tag (content control).
+    ContentControl {
+        control: ContentControl,
+        blocks: Vec<BlockNode>,
+    },
+}
+
+impl BlockNode {
+    /// Whether the block holds nothing but bookmarks.
+    pub fn is_bookmark_only(&self) -> bool {
+        match self {
+            Self::Paragraph(paragraph) => {
+                !paragraph.content.is_empty()
+                    && paragraph
+                        .content
+                        .iter()
+                        .all(|node| matches!(node, InlineNode::Anchor(_)))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Body paragraph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Paragraph {
+    pub content: Vec<InlineNode>,
+    /// Paragraph style id (`w:pStyle`), e.g. `Note`.
+    pub style: Option<String>,
+    /// Horizontal alignment, unless the paragraph is left aligned.
+    pub alignment: Option<Alignment>,
+}
+
+impl Paragraph {
+    /// Paragraph of pre-rendered Markdown, e.g. from a custom extractor.
+    pub fn from_markdown(markdown: impl Into<String>) -> Self {
+        Self {
+            content: vec![InlineNode::Raw(markdown.into())],
+            ..Default::default()
+        }
+    }
+
+    /// Role of the paragraph in a definition list, from its style
+    /// (`DefinitionTerm` and `Definition`).
+    pub fn definition_role(&self) -> Option<DefinitionRole> {
+        match self.style.as_deref()? {
+            "DefinitionTerm" => Some(DefinitionRole::Term),
+            "Definition" => Some(DefinitionRole::Definition),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for Paragraph {
+    fn from(markdown: String) -> Self {
+        Self::from_markdown(markdown)
+    }
+}
+
+impl From<&str> for Paragraph {
+    fn from(markdown: &str) -> Self {
+        Self::from_markdown(markdown)
+    }
+}
+
+/// Horizontal alignment of a paragraph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+impl Alignment {
+    /// Value of the CSS `text-align` property.
+    pub fn css(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+            Self::Justify => "justify",
+        }
+    }
+}
+
+/// Role of a paragraph in a definition list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefinitionRole {
+    Term,
+    Definition,
+}
+
+/// Table; cells covered by a merged cell are left out of its rows.
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub rows: Vec<Vec<TableCell>>,
+}
+
+impl Table {
+    /// Whether any cell spans several columns or rows.
+    pub fn has_merged_cells(&self) -> bool {
+        self.rows
+            .iter()
+            .flatten()
+            .any(|cell| cell.colspan > 1 || cell.rowspan > 1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableCell {
+    pub blocks: Vec<BlockNode>,
+    pub colspan: usize,
+    pub rowspan: usize,
+}
+
+/// Heading paragraph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heading {
+    /// Level from 1 (`#`) to 9.
+    pub level: usize,
+    /// Numbering label followed by its separator (e.g. `1.2 `), or empty.
+    pub label: String,
+    pub content: Vec<InlineNode>,
+    /// Explicit identifier (`{#id}`), with attribute syntax.
+    pub id: Option<String>,
+    /// Bookmarks placed before the heading.
+    pub anchors: Vec<String>,
+}
+
+impl Heading {
+    /// Text of the heading including its label, without formatting.
+    pub fn plain_text(&self) -> String {
+        let mut text = self.label.clone();
+        text.push_str(&plain_text(&self.content));
+        text.trim().to_string()
+    }
+}
+
+/// Entry of a regenerated table of contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocEntry {
+    /// Heading level, from 1.
+    pub level: usize,
+    pub text: String,
+    /// Link target, e.g. `#introduction`.
+    pub destination: String,
+}
+
+/// Bullet or ordered list built from numbered paragraphs.
+#[derive(Debug, Clone)]
+pub struct List {
+    pub kind: ListKind,
+    /// Number of the first item (ordered lists only).
+    pub start: Option<i32>,
+    /// Nesting depth of the list's numbering level. A list nested deeper than
+    /// one level below its parent (or a top-level list at depth > 0) is
+    /// indented for each missing level.
+    pub level: usize,
+    pub items: Vec<ListItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Bullet,
+    Ordered,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListItem {
+    /// Label as displayed by Word (e.g. `1.`, `(a)`, `제3조`).
+    pub label: String,
+    /// Text between the label and the content (`w:suff`).
+    pub separator: String,
+    /// Counter value (ordered lists only).
+    pub number: Option<i32>,
+    /// Task state from a check box at the start of the item.
+    pub checked: Option<bool>,
+    /// Item content; nested lists are `BlockNode::List` entries.
+    pub blocks: Vec<BlockNode>,
+}
+
+/// Inline content of a paragraph, independent of the output syntax.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InlineNode {
+    /// Literal document text with its character formatting.
+    Text {
+        text: String,
+        style: TextStyle,
+    },
+    /// Hyperlink to a URL or an in-document anchor (`#name`).
+    Link {
+        destination: String,
+        content: Vec<InlineNode>,
+    },
+    /// Bookmark target.
+    Anchor(String),
+    /// TeX math, inline or on its own line (display).
+    Math {
+        tex: String,
+        display: bool,
+    },
+    /// Image, by path or `data:` URI.
+    Image {
+        source: String,
+        alt: String,
+        /// Displayed width in pixels (at 96 dpi), when the document sets one.
+        width: Option<u32>,
+    },
+    /// Reference to a footnote, endnote or comment.
+    NoteReference {
+        kind: NoteKind,
+        id: String,
+    },
+    PageBreak,
+    /// Pre-rendered output emitted verbatim.
+    Raw(String),
+    /// Check box content control or form field.
+    CheckBox {
+        checked: bool,
+    },
+    /// Inline content of a structured document tag (content control).
+    ContentControl {
+        control: ContentControl,
+        content: Vec<InlineNode>,
+    },
+}
+
+/// Kind of note a reference points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteKind {
+    /// Identified by number, from 1, in order of first reference.
+    Footnote,
+    /// Identified by number, from 1, in order of first reference.
+    Endnote,
+    /// Identified by the comment's `w:id`.
+    Comment,
+}
+
+impl NoteKind {
+    /// Label of a note in the output, e.g. `en2` for the second endnote.
+    pub fn label(self, id: &str) -> String {
+        match self {
+            Self::Footnote => id.to_string(),
+            Self::Endnote => format!("en{}", id),
+            Self::Comment => format!("c{}", id),
+        }
+    }
+}
+
+/// Concatenates the text of inline nodes, including link and content control
+/// content.
+pub fn plain_text(nodes: &[InlineNode]) -> String {
+    let mut text = String::new();
+    for node in nodes {
+        match node {
+            InlineNode::Text { text: t, .. } => text.push_str(t),
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                text.push_str(&plain_text(content))
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Calls `f` with the kind and id of each note reference in `block`,
+/// including those in tables, list items and content controls.
+pub fn for_each_note_reference(block: &BlockNode, f: &mut dyn FnMut(NoteKind, &str)) {
+    match block {
+        BlockNode::Paragraph(paragraph) => inline_note_references(&paragraph.content, f),
+        BlockNode::Heading(heading) => inline_note_references(&heading.content, f),
+        BlockNode::Table(table) => {
+            for cell in table.rows.iter().flatten() {
+                for block in &cell.blocks {
+                    for_each_note_reference(block, f);
+                }
+            }
+        }
+        BlockNode::List(list) => {
+            for block in list.items.iter().flat_map(|item| &item.blocks) {
+                for_each_note_reference(block, f);
+            }
+        }
+        BlockNode::ContentControl { blocks, .. } => {
+            for block in blocks {
+                for_each_note_reference(block, f);
+            }
+        }
+        BlockNode::TableOfContents(_) | BlockNode::RawHtml(_) => {}
+    }
+}
+
+fn inline_note_references(nodes: &[InlineNode], f: &mut dyn FnMut(NoteKind, &str)) {
+    for node in nodes {
+        match node {
+            InlineNode::NoteReference { kind, id } => f(*kind, id),
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                inline_note_references(content, f)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Properties of a structured document tag (content control).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentControl {
+    /// `w:tag`: identifier set by the template author.
+    pub tag: Option<String>,
+    /// `w:alias`: friendly name shown in Word.
+    pub alias: Option<String>,
+    /// XPath of the custom XML data binding (`w:dataBinding`).
+    pub data_binding: Option<String>,
+    pub kind: ContentControlKind,
+    /// Whether the control shows its placeholder text (`w:showingPlcHdr`).
+    pub showing_placeholder: bool,
+}
+
+/// Type of a content control.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ContentControlKind {
+    #[default]
+    RichText,
+    PlainText,
+    Date,
+    DropDownList,
+    ComboBox,
+    CheckBox {
+        checked: bool,
+    },
+    Picture,
+    Group,
+    RepeatingSection,
+    RepeatingSectionItem,
+    /// Building block gallery, e.g. "Table of Contents" (`w:docPartObj`).
+    DocPart(Option<String>),
+    /// Other types (`w:citation`, `w:bibliography`, `w:equation`), by element name.
+    Other(String),
+}
+
+impl ContentControlKind {
+    /// Name of the type as its `w:sdtPr` element.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::RichText => "richText",
+            Self::PlainText => "text",
+            Self::Date => "date",
+            Self::DropDownList => "dropDownList",
+            Self::ComboBox => "comboBox",
+            Self::CheckBox { .. } => "checkbox",
+            Self::Picture => "picture",
+            Self::Group => "group",
+            Self::RepeatingSection => "repeatingSection",
+            Self::RepeatingSectionItem => "repeatingSectionItem",
+            Self::DocPart(_) => "docPartObj",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+/// Character formatting of a text span.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    /// Single or double strikethrough.
+    pub strike: bool,
+    pub superscript: bool,
+    pub subscript: bool,
+    pub small_caps: bool,
+    pub all_caps: bool,
+    pub highlight: bool,
+    /// Inline code, e.g. a Markdown code span.
+    pub code: bool,
+    /// CSS color (e.g. `#FF0000`), if the text is colored.
+    pub color: Option<String>,
+    /// Tracked insertion.
+    pub insertion: bool,
+    /// Tracked deletion.
+    pub deletion: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -17,3 +444,24 @@ pub struct ReferenceDefinitions {
     pub endnotes: Vec<String>,
     pub comments: Vec<(String, String)>,
 }
+
+impl ReferenceDefinitions {
+    /// Text of the note a reference points to.
+    pub fn note_text(&self, kind: NoteKind, id: &str) -> Option<&str> {
+        match kind {
+            NoteKind::Footnote | NoteKind::Endnote => {
+                let notes = match kind {
+                    NoteKind::Footnote => &self.footnotes,
+                    _ => &self.endnotes,
+                };
+                let index = id.parse::<usize>().ok()?.checked_sub(1)?;
+                notes.get(index).map(String::as_str)
+            }
+            NoteKind::Comment => self
+                .comments
+                .iter()
+                .find(|(comment_id, _)| comment_id == id)
+                .map(|(_, text)| text.as_str()),
+        }
+    }
+}
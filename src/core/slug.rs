@@ -0,0 +1,112 @@
+//! Heading identifiers ("slugs") as generated by GitHub and Pandoc for
+//! Markdown headings.
+
+use std::collections::HashMap;
+
+/// Returns the GitHub slug of a heading: lower case, with characters other
+/// than letters, digits, `_`, `-` and spaces removed and spaces turned into
+/// hyphens.
+pub fn slugify(text: &str) -> String {
+    text.trim()
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '_' || c == '-' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the Pandoc identifier of a heading (`auto_identifiers`): lower
+/// case, with characters other than letters, digits, `_`, `-` and `.`
+/// removed, words joined by hyphens and everything before the first letter
+/// dropped. Headings without letters become `section`.
+pub fn pandoc_slugify(text: &str) -> String {
+    let filtered: String = text
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|&c| c.is_whitespace() || c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .collect();
+    let joined = filtered.split_whitespace().collect::<Vec<_>>().join("-");
+    match joined.find(char::is_alphabetic) {
+        Some(start) => joined[start..].to_string(),
+        None => "section".to_string(),
+    }
+}
+
+/// Which tool's heading identifiers to reproduce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlugStyle {
+    #[default]
+    Github,
+    Pandoc,
+}
+
+impl SlugStyle {
+    pub fn slugify(self, text: &str) -> String {
+        match self {
+            Self::Github => slugify(text),
+            Self::Pandoc => pandoc_slugify(text),
+        }
+    }
+}
+
+/// Assigns unique slugs to headings in document order, suffixing repeated
+/// slugs with `-1`, `-2`, ... like GitHub and Pandoc.
+#[derive(Debug, Default, Clone)]
+pub struct SlugGenerator {
+    style: SlugStyle,
+    counts: HashMap<String, usize>,
+}
+
+impl SlugGenerator {
+    pub fn new(style: SlugStyle) -> Self {
+        Self {
+            style,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Returns the unique slug for the next heading with `text`.
+    pub fn slug(&mut self, text: &str) -> String {
+        let base = self.style.slugify(text);
+        let mut slug = base.clone();
+        while self.counts.contains_key(&slug) {
+            let count = self.counts.entry(base.clone()).or_insert(0);
+            *count += 1;
+            slug = format!("{}-{}", base, count);
+        }
+        self.counts.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugs_follow_github() {
+        assert_eq!(slugify("1.2 Scope & Purpose"), "12-scope--purpose");
+        assert_eq!(slugify("제3조 (목적)"), "제3조-목적");
+
+        let mut slugs = SlugGenerator::new(SlugStyle::Github);
+        assert_eq!(slugs.slug("Intro"), "intro");
+        assert_eq!(slugs.slug("Intro"), "intro-1");
+        assert_eq!(slugs.slug("Intro 1"), "intro-1-1");
+        assert_eq!(slugs.slug("Intro"), "intro-2");
+    }
+
+    #[test]
+    fn test_slugs_follow_pandoc() {
+        assert_eq!(pandoc_slugify("1.2 Scope & Purpose"), "scope-purpose");
+        assert_eq!(pandoc_slugify("Version 2.0  notes"), "version-2.0-notes");
+        assert_eq!(pandoc_slugify("3.1"), "section");
+
+        let mut slugs = SlugGenerator::new(SlugStyle::Pandoc);
+        assert_eq!(slugs.slug("1 Intro"), "intro");
+        assert_eq!(slugs.slug("2 Intro"), "intro-1");
+        assert_eq!(slugs.slug("42"), "section");
+    }
+}
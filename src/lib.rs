@@ -37,8 +37,8 @@
 //!         _context: &mut ConversionContext<'a>,
 //!     ) -> Result<DocumentAst> {
 //!         Ok(DocumentAst {
-//!             blocks: vec![BlockNode::Paragraph("custom pipeline".to_string())],
-//!             references: Default::default(),
+//!             blocks: vec![BlockNode::Paragraph("custom pipeline".into())],
+//!             ..Default::default()
 //!         })
 //!     }
 //! }
@@ -65,16 +65,20 @@
 //! ```
 
 pub mod adapters;
+pub mod chunk;
 pub mod converter;
 pub mod core;
 pub mod error;
 pub mod localization;
 pub mod render;
+pub mod writer;
 
 pub use converter::DocxToMarkdown;
 pub use error::{Error, Result};
 pub use localization::parse_heading_style;
+pub use writer::{MarkdownToDocx, WriteOptions};
 
+use crate::core::slug::SlugStyle;
 use std::path::PathBuf;
 
 /// Options for DOCX to Markdown conversion.
@@ -84,12 +88,65 @@ pub struct ConvertOptions {
     pub image_handling: ImageHandling,
     /// Whether to preserve exact whitespace.
     pub preserve_whitespace: bool,
+    /// Markdown dialect to target. When set, the flavor decides the syntax of
+    /// footnotes, tables, strikethrough, sub/superscript, highlights, math,
+    /// definition lists, task lists and anchors, overriding the `html_*`
+    /// options where the dialect has native syntax (or lacks it). List items
+    /// get `-` and `N.` markers valid in every dialect unless `list_markers`
+    /// is set to keep Word's labels.
+    /// When `None`, the `html_*` options alone control the output.
+    pub flavor: Option<MarkdownFlavor>,
+    /// Whether to escape literal text that would otherwise be read as
+    /// Markdown or HTML syntax (e.g. a paragraph starting with `#`).
+    pub escape_markdown: bool,
+    /// How bold and italic text is written.
+    pub emphasis_style: EmphasisStyle,
+    /// How list item markers, and the labels of numbered headings, are
+    /// written. Headings never show bullets.
+    pub list_markers: ListMarkerStyle,
+    /// Whether bullets keep their symbol-font glyphs (e.g. U+F0B7 in the
+    /// Symbol font) instead of the Unicode characters they show.
+    pub keep_bullet_glyphs: bool,
+    /// How content controls and their tag, alias, type and data binding are
+    /// marked up.
+    pub content_controls: ContentControlMarkup,
+    /// Whether mail merge fields (`MERGEFIELD`) are written as `{{name}}`
+    /// placeholders instead of their last merged value.
+    pub merge_field_placeholders: bool,
+    /// What becomes of tables of contents (TOC fields, `TOC N` styled
+    /// paragraphs and table of contents content controls).
+    pub toc: TocHandling,
+    /// How headings and bookmarks are targeted by internal links.
+    pub anchors: AnchorStyle,
+    /// Where the plain text renderer writes the text of notes and comments.
+    pub text_notes: NotePlacement,
+    /// Language of notebook code cells whose paragraph style names none, and
+    /// of notebooks without code cells.
+    pub notebook_language: String,
     /// Whether to use HTML for underlined text.
     pub html_underline: bool,
     /// Whether to use HTML for strikethrough text.
     pub html_strikethrough: bool,
+    /// Whether to use HTML (`<sup>`/`<sub>`) for superscript and subscript text.
+    /// When disabled, Pandoc-style `^x^` and `~x~` are emitted.
+    pub html_sub_superscript: bool,
+    /// Whether to use HTML (`<mark>`) for highlighted text.
+    /// When disabled, `==x==` is emitted.
+    pub html_highlight: bool,
+    /// Whether to use HTML `<span style>` for small caps and all caps text.
+    /// When disabled, all caps text is transformed to upper case and small
+    /// caps text is left as it is.
+    pub html_caps: bool,
+    /// Whether to keep the text color as an HTML `<span style>`.
+    pub html_text_color: bool,
+    /// Whether to keep hidden text (`w:vanish`).
+    pub include_hidden_text: bool,
     /// Whether to fail conversion when a referenced note/comment cannot be resolved.
     pub strict_reference_validation: bool,
+    /// Whether to infer headings for paragraphs without heading styles from
+    /// font size, outline levels and bold text kept with the next paragraph.
+    /// List paragraphs are never inferred as headings.
+    pub infer_headings: bool,
 }
 
 impl Default for ConvertOptions {
@@ -97,13 +154,41 @@ impl Default for ConvertOptions {
         Self {
             image_handling: ImageHandling::Inline,
             preserve_whitespace: false,
+            flavor: None,
+            escape_markdown: true,
+            emphasis_style: EmphasisStyle::Html,
+            list_markers: ListMarkerStyle::Markdown,
+            keep_bullet_glyphs: false,
+            content_controls: ContentControlMarkup::Flatten,
+            merge_field_placeholders: false,
+            toc: TocHandling::Keep,
+            anchors: AnchorStyle::Bookmarks,
+            text_notes: NotePlacement::End,
+            notebook_language: "python".to_string(),
             html_underline: true,
             html_strikethrough: false,
+            html_sub_superscript: true,
+            html_highlight: true,
+            html_caps: false,
+            html_text_color: false,
+            include_hidden_text: false,
             strict_reference_validation: false,
+            infer_headings: false,
         }
     }
 }
 
+impl ConvertOptions {
+    /// Slugs given to headings: those of the anchor style, else those the
+    /// flavor's renderer generates.
+    pub(crate) fn slug_style(&self) -> SlugStyle {
+        self.anchors.slug_style().unwrap_or(match self.flavor {
+            Some(MarkdownFlavor::Pandoc) => SlugStyle::Pandoc,
+            _ => SlugStyle::Github,
+        })
+    }
+}
+
 /// Specifies how images should be handled during conversion.
 #[derive(Debug, Clone)]
 pub enum ImageHandling {
@@ -115,12 +200,180 @@ pub enum ImageHandling {
     Skip,
 }
 
+/// Specifies how bold and italic text is written.
+///
+/// Markdown delimiters fall back to HTML tags for a span when they would not
+/// be recognized as emphasis (e.g. `_` inside a word).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmphasisStyle {
+    /// `<strong>` and `<em>` tags.
+    #[default]
+    Html,
+    /// `**bold**` and `*italic*`.
+    Asterisk,
+    /// `__bold__` and `_italic_`.
+    Underscore,
+}
+
+/// Specifies how list item markers are written.
+///
+/// Formats whose list syntax cannot show Word's labels (AsciiDoc,
+/// reStructuredText, Org, MediaWiki) write them as text after their own
+/// markers unless the style is `Markdown`; LaTeX and Typst reproduce them
+/// with `Original`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListMarkerStyle {
+    /// The label Word displays (`가.`, `(a)`, `제3조`), even where it is not
+    /// Markdown list syntax.
+    Original,
+    /// `-` and `N.` markers; the original label is dropped. Numbered
+    /// headings show `N.`.
+    #[default]
+    Markdown,
+    /// `-` and `N.` markers followed by the original label as literal text.
+    MarkdownWithLabel,
+}
+
+/// Specifies how content controls (structured document tags) are marked up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContentControlMarkup {
+    /// Only the content is written.
+    #[default]
+    Flatten,
+    /// `<!-- sdt tag="..." -->` and `<!-- /sdt -->` comments around the content.
+    Comments,
+    /// `<span data-tag="...">` wrappers (`<div>` around blocks).
+    Spans,
+}
+
+/// Specifies how tables of contents are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TocHandling {
+    /// The entries as in the document, without page numbers.
+    #[default]
+    Keep,
+    /// A nested list linking to the headings of the converted document.
+    Regenerate,
+    /// Left out, along with its title.
+    Remove,
+}
+
+/// Specifies how internal link targets are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnchorStyle {
+    /// Word bookmarks as HTML anchors (`<a id="_Toc12345">`).
+    #[default]
+    Bookmarks,
+    /// Headings are linked by the slugs GitHub generates for them; bookmarks
+    /// Word generates (`_GoBack`, `_Toc…`, `_Hlk…`) are dropped unless
+    /// something still links to them.
+    Github,
+    /// Like `Github`, with the identifiers Pandoc generates.
+    Pandoc,
+}
+
+impl AnchorStyle {
+    /// Slugs given to headings, if headings are linked by slug.
+    pub(crate) fn slug_style(self) -> Option<SlugStyle> {
+        match self {
+            Self::Bookmarks => None,
+            Self::Github => Some(SlugStyle::Github),
+            Self::Pandoc => Some(SlugStyle::Pandoc),
+        }
+    }
+}
+
+/// Specifies where plain text output places notes and comments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotePlacement {
+    /// A `[1]` marker in the text, with the notes after the document.
+    #[default]
+    End,
+    /// The note text in brackets where it is referenced.
+    Inline,
+}
+
+/// Markdown dialects the output can target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownFlavor {
+    /// Plain CommonMark; everything beyond it is written as HTML.
+    CommonMark,
+    /// GitHub Flavored Markdown.
+    Gfm,
+    /// Pandoc Markdown.
+    Pandoc,
+    /// MultiMarkdown 6.
+    MultiMarkdown,
+    /// Obsidian Markdown.
+    Obsidian,
+}
+
+impl MarkdownFlavor {
+    /// Whether `[^id]` footnotes are supported.
+    pub fn supports_footnotes(self) -> bool {
+        !matches!(self, Self::CommonMark)
+    }
+
+    /// Whether pipe tables are supported.
+    pub fn supports_pipe_tables(self) -> bool {
+        !matches!(self, Self::CommonMark)
+    }
+
+    /// Whether `~~strikethrough~~` is supported.
+    pub fn supports_strikethrough(self) -> bool {
+        matches!(self, Self::Gfm | Self::Pandoc | Self::Obsidian)
+    }
+
+    /// Whether `^superscript^` and `~subscript~` are supported.
+    pub fn supports_sub_superscript(self) -> bool {
+        matches!(self, Self::Pandoc | Self::MultiMarkdown)
+    }
+
+    /// Whether `Term` / `: definition` lists are supported.
+    pub fn supports_definition_lists(self) -> bool {
+        matches!(self, Self::Pandoc | Self::MultiMarkdown)
+    }
+
+    /// Whether `- [ ]` / `- [x]` task list items are supported.
+    pub fn supports_task_lists(self) -> bool {
+        matches!(self, Self::Gfm | Self::Pandoc | Self::Obsidian)
+    }
+
+    /// Whether `{#id}` attributes on headings and `[]{#id}` spans are used for
+    /// anchors instead of `<a id>` tags.
+    pub fn supports_attributes(self) -> bool {
+        matches!(self, Self::Pandoc)
+    }
+
+    /// Opening and closing delimiters for highlighted text, if the flavor has
+    /// native syntax for it.
+    pub fn highlight_delimiters(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Self::Pandoc => Some(("[", "]{.mark}")),
+            Self::MultiMarkdown => Some(("{==", "==}")),
+            Self::Obsidian => Some(("==", "==")),
+            Self::CommonMark | Self::Gfm => None,
+        }
+    }
+
+    /// Delimiters for inline and display math, if the flavor supports math.
+    pub fn math_delimiters(self, display: bool) -> Option<(&'static str, &'static str)> {
+        match (self, display) {
+            (Self::CommonMark, _) => None,
+            (Self::MultiMarkdown, false) => Some(("\\\\(", "\\\\)")),
+            (Self::MultiMarkdown, true) => Some(("\\\\[", "\\\\]")),
+            (_, false) => Some(("$", "$")),
+            (_, true) => Some(("$$", "$$")),
+        }
+    }
+}
+
 // Python bindings (only when 'python' feature is enabled)
 #[cfg(feature = "python")]
 mod python_bindings {
     use super::*;
     use pyo3::prelude::*;
-    use pyo3::types::PyBytes;
+    use pyo3::types::{PyBytes, PyDict, PyList};
 
     /// Converts a DOCX file to Markdown.
     ///
@@ -145,10 +398,71 @@ mod python_bindings {
         }
     }
 
+    /// Splits a DOCX file into heading-aware chunks of Markdown.
+    ///
+    /// Argument can be a file path (str) or file content (bytes). `unit` is
+    /// "chars" or "tokens". Each chunk is a dict with `text`, `headings`,
+    /// `blocks` and `notes`.
+    #[pyfunction]
+    #[pyo3(signature = (input, max_size=2000, unit="chars", heading_level=9))]
+    fn chunk_docx<'py>(
+        py: Python<'py>,
+        input: &Bound<'py, PyAny>,
+        max_size: usize,
+        unit: &str,
+        heading_level: usize,
+    ) -> PyResult<Bound<'py, PyList>> {
+        let target_size = match unit {
+            "chars" => chunk::ChunkSize::Chars(max_size),
+            "tokens" => chunk::ChunkSize::Tokens(max_size),
+            _ => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                    "Expected unit \"chars\" or \"tokens\"",
+                ))
+            }
+        };
+        let options = chunk::ChunkOptions {
+            target_size,
+            heading_level,
+        };
+        let converter = DocxToMarkdown::new(ConvertOptions::default());
+
+        let chunks = if let Ok(path) = input.extract::<String>() {
+            converter.chunk(&path, &options)
+        } else if let Ok(bytes) = input.downcast::<PyBytes>() {
+            converter.chunk_from_bytes(bytes.as_bytes(), &options)
+        } else {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "Expected string path or bytes",
+            ));
+        }
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+
+        let list = PyList::empty(py);
+        for chunk in chunks {
+            let notes = PyList::empty(py);
+            for note in chunk.notes {
+                let dict = PyDict::new(py);
+                dict.set_item("kind", chunk::note_kind_name(note.kind))?;
+                dict.set_item("label", note.label)?;
+                dict.set_item("text", note.text)?;
+                notes.append(dict)?;
+            }
+            let dict = PyDict::new(py);
+            dict.set_item("text", chunk.text)?;
+            dict.set_item("headings", chunk.headings)?;
+            dict.set_item("blocks", chunk.blocks)?;
+            dict.set_item("notes", notes)?;
+            list.append(dict)?;
+        }
+        Ok(list)
+    }
+
     /// A Python module implemented in Rust.
     #[pymodule]
     pub fn dm2xcod(m: &Bound<'_, PyModule>) -> PyResult<()> {
         m.add_function(wrap_pyfunction!(convert_docx, m)?)?;
+        m.add_function(wrap_pyfunction!(chunk_docx, m)?)?;
         Ok(())
     }
 }
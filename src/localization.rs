@@ -1,4 +1,4 @@
-//! Heading style parsing utilities.
+//! Heading and table of contents style parsing utilities.
 
 /// Parses a DOCX style name to determine the heading level.
 ///
@@ -21,3 +21,15 @@ pub fn parse_heading_style(style: &str) -> Option<usize> {
         _ => None,
     }
 }
+
+/// Parses a DOCX style name of a table of contents entry ("TOC1", "toc 2",
+/// ...), returning its level.
+pub(crate) fn parse_toc_style(style: &str) -> Option<usize> {
+    let style_lower = style.to_lowercase();
+    style_lower.strip_prefix("toc")?.trim().parse().ok()
+}
+
+/// Whether a DOCX style is the title of a table of contents ("TOCHeading").
+pub(crate) fn is_toc_heading_style(style: &str) -> bool {
+    matches!(style.to_lowercase().replace(' ', "").as_str(), "tocheading")
+}
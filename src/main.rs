@@ -1,16 +1,27 @@
 //! CLI for dm2xcod - DOCX to Markdown converter
 
-use clap::Parser;
-use dm2xcod::{ConvertOptions, DocxToMarkdown, ImageHandling};
+use clap::{Parser, ValueEnum};
+use dm2xcod::adapters::docx::DocxExtractor;
+use dm2xcod::chunk::{ChunkOptions, ChunkSize};
+use dm2xcod::render::{
+    AsciiDocRenderer, LatexRenderer, MediaWikiRenderer, NotebookRenderer, OrgRenderer,
+    PlainTextRenderer, Renderer, RstRenderer, TypstRenderer,
+};
+use dm2xcod::{
+    AnchorStyle, ContentControlMarkup, ConvertOptions, DocxToMarkdown, ImageHandling,
+    ListMarkerStyle, MarkdownFlavor, MarkdownToDocx, NotePlacement, TocHandling, WriteOptions,
+};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input DOCX file path
+    /// Input DOCX file path, or a Markdown file (.md, .markdown) to convert
+    /// to DOCX
     input: PathBuf,
 
-    /// Output Markdown file path (optional, prints to stdout if not specified)
+    /// Output Markdown file path (optional, prints to stdout if not specified),
+    /// or directory with --split-level
     output: Option<PathBuf>,
 
     /// Directory to extract images to (if not set, images are embedded/inline)
@@ -20,14 +31,288 @@ struct Args {
     /// Skip extracting images
     #[arg(long)]
     skip_images: bool,
+
+    /// Infer headings from font size and bold text when heading styles are missing
+    #[arg(long)]
+    infer_headings: bool,
+
+    /// Markdown dialect to target (default: Markdown with HTML fallbacks)
+    #[arg(long, value_enum)]
+    flavor: Option<Flavor>,
+
+    /// How list markers are written (`original` keeps the labels shown in Word)
+    #[arg(long, value_enum, default_value_t = ListMarkers::Markdown)]
+    list_markers: ListMarkers,
+
+    /// Keep symbol-font bullet glyphs instead of mapping them to Unicode
+    #[arg(long)]
+    keep_bullet_glyphs: bool,
+
+    /// How content controls are marked (default: only their content)
+    #[arg(long, value_enum, default_value_t = ContentControls::Flatten)]
+    content_controls: ContentControls,
+
+    /// Write mail merge fields as {{name}} placeholders
+    #[arg(long)]
+    merge_field_placeholders: bool,
+
+    /// How tables of contents are written
+    #[arg(long, value_enum, default_value_t = Toc::Keep)]
+    toc: Toc,
+
+    /// How internal links target headings and bookmarks
+    #[arg(long, value_enum, default_value_t = Anchors::Bookmarks)]
+    anchors: Anchors,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Markdown)]
+    to: OutputFormat,
+
+    /// Where plain text output places footnote, endnote and comment text
+    #[arg(long, value_enum, default_value_t = TextNotes::End)]
+    text_notes: TextNotes,
+
+    /// Language of notebook code cells whose paragraph style names none
+    #[arg(long, default_value = "python")]
+    notebook_language: String,
+
+    /// Split the output into heading-aware chunks of about this size, written
+    /// as JSON lines
+    #[arg(long)]
+    chunk_size: Option<usize>,
+
+    /// Unit of --chunk-size
+    #[arg(long, value_enum, default_value_t = ChunkUnit::Chars)]
+    chunk_unit: ChunkUnit,
+
+    /// Deepest heading level that starts a new chunk
+    #[arg(long, default_value_t = 9)]
+    chunk_heading_level: usize,
+
+    /// Write one Markdown file per heading of this level and above, plus
+    /// index.md and SUMMARY.md, to the output directory
+    #[arg(long, conflicts_with = "chunk_size")]
+    split_level: Option<usize>,
+
+    /// DOCX whose styles, theme and page setup are reused when writing DOCX
+    /// from Markdown
+    #[arg(long)]
+    reference_doc: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Flavor {
+    Commonmark,
+    Gfm,
+    Pandoc,
+    Multimarkdown,
+    Obsidian,
+}
+
+impl From<Flavor> for MarkdownFlavor {
+    fn from(flavor: Flavor) -> Self {
+        match flavor {
+            Flavor::Commonmark => MarkdownFlavor::CommonMark,
+            Flavor::Gfm => MarkdownFlavor::Gfm,
+            Flavor::Pandoc => MarkdownFlavor::Pandoc,
+            Flavor::Multimarkdown => MarkdownFlavor::MultiMarkdown,
+            Flavor::Obsidian => MarkdownFlavor::Obsidian,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ListMarkers {
+    Original,
+    Markdown,
+    MarkdownWithLabel,
+}
+
+impl From<ListMarkers> for ListMarkerStyle {
+    fn from(markers: ListMarkers) -> Self {
+        match markers {
+            ListMarkers::Original => ListMarkerStyle::Original,
+            ListMarkers::Markdown => ListMarkerStyle::Markdown,
+            ListMarkers::MarkdownWithLabel => ListMarkerStyle::MarkdownWithLabel,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ContentControls {
+    Flatten,
+    Comments,
+    Spans,
+}
+
+impl From<ContentControls> for ContentControlMarkup {
+    fn from(markup: ContentControls) -> Self {
+        match markup {
+            ContentControls::Flatten => ContentControlMarkup::Flatten,
+            ContentControls::Comments => ContentControlMarkup::Comments,
+            ContentControls::Spans => ContentControlMarkup::Spans,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Toc {
+    Keep,
+    Regenerate,
+    #[value(alias = "drop")]
+    Remove,
+}
+
+impl From<Toc> for TocHandling {
+    fn from(toc: Toc) -> Self {
+        match toc {
+            Toc::Keep => TocHandling::Keep,
+            Toc::Regenerate => TocHandling::Regenerate,
+            Toc::Remove => TocHandling::Remove,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Anchors {
+    Bookmarks,
+    Github,
+    Pandoc,
+}
+
+impl From<Anchors> for AnchorStyle {
+    fn from(anchors: Anchors) -> Self {
+        match anchors {
+            Anchors::Bookmarks => AnchorStyle::Bookmarks,
+            Anchors::Github => AnchorStyle::Github,
+            Anchors::Pandoc => AnchorStyle::Pandoc,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    Markdown,
+    Text,
+    Asciidoc,
+    Rst,
+    Latex,
+    Typst,
+    Org,
+    Mediawiki,
+    Ipynb,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TextNotes {
+    End,
+    Inline,
+}
+
+impl From<TextNotes> for NotePlacement {
+    fn from(notes: TextNotes) -> Self {
+        match notes {
+            TextNotes::End => NotePlacement::End,
+            TextNotes::Inline => NotePlacement::Inline,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ChunkUnit {
+    Chars,
+    Tokens,
+}
+
+/// Converts the input, or splits it into chunks written as JSON lines.
+fn run<R: Renderer>(
+    converter: DocxToMarkdown<DocxExtractor, R>,
+    args: &Args,
+) -> dm2xcod::Result<String> {
+    let Some(size) = args.chunk_size else {
+        return converter.convert(&args.input);
+    };
+    let options = ChunkOptions {
+        target_size: match args.chunk_unit {
+            ChunkUnit::Chars => ChunkSize::Chars(size),
+            ChunkUnit::Tokens => ChunkSize::Tokens(size),
+        },
+        heading_level: args.chunk_heading_level,
+    };
+    let chunks = converter.chunk(&args.input, &options)?;
+    Ok(chunks
+        .iter()
+        .map(|chunk| chunk.to_json())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Writes the document split at `level` to the output directory.
+fn split(converter: DocxToMarkdown, args: &Args, level: usize) {
+    if !matches!(args.to, OutputFormat::Markdown) {
+        eprintln!("Error: --split-level writes Markdown files only");
+        std::process::exit(1);
+    }
+    let Some(dir) = &args.output else {
+        eprintln!("Error: --split-level needs an output directory");
+        std::process::exit(1);
+    };
+
+    let files = match converter.split(&args.input, level) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Error converting DOCX: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let written = std::fs::create_dir_all(dir).and_then(|()| {
+        files
+            .iter()
+            .try_for_each(|file| std::fs::write(dir.join(&file.name), &file.content))
+    });
+    if let Err(e) = written {
+        eprintln!("Error writing output: {}", e);
+        std::process::exit(1);
+    }
+    println!("Successfully wrote {} files to {:?}", files.len(), dir);
+}
+
+/// Converts the Markdown input to a DOCX file.
+fn write_docx(args: &Args) {
+    let Some(output) = &args.output else {
+        eprintln!("Error: converting Markdown needs an output DOCX path");
+        std::process::exit(1);
+    };
+    let converter = MarkdownToDocx::new(WriteOptions {
+        reference_docx: args.reference_doc.clone(),
+        ..Default::default()
+    });
+    let written = converter
+        .convert(&args.input)
+        .and_then(|docx| std::fs::write(output, docx).map_err(Into::into));
+    if let Err(e) = written {
+        eprintln!("Error converting Markdown: {}", e);
+        std::process::exit(1);
+    }
+    println!("Successfully converted to {:?}", output);
 }
 
 fn main() {
     let args = Args::parse();
 
+    let is_markdown = args
+        .input
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "md" | "markdown"));
+    if is_markdown {
+        write_docx(&args);
+        return;
+    }
+
     let image_handling = if args.skip_images {
         ImageHandling::Skip
-    } else if let Some(dir) = args.images_dir {
+    } else if let Some(dir) = args.images_dir.clone() {
         ImageHandling::SaveToDir(dir)
     } else {
         ImageHandling::Inline
@@ -35,15 +320,88 @@ fn main() {
 
     let options = ConvertOptions {
         image_handling,
+        infer_headings: args.infer_headings,
+        flavor: args.flavor.map(MarkdownFlavor::from),
+        list_markers: args.list_markers.into(),
+        keep_bullet_glyphs: args.keep_bullet_glyphs,
+        content_controls: args.content_controls.into(),
+        merge_field_placeholders: args.merge_field_placeholders,
+        toc: args.toc.into(),
+        anchors: args.anchors.into(),
+        text_notes: args.text_notes.into(),
+        notebook_language: args.notebook_language.clone(),
         ..Default::default()
     };
 
-    let converter = DocxToMarkdown::new(options);
+    if let Some(level) = args.split_level {
+        split(DocxToMarkdown::new(options), &args, level);
+        return;
+    }
+
+    let result = match args.to {
+        OutputFormat::Markdown => run(DocxToMarkdown::new(options), &args),
+        OutputFormat::Text => {
+            let renderer = PlainTextRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Asciidoc => {
+            let renderer = AsciiDocRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Rst => {
+            let renderer = RstRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Latex => {
+            let renderer = LatexRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Typst => {
+            let renderer = TypstRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Org => {
+            let renderer = OrgRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Mediawiki => {
+            let renderer = MediaWikiRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+        OutputFormat::Ipynb => {
+            let renderer = NotebookRenderer::new(&options);
+            run(
+                DocxToMarkdown::with_components(options, DocxExtractor, renderer),
+                &args,
+            )
+        }
+    };
 
-    match converter.convert(&args.input) {
+    match result {
         Ok(markdown) => {
-            if let Some(output) = args.output {
-                if let Err(e) = std::fs::write(&output, &markdown) {
+            if let Some(output) = &args.output {
+                if let Err(e) = std::fs::write(output, &markdown) {
                     eprintln!("Error writing output: {}", e);
                     std::process::exit(1);
                 }
@@ -0,0 +1,631 @@
+//! AsciiDoc output, e.g. for Antora.
+//!
+//! Literal text that could be read as AsciiDoc markup is written as an inline
+//! passthrough (`++text++`); paragraphs that would start a block are
+//! prefixed with `{empty}`.
+
+use crate::core::ast::{
+    BlockNode, DocumentAst, Heading, InlineNode, List, ListKind, Paragraph, ReferenceDefinitions,
+    Table, TableCell, TextStyle, TocEntry,
+};
+use crate::render::{checkbox_glyph, item_label, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+use std::collections::HashMap;
+
+/// Admonition a paragraph style can be written as (`NOTE: text`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admonition {
+    Note,
+    Tip,
+    Important,
+    Caution,
+    Warning,
+}
+
+impl Admonition {
+    /// Label of the admonition, e.g. `NOTE`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Note => "NOTE",
+            Self::Tip => "TIP",
+            Self::Important => "IMPORTANT",
+            Self::Caution => "CAUTION",
+            Self::Warning => "WARNING",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AsciiDocRenderer {
+    /// Admonitions by lower-case paragraph style id.
+    admonitions: HashMap<String, Admonition>,
+    list_markers: ListMarkerStyle,
+}
+
+impl Default for AsciiDocRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl AsciiDocRenderer {
+    /// Creates a renderer using the list markers of `options`. Paragraphs
+    /// styled `Note`, `Tip`, `Important`, `Caution` or `Warning` become
+    /// admonitions.
+    pub fn new(options: &ConvertOptions) -> Self {
+        let admonitions = [
+            Admonition::Note,
+            Admonition::Tip,
+            Admonition::Important,
+            Admonition::Caution,
+            Admonition::Warning,
+        ]
+        .into_iter()
+        .map(|admonition| (admonition.label().to_lowercase(), admonition))
+        .collect();
+        Self {
+            admonitions,
+            list_markers: options.list_markers,
+        }
+    }
+
+    /// Writes paragraphs of the style with id `style` (case-insensitive) as
+    /// `admonition`.
+    pub fn with_admonition(mut self, style: &str, admonition: Admonition) -> Self {
+        self.admonitions.insert(style.to_lowercase(), admonition);
+        self
+    }
+
+    fn render_blocks(&self, blocks: &[BlockNode], refs: &ReferenceDefinitions) -> String {
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, refs))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&self, block: &BlockNode, refs: &ReferenceDefinitions) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph, refs),
+            BlockNode::Heading(heading) => self.render_heading(heading, refs),
+            BlockNode::TableOfContents(entries) => render_toc(entries),
+            BlockNode::Table(table) => self.render_table(table, '|', refs),
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list, list.level + 1, refs),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, refs),
+        }
+    }
+
+    fn render_paragraph(&self, paragraph: &Paragraph, refs: &ReferenceDefinitions) -> String {
+        match paragraph.content.as_slice() {
+            [InlineNode::Image { source, alt, .. }] => {
+                return format!("image::{}[{}]", source, image_alt(alt));
+            }
+            [InlineNode::Math { tex, display: true }] => {
+                return format!("[latexmath]\n++++\n{}\n++++", tex);
+            }
+            _ => {}
+        }
+
+        let text = self.render_inlines(&paragraph.content, refs);
+        let text = text.trim();
+        if text.is_empty() {
+            return String::new();
+        }
+        let admonition = paragraph
+            .style
+            .as_deref()
+            .and_then(|style| self.admonitions.get(&style.to_lowercase()));
+        match admonition {
+            Some(admonition) => format!("{}: {}", admonition.label(), text),
+            None if starts_block(text) => format!("{{empty}}{}", text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Renders a section title, or a discrete heading below the deepest
+    /// section level.
+    fn render_heading(&self, heading: &Heading, refs: &ReferenceDefinitions) -> String {
+        let mut out = String::new();
+        if let Some(id) = &heading.id {
+            out.push_str(&format!("[[{}]]\n", id));
+        }
+        if heading.level > 5 {
+            out.push_str("[discrete]\n");
+        }
+        out.push_str(&"=".repeat(heading.level.min(5) + 1));
+        out.push(' ');
+        for anchor in &heading.anchors {
+            out.push_str(&format!("[[{}]]", anchor));
+        }
+        out.push_str(&escape_span(&heading.label));
+        out.push_str(self.render_inlines(&heading.content, refs).trim());
+        out
+    }
+
+    /// Renders a list with markers of `depth` characters (`**`, `..`), and
+    /// Word's labels as text unless list markers drop them. Blocks after the
+    /// first of an item are attached with `+`; nested lists follow directly.
+    fn render_list(&self, list: &List, depth: usize, refs: &ReferenceDefinitions) -> String {
+        let mut lines = Vec::new();
+        if let Some(start) = list.start.filter(|&start| start != 1) {
+            if list.kind == ListKind::Ordered {
+                lines.push(format!("[start={}]", start));
+            }
+        }
+        let marker = match list.kind {
+            ListKind::Bullet => "*".repeat(depth),
+            ListKind::Ordered => ".".repeat(depth),
+        };
+        for item in &list.items {
+            let mut line = marker.clone();
+            line.push(' ');
+            match (item.checked, list.kind) {
+                (Some(true), ListKind::Bullet) => line.push_str("[x] "),
+                (Some(false), ListKind::Bullet) => line.push_str("[ ] "),
+                (Some(checked), ListKind::Ordered) => {
+                    line.push(checkbox_glyph(checked));
+                    line.push(' ');
+                }
+                (None, _) => {}
+            }
+            if let Some(label) = item_label(self.list_markers, list.kind, item) {
+                line.push_str(&escape_span(label));
+                line.push(' ');
+            }
+
+            let mut blocks = item.blocks.iter();
+            match item.blocks.first() {
+                Some(BlockNode::Paragraph(paragraph)) => {
+                    blocks.next();
+                    line.push_str(self.render_inlines(&paragraph.content, refs).trim());
+                }
+                _ => line.push_str("{empty}"),
+            }
+            lines.push(line);
+            for block in blocks {
+                let rendered = match block {
+                    BlockNode::List(nested) => self.render_list(nested, depth + 1, refs),
+                    block => {
+                        let rendered = self.render_block(block, refs);
+                        if rendered.is_empty() {
+                            continue;
+                        }
+                        format!("+\n{}", rendered)
+                    }
+                };
+                lines.push(rendered);
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Renders a table with cells separated by `separator`: `|`, or `!` for
+    /// a table nested in a cell. Tables nested deeper are flattened to text.
+    fn render_table(&self, table: &Table, separator: char, refs: &ReferenceDefinitions) -> String {
+        let columns: usize = table
+            .rows
+            .first()
+            .map_or(0, |row| row.iter().map(|cell| cell.colspan).sum());
+        let mut lines = Vec::new();
+        if columns > 0 {
+            lines.push(format!("[cols=\"{}*\"]", columns));
+        }
+        lines.push(format!("{}===", separator));
+        for row in &table.rows {
+            let cells = row
+                .iter()
+                .map(|cell| self.render_cell(cell, separator, refs))
+                .collect::<Vec<_>>();
+            lines.push(cells.join(" "));
+        }
+        lines.push(format!("{}===", separator));
+        lines.join("\n")
+    }
+
+    /// Renders a cell with its span (`2+|`, `.3+|`); cells with several
+    /// blocks use the AsciiDoc style (`a|`).
+    fn render_cell(
+        &self,
+        cell: &TableCell,
+        separator: char,
+        refs: &ReferenceDefinitions,
+    ) -> String {
+        let mut spec = match (cell.colspan, cell.rowspan) {
+            (1, 1) => String::new(),
+            (columns, 1) => format!("{}+", columns),
+            (1, rows) => format!(".{}+", rows),
+            (columns, rows) => format!("{}.{}+", columns, rows),
+        };
+        let content = match cell.blocks.as_slice() {
+            [] => String::new(),
+            [BlockNode::Paragraph(paragraph)] => self.render_paragraph(paragraph, refs),
+            blocks => {
+                spec.push('a');
+                blocks
+                    .iter()
+                    .map(|block| match block {
+                        BlockNode::Table(table) if separator == '|' => {
+                            self.render_table(table, '!', refs)
+                        }
+                        BlockNode::Table(table) => table
+                            .rows
+                            .iter()
+                            .flatten()
+                            .map(|cell| self.render_blocks(&cell.blocks, refs))
+                            .collect::<Vec<_>>()
+                            .join(" "),
+                        block => self.render_block(block, refs),
+                    })
+                    .filter(|text| !text.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+        };
+        let content = content.replace('|', "\\|");
+        let content = match separator {
+            '!' => content.replace('!', "\\!"),
+            _ => content,
+        };
+        format!("{}{}{}", spec, separator, content)
+            .trim()
+            .to_string()
+    }
+
+    fn render_inlines(&self, nodes: &[InlineNode], refs: &ReferenceDefinitions) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(&mut out, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = self.render_inlines(content, refs);
+                    let text = text.trim();
+                    match destination.strip_prefix('#') {
+                        Some(id) if text.is_empty() => out.push_str(&format!("<<{}>>", id)),
+                        Some(id) => out.push_str(&format!("<<{},{}>>", id, text)),
+                        None => out.push_str(&format!(
+                            "link:{}[{}]",
+                            destination.replace(' ', "%20"),
+                            text.replace(']', "\\]")
+                        )),
+                    }
+                }
+                InlineNode::Anchor(name) => out.push_str(&format!("[[{}]]", name)),
+                InlineNode::Math { tex, .. } => {
+                    out.push_str(&format!("latexmath:[{}]", tex.replace(']', "\\]")))
+                }
+                InlineNode::Image { source, alt, .. } => {
+                    out.push_str(&format!("image:{}[{}]", source, image_alt(alt)))
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = refs.note_text(*kind, id) {
+                        out.push_str(&format!(
+                            "footnote:[{}]",
+                            escape_span(text.trim()).replace(']', "\\]")
+                        ));
+                    }
+                }
+                InlineNode::PageBreak => out.push_str("\n\n<<<\n\n"),
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::CheckBox { checked } => out.push(checkbox_glyph(*checked)),
+                InlineNode::ContentControl { content, .. } => {
+                    out.push_str(&self.render_inlines(content, refs))
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Writes formatted text line by line, line breaks as hard breaks (` +`).
+/// Formatting uses the unconstrained forms (`**bold**`), which also work
+/// inside words, and stays inside the surrounding whitespace.
+fn push_text(out: &mut String, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str(" +\n");
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            out.push_str(line);
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        out.push_str(&line[..start]);
+        out.push_str(&format_span(core, style));
+        out.push_str(&line[start + core.len()..]);
+    }
+}
+
+fn format_span(text: &str, style: &TextStyle) -> String {
+    let mut text = if (style.superscript || style.subscript) && text.contains(' ') {
+        // Superscript and subscript cannot contain spaces, passthroughs can
+        passthrough(text)
+    } else {
+        escape_span(text)
+    };
+    if style.superscript {
+        text = format!("^{}^", text);
+    } else if style.subscript {
+        text = format!("~{}~", text);
+    }
+
+    let mut roles = Vec::new();
+    if style.underline || style.insertion {
+        roles.push("underline");
+    }
+    if style.strike || style.deletion {
+        roles.push("line-through");
+    }
+    if style.small_caps && !style.all_caps {
+        roles.push("small-caps");
+    }
+    if !roles.is_empty() {
+        if style.highlight {
+            roles.push("mark");
+        }
+        text = format!("[.{}]##{}##", roles.join("."), text);
+    } else if style.highlight {
+        text = format!("##{}##", text);
+    }
+
+    if style.italic {
+        text = format!("__{}__", text);
+    }
+    if style.bold {
+        text = format!("**{}**", text);
+    }
+    text
+}
+
+/// Escapes literal text that could be read as inline markup as a
+/// passthrough.
+fn escape_span(text: &str) -> String {
+    const MARKUP: &[char] = &['*', '_', '`', '#', '^', '~', '+', '{', '}', '\\'];
+    const PAIRS: &[&str] = &["[[", "]]", "<<", ">>", "((", "))", "::"];
+    if text.contains(MARKUP) || PAIRS.iter().any(|pair| text.contains(pair)) {
+        passthrough(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Wraps text in an inline passthrough, shown as is.
+fn passthrough(text: &str) -> String {
+    if text.contains("++") {
+        format!("pass:c[{}]", text.replace(']', "\\]"))
+    } else {
+        format!("++{}++", text)
+    }
+}
+
+/// Whether paragraph text would be read as the start of a block (a list
+/// item, title, attribute line, delimiter or admonition).
+fn starts_block(text: &str) -> bool {
+    if text.starts_with("[[") {
+        return false;
+    }
+    if text.starts_with(['=', '*', '-', '.', '/', '|', ':', '<', '>', '\'', '[', '+']) {
+        return true;
+    }
+    let marker_end = text
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(text.len());
+    let after = &text[marker_end..];
+    let numbered = marker_end > 0 && (after.starts_with(". ") || after.starts_with(") "));
+    let admonition = ["NOTE:", "TIP:", "IMPORTANT:", "CAUTION:", "WARNING:"]
+        .iter()
+        .any(|label| text.starts_with(label));
+    (numbered && marker_end <= 4) || admonition
+}
+
+/// Image macro attributes for alternative text.
+fn image_alt(alt: &str) -> String {
+    if alt.contains([',', ']', '"', '=']) {
+        format!("\"{}\"", alt.replace('"', "\\\"").replace(']', "\\]"))
+    } else {
+        alt.to_string()
+    }
+}
+
+/// Renders table of contents entries as a nested list of cross references.
+fn render_toc(entries: &[TocEntry]) -> String {
+    let top = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+    let mut lines = Vec::with_capacity(entries.len());
+    let mut depth = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        depth = if i == 0 {
+            0
+        } else {
+            (entry.level - top).min(depth + 1)
+        };
+        let text = escape_span(&entry.text);
+        let line = match entry.destination.strip_prefix('#') {
+            Some(id) => format!("{} <<{},{}>>", "*".repeat(depth + 1), id, text),
+            None => format!("{} {}", "*".repeat(depth + 1), text),
+        };
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+impl Renderer for AsciiDocRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let mut out = self.render_blocks(&document.blocks, &document.references);
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{ListItem, NoteKind};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn cell(content: &str, colspan: usize, rowspan: usize) -> TableCell {
+        TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan,
+        }
+    }
+
+    fn render(blocks: Vec<BlockNode>) -> String {
+        render_with(&ConvertOptions::default(), blocks)
+    }
+
+    fn render_with(options: &ConvertOptions, blocks: Vec<BlockNode>) -> String {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["See [1].".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        AsciiDocRenderer::new(options)
+            .render(&document)
+            .expect("render should work")
+    }
+
+    #[test]
+    fn test_asciidoc_headings_paragraphs_and_admonitions() {
+        let bold = InlineNode::Text {
+            text: "bold".to_string(),
+            style: TextStyle {
+                bold: true,
+                ..Default::default()
+            },
+        };
+        let rendered = render(vec![
+            BlockNode::Heading(Heading {
+                level: 1,
+                label: "1. ".to_string(),
+                content: vec![text("Intro")],
+                id: None,
+                anchors: vec!["_Toc1".to_string()],
+            }),
+            paragraph(vec![
+                text("Some "),
+                bold,
+                text(" and snake_case"),
+                InlineNode::NoteReference {
+                    kind: NoteKind::Footnote,
+                    id: "1".to_string(),
+                },
+                text(", see "),
+                InlineNode::Link {
+                    destination: "#_Toc1".to_string(),
+                    content: vec![text("intro")],
+                },
+            ]),
+            BlockNode::Paragraph(Paragraph {
+                content: vec![text("Back up first.")],
+                style: Some("Warning".to_string()),
+                ..Default::default()
+            }),
+            paragraph(vec![text("* not a list")]),
+        ]);
+        assert_eq!(
+            rendered,
+            "== [[_Toc1]]1. Intro\n\n\
+             Some **bold** ++and snake_case++footnote:[See [1\\].], see <<_Toc1,intro>>\n\n\
+             WARNING: Back up first.\n\n\
+             {empty}++* not a list++\n"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc_nested_lists_and_spanned_cells() {
+        let nested = BlockNode::List(List {
+            kind: ListKind::Ordered,
+            start: Some(3),
+            level: 1,
+            items: vec![ListItem {
+                label: "c.".to_string(),
+                separator: " ".to_string(),
+                number: Some(3),
+                checked: None,
+                blocks: vec![paragraph(vec![text("Third")])],
+            }],
+        });
+        let list = BlockNode::List(List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 0,
+            items: vec![ListItem {
+                label: "•".to_string(),
+                separator: "\t".to_string(),
+                number: None,
+                checked: Some(true),
+                blocks: vec![
+                    paragraph(vec![text("Done")]),
+                    paragraph(vec![text("More")]),
+                    nested,
+                ],
+            }],
+        });
+        let table = BlockNode::Table(Table {
+            rows: vec![
+                vec![cell("A", 2, 1), cell("B", 1, 2)],
+                vec![cell("C|D", 1, 1), cell("E", 1, 1)],
+            ],
+        });
+        assert_eq!(
+            render(vec![list, table]),
+            "* [x] Done\n+\nMore\n[start=3]\n.. Third\n\n\
+             [cols=\"3*\"]\n|===\n2+|A .2+|B\n|C\\|D |E\n|===\n"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc_list_markers_keep_labels_as_text() {
+        let list = || {
+            vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(1),
+                level: 0,
+                items: vec![ListItem {
+                    label: "(a)".to_string(),
+                    separator: "\t".to_string(),
+                    number: Some(1),
+                    checked: None,
+                    blocks: vec![paragraph(vec![text("First")])],
+                }],
+            })]
+        };
+        assert_eq!(render(list()), ". First\n");
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            ..Default::default()
+        };
+        assert_eq!(render_with(&options, list()), ". (a) First\n");
+    }
+}
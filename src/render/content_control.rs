@@ -0,0 +1,90 @@
+//! Markup around content controls.
+
+use super::escape::escape_html_attr;
+use crate::core::ast::ContentControl;
+use crate::ContentControlMarkup;
+
+/// Returns the opening and closing markup of a content control, or `None`
+/// when controls are flattened. `block` wraps blocks in `<div>` instead of
+/// `<span>`.
+pub(crate) fn content_control_delimiters(
+    markup: ContentControlMarkup,
+    control: &ContentControl,
+    block: bool,
+) -> Option<(String, String)> {
+    let mut attrs = Vec::new();
+    if let Some(tag) = &control.tag {
+        attrs.push(("tag", tag.as_str()));
+    }
+    if let Some(alias) = &control.alias {
+        attrs.push(("alias", alias.as_str()));
+    }
+    attrs.push(("type", control.kind.name()));
+    if let Some(binding) = &control.data_binding {
+        attrs.push(("binding", binding.as_str()));
+    }
+    if control.showing_placeholder {
+        attrs.push(("placeholder", "true"));
+    }
+
+    match markup {
+        ContentControlMarkup::Flatten => None,
+        ContentControlMarkup::Comments => {
+            let attrs: String = attrs
+                .iter()
+                // "--" may not appear inside a comment
+                .map(|(name, value)| {
+                    format!(
+                        " {}=\"{}\"",
+                        name,
+                        escape_html_attr(value).replace("--", "-&#45;")
+                    )
+                })
+                .collect();
+            Some((
+                format!("<!-- sdt{} -->", attrs),
+                "<!-- /sdt -->".to_string(),
+            ))
+        }
+        ContentControlMarkup::Spans => {
+            let element = if block { "div" } else { "span" };
+            let attrs: String = attrs
+                .iter()
+                .map(|(name, value)| format!(" data-{}=\"{}\"", name, escape_html_attr(value)))
+                .collect();
+            Some((format!("<{}{}>", element, attrs), format!("</{}>", element)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::ContentControlKind;
+
+    #[test]
+    fn test_delimiters_carry_metadata() {
+        let control = ContentControl {
+            tag: Some("client_name".to_string()),
+            alias: Some("Client \"name\"".to_string()),
+            data_binding: Some("/ns0:root[1]/ns0:client[1]".to_string()),
+            kind: ContentControlKind::PlainText,
+            showing_placeholder: false,
+        };
+
+        assert_eq!(
+            content_control_delimiters(ContentControlMarkup::Comments, &control, false),
+            Some((
+                "<!-- sdt tag=\"client_name\" alias=\"Client &quot;name&quot;\" type=\"text\" binding=\"/ns0:root[1]/ns0:client[1]\" -->".to_string(),
+                "<!-- /sdt -->".to_string()
+            ))
+        );
+        let (open, close) =
+            content_control_delimiters(ContentControlMarkup::Spans, &control, true).unwrap();
+        assert!(open.starts_with("<div data-tag=\"client_name\" data-alias="));
+        assert_eq!(close, "</div>");
+        assert!(
+            content_control_delimiters(ContentControlMarkup::Flatten, &control, false).is_none()
+        );
+    }
+}
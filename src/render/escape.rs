@@ -41,6 +41,137 @@ pub fn escape_markdown_link_destination(value: &str) -> String {
     escaped
 }
 
+/// Where literal text is placed, which decides what must be escaped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextContext {
+    /// Markdown block content.
+    #[default]
+    Block,
+    /// Raw HTML block content (e.g. HTML table cells), where Markdown
+    /// backslash escapes are not processed.
+    HtmlBlock,
+    /// Cell of a pipe table; like `Block`, with `|` escaped as well.
+    PipeTableCell,
+}
+
+/// Escapes HTML-significant characters in text content.
+pub fn escape_html_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Escapes literal document text so it renders as-is in `context`.
+///
+/// Inline syntax (emphasis delimiters, code spans, brackets, HTML tags and
+/// entities) is escaped everywhere; block markers (`#`, `>`, list bullets,
+/// ordered list numbers, setext underlines) only where a line starts, which
+/// includes the start of `value` when `at_line_start` is set.
+pub fn escape_markdown_text(value: &str, context: TextContext, at_line_start: bool) -> String {
+    escape_markdown_text_with(value, context, at_line_start, &[])
+}
+
+/// Like [`escape_markdown_text`], additionally escaping `extra` characters
+/// that carry meaning in the target flavor (e.g. `^`, `$`).
+pub fn escape_markdown_text_with(
+    value: &str,
+    context: TextContext,
+    at_line_start: bool,
+    extra: &[char],
+) -> String {
+    if context == TextContext::HtmlBlock {
+        return escape_html_text(value);
+    }
+
+    let chars: Vec<char> = value.chars().collect();
+    let mut escape = vec![false; chars.len()];
+    let mut line_start = at_line_start;
+
+    for i in 0..chars.len() {
+        let ch = chars[i];
+        if ch == '\n' {
+            line_start = true;
+            continue;
+        }
+        if line_start {
+            if ch == ' ' || ch == '\t' {
+                continue;
+            }
+            line_start = false;
+            if let Some(offset) = block_marker_offset(&chars[i..]) {
+                escape[i + offset] = true;
+            }
+        }
+
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        escape[i] |= match ch {
+            '\\' | '`' | '*' | '[' | ']' | '~' => true,
+            // Intraword underscores never delimit emphasis.
+            '_' => {
+                !(prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric))
+            }
+            '<' => next.is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?')),
+            '&' => starts_entity(&chars[i + 1..]),
+            '|' => context == TextContext::PipeTableCell,
+            _ => extra.contains(&ch),
+        };
+    }
+
+    let mut escaped = String::with_capacity(value.len());
+    for (ch, escape) in chars.into_iter().zip(escape) {
+        if escape {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Returns the offset of the character to escape when `line` starts with a
+/// Markdown block marker.
+fn block_marker_offset(line: &[char]) -> Option<usize> {
+    let end = line.iter().position(|&c| c == '\n').unwrap_or(line.len());
+    let line = &line[..end];
+    let followed_by_space = |idx: usize| line.get(idx).map_or(true, |c| *c == ' ' || *c == '\t');
+
+    match line.first()? {
+        '#' | '>' => Some(0),
+        '-' | '+' if followed_by_space(1) => Some(0),
+        // Thematic breaks and setext heading underlines
+        c @ ('-' | '=') if line.iter().all(|ch| ch == c || *ch == ' ') => Some(0),
+        c if c.is_ascii_digit() => {
+            let digits = line.iter().take_while(|c| c.is_ascii_digit()).count();
+            let is_marker = digits <= 9
+                && matches!(line.get(digits), Some('.' | ')'))
+                && followed_by_space(digits + 1);
+            is_marker.then_some(digits)
+        }
+        _ => None,
+    }
+}
+
+/// Returns true when the text after `&` forms an HTML entity reference.
+fn starts_entity(rest: &[char]) -> bool {
+    let Some(semicolon) = rest.iter().position(|&c| c == ';') else {
+        return false;
+    };
+    let name = &rest[..semicolon];
+    match name {
+        ['#', 'x' | 'X', hex @ ..] => !hex.is_empty() && hex.iter().all(char::is_ascii_hexdigit),
+        ['#', digits @ ..] => !digits.is_empty() && digits.iter().all(char::is_ascii_digit),
+        _ => !name.is_empty() && name.iter().all(char::is_ascii_alphanumeric),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,4 +196,49 @@ mod tests {
             "https://x.y/a\\ b\\(c\\)"
         );
     }
+
+    #[test]
+    fn test_escape_markdown_text_inline_syntax() {
+        assert_eq!(
+            escape_markdown_text(
+                "a*b* `c` [d] <br> x < y snake_case _u_",
+                TextContext::Block,
+                false
+            ),
+            "a\\*b\\* \\`c\\` \\[d\\] \\<br> x < y snake_case \\_u\\_"
+        );
+        assert_eq!(
+            escape_markdown_text("R&D &amp; &#123; a|b", TextContext::Block, false),
+            "R&D \\&amp; \\&#123; a|b"
+        );
+        assert_eq!(
+            escape_markdown_text("a|b", TextContext::PipeTableCell, false),
+            "a\\|b"
+        );
+    }
+
+    #[test]
+    fn test_escape_markdown_text_line_start_markers() {
+        let block = |s: &str| escape_markdown_text(s, TextContext::Block, true);
+        assert_eq!(block("# not a heading"), "\\# not a heading");
+        assert_eq!(block("> quote"), "\\> quote");
+        assert_eq!(block("- item\n+ item"), "\\- item\n\\+ item");
+        assert_eq!(block("1. first"), "1\\. first");
+        assert_eq!(block("2) second"), "2\\) second");
+        assert_eq!(block("==="), "\\===");
+        assert_eq!(block("-5 degrees"), "-5 degrees");
+        assert_eq!(block("2.5 million"), "2.5 million");
+        assert_eq!(
+            escape_markdown_text("# mid-line", TextContext::Block, false),
+            "# mid-line"
+        );
+    }
+
+    #[test]
+    fn test_escape_html_block_text() {
+        assert_eq!(
+            escape_markdown_text("*a* <b> & c", TextContext::HtmlBlock, true),
+            "*a* &lt;b&gt; &amp; c"
+        );
+    }
 }
@@ -0,0 +1,86 @@
+//! Layout of table cells on the grid of rows and columns they span, and
+//! the width of their text.
+
+use crate::core::ast::TableCell;
+
+/// Table cell at its position in the grid; `None` fills a position no cell
+/// covers.
+pub(crate) struct PlacedCell<'a> {
+    pub cell: Option<&'a TableCell>,
+    pub row: usize,
+    pub column: usize,
+    pub rowspan: usize,
+    pub colspan: usize,
+}
+
+/// Places the cells of `rows` in a grid, after the cells spanning into
+/// their row. Row spans are cut at the last row, and empty cells fill the
+/// rest of the grid.
+pub(crate) fn place_cells(rows: &[Vec<TableCell>]) -> Vec<PlacedCell<'_>> {
+    let mut covered: Vec<Vec<bool>> = vec![Vec::new(); rows.len()];
+    let mut placed = Vec::new();
+    for (r, row) in rows.iter().enumerate() {
+        let mut column = 0;
+        for cell in row {
+            while covered[r].get(column).copied().unwrap_or(false) {
+                column += 1;
+            }
+            let rowspan = cell.rowspan.clamp(1, rows.len() - r);
+            let colspan = cell.colspan.max(1);
+            for covered_row in &mut covered[r..r + rowspan] {
+                if covered_row.len() < column + colspan {
+                    covered_row.resize(column + colspan, false);
+                }
+                covered_row[column..column + colspan].fill(true);
+            }
+            placed.push(PlacedCell {
+                cell: Some(cell),
+                row: r,
+                column,
+                rowspan,
+                colspan,
+            });
+            column += colspan;
+        }
+    }
+    let columns = covered.iter().map(Vec::len).max().unwrap_or(0);
+    for (r, row) in covered.iter().enumerate() {
+        for column in 0..columns {
+            if !row.get(column).copied().unwrap_or(false) {
+                placed.push(PlacedCell {
+                    cell: None,
+                    row: r,
+                    column,
+                    rowspan: 1,
+                    colspan: 1,
+                });
+            }
+        }
+    }
+    placed
+}
+
+/// Width of text in columns, wide East Asian characters taking two.
+pub(crate) fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum()
+}
+
+pub(crate) fn is_wide(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6
+            | 0x1F300..=0x1F64F
+            | 0x1F900..=0x1F9FF
+            | 0x20000..=0x3FFFD
+    )
+}
@@ -0,0 +1,615 @@
+//! Inline formatting engine shared by paragraphs, runs and hyperlinks.
+
+use super::content_control::content_control_delimiters;
+use super::escape::{
+    escape_html_attr, escape_markdown_link_destination, escape_markdown_link_text,
+    escape_markdown_text_with, TextContext,
+};
+use crate::core::ast::{InlineNode, NoteKind, TextStyle};
+use crate::{ContentControlMarkup, ConvertOptions, EmphasisStyle, MarkdownFlavor};
+
+/// Markdown emitted for a page break.
+const PAGE_BREAK: &str = "\n\n---\n\n";
+
+/// Renders inline nodes to Markdown with the configured formatting syntax.
+#[derive(Debug, Clone, Copy)]
+pub struct InlineRenderer {
+    flavor: Option<MarkdownFlavor>,
+    escape_markdown: bool,
+    emphasis_style: EmphasisStyle,
+    html_underline: bool,
+    html_strikethrough: bool,
+    html_sub_superscript: bool,
+    html_highlight: bool,
+    html_caps: bool,
+    html_text_color: bool,
+    content_controls: ContentControlMarkup,
+}
+
+impl Default for InlineRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl InlineRenderer {
+    pub fn new(options: &ConvertOptions) -> Self {
+        let mut renderer = Self {
+            flavor: options.flavor,
+            escape_markdown: options.escape_markdown,
+            emphasis_style: options.emphasis_style,
+            html_underline: options.html_underline,
+            html_strikethrough: options.html_strikethrough,
+            html_sub_superscript: options.html_sub_superscript,
+            html_highlight: options.html_highlight,
+            html_caps: options.html_caps,
+            html_text_color: options.html_text_color,
+            content_controls: options.content_controls,
+        };
+        // A target flavor decides between native syntax and HTML fallbacks
+        if let Some(flavor) = options.flavor {
+            renderer.html_strikethrough = !flavor.supports_strikethrough();
+            renderer.html_sub_superscript = !flavor.supports_sub_superscript();
+            renderer.html_highlight = flavor.highlight_delimiters().is_none();
+        }
+        renderer
+    }
+
+    /// Renders a bookmark target.
+    pub fn render_anchor(&self, name: &str) -> String {
+        if self.uses_attributes() {
+            format!("[]{{#{}}}", name)
+        } else {
+            format!("<a id=\"{}\"></a>", escape_html_attr(name))
+        }
+    }
+
+    /// Renders a note reference; without footnote support it links to the
+    /// note definition instead.
+    pub fn render_note_reference(&self, kind: NoteKind, id: &str) -> String {
+        let label = kind.label(id);
+        match self.flavor {
+            Some(flavor) if !flavor.supports_footnotes() => {
+                format!("<sup>[{0}](#fn-{0})</sup>", label)
+            }
+            _ => format!("[^{}]", label),
+        }
+    }
+
+    /// Whether Pandoc attribute syntax (`{#id}`, `[text]{.class}`) is used.
+    fn uses_attributes(&self) -> bool {
+        self.flavor.is_some_and(MarkdownFlavor::supports_attributes)
+    }
+
+    /// Whether caps formatting is written as upper-case text: all caps
+    /// without a `<span style>`. Small caps text keeps its case.
+    fn uppercases(&self, style: &TextStyle) -> bool {
+        style.all_caps && (self.uses_attributes() || !self.html_caps)
+    }
+
+    /// Characters with flavor-specific meaning that literal text must escape.
+    fn extra_escapes(&self) -> &'static [char] {
+        let caret = !self.html_sub_superscript;
+        let dollar = self
+            .flavor
+            .and_then(|flavor| flavor.math_delimiters(false))
+            .is_some_and(|(open, _)| open == "$");
+        match (caret, dollar) {
+            (true, true) => &['^', '$'],
+            (true, false) => &['^'],
+            (false, true) => &['$'],
+            (false, false) => &[],
+        }
+    }
+
+    /// Renders a sequence of inline nodes placed in `context`.
+    pub fn render(&self, nodes: &[InlineNode], context: TextContext) -> String {
+        let mut out = String::new();
+        self.render_into(nodes, None, false, context, &mut out);
+        out
+    }
+
+    /// Renders `nodes` into `out`; `after` is the character following the
+    /// sequence (used for delimiter flanking checks).
+    fn render_into(
+        &self,
+        nodes: &[InlineNode],
+        after: Option<char>,
+        in_link: bool,
+        context: TextContext,
+        out: &mut String,
+    ) {
+        for (i, node) in nodes.iter().enumerate() {
+            let next = match nodes.get(i + 1) {
+                Some(next) => first_char(next),
+                None => after,
+            };
+            match node {
+                InlineNode::Text { text, style } => {
+                    self.render_text(text, style, next, in_link, context, out)
+                }
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    // Emphasis is rendered inside the label so it never spans
+                    // the link boundary.
+                    let mut label = String::new();
+                    self.render_into(content, Some(']'), true, context, &mut label);
+                    if label.is_empty() {
+                        out.push_str(destination);
+                    } else {
+                        out.push_str(&format!(
+                            "[{}]({})",
+                            label,
+                            escape_markdown_link_destination(destination)
+                        ));
+                    }
+                }
+                InlineNode::Anchor(name) => out.push_str(&self.render_anchor(name)),
+                InlineNode::Math { tex, display } => {
+                    let delimiters = match self.flavor {
+                        Some(flavor) => flavor.math_delimiters(*display),
+                        None if *display => Some(("$$", "$$")),
+                        None => Some(("$", "$")),
+                    };
+                    match delimiters {
+                        Some((open, close)) => out.push_str(&format!("{open}{tex}{close}")),
+                        // Without math support, keep the TeX source verbatim
+                        None => out.push_str(&format!("`{tex}`")),
+                    }
+                }
+                InlineNode::Image { source, alt, .. } => {
+                    // Embedded images use HTML, keeping the data URI out of
+                    // Markdown link syntax
+                    if source.starts_with("data:") {
+                        out.push_str(&format!(
+                            "<img src=\"{}\" alt=\"{}\" />",
+                            source,
+                            escape_html_attr(alt)
+                        ));
+                    } else {
+                        out.push_str(&format!("![{}]({})", alt, source));
+                    }
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    out.push_str(&self.render_note_reference(*kind, id))
+                }
+                InlineNode::PageBreak => out.push_str(PAGE_BREAK),
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::CheckBox { checked } => out.push(checkbox_glyph(*checked)),
+                InlineNode::ContentControl { control, content } => {
+                    match content_control_delimiters(self.content_controls, control, false) {
+                        Some((open, close)) => {
+                            out.push_str(&open);
+                            self.render_into(content, Some('<'), in_link, context, out);
+                            out.push_str(&close);
+                        }
+                        None => self.render_into(content, next, in_link, context, out),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders formatted text line by line, keeping surrounding whitespace
+    /// outside of the formatting markers.
+    fn render_text(
+        &self,
+        text: &str,
+        style: &TextStyle,
+        next: Option<char>,
+        in_link: bool,
+        context: TextContext,
+        out: &mut String,
+    ) {
+        let text = if self.uppercases(style) {
+            text.to_uppercase()
+        } else {
+            text.to_string()
+        };
+
+        let line_count = text.split('\n').count();
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let core = line.trim();
+            if core.is_empty() {
+                out.push_str(line);
+                continue;
+            }
+
+            let start = line.len() - line.trim_start().len();
+            let leading = &line[..start];
+            let trailing = &line[start + core.len()..];
+            let at_line_start = !in_link && (out.is_empty() || out.ends_with('\n'));
+            out.push_str(leading);
+
+            let before = out.chars().last();
+            let line_end = if i + 1 < line_count { Some('\n') } else { next };
+            let after = trailing.chars().next().or(line_end);
+            let core = if self.escape_markdown {
+                escape_markdown_text_with(core, context, at_line_start, self.extra_escapes())
+            } else if in_link {
+                escape_markdown_link_text(core)
+            } else {
+                core.to_string()
+            };
+            out.push_str(&self.format_span(core, style, before, after));
+            out.push_str(trailing);
+        }
+    }
+
+    /// Wraps a whitespace-trimmed span with its formatting, innermost first.
+    fn format_span(
+        &self,
+        mut text: String,
+        style: &TextStyle,
+        before: Option<char>,
+        after: Option<char>,
+    ) -> String {
+        let delimit = |text: String, marker: &str, open: &str, close: &str, intraword: bool| {
+            if can_delimit(&text, marker, before, after, intraword) {
+                format!("{marker}{text}{marker}")
+            } else {
+                format!("{open}{text}{close}")
+            }
+        };
+
+        if style.deletion {
+            text = if self.flavor.is_some() && self.html_strikethrough {
+                format!("<del>{}</del>", text)
+            } else {
+                delimit(text, "~~", "<del>", "</del>", true)
+            };
+        }
+        if style.insertion {
+            text = format!("<ins>{}</ins>", text);
+        }
+
+        if style.superscript || style.subscript {
+            let (tag, marker) = if style.superscript {
+                ("sup", "^")
+            } else {
+                ("sub", "~")
+            };
+            if self.html_sub_superscript {
+                text = format!("<{tag}>{text}</{tag}>");
+            } else {
+                // Pandoc syntax does not allow unescaped spaces inside the markers
+                text = format!("{marker}{}{marker}", text.replace(' ', "\\ "));
+            }
+        }
+
+        if self.uses_attributes() {
+            if style.small_caps && !style.all_caps {
+                text = format!("[{}]{{.smallcaps}}", text);
+            }
+        } else if (style.all_caps || style.small_caps) && self.html_caps {
+            let css = if style.all_caps {
+                "text-transform: uppercase;"
+            } else {
+                "font-variant: small-caps;"
+            };
+            text = format!("<span style=\"{}\">{}</span>", css, text);
+        }
+
+        if let Some(color) = style.color.as_ref().filter(|_| self.html_text_color) {
+            text = format!(
+                "<span style=\"color: {};\">{}</span>",
+                escape_html_attr(color),
+                text
+            );
+        }
+
+        if style.highlight {
+            let delimiters = self.flavor.and_then(MarkdownFlavor::highlight_delimiters);
+            text = match delimiters {
+                _ if self.html_highlight => format!("<mark>{}</mark>", text),
+                Some((open, close)) if open != close => format!("{open}{text}{close}"),
+                Some((marker, _)) => delimit(text, marker, "<mark>", "</mark>", true),
+                None => delimit(text, "==", "<mark>", "</mark>", true),
+            };
+        }
+
+        if style.underline && !style.insertion {
+            if self.uses_attributes() {
+                text = format!("[{}]{{.underline}}", text);
+            } else if self.html_underline {
+                text = format!("<u>{}</u>", text);
+            }
+        }
+
+        if style.strike && !style.deletion {
+            text = if self.html_strikethrough {
+                format!("<s>{}</s>", text)
+            } else {
+                delimit(text, "~~", "<s>", "</s>", true)
+            };
+        }
+
+        let (open, close, strength) = match (style.bold, style.italic) {
+            (true, true) => ("<strong><em>", "</em></strong>", 3),
+            (true, false) => ("<strong>", "</strong>", 2),
+            (false, true) => ("<em>", "</em>", 1),
+            (false, false) => return text,
+        };
+        match self.emphasis_style {
+            EmphasisStyle::Html => format!("{open}{text}{close}"),
+            EmphasisStyle::Asterisk => delimit(text, &"*".repeat(strength), open, close, true),
+            EmphasisStyle::Underscore => delimit(text, &"_".repeat(strength), open, close, false),
+        }
+    }
+}
+
+/// Returns the first character a node renders to, as far as flanking is concerned.
+fn first_char(node: &InlineNode) -> Option<char> {
+    match node {
+        InlineNode::Text { text, .. } => text.chars().next(),
+        InlineNode::Link { .. } => Some('['),
+        InlineNode::Anchor(_) => Some('<'),
+        InlineNode::Math { .. } => Some('$'),
+        InlineNode::Image { source, .. } if source.starts_with("data:") => Some('<'),
+        InlineNode::Image { .. } => Some('!'),
+        InlineNode::NoteReference { .. } => Some('['),
+        InlineNode::PageBreak => Some('\n'),
+        InlineNode::Raw(raw) => raw.chars().next(),
+        InlineNode::CheckBox { checked } => Some(checkbox_glyph(*checked)),
+        InlineNode::ContentControl { content, .. } => content.first().and_then(first_char),
+    }
+}
+
+/// Returns the ballot box shown for a check box.
+pub(crate) fn checkbox_glyph(checked: bool) -> char {
+    if checked {
+        '☒'
+    } else {
+        '☐'
+    }
+}
+
+fn is_punctuation(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace()
+}
+
+/// Checks CommonMark flanking rules for wrapping `text` in `marker`.
+///
+/// The opening run must be left-flanking and the closing run right-flanking;
+/// markers must not merge with neighbouring delimiter characters, and
+/// intraword markers are rejected when `intraword` is false (`_`).
+fn can_delimit(
+    text: &str,
+    marker: &str,
+    before: Option<char>,
+    after: Option<char>,
+    intraword: bool,
+) -> bool {
+    let (Some(delim), Some(first), Some(last)) = (
+        marker.chars().next(),
+        text.chars().next(),
+        text.chars().last(),
+    ) else {
+        return false;
+    };
+    let is_boundary = |c: Option<char>| c.map_or(true, |c| c.is_whitespace() || is_punctuation(c));
+
+    if first.is_whitespace() || last.is_whitespace() {
+        return false;
+    }
+    if before == Some(delim) || after == Some(delim) || first == delim || last == delim {
+        return false;
+    }
+    if is_punctuation(first) && !is_boundary(before) {
+        return false;
+    }
+    if is_punctuation(last) && !is_boundary(after) {
+        return false;
+    }
+    intraword || (is_boundary(before) && is_boundary(after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, style: TextStyle) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    fn bold() -> TextStyle {
+        TextStyle {
+            bold: true,
+            ..Default::default()
+        }
+    }
+
+    fn italic() -> TextStyle {
+        TextStyle {
+            italic: true,
+            ..Default::default()
+        }
+    }
+
+    fn renderer(emphasis_style: EmphasisStyle) -> InlineRenderer {
+        InlineRenderer::new(&ConvertOptions {
+            emphasis_style,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_whitespace_stays_outside_markers() {
+        let nodes = vec![
+            text("a", TextStyle::default()),
+            text(" bold ", bold()),
+            text("b", TextStyle::default()),
+        ];
+        assert_eq!(
+            renderer(EmphasisStyle::Html).render(&nodes, TextContext::Block),
+            "a <strong>bold</strong> b"
+        );
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "a **bold** b"
+        );
+    }
+
+    #[test]
+    fn test_emphasis_styles() {
+        let both = TextStyle {
+            bold: true,
+            italic: true,
+            ..Default::default()
+        };
+        let nodes = vec![
+            text("x", both),
+            text(" and ", TextStyle::default()),
+            text("y", italic()),
+        ];
+        assert_eq!(
+            renderer(EmphasisStyle::Html).render(&nodes, TextContext::Block),
+            "<strong><em>x</em></strong> and <em>y</em>"
+        );
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "***x*** and *y*"
+        );
+        assert_eq!(
+            renderer(EmphasisStyle::Underscore).render(&nodes, TextContext::Block),
+            "___x___ and _y_"
+        );
+    }
+
+    #[test]
+    fn test_multiline_text_is_formatted_per_line() {
+        let nodes = vec![text("one\n two", bold())];
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "**one**\n **two**"
+        );
+    }
+
+    #[test]
+    fn test_flanking_fallbacks() {
+        // Underscore emphasis does not work inside words.
+        let nodes = vec![text("snake", TextStyle::default()), text("case", italic())];
+        assert_eq!(
+            renderer(EmphasisStyle::Underscore).render(&nodes, TextContext::Block),
+            "snake<em>case</em>"
+        );
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "snake*case*"
+        );
+
+        // Punctuation next to a word breaks right-flanking.
+        let nodes = vec![text("\"quoted\"", bold()), text("s", TextStyle::default())];
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "<strong>\"quoted\"</strong>s"
+        );
+
+        // Adjacent delimiter runs would merge.
+        let nodes = vec![text("a", bold()), text("b", italic())];
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "**a**<em>b</em>"
+        );
+    }
+
+    #[test]
+    fn test_emphasis_stays_inside_link_label() {
+        let nodes = vec![
+            text("see ", bold()),
+            InlineNode::Link {
+                destination: "https://example.com/a b".to_string(),
+                content: vec![text("the [docs]", bold())],
+            },
+        ];
+        assert_eq!(
+            renderer(EmphasisStyle::Asterisk).render(&nodes, TextContext::Block),
+            "**see** [**the \\[docs\\]**](https://example.com/a\\ b)"
+        );
+    }
+
+    #[test]
+    fn test_flavor_syntax() {
+        let styled = |style: TextStyle| vec![text("x", style)];
+        let flavored = |flavor: MarkdownFlavor| {
+            InlineRenderer::new(&ConvertOptions {
+                flavor: Some(flavor),
+                ..Default::default()
+            })
+        };
+        let highlight = TextStyle {
+            highlight: true,
+            ..Default::default()
+        };
+        let strike = TextStyle {
+            strike: true,
+            ..Default::default()
+        };
+        let underline_small_caps = TextStyle {
+            underline: true,
+            small_caps: true,
+            ..Default::default()
+        };
+
+        let pandoc = flavored(MarkdownFlavor::Pandoc);
+        assert_eq!(
+            pandoc.render(&styled(highlight.clone()), TextContext::Block),
+            "[x]{.mark}"
+        );
+        assert_eq!(
+            pandoc.render(&styled(underline_small_caps), TextContext::Block),
+            "[[x]{.smallcaps}]{.underline}"
+        );
+        assert_eq!(pandoc.render_anchor("sec"), "[]{#sec}");
+
+        let obsidian = flavored(MarkdownFlavor::Obsidian);
+        assert_eq!(
+            obsidian.render(&styled(highlight.clone()), TextContext::Block),
+            "==x=="
+        );
+
+        let multimarkdown = flavored(MarkdownFlavor::MultiMarkdown);
+        assert_eq!(
+            multimarkdown.render(&styled(highlight), TextContext::Block),
+            "{==x==}"
+        );
+        assert_eq!(
+            multimarkdown.render(&styled(strike.clone()), TextContext::Block),
+            "<s>x</s>"
+        );
+
+        let commonmark = flavored(MarkdownFlavor::CommonMark);
+        assert_eq!(
+            commonmark.render(&styled(strike), TextContext::Block),
+            "<s>x</s>"
+        );
+        let math = vec![InlineNode::Math {
+            tex: "x^2".to_string(),
+            display: false,
+        }];
+        assert_eq!(commonmark.render(&math, TextContext::Block), "`x^2`");
+        assert_eq!(
+            flavored(MarkdownFlavor::Gfm).render(&math, TextContext::Block),
+            "$x^2$"
+        );
+        assert_eq!(
+            multimarkdown.render(&math, TextContext::Block),
+            "\\\\(x^2\\\\)"
+        );
+        assert_eq!(
+            pandoc.render(
+                &[text("2^10 costs $5", TextStyle::default())],
+                TextContext::Block
+            ),
+            "2\\^10 costs \\$5"
+        );
+    }
+}
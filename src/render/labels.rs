@@ -0,0 +1,21 @@
+//! Word's list labels in formats whose list syntax numbers items itself.
+
+use crate::core::ast::{ListItem, ListKind};
+use crate::ListMarkerStyle;
+
+/// Label of an ordered list item to write as literal text after the
+/// format's own marker: with `MarkdownWithLabel` or `Original` markers, when
+/// it says more than the item number (`(a)`, `제3조`, not `3.`).
+pub(crate) fn item_label(
+    markers: ListMarkerStyle,
+    kind: ListKind,
+    item: &ListItem,
+) -> Option<&str> {
+    let label = item.label.trim();
+    let number = item.number.map(|number| format!("{}.", number));
+    let shown = markers != ListMarkerStyle::Markdown
+        && kind == ListKind::Ordered
+        && !label.is_empty()
+        && number.as_deref() != Some(label);
+    shown.then_some(label)
+}
@@ -0,0 +1,778 @@
+//! LaTeX output, e.g. for PDF reports.
+//!
+//! Headings keep Word's numbering labels and are written as unnumbered
+//! sections; list items keep theirs with original list markers
+//! (`\item[(a)]`). Bookmarks become `\label`s that internal links point to
+//! (links to labels the document lacks become plain text), and merged table
+//! cells become `\multicolumn` and `\multirow` cells.
+
+use crate::core::ast::{
+    BlockNode, DocumentAst, DocumentProperties, Heading, InlineNode, List, ListKind, Paragraph,
+    ReferenceDefinitions, Table, TextStyle,
+};
+use crate::core::slug::SlugStyle;
+use crate::render::grid::place_cells;
+use crate::render::{item_label, link_targets, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+use std::collections::{HashMap, HashSet};
+
+/// Packages used by the output, loaded by the standalone preamble.
+const PREAMBLE: &str = "\\documentclass{article}
+\\usepackage[T1]{fontenc}
+\\usepackage[utf8]{inputenc}
+\\usepackage{amsmath,amssymb}
+\\usepackage{graphicx}
+\\usepackage{multirow}
+\\usepackage[normalem]{ulem}
+\\usepackage{footnote}
+\\makesavenoteenv{tabular}
+\\usepackage{hyperref}
+";
+
+/// Sectioning commands by heading level; deeper levels use the last.
+const SECTIONS: [&str; 5] = [
+    "section",
+    "subsection",
+    "subsubsection",
+    "paragraph",
+    "subparagraph",
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct LatexRenderer {
+    standalone: bool,
+    list_markers: ListMarkerStyle,
+    slugs: SlugStyle,
+}
+
+impl Default for LatexRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl LatexRenderer {
+    /// Creates a renderer of complete documents, with a preamble, using the
+    /// list markers and anchor style of `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            standalone: true,
+            list_markers: options.list_markers,
+            slugs: options.slug_style(),
+        }
+    }
+
+    /// Renders only the document body, e.g. to `\input` it into another
+    /// document loading the same packages.
+    pub fn fragment(self) -> Self {
+        Self {
+            standalone: false,
+            ..self
+        }
+    }
+}
+
+impl Renderer for LatexRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let (document, labels) = link_targets(document, self.slugs);
+        let writer = Writer {
+            refs: &document.references,
+            labels: &labels,
+            list_markers: self.list_markers,
+            toc: document
+                .blocks
+                .iter()
+                .any(|block| matches!(block, BlockNode::TableOfContents(_))),
+        };
+        let body = writer.render_blocks(&document.blocks, true);
+        if !self.standalone {
+            let mut out = body;
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            return Ok(out);
+        }
+
+        let mut out = String::from(PREAMBLE);
+        let title = title_commands(&document.properties);
+        if !title.is_empty() {
+            out.push('\n');
+            out.push_str(&title);
+        }
+        out.push_str("\n\\begin{document}\n\n");
+        if !title.is_empty() {
+            out.push_str("\\maketitle\n\n");
+        }
+        if !body.is_empty() {
+            out.push_str(&body);
+            out.push_str("\n\n");
+        }
+        out.push_str("\\end{document}\n");
+        Ok(out)
+    }
+}
+
+/// `\title` and `\author` from the document properties, when it has a title.
+fn title_commands(properties: &DocumentProperties) -> String {
+    let Some(title) = properties.title.as_deref().filter(|t| !t.trim().is_empty()) else {
+        return String::new();
+    };
+    let mut out = format!("\\title{{{}}}\n", escape(title.trim()));
+    if let Some(author) = properties.author.as_deref() {
+        out.push_str(&format!("\\author{{{}}}\n", escape(author.trim())));
+    }
+    out.push_str("\\date{}\n");
+    out
+}
+
+struct Writer<'a> {
+    refs: &'a ReferenceDefinitions,
+    /// Labels the document defines, which links can point to.
+    labels: &'a HashSet<String>,
+    list_markers: ListMarkerStyle,
+    /// Whether the document has a table of contents, which unnumbered
+    /// sections are then added to.
+    toc: bool,
+}
+
+impl Writer<'_> {
+    /// Renders blocks separated by blank lines. Nested blocks (in lists and
+    /// tables) cannot be sections.
+    fn render_blocks(&self, blocks: &[BlockNode], top: bool) -> String {
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, top))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&self, block: &BlockNode, top: bool) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph),
+            BlockNode::Heading(heading) => self.render_heading(heading, top),
+            BlockNode::TableOfContents(_) => "\\tableofcontents".to_string(),
+            BlockNode::Table(table) => self.render_table(table),
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, top),
+        }
+    }
+
+    fn render_paragraph(&self, paragraph: &Paragraph) -> String {
+        if let [InlineNode::Math { tex, display: true }] = paragraph.content.as_slice() {
+            return format!("\\[\n{}\n\\]", tex.trim());
+        }
+        let text = self.render_inlines(&paragraph.content);
+        // A line break cannot start or end a paragraph
+        text.trim()
+            .trim_start_matches("\\\\\n")
+            .trim_end_matches("\\\\")
+            .trim()
+            .to_string()
+    }
+
+    /// Renders an unnumbered section with the heading's own label, followed
+    /// by the heading's labels; a bold paragraph where sections are not
+    /// allowed.
+    fn render_heading(&self, heading: &Heading, top: bool) -> String {
+        let labels = heading
+            .id
+            .iter()
+            .chain(&heading.anchors)
+            .map(|name| format!("\\label{{{}}}", label(name)))
+            .collect::<String>();
+        let labels = match labels.is_empty() {
+            true => labels,
+            false => format!("\\phantomsection{}", labels),
+        };
+        let mut title = escape(&heading.label);
+        title.push_str(self.render_inlines(&heading.content).trim());
+        let title = title
+            .replace("\\\\\n", " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if title.is_empty() {
+            return labels;
+        }
+        if !top {
+            return format!("{}\\textbf{{{}}}", labels, title);
+        }
+
+        let command = SECTIONS[heading.level.clamp(1, SECTIONS.len()) - 1];
+        let mut out = format!("\\{}*{{{}}}", command, title);
+        if self.toc && heading.level <= 3 {
+            out.push_str(&format!(
+                "\n\\addcontentsline{{toc}}{{{}}}{{{}}}",
+                command, title
+            ));
+        }
+        if !labels.is_empty() {
+            out.push('\n');
+            out.push_str(&labels);
+        }
+        out
+    }
+
+    /// Renders an `itemize` or `enumerate` list. Ordered items are numbered
+    /// by LaTeX unless their numbers do not count from 1, or keep Word's
+    /// labels with original list markers.
+    fn render_list(&self, list: &List) -> String {
+        let environment = match list.kind {
+            ListKind::Bullet => "itemize",
+            ListKind::Ordered => "enumerate",
+        };
+        let counted = list
+            .items
+            .iter()
+            .enumerate()
+            .all(|(i, item)| item.number.map_or(true, |number| number == i as i32 + 1));
+        let mut lines = vec![format!("\\begin{{{}}}", environment)];
+        for item in &list.items {
+            let mut marker = match (list.kind, self.list_markers, item.number) {
+                (ListKind::Ordered, ListMarkerStyle::Original, _)
+                    if !item.label.trim().is_empty() =>
+                {
+                    format!("\\item[{{{}}}]", escape(item.label.trim()))
+                }
+                (ListKind::Ordered, _, Some(number)) if !counted => {
+                    format!("\\item[{{{}.}}]", number)
+                }
+                _ => "\\item".to_string(),
+            };
+            if let Some(checked) = item.checked {
+                marker.push(' ');
+                marker.push_str(checkbox(checked));
+            }
+            if self.list_markers == ListMarkerStyle::MarkdownWithLabel {
+                if let Some(label) = item_label(self.list_markers, list.kind, item) {
+                    marker.push(' ');
+                    marker.push_str(&escape(label));
+                }
+            }
+            let content = self.render_blocks(&item.blocks, false);
+            let content = content
+                .split('\n')
+                .map(|line| match line {
+                    "" => String::new(),
+                    line => format!("  {}", line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            lines.push(
+                format!("{} {}", marker, content.trim_start())
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+        lines.push(format!("\\end{{{}}}", environment));
+        lines.join("\n")
+    }
+
+    /// Renders a `tabular` of equally wide paragraph columns with rules
+    /// around all cells; merged cells span with `\multicolumn` and
+    /// `\multirow`.
+    fn render_table(&self, table: &Table) -> String {
+        let placed = place_cells(&table.rows);
+        let Some(rows) = placed.iter().map(|cell| cell.row + cell.rowspan).max() else {
+            return String::new();
+        };
+        let columns = placed
+            .iter()
+            .map(|cell| cell.column + cell.colspan)
+            .max()
+            .unwrap_or(0);
+        let width = |span: usize| match span {
+            1 => format!("p{{\\dimexpr\\linewidth/{}-2\\tabcolsep\\relax}}", columns),
+            span => format!(
+                "p{{\\dimexpr{}\\linewidth/{}-2\\tabcolsep\\relax}}",
+                span, columns
+            ),
+        };
+        let by_position = placed
+            .iter()
+            .map(|cell| ((cell.row, cell.column), cell))
+            .collect::<HashMap<_, _>>();
+
+        let mut lines = vec![
+            format!(
+                "\\begin{{tabular}}{{|{}}}",
+                (0..columns)
+                    .map(|_| format!("{}|", width(1)))
+                    .collect::<String>()
+            ),
+            "\\hline".to_string(),
+        ];
+        for row in 0..rows {
+            let mut cells = Vec::new();
+            let mut column = 0;
+            while column < columns {
+                let cell = by_position.get(&(row, column)).copied().or_else(|| {
+                    placed.iter().find(|cell| {
+                        cell.column == column && cell.row < row && row < cell.row + cell.rowspan
+                    })
+                });
+                let Some(cell) = cell else {
+                    cells.push(String::new());
+                    column += 1;
+                    continue;
+                };
+                // Cells below the start of a row span are left empty
+                let mut content = match (cell.row == row, cell.cell) {
+                    (true, Some(cell)) => self
+                        .render_blocks(&cell.blocks, false)
+                        .replace("\n\n", "\n\\par\n"),
+                    _ => String::new(),
+                };
+                if cell.row == row && cell.rowspan > 1 {
+                    content = format!("\\multirow{{{}}}{{=}}{{{}}}", cell.rowspan, content);
+                }
+                if cell.colspan > 1 {
+                    let left = if cell.column == 0 { "|" } else { "" };
+                    content = format!(
+                        "\\multicolumn{{{}}}{{{}{}|}}{{{}}}",
+                        cell.colspan,
+                        left,
+                        width(cell.colspan),
+                        content
+                    );
+                }
+                cells.push(content);
+                column += cell.colspan;
+            }
+            lines.push(format!("{} \\\\", cells.join(" & ")));
+
+            // Rules below the cells ending in this row
+            let ending = (0..columns)
+                .map(|column| {
+                    !placed.iter().any(|cell| {
+                        cell.column <= column
+                            && column < cell.column + cell.colspan
+                            && cell.row <= row
+                            && row + 1 < cell.row + cell.rowspan
+                    })
+                })
+                .collect::<Vec<_>>();
+            if ending.iter().all(|&ends| ends) {
+                lines.push("\\hline".to_string());
+            } else {
+                let mut rules = String::new();
+                let mut column = 0;
+                while column < columns {
+                    if !ending[column] {
+                        column += 1;
+                        continue;
+                    }
+                    let start = column;
+                    while column < columns && ending[column] {
+                        column += 1;
+                    }
+                    rules.push_str(&format!("\\cline{{{}-{}}}", start + 1, column));
+                }
+                if !rules.is_empty() {
+                    lines.push(rules);
+                }
+            }
+        }
+        lines.push("\\end{tabular}".to_string());
+        lines.join("\n")
+    }
+
+    fn render_inlines(&self, nodes: &[InlineNode]) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(&mut out, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = self.render_inlines(content);
+                    let text = text.trim();
+                    match destination.strip_prefix('#') {
+                        // Nothing to point to
+                        Some(name) if !self.labels.contains(name) => out.push_str(text),
+                        Some(name) if text.is_empty() => {
+                            out.push_str(&format!("\\ref{{{}}}", label(name)))
+                        }
+                        Some(name) => {
+                            out.push_str(&format!("\\hyperref[{}]{{{}}}", label(name), text))
+                        }
+                        None if text.is_empty() => {
+                            out.push_str(&format!("\\url{{{}}}", escape_url(destination)))
+                        }
+                        None => out.push_str(&format!(
+                            "\\href{{{}}}{{{}}}",
+                            escape_url(destination),
+                            text
+                        )),
+                    }
+                }
+                InlineNode::Anchor(name) => {
+                    out.push_str(&format!("\\phantomsection\\label{{{}}}", label(name)))
+                }
+                InlineNode::Math {
+                    tex,
+                    display: false,
+                } => out.push_str(&format!("${}$", tex.trim())),
+                InlineNode::Math { tex, display: true } => {
+                    out.push_str(&format!("\n\\[\n{}\n\\]\n", tex.trim()))
+                }
+                InlineNode::Image { source, alt, width } => {
+                    if source.starts_with("data:") {
+                        // LaTeX cannot read embedded images
+                        out.push_str(&format!("[{}]", escape(alt)));
+                        continue;
+                    }
+                    let options = match width {
+                        Some(width) => format!("[width={:.2}in]", f64::from(*width) / 96.0),
+                        None => String::new(),
+                    };
+                    out.push_str(&format!("\\includegraphics{}{{{}}}", options, source));
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = self.refs.note_text(*kind, id) {
+                        out.push_str(&format!("\\footnote{{{}}}", escape(text.trim())));
+                    }
+                }
+                InlineNode::PageBreak => out.push_str("\n\\newpage\n"),
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::CheckBox { checked } => out.push_str(checkbox(*checked)),
+                InlineNode::ContentControl { content, .. } => {
+                    out.push_str(&self.render_inlines(content))
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Writes formatted text line by line, line breaks as `\\`.
+fn push_text(out: &mut String, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str("\\\\\n");
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            out.push_str(line);
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        out.push_str(&line[..start]);
+        out.push_str(&format_span(core, style));
+        out.push_str(&line[start + core.len()..]);
+    }
+}
+
+fn format_span(text: &str, style: &TextStyle) -> String {
+    let mut text = escape(text);
+    let commands = [
+        (style.superscript, "textsuperscript"),
+        (style.subscript && !style.superscript, "textsubscript"),
+        (style.small_caps && !style.all_caps, "textsc"),
+        (style.strike || style.deletion, "sout"),
+        (style.underline || style.insertion, "uline"),
+        (style.italic, "textit"),
+        (style.bold, "textbf"),
+    ];
+    for (applies, command) in commands {
+        if applies {
+            text = format!("\\{}{{{}}}", command, text);
+        }
+    }
+    text
+}
+
+/// Escapes LaTeX special characters in text.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '^' => out.push_str("\\textasciicircum{}"),
+            '~' => out.push_str("\\textasciitilde{}"),
+            // Symbols missing from the text fonts
+            '•' => out.push_str("\\textbullet{}"),
+            '☐' => out.push_str(checkbox(false)),
+            '☒' => out.push_str(checkbox(true)),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes the characters of a URL that `\href` and `\url` need escaped.
+fn escape_url(url: &str) -> String {
+    let mut out = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            '\\' | '#' | '%' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' ' => out.push_str("\\%20"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Label for a bookmark name, without characters that labels cannot hold.
+fn label(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            '-' | '_' | ':' | '.' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+fn checkbox(checked: bool) -> &'static str {
+    if checked {
+        "$\\boxtimes$"
+    } else {
+        "$\\square$"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{ListItem, NoteKind, TableCell};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn cell(content: &str, colspan: usize, rowspan: usize) -> TableCell {
+        TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan,
+        }
+    }
+
+    fn render(blocks: Vec<BlockNode>) -> String {
+        render_with(&ConvertOptions::default(), blocks)
+    }
+
+    fn render_with(options: &ConvertOptions, blocks: Vec<BlockNode>) -> String {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["100% sure".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        LatexRenderer::new(options)
+            .fragment()
+            .render(&document)
+            .expect("render should work")
+    }
+
+    fn ordered(items: &[(&str, i32, &str)]) -> BlockNode {
+        BlockNode::List(List {
+            kind: ListKind::Ordered,
+            start: items.first().map(|&(_, number, _)| number),
+            level: 0,
+            items: items
+                .iter()
+                .map(|&(label, number, content)| ListItem {
+                    label: label.to_string(),
+                    separator: "\t".to_string(),
+                    number: Some(number),
+                    checked: None,
+                    blocks: vec![paragraph(vec![text(content)])],
+                })
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn test_latex_escaping_links_notes_and_lists() {
+        let bold = InlineNode::Text {
+            text: "R&D_{x}".to_string(),
+            style: TextStyle {
+                bold: true,
+                ..Default::default()
+            },
+        };
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        };
+        let rendered = render_with(
+            &options,
+            vec![
+                BlockNode::Heading(Heading {
+                    level: 1,
+                    label: "1. ".to_string(),
+                    content: vec![text("Costs")],
+                    id: None,
+                    anchors: vec!["_Toc1".to_string()],
+                }),
+                paragraph(vec![
+                    text("$5 ~ #1 "),
+                    bold,
+                    InlineNode::NoteReference {
+                        kind: NoteKind::Footnote,
+                        id: "1".to_string(),
+                    },
+                    text(", see "),
+                    InlineNode::Link {
+                        destination: "#_Toc1".to_string(),
+                        content: vec![text("costs")],
+                    },
+                    text(" and "),
+                    InlineNode::Link {
+                        destination: "https://example.com/a b#c".to_string(),
+                        content: vec![text("this")],
+                    },
+                    InlineNode::Image {
+                        source: "media/chart.png".to_string(),
+                        alt: "image".to_string(),
+                        width: Some(192),
+                    },
+                ]),
+                BlockNode::List(List {
+                    kind: ListKind::Ordered,
+                    start: Some(1),
+                    level: 0,
+                    items: vec![ListItem {
+                        label: "(a)".to_string(),
+                        separator: "\t".to_string(),
+                        number: Some(1),
+                        checked: None,
+                        blocks: vec![paragraph(vec![text("First")])],
+                    }],
+                }),
+            ],
+        );
+        assert_eq!(
+            rendered,
+            "\\section*{1. Costs}\n\\phantomsection\\label{_Toc1}\n\n\
+             \\$5 \\textasciitilde{} \\#1 \\textbf{R\\&D\\_\\{x\\}}\\footnote{100\\% sure}, \
+             see \\hyperref[_Toc1]{costs} and \\href{https://example.com/a\\%20b\\#c}{this}\
+             \\includegraphics[width=2.00in]{media/chart.png}\n\n\
+             \\begin{enumerate}\n\\item[{(a)}] First\n\\end{enumerate}\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_list_markers() {
+        let lists = || {
+            vec![
+                ordered(&[("1.", 1, "One"), ("2.", 2, "Two")]),
+                ordered(&[("(c)", 3, "Three")]),
+            ]
+        };
+        assert_eq!(
+            render(lists()),
+            "\\begin{enumerate}\n\\item One\n\\item Two\n\\end{enumerate}\n\n\
+             \\begin{enumerate}\n\\item[{3.}] Three\n\\end{enumerate}\n"
+        );
+
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_with(&options, lists()),
+            "\\begin{enumerate}\n\\item One\n\\item Two\n\\end{enumerate}\n\n\
+             \\begin{enumerate}\n\\item[{3.}] (c) Three\n\\end{enumerate}\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_links_only_to_defined_labels() {
+        let link = |name: &str, content: &str| InlineNode::Link {
+            destination: format!("#{}", name),
+            content: vec![text(content)],
+        };
+        let heading = |level, content: &str, anchors: &[&str]| {
+            BlockNode::Heading(Heading {
+                level,
+                label: String::new(),
+                content: vec![text(content)],
+                id: None,
+                anchors: anchors.iter().map(|name| name.to_string()).collect(),
+            })
+        };
+        let rendered = render(vec![
+            heading(1, "Overview", &[]),
+            BlockNode::List(List {
+                kind: ListKind::Bullet,
+                start: None,
+                level: 0,
+                items: vec![ListItem {
+                    label: "•".to_string(),
+                    separator: "\t".to_string(),
+                    number: None,
+                    checked: None,
+                    blocks: vec![heading(2, "Nested", &["_Toc2"])],
+                }],
+            }),
+            paragraph(vec![
+                text("• ☐ "),
+                link("overview", "overview"),
+                text(", "),
+                link("_Toc2", "nested"),
+                text(", "),
+                link("_Toc9", "gone"),
+            ]),
+        ]);
+        assert_eq!(
+            rendered,
+            "\\section*{Overview}\n\\phantomsection\\label{overview}\n\n\
+             \\begin{itemize}\n\\item \\phantomsection\\label{_Toc2}\\textbf{Nested}\n\\end{itemize}\n\n\
+             \\textbullet{} $\\square$ \\hyperref[overview]{overview}, \
+             \\hyperref[_Toc2]{nested}, gone\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_table_with_spans() {
+        let table = BlockNode::Table(Table {
+            rows: vec![
+                vec![cell("Name", 2, 1), cell("Note", 1, 2)],
+                vec![cell("A", 1, 1), cell("B", 1, 1)],
+            ],
+        });
+        let column = "p{\\dimexpr\\linewidth/3-2\\tabcolsep\\relax}";
+        assert_eq!(
+            render(vec![table]),
+            format!(
+                "\\begin{{tabular}}{{|{c}|{c}|{c}|}}\n\\hline\n\
+                 \\multicolumn{{2}}{{|p{{\\dimexpr2\\linewidth/3-2\\tabcolsep\\relax}}|}}{{Name}} \
+                 & \\multirow{{2}}{{=}}{{Note}} \\\\\n\\cline{{1-2}}\n\
+                 A & B &  \\\\\n\\hline\n\\end{{tabular}}\n",
+                c = column
+            )
+        );
+    }
+}
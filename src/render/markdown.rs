@@ -1,40 +1,501 @@
-use crate::core::ast::{BlockNode, DocumentAst};
-use crate::render::Renderer;
-use crate::Result;
+use crate::core::ast::{
+    BlockNode, DefinitionRole, DocumentAst, Heading, InlineNode, List, ListKind, Paragraph, Table,
+    TableCell, TocEntry,
+};
+use crate::render::{
+    checkbox_glyph, content_control_delimiters, escape_html_attr, escape_markdown_text,
+    InlineRenderer, Renderer, TextContext,
+};
+use crate::{ContentControlMarkup, ConvertOptions, ListMarkerStyle, MarkdownFlavor, Result};
+use std::borrow::Cow;
 
-#[derive(Debug, Default, Clone, Copy)]
-pub struct MarkdownRenderer;
+#[derive(Debug, Clone, Copy)]
+pub struct MarkdownRenderer {
+    flavor: Option<MarkdownFlavor>,
+    escape_markdown: bool,
+    list_markers: ListMarkerStyle,
+    content_controls: ContentControlMarkup,
+    preserve_whitespace: bool,
+    inline: InlineRenderer,
+}
 
-impl Renderer for MarkdownRenderer {
-    fn render(&self, document: &DocumentAst) -> Result<String> {
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl MarkdownRenderer {
+    /// Creates a renderer using the flavor and escaping settings of `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            flavor: options.flavor,
+            escape_markdown: options.escape_markdown,
+            list_markers: options.list_markers,
+            content_controls: options.content_controls,
+            preserve_whitespace: options.preserve_whitespace,
+            inline: InlineRenderer::new(options),
+        }
+    }
+
+    /// Renders a block placed in `context`, after the block `previous` of the
+    /// same sequence; lists are laid out starting at `indent` columns.
+    fn render_block<'b>(
+        &self,
+        block: &'b BlockNode,
+        context: TextContext,
+        indent: usize,
+        previous: Option<&BlockNode>,
+    ) -> Cow<'b, str> {
+        match block {
+            BlockNode::Paragraph(paragraph) => {
+                let previous = match previous {
+                    Some(BlockNode::Paragraph(previous)) => previous.definition_role(),
+                    _ => None,
+                };
+                Cow::Owned(self.render_paragraph(paragraph, context, previous, false))
+            }
+            BlockNode::Table(table) => Cow::Owned(self.render_table(table, context)),
+            BlockNode::RawHtml(text) => Cow::Borrowed(text),
+            BlockNode::Heading(heading) => Cow::Owned(render_heading(
+                &self.inline,
+                heading,
+                context,
+                self.preserve_whitespace,
+            )),
+            BlockNode::TableOfContents(entries) => Cow::Owned(self.render_toc(entries)),
+            BlockNode::List(list) => {
+                let mut out = String::new();
+                self.push_list(&mut out, list, context, indent);
+                Cow::Owned(out)
+            }
+            BlockNode::ContentControl { control, blocks } => {
+                let mut out = String::new();
+                match content_control_delimiters(self.content_controls, control, true) {
+                    Some((open, close)) => {
+                        out.push_str(&open);
+                        out.push_str("\n\n");
+                        self.push_blocks(&mut out, blocks);
+                        out.push_str(&close);
+                    }
+                    None => {
+                        self.push_blocks(&mut out, blocks);
+                        out.truncate(out.trim_end_matches('\n').len());
+                    }
+                }
+                Cow::Owned(out)
+            }
+        }
+    }
+
+    /// Renders a paragraph placed in `context`, after a paragraph with the
+    /// definition list role `previous`. Bookmarks starting the paragraph go
+    /// on the line before it, or before its text after a list marker.
+    fn render_paragraph(
+        &self,
+        paragraph: &Paragraph,
+        context: TextContext,
+        previous: Option<DefinitionRole>,
+        after_marker: bool,
+    ) -> String {
+        let uses_attributes = self.flavor.is_some_and(MarkdownFlavor::supports_attributes);
+        // With attribute syntax, bookmarks stay inline (`[]{#id}`)
+        let anchor_count = if uses_attributes {
+            0
+        } else {
+            paragraph
+                .content
+                .iter()
+                .take_while(|node| matches!(node, InlineNode::Anchor(_)))
+                .count()
+        };
+        let (anchors, content) = paragraph.content.split_at(anchor_count);
+        let anchors = self.inline.render(anchors, context);
+
+        // Aligned paragraphs become HTML blocks, where Markdown escapes do not apply
+        let text_context = if paragraph.alignment.is_some() {
+            TextContext::HtmlBlock
+        } else {
+            context
+        };
+        let text = self.inline.render(content, text_context);
+        let mut text = if self.preserve_whitespace {
+            text
+        } else {
+            text.trim().to_string()
+        };
+        if text.is_empty() {
+            return anchors;
+        }
+
+        if let Some(alignment) = paragraph.alignment {
+            text = format!(
+                "<div style=\"text-align: {};\">{}</div>",
+                alignment.css(),
+                text
+            );
+        }
+        let supports_definition_lists = self
+            .flavor
+            .is_some_and(MarkdownFlavor::supports_definition_lists);
+        if supports_definition_lists
+            && context == TextContext::Block
+            && paragraph.definition_role() == Some(DefinitionRole::Definition)
+        {
+            text = match previous {
+                Some(DefinitionRole::Term) => format!(":   {}", text),
+                // Further paragraphs continue the definition
+                Some(DefinitionRole::Definition) => text
+                    .lines()
+                    .map(|line| format!("    {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                None => text,
+            };
+        }
+
+        if anchors.is_empty() {
+            text
+        } else if after_marker {
+            // A line before a list item would end the list
+            format!("{}{}", anchors, text)
+        } else {
+            format!("{}\n{}", anchors, text)
+        }
+    }
+
+    /// Renders a table placed in `context`: a pipe table when the flavor
+    /// supports them and the table has a simple grid, HTML otherwise.
+    fn render_table(&self, table: &Table, context: TextContext) -> String {
+        let has_nested_table = table
+            .rows
+            .iter()
+            .flatten()
+            .flat_map(|cell| &cell.blocks)
+            .any(|block| matches!(block, BlockNode::Table(_)));
+        let as_pipe_table = context == TextContext::Block
+            && self
+                .flavor
+                .is_some_and(MarkdownFlavor::supports_pipe_tables)
+            && !table.rows.is_empty()
+            && !has_nested_table
+            && !table.has_merged_cells();
+
+        if as_pipe_table {
+            let columns = table.rows.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            let mut lines = Vec::with_capacity(table.rows.len() + 1);
+            for (i, row) in table.rows.iter().enumerate() {
+                let mut cells: Vec<String> = row
+                    .iter()
+                    .map(|cell| self.render_cell(cell, TextContext::PipeTableCell))
+                    .collect();
+                cells.resize(columns, String::new());
+                lines.push(format!("| {} |", cells.join(" | ")));
+                if i == 0 {
+                    lines.push(format!("|{}|", vec![" --- "; columns].join("|")));
+                }
+            }
+            return lines.join("\n");
+        }
+
+        let mut html = String::from("<table>\n");
+        for row in &table.rows {
+            html.push_str("  <tr>\n");
+            for cell in row {
+                let mut attrs = String::new();
+                if cell.rowspan > 1 {
+                    attrs.push_str(&format!(" rowspan=\"{}\"", cell.rowspan));
+                }
+                if cell.colspan > 1 {
+                    attrs.push_str(&format!(" colspan=\"{}\"", cell.colspan));
+                }
+                html.push_str(&format!(
+                    "    <td{}>{}</td>\n",
+                    attrs,
+                    self.render_cell(cell, TextContext::HtmlBlock)
+                ));
+            }
+            html.push_str("  </tr>\n");
+        }
+        html.push_str("</table>");
+        html
+    }
+
+    /// Renders the blocks of a table cell placed in `context`, separated by
+    /// line breaks. Pipe table cells and list items stay on one line each.
+    fn render_cell(&self, cell: &TableCell, context: TextContext) -> String {
+        let mut content = String::new();
+        let mut previous = None;
+        for block in &cell.blocks {
+            let text = self.render_block(block, context, 0, previous);
+            previous = Some(block);
+            if let BlockNode::Table(_) = block {
+                content.push_str(&text);
+                continue;
+            }
+            if text.is_empty() {
+                continue;
+            }
+            if !content.is_empty() {
+                content.push_str("<br/>");
+            }
+            if let BlockNode::List(_) = block {
+                let lines: Vec<&str> = text.lines().filter(|line| !line.is_empty()).collect();
+                content.push_str(&lines.join("<br/>"));
+            } else {
+                content.push_str(&text);
+            }
+        }
+        if context == TextContext::PipeTableCell {
+            content = content.replace('\n', "<br/>");
+        }
+        content
+    }
+
+    /// Renders blocks as a fragment of a document, without the blank line
+    /// after the last one.
+    pub fn render_blocks(&self, blocks: &[BlockNode]) -> String {
         let mut out = String::new();
+        self.push_blocks(&mut out, blocks);
+        out.truncate(out.trim_end_matches('\n').len());
+        out
+    }
 
-        for block in &document.blocks {
-            let rendered = match block {
-                BlockNode::Paragraph(text)
-                | BlockNode::TableHtml(text)
-                | BlockNode::RawHtml(text) => text,
+    /// Renders a table of contents as a nested bullet list of links. Levels
+    /// are relative to the highest level present and nest at most one level
+    /// below the previous entry.
+    fn render_toc(&self, entries: &[TocEntry]) -> String {
+        let top = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+        let mut lines = Vec::with_capacity(entries.len());
+        let mut depth = 0;
+        for (i, entry) in entries.iter().enumerate() {
+            let relative = entry.level - top;
+            depth = if i == 0 { 0 } else { relative.min(depth + 1) };
+            let link = InlineNode::Link {
+                destination: entry.destination.clone(),
+                content: vec![InlineNode::Text {
+                    text: entry.text.clone(),
+                    style: Default::default(),
+                }],
             };
+            lines.push(format!(
+                "{}- {}",
+                "  ".repeat(depth),
+                self.inline.render(&[link], TextContext::Block)
+            ));
+        }
+        lines.join("\n")
+    }
+
+    /// Writes top-level blocks, each followed by a blank line. Lists start at
+    /// the first column whatever their level, as indented text would be code.
+    fn push_blocks(&self, out: &mut String, blocks: &[BlockNode]) {
+        let mut previous = None;
+        for block in blocks {
+            let rendered = self.render_block(block, TextContext::Block, 0, previous);
+            previous = Some(block);
             if rendered.is_empty() {
                 continue;
             }
-            out.push_str(rendered);
+            out.push_str(&rendered);
             out.push_str("\n\n");
         }
+    }
 
-        let refs = &document.references;
-        if !refs.footnotes.is_empty() || !refs.endnotes.is_empty() || !refs.comments.is_empty() {
-            out.push_str("---\n\n");
-            for (i, note) in refs.footnotes.iter().enumerate() {
-                out.push_str(&format!("[^{}]: {}\n", i + 1, note));
+    /// Writes a list placed in `context` whose markers start at `indent`
+    /// columns. Item content and nested lists are indented to the column
+    /// after the marker, so nesting holds whatever the marker width.
+    fn push_list(&self, out: &mut String, list: &List, context: TextContext, indent: usize) {
+        for (i, item) in list.items.iter().enumerate() {
+            if i > 0 {
+                out.push_str("\n\n");
             }
-            for (i, note) in refs.endnotes.iter().enumerate() {
-                out.push_str(&format!("[^en{}]: {}\n", i + 1, note));
+
+            let (marker, separator) = match self.list_markers {
+                ListMarkerStyle::Original => (item.label.clone(), item.separator.as_str()),
+                ListMarkerStyle::Markdown | ListMarkerStyle::MarkdownWithLabel => {
+                    let marker = match list.kind {
+                        ListKind::Bullet => "-".to_string(),
+                        ListKind::Ordered => format!("{}.", item.number.unwrap_or(1)),
+                    };
+                    (marker, " ")
+                }
+            };
+            let content_indent = indent + marker.chars().count() + separator.chars().count();
+            out.push_str(&" ".repeat(indent));
+            out.push_str(&marker);
+            out.push_str(separator);
+
+            for (j, block) in item.blocks.iter().enumerate() {
+                if let BlockNode::List(nested) = block {
+                    // Nested lists start at the item's content column, even
+                    // when levels are skipped between them
+                    out.push_str("\n\n");
+                    self.push_list(out, nested, context, content_indent);
+                    continue;
+                }
+
+                let mut text = match block {
+                    BlockNode::Paragraph(paragraph) if j == 0 => {
+                        self.render_paragraph(paragraph, context, None, true)
+                    }
+                    block => {
+                        let previous = j.checked_sub(1).map(|i| &item.blocks[i]);
+                        self.render_block(block, context, content_indent, previous)
+                            .into_owned()
+                    }
+                };
+                // A label the marker already shows is left out; others are
+                // escaped so that `1.` is not read as a nested list
+                if j == 0
+                    && list.kind == ListKind::Ordered
+                    && self.list_markers == ListMarkerStyle::MarkdownWithLabel
+                    && item.label.trim() != marker
+                {
+                    let label = if self.escape_markdown {
+                        escape_markdown_text(&item.label, context, true)
+                    } else {
+                        item.label.clone()
+                    };
+                    text = format!("{}{}{}", label, item.separator, text);
+                }
+                if j == 0 {
+                    if let Some(checked) = item.checked {
+                        let marker = self.task_marker(checked);
+                        text = if text.is_empty() {
+                            marker.into_owned()
+                        } else {
+                            format!("{} {}", marker, text)
+                        };
+                    }
+                }
+                if j > 0 {
+                    out.push_str("\n\n");
+                    out.push_str(&" ".repeat(content_indent));
+                }
+                push_indented(out, &text, content_indent);
             }
-            for (id, text) in &refs.comments {
-                out.push_str(&format!("[^c{}]: {}\n", id, text));
+        }
+    }
+
+    /// Returns the task list marker of a checked or unchecked item: `[x]`/`[ ]`,
+    /// or a ballot box for flavors without task lists.
+    fn task_marker(&self, checked: bool) -> Cow<'static, str> {
+        match self.flavor {
+            Some(flavor) if !flavor.supports_task_lists() => {
+                Cow::Owned(checkbox_glyph(checked).to_string())
+            }
+            _ if checked => Cow::Borrowed("[x]"),
+            _ => Cow::Borrowed("[ ]"),
+        }
+    }
+
+    /// Writes notes after a `---` rule, by label; nothing when there are none.
+    pub(crate) fn push_notes<'n>(
+        &self,
+        out: &mut String,
+        notes: impl IntoIterator<Item = (String, &'n str)>,
+    ) {
+        for (i, (label, text)) in notes.into_iter().enumerate() {
+            if i == 0 {
+                out.push_str("---\n\n");
+            }
+            self.push_note(out, &label, text);
+        }
+    }
+
+    /// Writes one note definition.
+    fn push_note(&self, out: &mut String, label: &str, text: &str) {
+        let text = if self.escape_markdown {
+            escape_markdown_text(text, TextContext::Block, false)
+        } else {
+            text.to_string()
+        };
+        match self.flavor {
+            // Without footnote syntax, notes become anchored paragraphs that
+            // the inline references link to.
+            Some(flavor) if !flavor.supports_footnotes() => {
+                out.push_str(&format!(
+                    "<a id=\"fn-{0}\"></a><sup>{0}</sup> {1}\n\n",
+                    label, text
+                ));
             }
+            _ => out.push_str(&format!("[^{}]: {}\n", label, text)),
         }
+    }
+}
+
+/// Writes `text`, indenting every line after the first by `indent` columns.
+/// Blank lines stay empty.
+fn push_indented(out: &mut String, text: &str, indent: usize) {
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+            if !line.is_empty() {
+                out.push_str(&" ".repeat(indent));
+            }
+        }
+        out.push_str(line);
+    }
+}
+
+/// Renders an ATX heading, preceded by its bookmarks on their own line.
+fn render_heading(
+    inline: &InlineRenderer,
+    heading: &Heading,
+    context: TextContext,
+    preserve_whitespace: bool,
+) -> String {
+    let text = inline.render(&heading.content, context);
+    let text = if preserve_whitespace {
+        text.as_str()
+    } else {
+        text.trim()
+    };
+
+    // Use id attribute instead of name for better compatibility (VS Code etc.)
+    let mut out: String = heading
+        .anchors
+        .iter()
+        .map(|anchor| format!("<a id=\"{}\"></a>", escape_html_attr(anchor)))
+        .collect();
+    if !out.is_empty() {
+        // Anchors go on the line before so the heading syntax stays valid
+        out.push('\n');
+    }
+    out.push_str(&"#".repeat(heading.level));
+    out.push(' ');
+    out.push_str(&heading.label);
+    out.push_str(text);
+    if let Some(id) = &heading.id {
+        out.push_str(&format!(" {{#{}}}", id));
+    }
+    out
+}
+
+impl Renderer for MarkdownRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let mut out = String::new();
+        self.push_blocks(&mut out, &document.blocks);
+
+        let refs = &document.references;
+        let notes = refs
+            .footnotes
+            .iter()
+            .enumerate()
+            .map(|(i, note)| ((i + 1).to_string(), note.as_str()))
+            .chain(
+                refs.endnotes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, note)| (format!("en{}", i + 1), note.as_str())),
+            )
+            .chain(
+                refs.comments
+                    .iter()
+                    .map(|(id, text)| (format!("c{}", id), text.as_str())),
+            );
+        self.push_notes(&mut out, notes);
 
         Ok(out)
     }
@@ -43,20 +504,390 @@ impl Renderer for MarkdownRenderer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::ast::{DocumentAst, ReferenceDefinitions};
+    use crate::core::ast::{
+        ContentControl, DocumentAst, Heading, InlineNode, ListItem, ReferenceDefinitions, Table,
+        TocEntry,
+    };
 
     #[test]
     fn test_renderer_appends_references() {
         let doc = DocumentAst {
-            blocks: vec![BlockNode::Paragraph("A".to_string())],
+            blocks: vec![BlockNode::Paragraph("A".into())],
             references: ReferenceDefinitions {
                 footnotes: vec!["note".to_string()],
                 endnotes: Vec::new(),
                 comments: Vec::new(),
             },
+            ..Default::default()
         };
-        let rendered = MarkdownRenderer.render(&doc).expect("render should work");
+        let rendered = MarkdownRenderer::default()
+            .render(&doc)
+            .expect("render should work");
         assert!(rendered.contains("A"));
         assert!(rendered.contains("[^1]: note"));
     }
+
+    #[test]
+    fn test_commonmark_notes_without_footnote_syntax() {
+        let doc = DocumentAst {
+            blocks: vec![BlockNode::Paragraph("A".into())],
+            references: ReferenceDefinitions {
+                footnotes: vec!["note *1*".to_string()],
+                endnotes: Vec::new(),
+                comments: vec![("7".to_string(), "comment".to_string())],
+            },
+            ..Default::default()
+        };
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            flavor: Some(MarkdownFlavor::CommonMark),
+            ..Default::default()
+        });
+        let rendered = renderer.render(&doc).expect("render should work");
+        assert!(rendered.contains("<a id=\"fn-1\"></a><sup>1</sup> note \\*1\\*\n\n"));
+        assert!(rendered.contains("<a id=\"fn-c7\"></a><sup>c7</sup> comment"));
+        assert!(!rendered.contains("[^"));
+    }
+
+    fn item(label: &str, number: Option<i32>, blocks: Vec<BlockNode>) -> ListItem {
+        ListItem {
+            label: label.to_string(),
+            separator: " ".to_string(),
+            number,
+            checked: None,
+            blocks,
+        }
+    }
+
+    fn article_list() -> DocumentAst {
+        let clauses = List {
+            kind: ListKind::Ordered,
+            start: Some(1),
+            level: 1,
+            items: vec![item(
+                "①",
+                Some(1),
+                vec![BlockNode::Paragraph("First clause".into())],
+            )],
+        };
+        DocumentAst {
+            blocks: vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(3),
+                level: 0,
+                items: vec![item(
+                    "제3조",
+                    Some(3),
+                    vec![
+                        BlockNode::Paragraph("Purpose\nof this act".into()),
+                        BlockNode::List(clauses),
+                    ],
+                )],
+            })],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_lists_starting_below_first_level_are_not_indented_as_code() {
+        let deep = List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 4,
+            items: vec![item("-", None, vec![BlockNode::Paragraph("Deep".into())])],
+        };
+        let document = DocumentAst {
+            blocks: vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(1),
+                level: 2,
+                items: vec![item(
+                    "1.",
+                    Some(1),
+                    vec![BlockNode::Paragraph("Top".into()), BlockNode::List(deep)],
+                )],
+            })],
+            ..Default::default()
+        };
+        let rendered = MarkdownRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(rendered, "1. Top\n\n   - Deep\n\n");
+    }
+
+    #[test]
+    fn test_nested_list_indent_follows_marker_width() {
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        });
+        let rendered = renderer
+            .render(&article_list())
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            "제3조 Purpose\n    of this act\n\n    ① First clause\n\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_list_markers_keep_label_as_text() {
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            ..Default::default()
+        });
+        let rendered = renderer
+            .render(&article_list())
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            "3. 제3조 Purpose\n   of this act\n\n   1. ① First clause\n\n"
+        );
+        let numbered = DocumentAst {
+            blocks: vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(1),
+                level: 0,
+                items: vec![
+                    item("1.", Some(1), vec![BlockNode::Paragraph("one".into())]),
+                    item("2)", Some(2), vec![BlockNode::Paragraph("two".into())]),
+                ],
+            })],
+            ..Default::default()
+        };
+        let rendered = renderer.render(&numbered).expect("render should work");
+        assert_eq!(rendered, "1. one\n\n2. 2\\) two\n\n");
+
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            list_markers: ListMarkerStyle::Markdown,
+            ..Default::default()
+        });
+        let rendered = renderer
+            .render(&article_list())
+            .expect("render should work");
+        assert!(rendered.starts_with("3. Purpose\n"));
+    }
+
+    #[test]
+    fn test_list_item_continuation_blocks_are_indented() {
+        let document = DocumentAst {
+            blocks: vec![BlockNode::List(List {
+                kind: ListKind::Bullet,
+                start: None,
+                level: 0,
+                items: vec![item(
+                    "-",
+                    None,
+                    vec![
+                        BlockNode::Paragraph("Item".into()),
+                        BlockNode::Paragraph("More text".into()),
+                        BlockNode::Table(Table {
+                            rows: ["A", "1"]
+                                .iter()
+                                .map(|text| {
+                                    vec![TableCell {
+                                        blocks: vec![BlockNode::Paragraph((*text).into())],
+                                        colspan: 1,
+                                        rowspan: 1,
+                                    }]
+                                })
+                                .collect(),
+                        }),
+                    ],
+                )],
+            })],
+            ..Default::default()
+        };
+        let rendered = MarkdownRenderer::new(&ConvertOptions {
+            flavor: Some(MarkdownFlavor::Gfm),
+            ..Default::default()
+        })
+        .render(&document)
+        .expect("render should work");
+        assert_eq!(
+            rendered,
+            "- Item\n\n  More text\n\n  | A |\n  | --- |\n  | 1 |\n\n"
+        );
+    }
+
+    #[test]
+    fn test_lists_in_table_cells_follow_list_markers() {
+        let list = BlockNode::List(List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 0,
+            items: vec![
+                item("•", None, vec![BlockNode::Paragraph("One".into())]),
+                item("•", None, vec![BlockNode::Paragraph("Two".into())]),
+            ],
+        });
+        let document = DocumentAst {
+            blocks: vec![BlockNode::Table(Table {
+                rows: vec![vec![TableCell {
+                    blocks: vec![BlockNode::Paragraph("Items".into()), list],
+                    colspan: 1,
+                    rowspan: 1,
+                }]],
+            })],
+            ..Default::default()
+        };
+        for (list_markers, expected) in [
+            (ListMarkerStyle::Markdown, "Items<br/>- One<br/>- Two"),
+            (ListMarkerStyle::Original, "Items<br/>• One<br/>• Two"),
+        ] {
+            let rendered = MarkdownRenderer::new(&ConvertOptions {
+                flavor: Some(MarkdownFlavor::Gfm),
+                list_markers,
+                ..Default::default()
+            })
+            .render(&document)
+            .expect("render should work");
+            assert_eq!(rendered, format!("| {} |\n| --- |\n\n", expected));
+        }
+    }
+
+    #[test]
+    fn test_task_items_use_checkbox_syntax_of_flavor() {
+        let task = |checked: bool, text: &str| ListItem {
+            checked: Some(checked),
+            ..item("-", None, vec![BlockNode::Paragraph(text.into())])
+        };
+        let document = DocumentAst {
+            blocks: vec![BlockNode::List(List {
+                kind: ListKind::Bullet,
+                start: None,
+                level: 0,
+                items: vec![task(true, "Done"), task(false, "Todo")],
+            })],
+            ..Default::default()
+        };
+
+        let rendered = MarkdownRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(rendered, "- [x] Done\n\n- [ ] Todo\n\n");
+
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            flavor: Some(MarkdownFlavor::CommonMark),
+            ..Default::default()
+        });
+        let rendered = renderer.render(&document).expect("render should work");
+        assert_eq!(rendered, "- ☒ Done\n\n- ☐ Todo\n\n");
+    }
+
+    #[test]
+    fn test_flavors_write_valid_list_markers() {
+        let document = DocumentAst {
+            blocks: vec![
+                BlockNode::List(List {
+                    kind: ListKind::Bullet,
+                    start: None,
+                    level: 0,
+                    items: vec![item("•", None, vec![BlockNode::Paragraph("one".into())])],
+                }),
+                BlockNode::List(List {
+                    kind: ListKind::Ordered,
+                    start: Some(1),
+                    level: 0,
+                    items: vec![item("(a)", Some(1), vec![BlockNode::Paragraph("a".into())])],
+                }),
+            ],
+            ..Default::default()
+        };
+        for flavor in [
+            MarkdownFlavor::CommonMark,
+            MarkdownFlavor::Gfm,
+            MarkdownFlavor::Pandoc,
+            MarkdownFlavor::MultiMarkdown,
+            MarkdownFlavor::Obsidian,
+        ] {
+            let renderer = MarkdownRenderer::new(&ConvertOptions {
+                flavor: Some(flavor),
+                ..Default::default()
+            });
+            let rendered = renderer.render(&document).expect("render should work");
+            assert_eq!(rendered, "- one\n\n1. a\n\n", "{:?}", flavor);
+        }
+
+        // Word's labels when asked for
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            flavor: Some(MarkdownFlavor::Gfm),
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        });
+        let rendered = renderer.render(&document).expect("render should work");
+        assert_eq!(rendered, "• one\n\n(a) a\n\n");
+    }
+
+    #[test]
+    fn test_content_controls_wrap_blocks_when_requested() {
+        let document = DocumentAst {
+            blocks: vec![BlockNode::ContentControl {
+                control: ContentControl {
+                    tag: Some("terms".to_string()),
+                    ..Default::default()
+                },
+                blocks: vec![
+                    BlockNode::Paragraph("First".into()),
+                    BlockNode::Paragraph("Second".into()),
+                ],
+            }],
+            ..Default::default()
+        };
+
+        let rendered = MarkdownRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(rendered, "First\n\nSecond\n\n");
+
+        let renderer = MarkdownRenderer::new(&ConvertOptions {
+            content_controls: ContentControlMarkup::Spans,
+            ..Default::default()
+        });
+        let rendered = renderer.render(&document).expect("render should work");
+        assert_eq!(
+            rendered,
+            "<div data-tag=\"terms\" data-type=\"richText\">\n\nFirst\n\nSecond\n\n</div>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_headings_and_regenerated_toc() {
+        let entry = |level: usize, text: &str, destination: &str| TocEntry {
+            level,
+            text: text.to_string(),
+            destination: destination.to_string(),
+        };
+        let document = DocumentAst {
+            blocks: vec![
+                BlockNode::TableOfContents(vec![
+                    entry(1, "1 Intro", "#1-intro"),
+                    entry(3, "Detail", "#detail"),
+                    entry(2, "Scope", "#scope"),
+                    entry(1, "End", "#end"),
+                ]),
+                BlockNode::Heading(Heading {
+                    level: 2,
+                    label: "1 ".to_string(),
+                    content: vec![InlineNode::Text {
+                        text: "Intro ".to_string(),
+                        style: Default::default(),
+                    }],
+                    id: None,
+                    anchors: vec!["_Toc1".to_string()],
+                }),
+            ],
+            ..Default::default()
+        };
+
+        let rendered = MarkdownRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            concat!(
+                "- [1 Intro](#1-intro)\n  - [Detail](#detail)\n  - [Scope](#scope)\n- [End](#end)\n\n",
+                "<a id=\"_Toc1\"></a>\n## 1 Intro\n\n"
+            )
+        );
+    }
 }
@@ -0,0 +1,533 @@
+//! MediaWiki output.
+//!
+//! Notes become `<ref>` footnotes listed by `<references />` at the end, and
+//! bookmarks become empty `<span>`s that internal links point to. Tables use
+//! the wiki table syntax, which keeps merged cells. Wiki markup in the text
+//! is escaped with character references, which the parser does not read as
+//! markup.
+
+use crate::core::ast::{
+    BlockNode, DocumentAst, Heading, InlineNode, List, ListKind, Paragraph, ReferenceDefinitions,
+    Table, TextStyle,
+};
+use crate::render::grid::place_cells;
+use crate::render::{checkbox_glyph, escape_html_attr, item_label, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+
+#[derive(Debug, Clone, Copy)]
+pub struct MediaWikiRenderer {
+    list_markers: ListMarkerStyle,
+}
+
+impl Default for MediaWikiRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl MediaWikiRenderer {
+    /// Creates a renderer using the list markers of `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            list_markers: options.list_markers,
+        }
+    }
+}
+
+impl Renderer for MediaWikiRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let writer = Writer {
+            refs: &document.references,
+            list_markers: self.list_markers,
+        };
+        let body = writer.render_blocks(&document.blocks, "");
+        let mut out = body.trim_end().to_string();
+        if out.contains("<ref>") {
+            out.push_str("\n\n<references />");
+        }
+        out.push('\n');
+        Ok(out.trim_start().to_string())
+    }
+}
+
+struct Writer<'a> {
+    refs: &'a ReferenceDefinitions,
+    list_markers: ListMarkerStyle,
+}
+
+impl Writer<'_> {
+    /// Renders blocks separated by blank lines. Inside lists, `prefix` is the
+    /// item markers that continue the enclosing item, and blocks are kept
+    /// on consecutive lines.
+    fn render_blocks(&self, blocks: &[BlockNode], prefix: &str) -> String {
+        let separator = if prefix.is_empty() { "\n\n" } else { "\n" };
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, prefix))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&self, block: &BlockNode, prefix: &str) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph, prefix),
+            BlockNode::Heading(heading) => self.render_heading(heading, prefix),
+            BlockNode::TableOfContents(entries) => match entries.is_empty() {
+                true => String::new(),
+                false => "__TOC__".to_string(),
+            },
+            BlockNode::Table(table) => {
+                let table = self.render_table(table);
+                match prefix.is_empty() {
+                    true => table,
+                    // an indented table starts after the markers
+                    false => format!("{}:{}", prefix, table),
+                }
+            }
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list, prefix),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, prefix),
+        }
+    }
+
+    fn render_paragraph(&self, paragraph: &Paragraph, prefix: &str) -> String {
+        let text = self.render_inlines(&paragraph.content);
+        let text = text.trim();
+        if text.is_empty() {
+            return String::new();
+        }
+        match prefix.is_empty() {
+            true => escape_line_starts(text),
+            // line breaks would end the item
+            false => format!("{}:{}", prefix, text.replace('\n', "")),
+        }
+    }
+
+    /// Renders a section heading one level below the page title, with its
+    /// bookmarks as anchors; bold text inside lists.
+    fn render_heading(&self, heading: &Heading, prefix: &str) -> String {
+        let mut title = escape(&heading.label);
+        title.push_str(self.render_inlines(&heading.content).trim());
+        let title = title
+            .replace("<br />\n", " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !prefix.is_empty() {
+            return match title.is_empty() {
+                true => String::new(),
+                false => format!("{}:'''{}'''", prefix, title),
+            };
+        }
+        let names = heading.id.iter().chain(&heading.anchors).cloned();
+        let marker = "=".repeat((heading.level + 1).min(6));
+        format!(
+            "{}{} {} {}",
+            anchors(&names.collect::<Vec<_>>()),
+            marker,
+            title,
+            marker
+        )
+    }
+
+    /// Renders list items with `*` or `#` markers repeated for each level of
+    /// nesting, followed by Word's labels unless list markers drop them;
+    /// item content continues with `:` after the markers.
+    fn render_list(&self, list: &List, prefix: &str) -> String {
+        let marker = match list.kind {
+            ListKind::Bullet => '*',
+            ListKind::Ordered => '#',
+        };
+        let item_prefix = format!("{}{}", prefix, marker);
+        let mut items = Vec::with_capacity(list.items.len());
+        for item in &list.items {
+            let mut head = item_prefix.clone();
+            if let Some(checked) = item.checked {
+                head.push(' ');
+                head.push(checkbox_glyph(checked));
+            }
+            if let Some(label) = item_label(self.list_markers, list.kind, item) {
+                head.push(' ');
+                head.push_str(&escape(label));
+            }
+            let content = self.render_blocks(&item.blocks, &item_prefix);
+            // the first paragraph belongs on the item's line
+            let content = match content.strip_prefix(&format!("{}:", item_prefix)) {
+                Some(rest) => format!("{} {}", head, rest.trim_start()),
+                None if content.is_empty() => head,
+                None => format!("{}\n{}", head, content),
+            };
+            items.push(content.trim_end().to_string());
+        }
+        items.join("\n")
+    }
+
+    /// Renders a wiki table; cells of the first row are headers unless it
+    /// has cells spanning rows.
+    fn render_table(&self, table: &Table) -> String {
+        let placed = place_cells(&table.rows);
+        if placed.is_empty() {
+            return String::new();
+        }
+        let header =
+            table.rows.len() > 1 && placed.iter().all(|cell| cell.row > 0 || cell.rowspan == 1);
+        let mut lines = vec!["{| class=\"wikitable\"".to_string()];
+        for row in 0..table.rows.len() {
+            if row > 0 {
+                lines.push("|-".to_string());
+            }
+            let mut cells = placed
+                .iter()
+                .filter(|cell| cell.row == row && cell.cell.is_some())
+                .collect::<Vec<_>>();
+            cells.sort_by_key(|cell| cell.column);
+            let marker = if row == 0 && header { '!' } else { '|' };
+            for cell in cells {
+                let Some(content) = cell.cell else { continue };
+                let mut attributes = String::new();
+                if cell.rowspan > 1 {
+                    attributes.push_str(&format!("rowspan=\"{}\" ", cell.rowspan));
+                }
+                if cell.colspan > 1 {
+                    attributes.push_str(&format!("colspan=\"{}\" ", cell.colspan));
+                }
+                // paragraphs escape their own line starts; lists keep theirs
+                let text = self.render_blocks(&content.blocks, "");
+                let text = text.trim();
+                let separator = match text.contains('\n') || text.starts_with(['*', '#']) {
+                    true => "\n",
+                    false => " ",
+                };
+                let attributes = match attributes.is_empty() {
+                    true => String::new(),
+                    false => format!(" {}|", attributes),
+                };
+                lines.push(
+                    format!("{}{}{}{}", marker, attributes, separator, text)
+                        .trim_end()
+                        .to_string(),
+                );
+            }
+        }
+        lines.push("|}".to_string());
+        lines.join("\n")
+    }
+
+    fn render_inlines(&self, nodes: &[InlineNode]) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(&mut out, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = self.render_inlines(content);
+                    let text = text.trim().replace("<br />\n", " ");
+                    match destination.strip_prefix('#') {
+                        Some(name) => {
+                            let name = escape(name);
+                            match text.is_empty() {
+                                true => out.push_str(&format!("[[#{}]]", name)),
+                                false => out.push_str(&format!("[[#{}|{}]]", name, text)),
+                            }
+                        }
+                        None => {
+                            let url = destination
+                                .replace(' ', "%20")
+                                .replace('[', "%5B")
+                                .replace(']', "%5D");
+                            match text.is_empty() {
+                                true => out.push_str(&url),
+                                false => out.push_str(&format!("[{} {}]", url, text)),
+                            }
+                        }
+                    }
+                }
+                InlineNode::Anchor(name) => out.push_str(&anchors(std::slice::from_ref(name))),
+                InlineNode::Math { tex, display } => {
+                    let attribute = if *display { " display=\"block\"" } else { "" };
+                    let tex = tex.trim().replace("</math", "<\\/math");
+                    out.push_str(&format!("<math{}>{}</math>", attribute, tex))
+                }
+                InlineNode::Image { source, alt, width } => {
+                    if source.starts_with("data:") {
+                        // wiki pages cannot embed images
+                        out.push_str(&format!("[{}]", escape(alt)));
+                    } else {
+                        let name = source.rsplit('/').next().unwrap_or(source);
+                        let mut link = format!("[[File:{}", name);
+                        if let Some(width) = width {
+                            link.push_str(&format!("|{}px", width));
+                        }
+                        if !alt.is_empty() {
+                            link.push_str(&format!("|alt={}", escape(alt)));
+                        }
+                        link.push_str("]]");
+                        out.push_str(&link);
+                    }
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = self.refs.note_text(*kind, id) {
+                        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                        out.push_str(&format!("<ref>{}</ref>", escape(&text)));
+                    }
+                }
+                InlineNode::PageBreak => {}
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::CheckBox { checked } => out.push(checkbox_glyph(*checked)),
+                InlineNode::ContentControl { content, .. } => {
+                    out.push_str(&self.render_inlines(content))
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Empty spans carrying the bookmark names, for `[[#name]]` links.
+fn anchors<S: AsRef<str>>(names: &[S]) -> String {
+    names
+        .iter()
+        .map(|name| format!("<span id=\"{}\"></span>", escape_html_attr(name.as_ref())))
+        .collect()
+}
+
+/// Writes formatted text line by line, line breaks as `<br />`. Formatting
+/// stays inside the surrounding whitespace.
+fn push_text(out: &mut String, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str("<br />\n");
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            out.push_str(line);
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        out.push_str(&line[..start]);
+        out.push_str(&format_span(core, style));
+        out.push_str(&line[start + core.len()..]);
+    }
+}
+
+fn format_span(text: &str, style: &TextStyle) -> String {
+    let mut text = escape(text);
+    let tags = [
+        (style.superscript, "sup"),
+        (style.subscript && !style.superscript, "sub"),
+        (style.strike || style.deletion, "s"),
+        (style.underline || style.insertion, "u"),
+    ];
+    for (applies, tag) in tags {
+        if applies {
+            text = format!("<{}>{}</{}>", tag, text, tag);
+        }
+    }
+    if style.italic {
+        text = format!("''{}''", text);
+    }
+    if style.bold {
+        text = format!("'''{}'''", text);
+    }
+    text
+}
+
+/// Replaces characters that start wiki markup, links, templates or tags, and
+/// the second of two quotes, tildes or underscores, which would form bold,
+/// italic, signatures or behavior switches.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '[' => out.push_str("&#91;"),
+            ']' => out.push_str("&#93;"),
+            '{' => out.push_str("&#123;"),
+            '}' => out.push_str("&#125;"),
+            '|' => out.push_str("&#124;"),
+            '\'' | '~' | '_' if previous == Some(c) => out.push_str(&format!("&#{};", c as u32)),
+            _ => out.push(c),
+        }
+        // an escaped character ends the sequence
+        previous = if out.ends_with(c) { Some(c) } else { None };
+    }
+    out
+}
+
+/// Escapes characters at the start of a line that would start a list item,
+/// heading, rule, table or preformatted text.
+fn escape_line_starts(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let line = line.trim_start();
+            match line.chars().next() {
+                Some(c @ ('*' | '#' | ':' | ';' | '=' | '-')) => {
+                    format!("&#{};{}", c as u32, &line[c.len_utf8()..])
+                }
+                _ => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{ListItem, NoteKind, TableCell};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn render(blocks: Vec<BlockNode>) -> String {
+        render_with(&ConvertOptions::default(), blocks)
+    }
+
+    fn render_with(options: &ConvertOptions, blocks: Vec<BlockNode>) -> String {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["See [[this]].".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        MediaWikiRenderer::new(options)
+            .render(&document)
+            .expect("render should work")
+    }
+
+    #[test]
+    fn test_mediawiki_headings_links_notes_and_escaping() {
+        let italic = InlineNode::Text {
+            text: "it's".to_string(),
+            style: TextStyle {
+                italic: true,
+                ..Default::default()
+            },
+        };
+        let rendered = render(vec![
+            BlockNode::Heading(Heading {
+                level: 1,
+                label: String::new(),
+                content: vec![text("Costs")],
+                id: None,
+                anchors: vec!["_Toc1".to_string()],
+            }),
+            paragraph(vec![
+                text("* {{a|b}} '' "),
+                italic,
+                InlineNode::NoteReference {
+                    kind: NoteKind::Footnote,
+                    id: "1".to_string(),
+                },
+                text(", see "),
+                InlineNode::Link {
+                    destination: "#_Toc1".to_string(),
+                    content: vec![text("costs")],
+                },
+                text(" or "),
+                InlineNode::Link {
+                    destination: "https://example.com/a b".to_string(),
+                    content: vec![text("the site")],
+                },
+            ]),
+        ]);
+        assert_eq!(
+            rendered,
+            "<span id=\"_Toc1\"></span>== Costs ==\n\n\
+             &#42; &#123;&#123;a&#124;b&#125;&#125; '&#39; ''it's''<ref>See &#91;&#91;this&#93;&#93;.</ref>, \
+             see [[#_Toc1|costs]] or [https://example.com/a%20b the site]\n\n\
+             <references />\n"
+        );
+    }
+
+    #[test]
+    fn test_mediawiki_nested_lists_and_spanned_table() {
+        let item = |content: &str, blocks: Vec<BlockNode>| ListItem {
+            label: String::new(),
+            separator: "\t".to_string(),
+            number: None,
+            checked: None,
+            blocks: [vec![paragraph(vec![text(content)])], blocks].concat(),
+        };
+        let nested = BlockNode::List(List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 1,
+            items: vec![item("inner", vec![paragraph(vec![text("more")])])],
+        });
+        let list = BlockNode::List(List {
+            kind: ListKind::Ordered,
+            start: None,
+            level: 0,
+            items: vec![item("outer", vec![nested]), item("next", Vec::new())],
+        });
+        let cell = |content: &str, colspan, rowspan| TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan,
+        };
+        let table = BlockNode::Table(Table {
+            rows: vec![
+                vec![cell("A", 1, 2), cell("B", 2, 1)],
+                vec![cell("C", 1, 1), cell("D", 1, 1)],
+            ],
+        });
+        assert_eq!(
+            render(vec![list, table]),
+            "# outer\n#* inner\n#*:more\n# next\n\n\
+             {| class=\"wikitable\"\n\
+             | rowspan=\"2\" | A\n\
+             | colspan=\"2\" | B\n\
+             |-\n| C\n| D\n|}\n"
+        );
+    }
+
+    #[test]
+    fn test_mediawiki_list_markers_keep_labels_as_text() {
+        let list = || {
+            vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(1),
+                level: 0,
+                items: vec![ListItem {
+                    label: "(a)".to_string(),
+                    separator: "\t".to_string(),
+                    number: Some(1),
+                    checked: None,
+                    blocks: vec![paragraph(vec![text("First")])],
+                }],
+            })]
+        };
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            ..Default::default()
+        };
+        assert_eq!(render(list()), "# First\n");
+        assert_eq!(render_with(&options, list()), "# (a) First\n");
+    }
+}
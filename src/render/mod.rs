@@ -1,11 +1,41 @@
+mod asciidoc;
+mod content_control;
 mod escape;
+mod grid;
+mod inline;
+mod labels;
+mod latex;
 mod markdown;
+mod mediawiki;
+mod notebook;
+mod org;
+mod rst;
+mod targets;
+mod text;
+mod typst;
 
 use crate::core::ast::DocumentAst;
 use crate::Result;
 
-pub use escape::{escape_html_attr, escape_markdown_link_destination, escape_markdown_link_text};
+pub use asciidoc::{Admonition, AsciiDocRenderer};
+pub(crate) use content_control::content_control_delimiters;
+pub use escape::{
+    escape_html_attr, escape_html_text, escape_markdown_link_destination,
+    escape_markdown_link_text, escape_markdown_text, escape_markdown_text_with, TextContext,
+};
+pub(crate) use grid::place_cells;
+pub(crate) use inline::checkbox_glyph;
+pub use inline::InlineRenderer;
+pub(crate) use labels::item_label;
+pub use latex::LatexRenderer;
 pub use markdown::MarkdownRenderer;
+pub use mediawiki::MediaWikiRenderer;
+pub use notebook::NotebookRenderer;
+pub use org::OrgRenderer;
+pub use rst::RstRenderer;
+pub(crate) use targets::{block_targets, link_targets};
+pub use text::PlainTextRenderer;
+pub use typst::TypstRenderer;
 
 pub trait Renderer {
     fn render(&self, document: &DocumentAst) -> Result<String>;
@@ -0,0 +1,524 @@
+//! Jupyter notebook (`.ipynb`) output.
+//!
+//! Runs of top-level paragraphs in a code style (`SourceCode`,
+//! `HTMLPreformatted`, `CodePython`, ...) become code cells, and the blocks
+//! between them Markdown cells. Embedded images become attachments of the
+//! cell showing them, notes are defined in a last Markdown cell, and the
+//! title and author go to a raw front matter cell, where Quarto reads them.
+//! The notebook's language is that of most code cells; cells in other
+//! languages are marked with theirs.
+
+use crate::chunk::json_string;
+use crate::core::ast::{plain_text, BlockNode, DocumentAst, DocumentProperties};
+use crate::render::{MarkdownRenderer, Renderer};
+use crate::{ConvertOptions, Result};
+
+/// Paragraph style ids (lower case) of preformatted text without a language.
+const PLAIN_CODE_STYLES: [&str; 5] = [
+    "htmlpreformatted",
+    "plaintext",
+    "macrotext",
+    "preformatted",
+    "verbatim",
+];
+
+/// Words of code style ids that do not name the language, as in `SourceCode`
+/// or `CodeBlockPython`.
+const CODE_STYLE_WORDS: [&str; 6] = ["source", "code", "block", "listing", "snippet", "sample"];
+
+#[derive(Debug, Clone)]
+pub struct NotebookRenderer {
+    markdown: MarkdownRenderer,
+    language: String,
+}
+
+impl Default for NotebookRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl NotebookRenderer {
+    /// Creates a renderer writing Markdown cells with the settings of
+    /// `options`, and code cells in `options.notebook_language` unless their
+    /// style names another language.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            markdown: MarkdownRenderer::new(options),
+            language: options.notebook_language.to_lowercase(),
+        }
+    }
+
+    /// Renders blocks as a Markdown cell, nothing when they have no text.
+    fn push_markdown_cell(&self, cells: &mut Vec<Cell>, blocks: Vec<BlockNode>) -> Result<()> {
+        if blocks.is_empty() {
+            return Ok(());
+        }
+        let source = self.markdown.render(&DocumentAst {
+            blocks,
+            ..Default::default()
+        })?;
+        let source = source.trim_end();
+        if !source.is_empty() {
+            let image_count = cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Markdown { attachments, .. } => attachments.len(),
+                    _ => 0,
+                })
+                .sum();
+            let (source, attachments) = attach_images(source, image_count);
+            cells.push(Cell::Markdown {
+                source,
+                attachments,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Renderer for NotebookRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let mut cells = Vec::new();
+        if let Some(front_matter) = front_matter(&document.properties) {
+            cells.push(Cell::Raw(front_matter));
+        }
+
+        let mut blocks = Vec::new();
+        for block in &document.blocks {
+            let code = match block {
+                BlockNode::Paragraph(paragraph) => paragraph
+                    .style
+                    .as_deref()
+                    .and_then(code_language)
+                    .map(|language| (language, plain_text(&paragraph.content))),
+                _ => None,
+            };
+            let Some((language, text)) = code else {
+                blocks.push(block.clone());
+                continue;
+            };
+            self.push_markdown_cell(&mut cells, std::mem::take(&mut blocks))?;
+            let language = language.unwrap_or_else(|| self.language.clone());
+            match cells.last_mut() {
+                Some(Cell::Code {
+                    source,
+                    language: previous,
+                }) if *previous == language => {
+                    source.push('\n');
+                    source.push_str(&text);
+                }
+                _ => cells.push(Cell::Code {
+                    source: text,
+                    language,
+                }),
+            }
+        }
+        self.push_markdown_cell(&mut cells, blocks)?;
+
+        // Notes are numbered across the document, so they are defined once
+        let notes = self.markdown.render(&DocumentAst {
+            references: document.references.clone(),
+            ..Default::default()
+        })?;
+        if !notes.trim().is_empty() {
+            cells.push(Cell::Markdown {
+                source: notes.trim_end().to_string(),
+                attachments: Vec::new(),
+            });
+        }
+
+        let language = notebook_language(&cells).unwrap_or(&self.language);
+        let metadata = Json::Object(vec![(
+            "language_info",
+            Json::Object(vec![("name", Json::String(language.to_string()))]),
+        )]);
+        let cells = cells.iter().map(|cell| cell.to_json(language)).collect();
+        let notebook = Json::Object(vec![
+            ("cells", Json::Array(cells)),
+            ("metadata", metadata),
+            ("nbformat", Json::Number(4)),
+            ("nbformat_minor", Json::Number(4)),
+        ]);
+        let mut out = String::new();
+        notebook.write(&mut out, 0);
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+enum Cell {
+    Raw(String),
+    Markdown {
+        source: String,
+        /// Name, media type and base64 data of each embedded image.
+        attachments: Vec<(String, String, String)>,
+    },
+    Code {
+        source: String,
+        language: String,
+    },
+}
+
+impl Cell {
+    /// Serializes the cell; code in another language than the notebook's is
+    /// marked with the language, as VS Code does.
+    fn to_json(&self, notebook_language: &str) -> Json {
+        match self {
+            Self::Raw(source) => Json::Object(vec![
+                ("cell_type", Json::String("raw".to_string())),
+                ("metadata", Json::Object(Vec::new())),
+                ("source", source_lines(source)),
+            ]),
+            Self::Markdown {
+                source,
+                attachments,
+            } => {
+                let mut fields = vec![("cell_type", Json::String("markdown".to_string()))];
+                if !attachments.is_empty() {
+                    let attachments = attachments
+                        .iter()
+                        .map(|(name, media_type, data)| {
+                            (
+                                name.clone(),
+                                Json::Map(vec![(media_type.clone(), Json::String(data.clone()))]),
+                            )
+                        })
+                        .collect();
+                    fields.push(("attachments", Json::Map(attachments)));
+                }
+                fields.push(("metadata", Json::Object(Vec::new())));
+                fields.push(("source", source_lines(source)));
+                Json::Object(fields)
+            }
+            Self::Code { source, language } => {
+                let metadata = match language == notebook_language {
+                    true => Vec::new(),
+                    false => vec![(
+                        "vscode",
+                        Json::Object(vec![("languageId", Json::String(language.clone()))]),
+                    )],
+                };
+                Json::Object(vec![
+                    ("cell_type", Json::String("code".to_string())),
+                    ("execution_count", Json::Null),
+                    ("metadata", Json::Object(metadata)),
+                    ("outputs", Json::Array(Vec::new())),
+                    ("source", source_lines(source.trim_end())),
+                ])
+            }
+        }
+    }
+}
+
+/// Language of the most code cells, the first one's on a tie; `None` without
+/// code cells.
+fn notebook_language(cells: &[Cell]) -> Option<&str> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for cell in cells {
+        if let Cell::Code { language, .. } = cell {
+            match counts.iter_mut().find(|(name, _)| name == language) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((language, 1)),
+            }
+        }
+    }
+    counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(name, _)| *name)
+}
+
+/// Language of paragraphs with style id `style` if it is a code style: the
+/// part of the id naming it (`CodePython` is `python`), or `None` for the
+/// notebook's language.
+fn code_language(style: &str) -> Option<Option<String>> {
+    let id = style.to_lowercase();
+    if PLAIN_CODE_STYLES.contains(&id.as_str()) {
+        return Some(None);
+    }
+    if !id.contains("code") {
+        return None;
+    }
+    let language = CODE_STYLE_WORDS
+        .iter()
+        .fold(id, |id, word| id.replace(word, ""))
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect::<String>();
+    Some((!language.is_empty()).then_some(language))
+}
+
+/// YAML front matter with the title and author, if the document has either.
+fn front_matter(properties: &DocumentProperties) -> Option<String> {
+    let fields = [("title", &properties.title), ("author", &properties.author)]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = value.as_deref()?.trim();
+            (!value.is_empty()).then(|| format!("{}: {}\n", name, json_string(value)))
+        })
+        .collect::<String>();
+    (!fields.is_empty()).then(|| format!("---\n{}---", fields))
+}
+
+/// Points the `src` of embedded images in rendered Markdown to attachments,
+/// named from `image{first + 1}` on. Returns the new text and the name,
+/// media type and data of the attachments.
+fn attach_images(source: &str, first: usize) -> (String, Vec<(String, String, String)>) {
+    const PREFIX: &str = "src=\"data:";
+    let mut out = String::with_capacity(source.len());
+    let mut attachments: Vec<(String, String, String)> = Vec::new();
+    let mut uris: Vec<&str> = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find(PREFIX) {
+        let uri_start = start + "src=\"".len();
+        let Some(length) = rest[uri_start..].find('"') else {
+            break;
+        };
+        let uri = &rest[uri_start..uri_start + length];
+        let Some((media_type, data)) = uri["data:".len()..].split_once(";base64,") else {
+            out.push_str(&rest[..uri_start + length]);
+            rest = &rest[uri_start + length..];
+            continue;
+        };
+        let index = match uris.iter().position(|known| *known == uri) {
+            Some(index) => index,
+            None => {
+                let extension = media_type
+                    .rsplit('/')
+                    .next()
+                    .and_then(|subtype| subtype.split('+').next())
+                    .unwrap_or("bin");
+                let name = format!("image{}.{}", first + attachments.len() + 1, extension);
+                attachments.push((name, media_type.to_string(), data.to_string()));
+                uris.push(uri);
+                attachments.len() - 1
+            }
+        };
+        out.push_str(&rest[..uri_start]);
+        out.push_str("attachment:");
+        out.push_str(&attachments[index].0);
+        rest = &rest[uri_start + length..];
+    }
+    out.push_str(rest);
+    (out, attachments)
+}
+
+/// Cell source as an array of lines, each keeping its line break.
+fn source_lines(source: &str) -> Json {
+    Json::Array(
+        source
+            .split_inclusive('\n')
+            .map(|line| Json::String(line.to_string()))
+            .collect(),
+    )
+}
+
+/// JSON value, written with one space of indentation per level as Jupyter
+/// does.
+enum Json {
+    Null,
+    Number(u32),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+    /// Object with computed keys.
+    Map(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Self::Null => out.push_str("null"),
+            Self::Number(number) => out.push_str(&number.to_string()),
+            Self::String(text) => out.push_str(&json_string(text)),
+            Self::Array(items) if items.is_empty() => out.push_str("[]"),
+            Self::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    out.push_str(&" ".repeat(indent + 1));
+                    item.write(out, indent + 1);
+                }
+                out.push('\n');
+                out.push_str(&" ".repeat(indent));
+                out.push(']');
+            }
+            Self::Object(fields) => {
+                Self::write_object(out, fields.iter().map(|(key, value)| (*key, value)), indent)
+            }
+            Self::Map(fields) => Self::write_object(
+                out,
+                fields.iter().map(|(key, value)| (key.as_str(), value)),
+                indent,
+            ),
+        }
+    }
+
+    fn write_object<'j>(
+        out: &mut String,
+        fields: impl ExactSizeIterator<Item = (&'j str, &'j Json)>,
+        indent: usize,
+    ) {
+        if fields.len() == 0 {
+            out.push_str("{}");
+            return;
+        }
+        out.push('{');
+        for (i, (key, value)) in fields.enumerate() {
+            out.push_str(if i == 0 { "\n" } else { ",\n" });
+            out.push_str(&" ".repeat(indent + 1));
+            out.push_str(&json_string(key));
+            out.push_str(": ");
+            value.write(out, indent + 1);
+        }
+        out.push('\n');
+        out.push_str(&" ".repeat(indent));
+        out.push('}');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{InlineNode, Paragraph, TextStyle};
+
+    fn paragraph(style: Option<&str>, text: &str) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content: vec![InlineNode::Text {
+                text: text.to_string(),
+                style: TextStyle::default(),
+            }],
+            style: style.map(str::to_string),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_code_language_from_style_id() {
+        assert_eq!(code_language("SourceCode"), Some(None));
+        assert_eq!(code_language("HTMLPreformatted"), Some(None));
+        assert_eq!(
+            code_language("CodePython"),
+            Some(Some("python".to_string()))
+        );
+        assert_eq!(code_language("SQL-Code1"), Some(Some("sql".to_string())));
+        assert_eq!(code_language("Normal"), None);
+    }
+
+    #[test]
+    fn test_notebook_language_from_code_cells() {
+        let render = |blocks| {
+            NotebookRenderer::default()
+                .render(&DocumentAst {
+                    blocks,
+                    ..Default::default()
+                })
+                .expect("render should work")
+        };
+        let rendered = render(vec![
+            paragraph(Some("SourceCode"), "print(1)"),
+            paragraph(None, "Then:"),
+            paragraph(Some("CodeSql"), "SELECT 1;"),
+            paragraph(None, "And:"),
+            paragraph(Some("CodeSql"), "SELECT 2;"),
+        ]);
+        assert!(rendered.contains("\"language_info\": {\n   \"name\": \"sql\""));
+        assert_eq!(rendered.matches("\"languageId\"").count(), 1);
+        assert!(rendered.contains("\"languageId\": \"python\""));
+
+        let rendered = render(vec![paragraph(None, "No code")]);
+        assert!(rendered.contains("\"language_info\": {\n   \"name\": \"python\""));
+    }
+
+    #[test]
+    fn test_notebook_cells_front_matter_and_attachments() {
+        let document = DocumentAst {
+            blocks: vec![
+                paragraph(None, "Run *this*:"),
+                paragraph(Some("SourceCode"), "x = 1\nprint(x)"),
+                paragraph(Some("SourceCode"), "print(x * 2)"),
+                paragraph(Some("CodeSql"), "SELECT 1;"),
+                BlockNode::Paragraph(Paragraph::from_markdown(
+                    "<img src=\"data:image/png;base64,iVBO\" alt=\"chart\" />",
+                )),
+            ],
+            properties: DocumentProperties {
+                title: Some("Intro \"101\"".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rendered = NotebookRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            r#"{
+ "cells": [
+  {
+   "cell_type": "raw",
+   "metadata": {},
+   "source": [
+    "---\n",
+    "title: \"Intro \\\"101\\\"\"\n",
+    "---"
+   ]
+  },
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": [
+    "Run \\*this\\*:"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "metadata": {},
+   "outputs": [],
+   "source": [
+    "x = 1\n",
+    "print(x)\n",
+    "print(x * 2)"
+   ]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "metadata": {
+    "vscode": {
+     "languageId": "sql"
+    }
+   },
+   "outputs": [],
+   "source": [
+    "SELECT 1;"
+   ]
+  },
+  {
+   "cell_type": "markdown",
+   "attachments": {
+    "image1.png": {
+     "image/png": "iVBO"
+    }
+   },
+   "metadata": {},
+   "source": [
+    "<img src=\"attachment:image1.png\" alt=\"chart\" />"
+   ]
+  }
+ ],
+ "metadata": {
+  "language_info": {
+   "name": "python"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 4
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,562 @@
+//! Org-mode output.
+//!
+//! Bookmarks become dedicated targets (`<<name>>`) that internal links point
+//! to, and notes become footnotes defined at the end. Org tables cannot
+//! merge cells, so merged cells are written once and their other positions
+//! left empty. Text that would start another element, or form a link or
+//! target, is broken up with a zero width space, Org's escape character.
+
+use crate::core::ast::{
+    BlockNode, DocumentAst, DocumentProperties, Heading, InlineNode, List, ListKind, Paragraph,
+    ReferenceDefinitions, Table, TextStyle,
+};
+use crate::render::grid::{display_width, place_cells};
+use crate::render::{checkbox_glyph, item_label, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+#[derive(Debug, Clone, Copy)]
+pub struct OrgRenderer {
+    list_markers: ListMarkerStyle,
+}
+
+impl Default for OrgRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl OrgRenderer {
+    /// Creates a renderer using the list markers of `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            list_markers: options.list_markers,
+        }
+    }
+}
+
+impl Renderer for OrgRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let mut writer = Writer {
+            refs: &document.references,
+            list_markers: self.list_markers,
+            notes: Vec::new(),
+        };
+        let mut parts = vec![keywords(&document.properties)];
+        let body = writer.render_blocks(&document.blocks, true);
+        if !body.is_empty() {
+            parts.push(body);
+        }
+
+        let notes = writer
+            .notes
+            .iter()
+            .map(|(label, text)| {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                format!("[fn:{}] {}", label, escape(&text))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        if !notes.is_empty() {
+            parts.push(notes.join("\n"));
+        }
+
+        let mut out = parts.join("\n\n");
+        out.push('\n');
+        Ok(out)
+    }
+}
+
+/// In-buffer settings: the document properties, and `^:{}` so that only
+/// `_{..}` and `^{..}` are sub- and superscripts, not underscores in words.
+fn keywords(properties: &DocumentProperties) -> String {
+    let mut lines = [
+        ("TITLE", &properties.title),
+        ("AUTHOR", &properties.author),
+        ("DESCRIPTION", &properties.description),
+        ("KEYWORDS", &properties.keywords),
+        ("LANGUAGE", &properties.language),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        let value = value.as_deref()?.split_whitespace().collect::<Vec<_>>();
+        (!value.is_empty()).then(|| format!("#+{}: {}", name, value.join(" ")))
+    })
+    .collect::<Vec<_>>();
+    lines.push("#+OPTIONS: ^:{}".to_string());
+    lines.join("\n")
+}
+
+/// Rendering state: the notes referenced so far, by label, to define at the
+/// end.
+struct Writer<'a> {
+    refs: &'a ReferenceDefinitions,
+    list_markers: ListMarkerStyle,
+    notes: Vec<(String, String)>,
+}
+
+impl Writer<'_> {
+    /// Renders blocks separated by blank lines. Nested blocks (in lists and
+    /// tables) cannot be headlines.
+    fn render_blocks(&mut self, blocks: &[BlockNode], top: bool) -> String {
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, top))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&mut self, block: &BlockNode, top: bool) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph),
+            BlockNode::Heading(heading) => self.render_heading(heading, top),
+            BlockNode::TableOfContents(entries) => {
+                let top = entries.iter().map(|entry| entry.level).min();
+                let bottom = entries.iter().map(|entry| entry.level).max();
+                match (top, bottom) {
+                    (Some(top), Some(bottom)) => {
+                        format!("#+TOC: headlines {}", bottom - top + 1)
+                    }
+                    _ => String::new(),
+                }
+            }
+            BlockNode::Table(table) => self.render_table(table),
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, top),
+        }
+    }
+
+    fn render_paragraph(&mut self, paragraph: &Paragraph) -> String {
+        if let [InlineNode::Image {
+            source,
+            alt,
+            width: Some(width),
+        }] = paragraph.content.as_slice()
+        {
+            if !source.starts_with("data:") {
+                return format!(
+                    "#+ATTR_HTML: :width {}px :alt {}\n#+ATTR_ORG: :width {}\n{}",
+                    width,
+                    alt,
+                    width,
+                    image_link(source)
+                );
+            }
+        }
+        let text = self.render_inlines(&paragraph.content);
+        text.trim()
+            .split('\n')
+            .map(|line| escape_line_start(line.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Renders a headline with its bookmarks as targets; a bold paragraph
+    /// where headlines are not allowed.
+    fn render_heading(&mut self, heading: &Heading, top: bool) -> String {
+        let mut title = escape(&heading.label);
+        title.push_str(self.render_inlines(&heading.content).trim());
+        let mut title = title
+            .replace("\\\\\n", " ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        if !top {
+            return match title.is_empty() {
+                true => String::new(),
+                false => escape_line_start(&format!("*{}*", title)),
+            };
+        }
+        for name in heading.id.iter().chain(&heading.anchors) {
+            title.push_str(&format!(" <<{}>>", name));
+        }
+        format!("{} {}", "*".repeat(heading.level.max(1)), title.trim())
+    }
+
+    /// Renders list items with `-` or their numbers, followed by Word's
+    /// labels unless list markers drop them; item content and nested lists
+    /// are indented under the item text.
+    fn render_list(&mut self, list: &List) -> String {
+        let mut items = Vec::with_capacity(list.items.len());
+        for (i, item) in list.items.iter().enumerate() {
+            let number = item
+                .number
+                .unwrap_or_else(|| list.start.unwrap_or(1) + i as i32);
+            let mut marker = match list.kind {
+                ListKind::Bullet => "- ".to_string(),
+                ListKind::Ordered => format!("{}. ", number),
+            };
+            let padding = " ".repeat(marker.len());
+            if list.kind == ListKind::Ordered && i == 0 && number != 1 {
+                marker.push_str(&format!("[@{}] ", number));
+            }
+            match item.checked {
+                Some(true) => marker.push_str("[X] "),
+                Some(false) => marker.push_str("[ ] "),
+                None => {}
+            }
+            if let Some(label) = item_label(self.list_markers, list.kind, item) {
+                marker.push_str(&escape(label));
+                marker.push(' ');
+            }
+            let content = self.render_blocks(&item.blocks, false);
+            let content = content
+                .split('\n')
+                .enumerate()
+                .map(|(i, line)| match i {
+                    0 => line.to_string(),
+                    _ if line.is_empty() => String::new(),
+                    _ => format!("{}{}", padding, line),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            items.push(format!("{}{}", marker, content).trim_end().to_string());
+        }
+        items.join("\n")
+    }
+
+    /// Renders an aligned table; the first row is the header unless it has
+    /// cells spanning rows.
+    fn render_table(&mut self, table: &Table) -> String {
+        let placed = place_cells(&table.rows);
+        let Some(rows) = placed.iter().map(|cell| cell.row + cell.rowspan).max() else {
+            return String::new();
+        };
+        let columns = placed
+            .iter()
+            .map(|cell| cell.column + cell.colspan)
+            .max()
+            .unwrap_or(0);
+        let mut grid = vec![vec![String::new(); columns]; rows];
+        for cell in &placed {
+            if let Some(cell_content) = cell.cell {
+                let text = self.render_blocks(&cell_content.blocks, false);
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                grid[cell.row][cell.column] = text.replace('|', "\\vert{}");
+            }
+        }
+        let widths = (0..columns)
+            .map(|column| {
+                grid.iter()
+                    .map(|row| display_width(&row[column]))
+                    .max()
+                    .unwrap_or(0)
+                    .max(1)
+            })
+            .collect::<Vec<_>>();
+        let header = rows > 1 && placed.iter().all(|cell| cell.row > 0 || cell.rowspan == 1);
+
+        let mut lines = Vec::with_capacity(rows + 1);
+        for (i, row) in grid.iter().enumerate() {
+            let cells = row
+                .iter()
+                .zip(&widths)
+                .map(|(text, width)| format!("{}{}", text, " ".repeat(width - display_width(text))))
+                .collect::<Vec<_>>();
+            lines.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 && header {
+                let rule = widths
+                    .iter()
+                    .map(|width| "-".repeat(width + 2))
+                    .collect::<Vec<_>>();
+                lines.push(format!("|{}|", rule.join("+")));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn render_inlines(&mut self, nodes: &[InlineNode]) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(&mut out, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = self.render_inlines(content);
+                    let text = text.trim().replace(['[', ']'], "");
+                    let target = match destination.strip_prefix('#') {
+                        Some(name) => name.to_string(),
+                        None => destination.replace(' ', "%20"),
+                    }
+                    .replace('[', "%5B")
+                    .replace(']', "%5D");
+                    match text.is_empty() {
+                        true => out.push_str(&format!("[[{}]]", target)),
+                        false => out.push_str(&format!("[[{}][{}]]", target, text)),
+                    }
+                }
+                InlineNode::Anchor(name) => out.push_str(&format!("<<{}>>", name)),
+                InlineNode::Math {
+                    tex,
+                    display: false,
+                } => out.push_str(&format!("\\({}\\)", tex.trim())),
+                InlineNode::Math { tex, display: true } => {
+                    out.push_str(&format!("\\[{}\\]", tex.trim()))
+                }
+                InlineNode::Image { source, alt, .. } => {
+                    if source.starts_with("data:") {
+                        // Org cannot show embedded images
+                        out.push_str(&format!("[{}]", escape(alt)));
+                    } else {
+                        out.push_str(&image_link(source));
+                    }
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = self.refs.note_text(*kind, id) {
+                        let label = kind.label(id);
+                        if !self.notes.iter().any(|(defined, _)| *defined == label) {
+                            self.notes.push((label.clone(), text.to_string()));
+                        }
+                        out.push_str(&format!("[fn:{}]", label));
+                    }
+                }
+                InlineNode::PageBreak => {}
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::CheckBox { checked } => out.push(checkbox_glyph(*checked)),
+                InlineNode::ContentControl { content, .. } => {
+                    out.push_str(&self.render_inlines(content))
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Writes formatted text line by line, line breaks as `\\`. Formatting stays
+/// inside the surrounding whitespace.
+fn push_text(out: &mut String, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str("\\\\\n");
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            out.push_str(line);
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        out.push_str(&line[..start]);
+        out.push_str(&format_span(core, style));
+        out.push_str(&line[start + core.len()..]);
+    }
+}
+
+fn format_span(text: &str, style: &TextStyle) -> String {
+    let mut text = escape(text);
+    if style.superscript {
+        text = format!("^{{{}}}", text);
+    } else if style.subscript {
+        text = format!("_{{{}}}", text);
+    }
+    let markers = [
+        (style.strike || style.deletion, '+'),
+        (style.underline || style.insertion, '_'),
+        (style.italic, '/'),
+        (style.bold, '*'),
+    ];
+    for (applies, marker) in markers {
+        if applies {
+            text = format!("{}{}{}", marker, text, marker);
+        }
+    }
+    text
+}
+
+fn image_link(source: &str) -> String {
+    format!("[[file:{}]]", source.replace(' ', "%20"))
+}
+
+/// Breaks up literal text that would form a link, target or footnote
+/// reference.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if matches!(
+            (previous, c),
+            (Some('['), '[' | 'f') | (Some(']'), ']') | (Some('<'), '<')
+        ) {
+            out.push(ZERO_WIDTH_SPACE);
+        }
+        out.push(c);
+        previous = Some(c);
+    }
+    out
+}
+
+/// Escapes the start of a line of text that would start a headline, list
+/// item, table, keyword, comment, fixed-width line or rule.
+fn escape_line_start(line: &str) -> String {
+    let marker_end = line
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(line.len());
+    let after = &line[marker_end..];
+    let enumerated = marker_end > 0 && after.starts_with(['.', ')']);
+    let element =
+        line.starts_with(['*', '-', '+', '|', '#', ':']) || line.starts_with("[fn:") || enumerated;
+    if element {
+        format!("{}{}", ZERO_WIDTH_SPACE, line)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{ListItem, NoteKind, TableCell};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn render(blocks: Vec<BlockNode>) -> String {
+        render_with(&ConvertOptions::default(), blocks)
+    }
+
+    fn render_with(options: &ConvertOptions, blocks: Vec<BlockNode>) -> String {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["See [[this]].".to_string()],
+                ..Default::default()
+            },
+            properties: DocumentProperties {
+                title: Some("Report".to_string()),
+                ..Default::default()
+            },
+        };
+        OrgRenderer::new(options)
+            .render(&document)
+            .expect("render should work")
+    }
+
+    #[test]
+    fn test_org_headings_links_notes_and_escaping() {
+        let bold = InlineNode::Text {
+            text: "x_1".to_string(),
+            style: TextStyle {
+                bold: true,
+                ..Default::default()
+            },
+        };
+        let rendered = render(vec![
+            BlockNode::Heading(Heading {
+                level: 2,
+                label: "1.2 ".to_string(),
+                content: vec![text("Costs")],
+                id: None,
+                anchors: vec!["_Toc1".to_string()],
+            }),
+            paragraph(vec![
+                text("* costs "),
+                bold,
+                InlineNode::NoteReference {
+                    kind: NoteKind::Footnote,
+                    id: "1".to_string(),
+                },
+                text(", see "),
+                InlineNode::Link {
+                    destination: "#_Toc1".to_string(),
+                    content: vec![text("costs")],
+                },
+            ]),
+        ]);
+        assert_eq!(
+            rendered,
+            "#+TITLE: Report\n#+OPTIONS: ^:{}\n\n\
+             ** 1.2 Costs <<_Toc1>>\n\n\
+             \u{200B}* costs *x_1*[fn:1], see [[_Toc1][costs]]\n\n\
+             [fn:1] See [\u{200B}[this]\u{200B}].\n"
+        );
+    }
+
+    #[test]
+    fn test_org_nested_lists_and_spanned_table() {
+        let item = |content: &str, blocks: Vec<BlockNode>| ListItem {
+            label: String::new(),
+            separator: "\t".to_string(),
+            number: None,
+            checked: None,
+            blocks: [vec![paragraph(vec![text(content)])], blocks].concat(),
+        };
+        let nested = BlockNode::List(List {
+            kind: ListKind::Bullet,
+            start: None,
+            level: 1,
+            items: vec![item("inner", Vec::new())],
+        });
+        let list = BlockNode::List(List {
+            kind: ListKind::Ordered,
+            start: Some(3),
+            level: 0,
+            items: vec![item("outer", vec![nested]), item("next", Vec::new())],
+        });
+        let cell = |content: &str, colspan| TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan: 1,
+        };
+        let table = BlockNode::Table(Table {
+            rows: vec![vec![cell("Wide", 2)], vec![cell("a|b", 1), cell("표", 1)]],
+        });
+        assert_eq!(
+            render(vec![list, table]),
+            "#+TITLE: Report\n#+OPTIONS: ^:{}\n\n\
+             3. [@3] outer\n\n   - inner\n4. next\n\n\
+             | Wide      |    |\n\
+             |-----------+----|\n\
+             | a\\vert{}b | 표 |\n"
+        );
+    }
+
+    #[test]
+    fn test_org_list_markers_keep_labels_as_text() {
+        let list = || {
+            vec![BlockNode::List(List {
+                kind: ListKind::Ordered,
+                start: Some(1),
+                level: 0,
+                items: vec![ListItem {
+                    label: "(a)".to_string(),
+                    separator: "\t".to_string(),
+                    number: Some(1),
+                    checked: None,
+                    blocks: vec![paragraph(vec![text("First")])],
+                }],
+            })]
+        };
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        };
+        assert_eq!(render(list()).lines().last(), Some("1. First"));
+        assert_eq!(
+            render_with(&options, list()).lines().last(),
+            Some("1. (a) First")
+        );
+    }
+}
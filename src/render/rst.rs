@@ -0,0 +1,802 @@
+//! reStructuredText output, e.g. for Sphinx.
+//!
+//! Document properties become a field list at the top, bookmarks become
+//! targets (`.. _name:`) before the block holding them (links to targets
+//! the document lacks become plain text), notes become
+//! auto-numbered footnotes and images substitutions defined at the end.
+//! Tables are grid tables, so merged cells keep their spans.
+
+use crate::core::ast::{
+    plain_text, BlockNode, DocumentAst, Heading, InlineNode, List, ListKind, Paragraph,
+    ReferenceDefinitions, Table, TextStyle,
+};
+use crate::core::slug::SlugStyle;
+use crate::render::grid::{display_width, is_wide, place_cells};
+use crate::render::{block_targets, checkbox_glyph, item_label, link_targets, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+use std::collections::HashSet;
+
+/// Section title underline characters, by nesting depth.
+const UNDERLINES: [char; 9] = ['=', '-', '~', '^', '"', '\'', '`', ':', '.'];
+
+#[derive(Debug, Clone, Copy)]
+pub struct RstRenderer {
+    list_markers: ListMarkerStyle,
+    slugs: SlugStyle,
+}
+
+impl Default for RstRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl RstRenderer {
+    /// Creates a renderer using the list markers and anchor style of
+    /// `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            list_markers: options.list_markers,
+            slugs: options.slug_style(),
+        }
+    }
+}
+
+impl Renderer for RstRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let (document, targets) = link_targets(document, self.slugs);
+        let mut writer = Writer {
+            refs: &document.references,
+            targets: &targets,
+            list_markers: self.list_markers,
+            sections: Vec::new(),
+            notes: Vec::new(),
+            images: Vec::new(),
+        };
+        let mut parts = Vec::new();
+
+        let fields = document
+            .properties
+            .fields()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+                format!(":{}: {}", name, escape_block_start(escape(&value)))
+            })
+            .collect::<Vec<_>>();
+        if !fields.is_empty() {
+            parts.push(fields.join("\n"));
+        }
+
+        let body = writer.render_blocks(&document.blocks, true);
+        if !body.is_empty() {
+            parts.push(body);
+        }
+        parts.extend(writer.notes.iter().map(|text| {
+            let text = indent(&escape(text.trim()), "   ");
+            format!(".. [#] {}", text.trim_start())
+                .trim_end()
+                .to_string()
+        }));
+        parts.extend(writer.images.iter().enumerate().map(|(i, image)| {
+            let mut definition = format!(".. |image{}| image:: {}", i + 1, image.source);
+            if !image.alt.is_empty() {
+                definition.push_str(&format!("\n   :alt: {}", image.alt));
+            }
+            if let Some(width) = image.width {
+                definition.push_str(&format!("\n   :width: {}px", width));
+            }
+            definition
+        }));
+
+        let mut out = parts.join("\n\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+struct ImageDefinition {
+    source: String,
+    alt: String,
+    width: Option<u32>,
+}
+
+/// Rendering state: the open sections and the notes and images referenced
+/// so far, defined after the body.
+struct Writer<'a> {
+    refs: &'a ReferenceDefinitions,
+    /// Targets the document defines, which links can point to.
+    targets: &'a HashSet<String>,
+    list_markers: ListMarkerStyle,
+    /// Heading levels of the open sections, outermost first.
+    sections: Vec<usize>,
+    notes: Vec<String>,
+    images: Vec<ImageDefinition>,
+}
+
+impl Writer<'_> {
+    /// Renders blocks separated by blank lines. Top-level blocks are preceded
+    /// by the targets of the bookmarks they hold; nested blocks (in lists and
+    /// tables) cannot start sections.
+    fn render_blocks(&mut self, blocks: &[BlockNode], top: bool) -> String {
+        let mut parts = Vec::new();
+        for block in blocks {
+            if let (true, BlockNode::ContentControl { blocks, .. }) = (top, block) {
+                let rendered = self.render_blocks(blocks, true);
+                if !rendered.is_empty() {
+                    parts.push(rendered);
+                }
+                continue;
+            }
+            if top {
+                let mut names = Vec::new();
+                block_targets(block, &mut names);
+                parts.extend(names.iter().map(|name| target(name)));
+            }
+            let rendered = self.render_block(block, top);
+            if !rendered.is_empty() {
+                parts.push(rendered);
+            }
+        }
+        parts.join("\n\n")
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&mut self, block: &BlockNode, top: bool) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph),
+            BlockNode::Heading(heading) => self.render_heading(heading, top),
+            BlockNode::TableOfContents(entries) => {
+                let top = entries.iter().map(|entry| entry.level).min();
+                let bottom = entries.iter().map(|entry| entry.level).max();
+                match (top, bottom) {
+                    (Some(top), Some(bottom)) => {
+                        format!(".. contents::\n   :depth: {}", bottom - top + 1)
+                    }
+                    _ => String::new(),
+                }
+            }
+            BlockNode::Table(table) => self.render_table(table),
+            // Bookmarks only, written as targets
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, false),
+        }
+    }
+
+    fn render_paragraph(&mut self, paragraph: &Paragraph) -> String {
+        if let [InlineNode::Math { tex, display: true }] = paragraph.content.as_slice() {
+            return format!(".. math::\n\n{}", indent(tex.trim(), "   "));
+        }
+
+        let text = self.render_inlines(&paragraph.content);
+        let text = text.trim();
+        if text.is_empty() {
+            return String::new();
+        }
+        if text.contains('\n') {
+            // Line block, keeping the line breaks
+            return text
+                .split('\n')
+                .map(|line| format!("| {}", line.trim()).trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        let mut text = escape_block_start(text.to_string());
+        // A final `::` would start a literal block
+        if text.ends_with("::") {
+            text.insert(text.len() - 1, '\\');
+        }
+        text
+    }
+
+    /// Renders a section title underlined by its nesting depth, or a rubric
+    /// where sections are not allowed.
+    fn render_heading(&mut self, heading: &Heading, top: bool) -> String {
+        let mut title = escape(&heading.label);
+        title.push_str(self.render_inlines(&heading.content).trim());
+        let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let title = escape_block_start(title);
+        if title.is_empty() {
+            return String::new();
+        }
+        if !top {
+            return format!(".. rubric:: {}", title);
+        }
+
+        while self
+            .sections
+            .last()
+            .is_some_and(|&level| level >= heading.level)
+        {
+            self.sections.pop();
+        }
+        let depth = self.sections.len().min(UNDERLINES.len() - 1);
+        self.sections.push(heading.level);
+        let underline = UNDERLINES[depth]
+            .to_string()
+            .repeat(display_width(&title).max(1));
+        format!("{}\n{}", title, underline)
+    }
+
+    /// Renders list items with `-` or their numbers, followed by Word's
+    /// labels unless list markers drop them; item content and nested lists
+    /// are indented under the item text.
+    fn render_list(&mut self, list: &List) -> String {
+        let mut items = Vec::with_capacity(list.items.len());
+        let mut compact = true;
+        for (i, item) in list.items.iter().enumerate() {
+            let mut marker = match list.kind {
+                ListKind::Bullet => "- ".to_string(),
+                ListKind::Ordered => {
+                    let number = item
+                        .number
+                        .unwrap_or_else(|| list.start.unwrap_or(1) + i as i32);
+                    format!("{}. ", number)
+                }
+            };
+            let padding = " ".repeat(marker.len());
+            if let Some(checked) = item.checked {
+                marker.push(checkbox_glyph(checked));
+                marker.push(' ');
+            }
+            if let Some(label) = item_label(self.list_markers, list.kind, item) {
+                // The label would otherwise start a nested list
+                marker.push_str(&escape_block_start(format!("{} ", escape(label))));
+            }
+
+            let content = self.render_blocks(&item.blocks, false);
+            compact &= !content.contains('\n');
+            let content = indent(&content, &padding);
+            items.push(
+                format!("{}{}", marker, content.trim_start())
+                    .trim_end()
+                    .to_string(),
+            );
+        }
+        items.join(if compact { "\n" } else { "\n\n" })
+    }
+
+    /// Renders a grid table; the first row is the header unless it has
+    /// cells spanning rows.
+    fn render_table(&mut self, table: &Table) -> String {
+        let placed = place_cells(&table.rows);
+        let Some(rows) = placed.iter().map(|cell| cell.row + cell.rowspan).max() else {
+            return String::new();
+        };
+        let columns = placed
+            .iter()
+            .map(|cell| cell.column + cell.colspan)
+            .max()
+            .unwrap_or(0);
+        let contents = placed
+            .iter()
+            .map(|cell| {
+                let text = match cell.cell {
+                    Some(cell) => self.render_blocks(&cell.blocks, false),
+                    None => String::new(),
+                };
+                text.split('\n').map(str::to_string).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Column widths and row heights fitting the content, spans widening
+        // their last column or row
+        let mut widths = vec![1; columns];
+        let mut heights = vec![1; rows];
+        let mut order = (0..placed.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| placed[i].colspan);
+        for &i in &order {
+            let cell = &placed[i];
+            let needed = contents[i]
+                .iter()
+                .map(|line| display_width(line))
+                .max()
+                .unwrap_or(0);
+            let span = cell.column..cell.column + cell.colspan;
+            let available = widths[span.clone()].iter().sum::<usize>() + 3 * (cell.colspan - 1);
+            if needed > available {
+                widths[span.end - 1] += needed - available;
+            }
+        }
+        order.sort_by_key(|&i| placed[i].rowspan);
+        for &i in &order {
+            let cell = &placed[i];
+            let span = cell.row..cell.row + cell.rowspan;
+            let available = heights[span.clone()].iter().sum::<usize>() + cell.rowspan - 1;
+            if contents[i].len() > available {
+                heights[span.end - 1] += contents[i].len() - available;
+            }
+        }
+
+        let mut xs = vec![0];
+        for width in &widths {
+            xs.push(xs[xs.len() - 1] + width + 3);
+        }
+        let mut ys = vec![0];
+        for height in &heights {
+            ys.push(ys[ys.len() - 1] + height + 1);
+        }
+        let header = rows > 1 && placed.iter().all(|cell| cell.row > 0 || cell.rowspan == 1);
+
+        // Borders first, corners over them, then the content
+        let mut canvas = vec![vec![' '; xs[columns] + 1]; ys[rows] + 1];
+        for cell in &placed {
+            let (x0, x1) = (xs[cell.column], xs[cell.column + cell.colspan]);
+            let (y0, y1) = (ys[cell.row], ys[cell.row + cell.rowspan]);
+            for y in [y0, y1] {
+                let fill = if header && y == ys[1] { '=' } else { '-' };
+                canvas[y][x0..=x1].fill(fill);
+            }
+            for row in &mut canvas[y0..=y1] {
+                row[x0] = '|';
+                row[x1] = '|';
+            }
+        }
+        for cell in &placed {
+            for x in [xs[cell.column], xs[cell.column + cell.colspan]] {
+                for y in [ys[cell.row], ys[cell.row + cell.rowspan]] {
+                    canvas[y][x] = '+';
+                }
+            }
+        }
+        for (cell, lines) in placed.iter().zip(&contents) {
+            for (i, line) in lines.iter().enumerate() {
+                let row = &mut canvas[ys[cell.row] + 1 + i];
+                let mut x = xs[cell.column] + 2;
+                for c in line.chars() {
+                    row[x] = c;
+                    if is_wide(c) {
+                        // Placeholder for the second column of a wide character
+                        row[x + 1] = '\0';
+                        x += 1;
+                    }
+                    x += 1;
+                }
+            }
+        }
+        canvas
+            .into_iter()
+            .map(|row| row.into_iter().filter(|&c| c != '\0').collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn render_inlines(&mut self, nodes: &[InlineNode]) -> String {
+        let mut pieces = Vec::new();
+        self.collect_pieces(nodes, &mut pieces);
+        join_pieces(pieces)
+    }
+
+    /// Collects rendered inline content as pieces of text and inline markup.
+    fn collect_pieces(&mut self, nodes: &[InlineNode], pieces: &mut Vec<Piece>) {
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(pieces, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } if destination
+                    .strip_prefix('#')
+                    .is_some_and(|name| !self.targets.contains(name)) =>
+                {
+                    // Nothing to point to
+                    self.collect_pieces(content, pieces)
+                }
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = plain_text(content)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let target = match destination.strip_prefix('#') {
+                        Some(name) => format!("{}_", name),
+                        None => destination
+                            .replace(' ', "%20")
+                            .replace('<', "%3C")
+                            .replace('>', "%3E")
+                            .replace('`', "%60"),
+                    };
+                    let text = match text.as_str() {
+                        "" if destination.starts_with('#') => escape(&destination[1..]),
+                        "" => String::new(),
+                        text => escape(text).replace('<', "\\<"),
+                    };
+                    let link = match text.as_str() {
+                        "" => format!("`<{}>`__", target),
+                        text => format!("`{} <{}>`__", text, target),
+                    };
+                    pieces.push(Piece::Markup(link));
+                }
+                // Written as targets before the block
+                InlineNode::Anchor(_) => {}
+                InlineNode::Math { tex, .. } => pieces.push(Piece::Markup(format!(
+                    ":math:`{}`",
+                    tex.replace('`', "\\`")
+                ))),
+                InlineNode::Image { source, alt, width } => {
+                    self.images.push(ImageDefinition {
+                        source: source.clone(),
+                        alt: alt.clone(),
+                        width: *width,
+                    });
+                    pieces.push(Piece::Markup(format!("|image{}|", self.images.len())));
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = self.refs.note_text(*kind, id) {
+                        self.notes.push(text.to_string());
+                        pieces.push(Piece::Markup("[#]_".to_string()));
+                    }
+                }
+                InlineNode::PageBreak => {}
+                InlineNode::Raw(raw) => pieces.push(Piece::Text(raw.clone())),
+                InlineNode::CheckBox { checked } => {
+                    pieces.push(Piece::Text(checkbox_glyph(*checked).to_string()))
+                }
+                InlineNode::ContentControl { content, .. } => self.collect_pieces(content, pieces),
+            }
+        }
+    }
+}
+
+/// Rendered inline content. Inline markup must be separated from adjacent
+/// text, which [`join_pieces`] does with escaped spaces.
+enum Piece {
+    Text(String),
+    Markup(String),
+}
+
+fn join_pieces(pieces: Vec<Piece>) -> String {
+    let mut out = String::new();
+    let mut after_markup = false;
+    for piece in pieces {
+        let (text, markup) = match piece {
+            Piece::Text(text) => (text, false),
+            Piece::Markup(markup) => (markup, true),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let touching = out.chars().last().is_some_and(|c| !c.is_whitespace())
+            && text.chars().next().is_some_and(|c| !c.is_whitespace());
+        if (markup || after_markup) && touching {
+            out.push_str("\\ ");
+        }
+        out.push_str(&text);
+        after_markup = markup;
+    }
+    out
+}
+
+/// Adds formatted text line by line, the formatting inside the surrounding
+/// whitespace. Markup cannot nest, so superscript and subscript win over
+/// bold, and bold over italic.
+fn push_text(pieces: &mut Vec<Piece>, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            pieces.push(Piece::Text("\n".to_string()));
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            pieces.push(Piece::Text(line.to_string()));
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        pieces.push(Piece::Text(line[..start].to_string()));
+        let escaped = escape(core);
+        pieces.push(if style.superscript {
+            Piece::Markup(format!(":sup:`{}`", escaped))
+        } else if style.subscript {
+            Piece::Markup(format!(":sub:`{}`", escaped))
+        } else if style.bold {
+            Piece::Markup(format!("**{}**", escaped))
+        } else if style.italic {
+            Piece::Markup(format!("*{}*", escaped))
+        } else {
+            Piece::Text(escaped)
+        });
+        pieces.push(Piece::Text(line[start + core.len()..].to_string()));
+    }
+}
+
+/// Escapes characters of literal text that could be read as inline markup.
+fn escape(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let escaped = match c {
+            '\\' | '*' | '`' | '|' => true,
+            // References (`name_`) and inline targets (`_name`)
+            '_' => {
+                let before = i.checked_sub(1).map(|i| chars[i]);
+                let after = chars.get(i + 1);
+                !before.is_some_and(char::is_alphanumeric)
+                    || !after.is_some_and(|c| c.is_alphanumeric())
+            }
+            _ => false,
+        };
+        if escaped {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escapes the first character of text that would start another block.
+fn escape_block_start(text: String) -> String {
+    if starts_block(&text) {
+        format!("\\{}", text)
+    } else {
+        text
+    }
+}
+
+/// Whether paragraph text would be read as the start of another block (a
+/// list item, field list, option list, comment, table, transition or doctest
+/// block). Text starting with inline markup is not: literal markup
+/// characters are escaped.
+fn starts_block(text: &str) -> bool {
+    if [":sup:`", ":sub:`", ":math:`"]
+        .iter()
+        .any(|role| text.starts_with(role))
+    {
+        return false;
+    }
+    if text.starts_with(['-', '+', '•', '‣', '⁃', '#', '.', ':', '>', '=', '/']) {
+        return true;
+    }
+    if text.chars().count() >= 4 && text.chars().all(|c| c.is_ascii_punctuation()) {
+        return true;
+    }
+    // Enumerators: numbers, single letters and roman numerals
+    let enumerator = text.strip_prefix('(').unwrap_or(text);
+    let marker_end = enumerator
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(enumerator.len());
+    let (marker, after) = enumerator.split_at(marker_end);
+    let roman = |numerals: &str| !marker.is_empty() && marker.chars().all(|c| numerals.contains(c));
+    let enumerated = marker.chars().all(|c| c.is_ascii_digit())
+        || marker.len() == 1
+        || roman("ivxlcdm")
+        || roman("IVXLCDM");
+    !marker.is_empty()
+        && enumerated
+        && after.starts_with(['.', ')'])
+        && after[1..].chars().next().map_or(true, char::is_whitespace)
+}
+
+/// Internal hyperlink target; names that are not simple reference names
+/// (such as Word's `_Toc` bookmarks) are quoted.
+fn target(name: &str) -> String {
+    let simple = name.starts_with(char::is_alphanumeric)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if simple {
+        format!(".. _{}:", name)
+    } else {
+        format!(".. _`{}`:", name.replace('`', "\\`"))
+    }
+}
+
+/// Indents the non-empty lines of `text` after the first.
+fn indent(text: &str, prefix: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line.to_string(),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{}{}", prefix, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{DocumentProperties, ListItem, NoteKind, TableCell};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn cell(content: &str, colspan: usize, rowspan: usize) -> TableCell {
+        TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan,
+        }
+    }
+
+    fn heading(level: usize, content: &str) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level,
+            label: String::new(),
+            content: vec![text(content)],
+            id: None,
+            anchors: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_rst_properties_sections_notes_and_images() {
+        let bold = InlineNode::Text {
+            text: "bold".to_string(),
+            style: TextStyle {
+                bold: true,
+                ..Default::default()
+            },
+        };
+        let document = DocumentAst {
+            blocks: vec![
+                paragraph(vec![InlineNode::Anchor("_Toc1".to_string())]),
+                heading(1, "Intro"),
+                heading(3, "Details"),
+                paragraph(vec![
+                    text("A "),
+                    bold,
+                    text("ly snake_case_"),
+                    InlineNode::NoteReference {
+                        kind: NoteKind::Footnote,
+                        id: "1".to_string(),
+                    },
+                    text(" "),
+                    InlineNode::Image {
+                        source: "media/a.png".to_string(),
+                        alt: "Logo".to_string(),
+                        width: Some(120),
+                    },
+                ]),
+                paragraph(vec![
+                    text("1. Not a list, see "),
+                    InlineNode::Link {
+                        destination: "#_Toc1".to_string(),
+                        content: vec![text("intro")],
+                    },
+                ]),
+                heading(2, "Next"),
+            ],
+            references: ReferenceDefinitions {
+                footnotes: vec!["A *note*.".to_string()],
+                ..Default::default()
+            },
+            properties: DocumentProperties {
+                title: Some("Guide".to_string()),
+                author: Some("A. M.".to_string()),
+                ..Default::default()
+            },
+        };
+        let rendered = RstRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            ":Title: Guide\n:Author: \\A. M.\n\n\
+             .. _`_Toc1`:\n\nIntro\n=====\n\nDetails\n-------\n\n\
+             A **bold**\\ ly snake_case\\_\\ [#]_ |image1|\n\n\
+             \\1. Not a list, see `intro <_Toc1_>`__\n\n\
+             Next\n----\n\n\
+             .. [#] A \\*note\\*.\n\n\
+             .. |image1| image:: media/a.png\n   :alt: Logo\n   :width: 120px\n"
+        );
+    }
+
+    #[test]
+    fn test_rst_links_only_to_defined_targets() {
+        let link = |name: &str, content: &str| InlineNode::Link {
+            destination: format!("#{}", name),
+            content: vec![text(content)],
+        };
+        let document = DocumentAst {
+            blocks: vec![
+                heading(1, "Getting Started"),
+                paragraph(vec![
+                    link("getting-started", "start"),
+                    text(", "),
+                    link("_Toc9", "gone"),
+                ]),
+            ],
+            ..Default::default()
+        };
+        let rendered = RstRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            ".. _getting-started:\n\nGetting Started\n===============\n\n\
+             `start <getting-started_>`__\\ , gone\n"
+        );
+    }
+
+    #[test]
+    fn test_rst_grid_table_with_spans() {
+        let table = BlockNode::Table(Table {
+            rows: vec![
+                vec![cell("Name", 2, 1), cell("Note", 1, 1)],
+                vec![cell("A", 1, 2), cell("B", 1, 1), cell("Long cell", 1, 1)],
+                vec![cell("C", 2, 1)],
+            ],
+        });
+        let rendered = RstRenderer::default()
+            .render(&DocumentAst {
+                blocks: vec![table],
+                ..Default::default()
+            })
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            "+-------+-----------+\n\
+             | Name  | Note      |\n\
+             +===+===+===========+\n\
+             | A | B | Long cell |\n\
+             |   +---+-----------+\n\
+             |   | C             |\n\
+             +---+---------------+\n"
+        );
+    }
+
+    #[test]
+    fn test_rst_list_markers_and_anchor_style() {
+        let document = DocumentAst {
+            blocks: vec![
+                heading(1, "1.2 Costs"),
+                paragraph(vec![InlineNode::Link {
+                    destination: "#costs".to_string(),
+                    content: vec![text("costs")],
+                }]),
+                BlockNode::List(List {
+                    kind: ListKind::Ordered,
+                    start: Some(1),
+                    level: 0,
+                    items: vec![ListItem {
+                        label: "(a)".to_string(),
+                        separator: "\t".to_string(),
+                        number: Some(1),
+                        checked: None,
+                        blocks: vec![paragraph(vec![text("First")])],
+                    }],
+                }),
+            ],
+            ..Default::default()
+        };
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            anchors: crate::AnchorStyle::Pandoc,
+            ..Default::default()
+        };
+
+        let rendered = RstRenderer::default()
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(rendered, "1.2 Costs\n=========\n\ncosts\n\n1. First\n");
+        let rendered = RstRenderer::new(&options)
+            .render(&document)
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            ".. _costs:\n\n1.2 Costs\n=========\n\n`costs <costs_>`__\n\n1. \\(a) First\n"
+        );
+    }
+}
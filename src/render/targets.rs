@@ -0,0 +1,209 @@
+//! Targets of links within the document, for formats whose headings have no
+//! identifiers of their own.
+//!
+//! Links point to Word bookmarks or, with slug anchors, to the slugs Markdown
+//! renderers give headings. Headings get the slugs of the anchor style that
+//! links point to as anchors, so that they are labelled like bookmarks;
+//! links to names nothing defines are written as plain text by the
+//! renderers.
+
+use crate::converter::for_each_block;
+use crate::core::ast::{BlockNode, DocumentAst, InlineNode};
+use crate::core::slug::{SlugGenerator, SlugStyle};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Returns the document with the `style` slugs links point to added to the
+/// anchors of their headings, and the names links can point to in it.
+pub(crate) fn link_targets(
+    document: &DocumentAst,
+    style: SlugStyle,
+) -> (Cow<'_, DocumentAst>, HashSet<String>) {
+    let mut defined = Vec::new();
+    for block in &document.blocks {
+        block_targets(block, &mut defined);
+    }
+    let mut defined: HashSet<String> = defined.into_iter().collect();
+    let mut missing = HashSet::new();
+    linked_names(&document.blocks, &mut missing);
+    missing.retain(|name| !defined.contains(name));
+    if missing.is_empty() {
+        return (Cow::Borrowed(document), defined);
+    }
+
+    // Slugs in document order, so that repeated titles get their suffixes
+    let mut document = document.clone();
+    let mut slugs = SlugGenerator::new(style);
+    for_each_block(&mut document.blocks, &mut |block| {
+        if let BlockNode::Heading(heading) = block {
+            let slug = slugs.slug(&heading.plain_text());
+            if missing.remove(&slug) {
+                heading.anchors.push(slug.clone());
+                defined.insert(slug);
+            }
+        }
+    });
+    (Cow::Owned(document), defined)
+}
+
+/// Names of the bookmarks in `block`, including those nested in its lists,
+/// tables and content controls.
+pub(crate) fn block_targets(block: &BlockNode, names: &mut Vec<String>) {
+    match block {
+        BlockNode::Paragraph(paragraph) => inline_targets(&paragraph.content, names),
+        BlockNode::Heading(heading) => {
+            names.extend(heading.id.iter().cloned());
+            names.extend(heading.anchors.iter().cloned());
+            inline_targets(&heading.content, names);
+        }
+        BlockNode::Table(table) => {
+            for block in table.rows.iter().flatten().flat_map(|cell| &cell.blocks) {
+                block_targets(block, names);
+            }
+        }
+        BlockNode::List(list) => {
+            for block in list.items.iter().flat_map(|item| &item.blocks) {
+                block_targets(block, names);
+            }
+        }
+        BlockNode::ContentControl { blocks, .. } => {
+            for block in blocks {
+                block_targets(block, names);
+            }
+        }
+        BlockNode::TableOfContents(_) | BlockNode::RawHtml(_) => {}
+    }
+}
+
+fn inline_targets(nodes: &[InlineNode], names: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            InlineNode::Anchor(name) => names.push(name.clone()),
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                inline_targets(content, names)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Names in-document links (`#name`) in `blocks` point to.
+fn linked_names(blocks: &[BlockNode], names: &mut HashSet<String>) {
+    fn inline(nodes: &[InlineNode], names: &mut HashSet<String>) {
+        for node in nodes {
+            match node {
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    if let Some(name) = destination.strip_prefix('#') {
+                        names.insert(name.to_string());
+                    }
+                    inline(content, names);
+                }
+                InlineNode::ContentControl { content, .. } => inline(content, names),
+                _ => {}
+            }
+        }
+    }
+    for block in blocks {
+        match block {
+            BlockNode::Paragraph(paragraph) => inline(&paragraph.content, names),
+            BlockNode::Heading(heading) => inline(&heading.content, names),
+            BlockNode::Table(table) => {
+                for cell in table.rows.iter().flatten() {
+                    linked_names(&cell.blocks, names);
+                }
+            }
+            BlockNode::List(list) => {
+                for item in &list.items {
+                    linked_names(&item.blocks, names);
+                }
+            }
+            BlockNode::ContentControl { blocks, .. } => linked_names(blocks, names),
+            BlockNode::TableOfContents(_) | BlockNode::RawHtml(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{Heading, Paragraph, TextStyle};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn heading(title: &str, anchors: &[&str]) -> BlockNode {
+        BlockNode::Heading(Heading {
+            level: 1,
+            label: String::new(),
+            content: vec![text(title)],
+            id: None,
+            anchors: anchors.iter().map(|name| name.to_string()).collect(),
+        })
+    }
+
+    fn link(name: &str) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content: vec![InlineNode::Link {
+                destination: format!("#{}", name),
+                content: vec![text("see")],
+            }],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_linked_slugs_become_heading_anchors() {
+        let document = DocumentAst {
+            blocks: vec![
+                heading("Intro", &["_Toc1"]),
+                heading("Intro", &[]),
+                heading("1.2 Costs", &[]),
+                link("_Toc1"),
+                link("intro-1"),
+                link("costs"),
+                link("12-costs"),
+                link("nowhere"),
+            ],
+            ..Default::default()
+        };
+        let anchors = |style| {
+            let (document, defined) = link_targets(&document, style);
+            let anchors = document
+                .blocks
+                .iter()
+                .filter_map(|block| match block {
+                    BlockNode::Heading(heading) => Some(heading.anchors.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let mut defined = defined.into_iter().collect::<Vec<_>>();
+            defined.sort();
+            (anchors, defined)
+        };
+
+        let (github, defined) = anchors(SlugStyle::Github);
+        assert_eq!(github, [vec!["_Toc1"], vec!["intro-1"], vec!["12-costs"]]);
+        assert_eq!(defined, ["12-costs", "_Toc1", "intro-1"]);
+        let (pandoc, defined) = anchors(SlugStyle::Pandoc);
+        assert_eq!(pandoc, [vec!["_Toc1"], vec!["intro-1"], vec!["costs"]]);
+        assert_eq!(defined, ["_Toc1", "costs", "intro-1"]);
+    }
+
+    #[test]
+    fn test_documents_linking_bookmarks_only_are_borrowed() {
+        let document = DocumentAst {
+            blocks: vec![heading("Intro", &["_Toc1"]), link("_Toc1")],
+            ..Default::default()
+        };
+        let (rendered, defined) = link_targets(&document, SlugStyle::Github);
+        assert!(matches!(rendered, Cow::Borrowed(_)));
+        assert_eq!(defined, HashSet::from(["_Toc1".to_string()]));
+    }
+}
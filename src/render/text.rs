@@ -0,0 +1,291 @@
+//! Plain text output, e.g. for search indexing or language model input.
+//!
+//! Formatting, bookmarks and images are left out. Paragraphs are separated by
+//! blank lines, list items keep their labels and table rows become lines of
+//! tab-separated cells.
+
+use crate::core::ast::{
+    BlockNode, DocumentAst, InlineNode, List, NoteKind, ReferenceDefinitions, Table, TocEntry,
+};
+use crate::render::{checkbox_glyph, Renderer};
+use crate::{ConvertOptions, NotePlacement, Result};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainTextRenderer {
+    notes: NotePlacement,
+}
+
+impl PlainTextRenderer {
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            notes: options.text_notes,
+        }
+    }
+
+    /// Renders a block without trailing line break; empty when it has no text.
+    fn render_block(&self, block: &BlockNode, refs: &ReferenceDefinitions) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self
+                .render_inlines(&paragraph.content, refs)
+                .trim()
+                .to_string(),
+            BlockNode::Heading(heading) => {
+                let text = self.render_inlines(&heading.content, refs);
+                format!("{}{}", heading.label, text.trim())
+                    .trim()
+                    .to_string()
+            }
+            BlockNode::TableOfContents(entries) => render_toc(entries),
+            BlockNode::Table(table) => self.render_table(table, refs),
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list, refs),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, refs, "\n\n"),
+        }
+    }
+
+    /// Renders blocks with text, joined by `separator`.
+    fn render_blocks(
+        &self,
+        blocks: &[BlockNode],
+        refs: &ReferenceDefinitions,
+        separator: &str,
+    ) -> String {
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, refs))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn render_inlines(&self, nodes: &[InlineNode], refs: &ReferenceDefinitions) -> String {
+        let mut out = String::new();
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } if style.all_caps => {
+                    out.push_str(&text.to_uppercase())
+                }
+                InlineNode::Text { text, .. } => out.push_str(text),
+                InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                    out.push_str(&self.render_inlines(content, refs))
+                }
+                InlineNode::Math { tex, .. } => out.push_str(tex),
+                InlineNode::NoteReference { kind, id } => match self.notes {
+                    NotePlacement::End => out.push_str(&format!("[{}]", kind.label(id))),
+                    NotePlacement::Inline => {
+                        if let Some(text) = refs.note_text(*kind, id).filter(|t| !t.is_empty()) {
+                            out.push_str(&format!(" [{}]", text.trim()));
+                        }
+                    }
+                },
+                InlineNode::PageBreak => out.push('\n'),
+                InlineNode::CheckBox { checked } => out.push(checkbox_glyph(*checked)),
+                InlineNode::Raw(raw) => out.push_str(raw),
+                InlineNode::Anchor(_) | InlineNode::Image { .. } => {}
+            }
+        }
+        out
+    }
+
+    /// Renders list items on consecutive lines, continuation lines and
+    /// nested lists indented under the item text.
+    fn render_list(&self, list: &List, refs: &ReferenceDefinitions) -> String {
+        let mut lines = Vec::new();
+        for item in &list.items {
+            let mut marker = item.label.clone();
+            if !marker.is_empty() {
+                marker.push_str(match item.separator.as_str() {
+                    "" | "\t" => " ",
+                    separator => separator,
+                });
+            }
+            if let Some(checked) = item.checked {
+                marker.push(checkbox_glyph(checked));
+                marker.push(' ');
+            }
+            let indent = " ".repeat(marker.chars().count());
+
+            let text = self.render_blocks(&item.blocks, refs, "\n");
+            for (i, line) in text.split('\n').enumerate() {
+                match i {
+                    0 => lines.push(format!("{}{}", marker, line)),
+                    _ if line.is_empty() => lines.push(String::new()),
+                    _ => lines.push(format!("{}{}", indent, line)),
+                }
+            }
+            if text.is_empty() {
+                lines.push(marker.trim_end().to_string());
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Renders each row on a line, cells separated by tabs. Merged cells
+    /// leave their other columns empty.
+    fn render_table(&self, table: &Table, refs: &ReferenceDefinitions) -> String {
+        table
+            .rows
+            .iter()
+            .map(|row| {
+                let mut cells = Vec::new();
+                for cell in row {
+                    let text = self.render_blocks(&cell.blocks, refs, " ");
+                    cells.push(text.split_whitespace().collect::<Vec<_>>().join(" "));
+                    cells.extend((1..cell.colspan).map(|_| String::new()));
+                }
+                cells.join("\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Renders table of contents entries as lines indented by level.
+fn render_toc(entries: &[TocEntry]) -> String {
+    let top = entries.iter().map(|entry| entry.level).min().unwrap_or(1);
+    entries
+        .iter()
+        .map(|entry| format!("{}{}", "  ".repeat(entry.level - top), entry.text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Renderer for PlainTextRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let refs = &document.references;
+        let mut out = self.render_blocks(&document.blocks, refs, "\n\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        if self.notes == NotePlacement::End {
+            let notes = refs
+                .footnotes
+                .iter()
+                .enumerate()
+                .map(|(i, text)| (NoteKind::Footnote.label(&(i + 1).to_string()), text))
+                .chain(
+                    refs.endnotes
+                        .iter()
+                        .enumerate()
+                        .map(|(i, text)| (NoteKind::Endnote.label(&(i + 1).to_string()), text)),
+                )
+                .chain(
+                    refs.comments
+                        .iter()
+                        .map(|(id, text)| (NoteKind::Comment.label(id), text)),
+                );
+            let mut first = true;
+            for (label, text) in notes {
+                if first {
+                    out.push('\n');
+                    first = false;
+                }
+                out.push_str(&format!("[{}] {}\n", label, text.trim()));
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{ListItem, ListKind, Paragraph, TableCell, TextStyle};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn cell(content: &str, colspan: usize) -> TableCell {
+        TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan: 1,
+        }
+    }
+
+    fn document() -> DocumentAst {
+        DocumentAst {
+            blocks: vec![
+                paragraph(vec![
+                    InlineNode::Anchor("_Toc1".to_string()),
+                    InlineNode::Link {
+                        destination: "https://example.com".to_string(),
+                        content: vec![text("Intro")],
+                    },
+                    text(" text"),
+                    InlineNode::NoteReference {
+                        kind: NoteKind::Footnote,
+                        id: "1".to_string(),
+                    },
+                    InlineNode::Image {
+                        source: "data:image/png;base64,AAAA".to_string(),
+                        alt: "image".to_string(),
+                        width: None,
+                    },
+                ]),
+                BlockNode::RawHtml("<a id=\"x\"></a>".to_string()),
+                BlockNode::List(List {
+                    kind: ListKind::Ordered,
+                    start: Some(1),
+                    level: 0,
+                    items: vec![ListItem {
+                        label: "1.".to_string(),
+                        separator: "\t".to_string(),
+                        number: Some(1),
+                        checked: None,
+                        blocks: vec![
+                            paragraph(vec![text("First")]),
+                            paragraph(vec![text("More")]),
+                        ],
+                    }],
+                }),
+                BlockNode::Table(Table {
+                    rows: vec![
+                        vec![cell("Name", 1), cell("Value", 1)],
+                        vec![cell("Both  columns", 2)],
+                    ],
+                }),
+            ],
+            references: ReferenceDefinitions {
+                footnotes: vec!["A note.".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plain_text_blocks_and_appended_notes() {
+        let rendered = PlainTextRenderer::default()
+            .render(&document())
+            .expect("render should work");
+        assert_eq!(
+            rendered,
+            "Intro text[1]\n\n1. First\n   More\n\nName\tValue\nBoth columns\t\n\n[1] A note.\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_text_inline_notes() {
+        let renderer = PlainTextRenderer::new(&ConvertOptions {
+            text_notes: NotePlacement::Inline,
+            ..Default::default()
+        });
+        let rendered = renderer.render(&document()).expect("render should work");
+        assert!(rendered.starts_with("Intro text [A note.]\n\n"));
+        assert!(!rendered.contains("[1]"));
+    }
+}
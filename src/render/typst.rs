@@ -0,0 +1,834 @@
+//! Typst output.
+//!
+//! Formatting uses function calls (`#strong[..]`), which also work inside
+//! words. Ordered lists get a numbering pattern matching their Word labels
+//! with original list markers, bookmarks become labels that links point to (links to labels the document
+//! lacks become plain text), and math is typeset from its TeX source with the
+//! `mitex` package.
+
+use crate::converter::format_num;
+use crate::core::ast::{
+    BlockNode, DocumentAst, DocumentProperties, Heading, InlineNode, List, ListItem, ListKind,
+    Paragraph, ReferenceDefinitions, Table, TextStyle,
+};
+use crate::core::slug::SlugStyle;
+use crate::render::grid::place_cells;
+use crate::render::{checkbox_glyph, item_label, link_targets, Renderer};
+use crate::{ConvertOptions, ListMarkerStyle, Result};
+use std::collections::HashSet;
+
+/// Package typesetting TeX math, imported when the document has math.
+const MITEX_IMPORT: &str = "#import \"@preview/mitex:0.2.4\": mi, mitex";
+
+/// Word number formats with a Typst counting symbol, tried in order when
+/// matching list labels.
+const COUNTING: [(&str, &str); 8] = [
+    ("decimal", "1"),
+    ("lowerRoman", "i"),
+    ("upperRoman", "I"),
+    ("lowerLetter", "a"),
+    ("upperLetter", "A"),
+    ("decimalEnclosedCircle", "①"),
+    ("ganada", "가"),
+    ("chosung", "ㄱ"),
+];
+
+/// Characters Typst reads as counting symbols in numbering patterns.
+const COUNTING_SYMBOLS: &[char] = &[
+    '1', 'a', 'A', 'i', 'I', 'α', 'Α', '一', '壹', 'あ', 'い', 'ア', 'イ', 'א', '가', 'ㄱ', '*',
+    '①', '⓵',
+];
+
+#[derive(Debug, Clone, Copy)]
+pub struct TypstRenderer {
+    list_markers: ListMarkerStyle,
+    slugs: SlugStyle,
+}
+
+impl Default for TypstRenderer {
+    fn default() -> Self {
+        Self::new(&ConvertOptions::default())
+    }
+}
+
+impl TypstRenderer {
+    /// Creates a renderer using the list markers and anchor style of
+    /// `options`.
+    pub fn new(options: &ConvertOptions) -> Self {
+        Self {
+            list_markers: options.list_markers,
+            slugs: options.slug_style(),
+        }
+    }
+}
+
+impl Renderer for TypstRenderer {
+    fn render(&self, document: &DocumentAst) -> Result<String> {
+        let (document, labels) = link_targets(document, self.slugs);
+        let writer = Writer {
+            refs: &document.references,
+            labels: &labels,
+            list_markers: self.list_markers,
+        };
+        let mut parts = Vec::new();
+        if has_math(&document.blocks) {
+            parts.push(MITEX_IMPORT.to_string());
+        }
+        if let Some(set) = document_set_rule(&document.properties) {
+            parts.push(set);
+        }
+        let body = writer.render_blocks(&document.blocks, true);
+        if !body.is_empty() {
+            parts.push(body);
+        }
+        let mut out = parts.join("\n\n");
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// `#set document(..)` with the title and author, when either is set.
+fn document_set_rule(properties: &DocumentProperties) -> Option<String> {
+    let fields = [("title", &properties.title), ("author", &properties.author)]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = value.as_deref()?.trim();
+            (!value.is_empty()).then(|| format!("{}: {}", name, string(value)))
+        })
+        .collect::<Vec<_>>();
+    (!fields.is_empty()).then(|| format!("#set document({})", fields.join(", ")))
+}
+
+struct Writer<'a> {
+    refs: &'a ReferenceDefinitions,
+    /// Labels the document defines, which links can point to.
+    labels: &'a HashSet<String>,
+    list_markers: ListMarkerStyle,
+}
+
+impl Writer<'_> {
+    /// Renders blocks separated by blank lines. Nested blocks (in lists and
+    /// tables) cannot be headings.
+    fn render_blocks(&self, blocks: &[BlockNode], top: bool) -> String {
+        blocks
+            .iter()
+            .map(|block| self.render_block(block, top))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Renders a block without trailing line break; empty when it has no
+    /// content.
+    fn render_block(&self, block: &BlockNode, top: bool) -> String {
+        match block {
+            BlockNode::Paragraph(paragraph) => self.render_paragraph(paragraph),
+            BlockNode::Heading(heading) => self.render_heading(heading, top),
+            BlockNode::TableOfContents(_) => "#outline()".to_string(),
+            BlockNode::Table(table) => self.render_table(table),
+            BlockNode::RawHtml(_) => String::new(),
+            BlockNode::List(list) => self.render_list(list),
+            BlockNode::ContentControl { blocks, .. } => self.render_blocks(blocks, top),
+        }
+    }
+
+    fn render_paragraph(&self, paragraph: &Paragraph) -> String {
+        match paragraph.content.as_slice() {
+            [InlineNode::Math { tex, display: true }] => {
+                return format!("#mitex({})", string(tex.trim()));
+            }
+            [InlineNode::Image { source, alt, width }] if !source.starts_with("data:") => {
+                return format!("#{}", image(source, alt, *width));
+            }
+            _ => {}
+        }
+        escape_block_start(self.render_inlines(&paragraph.content).trim())
+    }
+
+    /// Renders a heading with its first bookmark as label; other bookmarks
+    /// are anchored in the title. Headings nested in lists and tables
+    /// become strong paragraphs, with all bookmarks anchored.
+    fn render_heading(&self, heading: &Heading, top: bool) -> String {
+        let mut names = heading.id.iter().chain(&heading.anchors);
+        let first = names.next();
+        let mut title = escape(&heading.label);
+        title.push_str(self.render_inlines(&heading.content).trim());
+        for name in names {
+            title.push_str(&anchor(name));
+        }
+        let title = title.trim();
+        if title.is_empty() {
+            return first.map(|name| anchor(name)).unwrap_or_default();
+        }
+        if !top {
+            let anchor = first.map(|name| anchor(name)).unwrap_or_default();
+            return format!("{}#strong[{}]", anchor, title);
+        }
+        let mut out = format!("{} {}", "=".repeat(heading.level.max(1)), title);
+        if let Some(name) = first {
+            out.push_str(&format!(" <{}>", label(name)));
+        }
+        out
+    }
+
+    /// Renders a bullet list as markup items, content indented under the
+    /// marker, and an ordered list as an `enum` call. With original list
+    /// markers, the list is numbered like its labels, or the labels are
+    /// written as text when they follow no single format.
+    fn render_list(&self, list: &List) -> String {
+        match list.kind {
+            ListKind::Bullet => list
+                .items
+                .iter()
+                .map(|item| {
+                    let content = self.render_item(item, None);
+                    let content = indent(&content, "  ");
+                    format!("- {}", content).trim_end().to_string()
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ListKind::Ordered => {
+                let mut args = Vec::new();
+                let numbering = match self.list_markers {
+                    ListMarkerStyle::Original => numbering(&list.items),
+                    ListMarkerStyle::Markdown | ListMarkerStyle::MarkdownWithLabel => None,
+                };
+                let labelled = match self.list_markers {
+                    ListMarkerStyle::Original => numbering.is_none(),
+                    ListMarkerStyle::Markdown => false,
+                    ListMarkerStyle::MarkdownWithLabel => true,
+                };
+                if let Some(numbering) = numbering.filter(|n| n != "\"1.\"") {
+                    args.push(format!("numbering: {}", numbering));
+                }
+                let start = list
+                    .items
+                    .first()
+                    .and_then(|item| item.number)
+                    .or(list.start)
+                    .unwrap_or(1);
+                if start != 1 {
+                    args.push(format!("start: {}", start));
+                }
+                let mut out = match args.is_empty() {
+                    true => "#enum".to_string(),
+                    false => format!("#enum({})", args.join(", ")),
+                };
+                for item in &list.items {
+                    let label = labelled
+                        .then(|| item_label(self.list_markers, list.kind, item))
+                        .flatten();
+                    let content = self.render_item(item, label);
+                    if content.contains('\n') {
+                        out.push_str(&format!("[\n  {}\n]", indent(&content, "  ")));
+                    } else {
+                        out.push_str(&format!("[{}]", content));
+                    }
+                }
+                out
+            }
+        }
+    }
+
+    /// Renders the content of an item after its check box and `label`.
+    fn render_item(&self, item: &ListItem, label: Option<&str>) -> String {
+        let mut content = self.render_blocks(&item.blocks, false);
+        if let Some(label) = label {
+            content = format!("{} {}", escape(label), content)
+                .trim_end()
+                .to_string();
+        }
+        match item.checked {
+            Some(checked) => format!("{} {}", checkbox_glyph(checked), content)
+                .trim_end()
+                .to_string(),
+            None => content,
+        }
+    }
+
+    /// Renders a `#table` call; merged cells are `table.cell`s with their
+    /// spans, placed by Typst like the cells of Word's grid. The first row is
+    /// the header unless it has cells spanning rows.
+    fn render_table(&self, table: &Table) -> String {
+        let columns = place_cells(&table.rows)
+            .iter()
+            .map(|cell| cell.column + cell.colspan)
+            .max()
+            .unwrap_or(0);
+        if columns == 0 {
+            return String::new();
+        }
+        let header = table.rows.len() > 1
+            && table
+                .rows
+                .first()
+                .is_some_and(|row| row.iter().all(|cell| cell.rowspan <= 1));
+
+        let mut lines = vec![format!("#table(\n  columns: {},", columns)];
+        for (i, row) in table.rows.iter().enumerate() {
+            let cells = row
+                .iter()
+                .map(|cell| {
+                    let content = self.render_blocks(&cell.blocks, false);
+                    let mut spans = Vec::new();
+                    if cell.colspan > 1 {
+                        spans.push(format!("colspan: {}", cell.colspan));
+                    }
+                    if cell.rowspan > 1 {
+                        spans.push(format!("rowspan: {}", cell.rowspan));
+                    }
+                    let call = match spans.is_empty() {
+                        true => String::new(),
+                        false => format!("table.cell({})", spans.join(", ")),
+                    };
+                    // Items of a list starting the cell only line up when
+                    // the first one starts a line too
+                    match content.starts_with("- ") && content.contains('\n') {
+                        true => format!("{}[\n    {}\n  ]", call, indent(&content, "    ")),
+                        false => format!("{}[{}]", call, indent(&content, "    ")),
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            if i == 0 && header {
+                lines.push(format!("  table.header({}),", cells));
+            } else {
+                lines.push(format!("  {},", cells));
+            }
+        }
+        lines.push(")".to_string());
+        lines.join("\n")
+    }
+
+    fn render_inlines(&self, nodes: &[InlineNode]) -> String {
+        let mut pieces = Vec::new();
+        self.collect_pieces(nodes, &mut pieces);
+        join_pieces(pieces)
+    }
+
+    /// Collects rendered inline content as pieces of text and function
+    /// calls.
+    fn collect_pieces(&self, nodes: &[InlineNode], pieces: &mut Vec<Piece>) {
+        for node in nodes {
+            match node {
+                InlineNode::Text { text, style } => push_text(pieces, text, style),
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    let text = self.render_inlines(content);
+                    let text = text.trim();
+                    let target = match destination.strip_prefix('#') {
+                        Some(name) if self.labels.contains(name) => format!("<{}>", label(name)),
+                        // Nothing to point to
+                        Some(_) => {
+                            self.collect_pieces(content, pieces);
+                            continue;
+                        }
+                        None => string(destination),
+                    };
+                    let text = match (text, destination.strip_prefix('#')) {
+                        ("", Some(name)) => escape(name),
+                        (text, _) => text.to_string(),
+                    };
+                    pieces.push(Piece::Call(match text.as_str() {
+                        "" => format!("#link({})", target),
+                        text => format!("#link({})[{}]", target, text),
+                    }));
+                }
+                InlineNode::Anchor(name) => pieces.push(Piece::Call(anchor(name))),
+                InlineNode::Math { tex, .. } => {
+                    pieces.push(Piece::Call(format!("#mi({})", string(tex.trim()))))
+                }
+                InlineNode::Image { source, alt, width } => {
+                    if source.starts_with("data:") {
+                        // Typst cannot read embedded images
+                        pieces.push(Piece::Text(format!("\\[{}\\]", escape(alt))));
+                    } else {
+                        let image = image(source, alt, *width);
+                        pieces.push(Piece::Call(format!("#box({})", image)));
+                    }
+                }
+                InlineNode::NoteReference { kind, id } => {
+                    if let Some(text) = self.refs.note_text(*kind, id) {
+                        let text = text.split('\n').map(escape).collect::<Vec<_>>();
+                        pieces.push(Piece::Call(format!(
+                            "#footnote[{}]",
+                            text.join(" \\ ").trim()
+                        )));
+                    }
+                }
+                InlineNode::PageBreak => pieces.push(Piece::Call("#pagebreak()".to_string())),
+                InlineNode::Raw(raw) => pieces.push(Piece::Text(raw.clone())),
+                InlineNode::CheckBox { checked } => {
+                    pieces.push(Piece::Text(checkbox_glyph(*checked).to_string()))
+                }
+                InlineNode::ContentControl { content, .. } => self.collect_pieces(content, pieces),
+            }
+        }
+    }
+}
+
+/// Rendered inline content. Function calls followed by text that would
+/// continue them (`(..)`, `.field`) are ended with `;` by [`join_pieces`].
+enum Piece {
+    Text(String),
+    Call(String),
+}
+
+fn join_pieces(pieces: Vec<Piece>) -> String {
+    let mut out = String::new();
+    let mut after_call = false;
+    for piece in pieces {
+        let (text, call) = match piece {
+            Piece::Text(text) => (text, false),
+            Piece::Call(call) => (call, true),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        let mut chars = text.chars();
+        let continues = match chars.next() {
+            Some('(' | '[') => true,
+            Some('.') => chars.next().is_some_and(|c| c.is_alphabetic() || c == '_'),
+            _ => false,
+        };
+        if after_call && continues {
+            out.push(';');
+        }
+        out.push_str(&text);
+        after_call = call;
+    }
+    out
+}
+
+/// Adds formatted text line by line, line breaks as `\`. Each formatting
+/// wraps the text in a function call, inside the surrounding whitespace.
+fn push_text(pieces: &mut Vec<Piece>, text: &str, style: &TextStyle) {
+    let text = if style.all_caps {
+        text.to_uppercase()
+    } else {
+        text.to_string()
+    };
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            pieces.push(Piece::Text(" \\ ".to_string()));
+        }
+        let core = line.trim();
+        if core.is_empty() {
+            pieces.push(Piece::Text(line.to_string()));
+            continue;
+        }
+        let start = line.len() - line.trim_start().len();
+        pieces.push(Piece::Text(line[..start].to_string()));
+        let mut span = escape(core);
+        let functions = [
+            (style.superscript, "super"),
+            (style.subscript && !style.superscript, "sub"),
+            (style.small_caps && !style.all_caps, "smallcaps"),
+            (style.highlight, "highlight"),
+            (style.strike || style.deletion, "strike"),
+            (style.underline || style.insertion, "underline"),
+            (style.italic, "emph"),
+            (style.bold, "strong"),
+        ];
+        let mut formatted = false;
+        for (applies, function) in functions {
+            if applies {
+                span = format!("#{}[{}]", function, span);
+                formatted = true;
+            }
+        }
+        pieces.push(match formatted {
+            true => Piece::Call(span),
+            false => Piece::Text(span),
+        });
+        pieces.push(Piece::Text(line[start + core.len()..].to_string()));
+    }
+}
+
+/// Numbering of an ordered list reproducing its labels: a pattern such as
+/// `"(a)"`, or a function when the text around the number holds counting
+/// symbols (`"Article 1."`). `None` when the labels follow no single format.
+fn numbering(items: &[ListItem]) -> Option<String> {
+    let numbered = items
+        .iter()
+        .map(|item| Some((item.label.trim(), item.number?)))
+        .collect::<Option<Vec<_>>>()?;
+    let &(first_label, first_number) = numbered.first()?;
+    for (format, symbol) in COUNTING {
+        let number = format_num(format, first_number);
+        let Some(position) = first_label.rfind(&number) else {
+            continue;
+        };
+        let prefix = &first_label[..position];
+        let suffix = &first_label[position + number.len()..];
+        let matches = numbered.iter().all(|&(label, number)| {
+            label == format!("{}{}{}", prefix, format_num(format, number), suffix)
+        });
+        if !matches {
+            continue;
+        }
+        if format!("{}{}", prefix, suffix).contains(COUNTING_SYMBOLS) {
+            let end = if suffix.starts_with(['(', '.', '[']) {
+                ";"
+            } else {
+                ""
+            };
+            return Some(format!(
+                "n => [{}#numbering(\"{}\", n){}{}]",
+                escape(prefix),
+                symbol,
+                end,
+                escape(suffix)
+            ));
+        }
+        return Some(string(&format!("{}{}{}", prefix, symbol, suffix)));
+    }
+    None
+}
+
+/// `image(..)` call with the alternative text and the width in points.
+fn image(source: &str, alt: &str, width: Option<u32>) -> String {
+    let mut args = vec![string(source)];
+    if let Some(width) = width {
+        // Pixels at 96 dpi
+        args.push(format!("width: {}pt", f64::from(width) * 0.75));
+    }
+    if !alt.is_empty() {
+        args.push(format!("alt: {}", string(alt)));
+    }
+    format!("image({})", args.join(", "))
+}
+
+/// Invisible element labelled with a bookmark name, for links to point to.
+fn anchor(name: &str) -> String {
+    format!("#metadata(none)<{}>", label(name))
+}
+
+/// Label for a bookmark name, without characters that labels cannot hold.
+fn label(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() => c,
+            '-' | '_' | ':' | '.' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Typst string literal.
+fn string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Escapes characters of literal text that could be read as markup.
+fn escape(text: &str) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let next = chars.get(i + 1).copied();
+        let escaped = match c {
+            '\\' | '*' | '_' | '`' | '#' | '$' | '<' | '>' | '@' | '[' | ']' | '~' => true,
+            // En dash (`--`), soft hyphen (`-?`) and comments (`//`, `/*`)
+            '-' => matches!(next, Some('-' | '?')),
+            '/' => matches!(next, Some('/' | '*')),
+            _ => false,
+        };
+        if escaped {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escapes the start of paragraph text that would start a heading, list
+/// item or term.
+fn escape_block_start(text: &str) -> String {
+    if text.starts_with(['=', '-', '+', '/']) {
+        return format!("\\{}", text);
+    }
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let after = &text[digits..];
+    if digits > 0
+        && after.starts_with('.')
+        && after[1..].chars().next().map_or(true, char::is_whitespace)
+    {
+        return format!("{}\\{}", &text[..digits], after);
+    }
+    text.to_string()
+}
+
+/// Indents the non-empty lines of `text` after the first.
+fn indent(text: &str, prefix: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| match i {
+            0 => line.to_string(),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{}{}", prefix, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn has_math(blocks: &[BlockNode]) -> bool {
+    fn inline(nodes: &[InlineNode]) -> bool {
+        nodes.iter().any(|node| match node {
+            InlineNode::Math { .. } => true,
+            InlineNode::Link { content, .. } | InlineNode::ContentControl { content, .. } => {
+                inline(content)
+            }
+            _ => false,
+        })
+    }
+    blocks.iter().any(|block| match block {
+        BlockNode::Paragraph(paragraph) => inline(&paragraph.content),
+        BlockNode::Heading(heading) => inline(&heading.content),
+        BlockNode::Table(table) => table
+            .rows
+            .iter()
+            .flatten()
+            .any(|cell| has_math(&cell.blocks)),
+        BlockNode::List(list) => list.items.iter().any(|item| has_math(&item.blocks)),
+        BlockNode::ContentControl { blocks, .. } => has_math(blocks),
+        BlockNode::TableOfContents(_) | BlockNode::RawHtml(_) => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ast::{NoteKind, TableCell};
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.to_string(),
+            style: TextStyle::default(),
+        }
+    }
+
+    fn paragraph(content: Vec<InlineNode>) -> BlockNode {
+        BlockNode::Paragraph(Paragraph {
+            content,
+            ..Default::default()
+        })
+    }
+
+    fn item(label: &str, number: i32, content: &str) -> ListItem {
+        ListItem {
+            label: label.to_string(),
+            separator: "\t".to_string(),
+            number: Some(number),
+            checked: None,
+            blocks: vec![paragraph(vec![text(content)])],
+        }
+    }
+
+    fn ordered(items: Vec<ListItem>) -> BlockNode {
+        BlockNode::List(List {
+            kind: ListKind::Ordered,
+            start: items.first().and_then(|item| item.number),
+            level: 0,
+            items,
+        })
+    }
+
+    fn render(blocks: Vec<BlockNode>) -> String {
+        render_with(&ConvertOptions::default(), blocks)
+    }
+
+    fn render_with(options: &ConvertOptions, blocks: Vec<BlockNode>) -> String {
+        let document = DocumentAst {
+            blocks,
+            references: ReferenceDefinitions {
+                footnotes: vec!["See *this*.".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        TypstRenderer::new(options)
+            .render(&document)
+            .expect("render should work")
+    }
+
+    #[test]
+    fn test_typst_headings_links_notes_and_escaping() {
+        let bold = InlineNode::Text {
+            text: "x_1".to_string(),
+            style: TextStyle {
+                bold: true,
+                ..Default::default()
+            },
+        };
+        let rendered = render(vec![
+            BlockNode::Heading(Heading {
+                level: 2,
+                label: String::new(),
+                content: vec![text("Costs")],
+                id: None,
+                anchors: vec!["_Toc1".to_string(), "costs".to_string()],
+            }),
+            paragraph(vec![
+                text("- #1 costs $5 @home "),
+                bold,
+                text("(approx)"),
+                InlineNode::NoteReference {
+                    kind: NoteKind::Footnote,
+                    id: "1".to_string(),
+                },
+                text(", see "),
+                InlineNode::Link {
+                    destination: "#_Toc1".to_string(),
+                    content: vec![text("costs")],
+                },
+            ]),
+            paragraph(vec![InlineNode::Image {
+                source: "media/chart.png".to_string(),
+                alt: "Chart".to_string(),
+                width: Some(200),
+            }]),
+        ]);
+        assert_eq!(
+            rendered,
+            "== Costs#metadata(none)<costs> <_Toc1>\n\n\
+             \\- \\#1 costs \\$5 \\@home #strong[x\\_1];(approx)#footnote[See \\*this\\*.], \
+             see #link(<_Toc1>)[costs]\n\n\
+             #image(\"media/chart.png\", width: 150pt, alt: \"Chart\")\n"
+        );
+    }
+
+    #[test]
+    fn test_typst_links_only_to_defined_labels() {
+        let link = |name: &str, content: &str| InlineNode::Link {
+            destination: format!("#{}", name),
+            content: vec![text(content)],
+        };
+        let rendered = render(vec![
+            BlockNode::Heading(Heading {
+                level: 1,
+                label: String::new(),
+                content: vec![text("Costs")],
+                id: None,
+                anchors: Vec::new(),
+            }),
+            BlockNode::List(List {
+                kind: ListKind::Bullet,
+                start: None,
+                level: 0,
+                items: vec![ListItem {
+                    label: "•".to_string(),
+                    separator: "\t".to_string(),
+                    number: None,
+                    checked: None,
+                    blocks: vec![BlockNode::Heading(Heading {
+                        level: 2,
+                        label: String::new(),
+                        content: vec![text("Nested")],
+                        id: None,
+                        anchors: vec!["_Toc2".to_string()],
+                    })],
+                }],
+            }),
+            paragraph(vec![
+                link("costs", "costs"),
+                text(", "),
+                link("_Toc2", "nested"),
+                text(", "),
+                link("_Toc9", "gone"),
+            ]),
+        ]);
+        assert_eq!(
+            rendered,
+            "= Costs <costs>\n\n\
+             - #metadata(none)<_Toc2>#strong[Nested]\n\n\
+             #link(<costs>)[costs], #link(<_Toc2>)[nested], gone\n"
+        );
+    }
+
+    #[test]
+    fn test_typst_enum_numbering_from_labels() {
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        };
+        let rendered = render_with(
+            &options,
+            vec![
+                ordered(vec![item("(c)", 3, "Third"), item("(d)", 4, "Fourth")]),
+                ordered(vec![
+                    item("Article 1.", 1, "One"),
+                    item("Article 2.", 2, "Two"),
+                ]),
+                ordered(vec![item("제1조", 1, "하나")]),
+                ordered(vec![item("(a)", 1, "First"), item("B.", 2, "Second")]),
+            ],
+        );
+        assert_eq!(
+            rendered,
+            "#enum(numbering: \"(a)\", start: 3)[Third][Fourth]\n\n\
+             #enum(numbering: n => [Article #numbering(\"1\", n);.])[One][Two]\n\n\
+             #enum(numbering: \"제1조\")[하나]\n\n\
+             #enum[(a) First][B. Second]\n"
+        );
+    }
+
+    #[test]
+    fn test_typst_enum_numbering_follows_list_markers() {
+        let lists = || {
+            vec![ordered(vec![
+                item("(c)", 3, "Third"),
+                item("(d)", 4, "Fourth"),
+            ])]
+        };
+        assert_eq!(render(lists()), "#enum(start: 3)[Third][Fourth]\n");
+
+        let options = ConvertOptions {
+            list_markers: ListMarkerStyle::MarkdownWithLabel,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_with(&options, lists()),
+            "#enum(start: 3)[(c) Third][(d) Fourth]\n"
+        );
+    }
+
+    #[test]
+    fn test_typst_table_with_spans() {
+        let cell = |content: &str, colspan, rowspan| TableCell {
+            blocks: vec![paragraph(vec![text(content)])],
+            colspan,
+            rowspan,
+        };
+        let table = BlockNode::Table(Table {
+            rows: vec![
+                vec![cell("A", 1, 2), cell("B", 2, 1)],
+                vec![cell("C", 1, 1), cell("D", 1, 1)],
+            ],
+        });
+        assert_eq!(
+            render(vec![table]),
+            "#table(\n  columns: 3,\n  table.cell(rowspan: 2)[A], table.cell(colspan: 2)[B],\n  \
+             [C], [D],\n)\n"
+        );
+    }
+}
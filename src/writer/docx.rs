@@ -0,0 +1,1817 @@
+//! Lays out a document AST as a DOCX package with `rs_docx`.
+//!
+//! Styles are looked up by name in the reference document when one is
+//! given, and only the missing ones are added from built-in definitions.
+//! Every list gets its own numbering instance, so restarted and nested lists
+//! keep the numbers they had in Markdown.
+
+use super::markdown::{ordered_counter, parse_inline};
+use super::WriteOptions;
+use crate::adapters::docx::{replace_part, RawParts};
+use crate::core::ast::{
+    Alignment, BlockNode, DocumentAst, Heading, InlineNode, List, ListKind, NoteKind, Paragraph,
+    ReferenceDefinitions, Table, TextStyle,
+};
+use crate::core::slug::SlugGenerator;
+use crate::render::{escape_html_attr, place_cells};
+use crate::{Error, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rs_docx::content_type::{DefaultContentType, OverrideContentType};
+use rs_docx::document::{
+    AbstractNum, AbstractNumId, AnnotationRef, AvList, Blip, BlipFill, BodyContent, BookmarkEnd,
+    BookmarkStart, Break, BreakType, CNvPicPr, CNvPr, Comment, CommentReference, Comments,
+    ContinuationSeparator, DelText, Deletion, DocPr, Drawing, EndNote, EndNotes, EndnoteRef,
+    EndnoteReference, Extent, FillRect, FootNote, FootNotes, FootnoteRef, FootnoteReference,
+    Graphic, GraphicData, Hyperlink, Inline, Insertion, Level, LevelJustification, LevelOverride,
+    LevelStart, LevelText, MultiLevelType, NoteSeparator, Num, NumFmt, Numbering, NvPicPr, Offset,
+    PPr, ParagraphContent, Picture, PrstGeom, Run, RunContent, SDTContent, SDTProperty, STDId,
+    Separator, SpPr, StartOverride, Stretch, Tab, TableCell as DocxTableCell, TableCellContent,
+    TableGrid, TableRow, Text, TextSpace, Xfrm, SDT,
+};
+use rs_docx::formatting::{
+    BorderStyle, BottomBorder, CharacterProperty, ContextualSpacing, Fonts, GridSpan, Highlight,
+    HighlightType, Indent, InsideHorizonBorder, InsideVerticalBorder, Justification,
+    JustificationVal, KeepNext, LeftBorder, NumberingProperty, OutlineLvl, PageMargin, PageSize,
+    ParagraphProperty, RightBorder, SectionProperty, Spacing, TableBorders, TableProperty,
+    TableWidth, TableWidthUnit, TopBorder, Underline, UnderlineStyle, VMerge, VMergeType,
+    VertAlign, VertAlignType,
+};
+use rs_docx::media::MediaType;
+use rs_docx::rels::{Relationship, TargetMode};
+use rs_docx::styles::{
+    BasedOn, DefaultCharacterProperty, DefaultParagraphProperty, DefaultStyle, Next, Style,
+    StyleType, Styles,
+};
+use rs_docx::{Docx, DocxFile};
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+const REL_IMAGE: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/image";
+const REL_HYPERLINK: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/hyperlink";
+const REL_THEME: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships/theme";
+const NS_RELATIONSHIPS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+const NS_DRAWING: &str = "http://schemas.openxmlformats.org/drawingml/2006/main";
+const NS_PICTURE: &str = "http://schemas.openxmlformats.org/drawingml/2006/picture";
+const CONTENT_TYPE_PREFIX: &str = "application/vnd.openxmlformats-officedocument.";
+const THEME_PART: &str = "word/theme/theme1.xml";
+const DOCUMENT_PART: &str = "word/document.xml";
+
+/// EMUs per pixel at 96 dpi, and per twentieth of a point.
+const EMU_PER_PIXEL: u64 = 9525;
+const EMU_PER_TWIP: u64 = 635;
+
+/// Writes `document` as a DOCX package.
+pub(crate) fn write_docx(document: &DocumentAst, options: &WriteOptions) -> Result<Vec<u8>> {
+    let reference_file = match &options.reference_docx {
+        Some(path) => {
+            Some(DocxFile::from_file(path).map_err(|e| Error::DocxParse(format!("{:?}", e)))?)
+        }
+        None => None,
+    };
+    let reference = match &reference_file {
+        Some(file) => Some(
+            file.parse()
+                .map_err(|e| Error::DocxParse(format!("{:?}", e)))?,
+        ),
+        None => None,
+    };
+    let theme = match &options.reference_docx {
+        Some(path) => read_theme(path)?,
+        None => None,
+    };
+
+    let section = reference
+        .as_ref()
+        .and_then(|docx| {
+            docx.document
+                .body
+                .content
+                .iter()
+                .rev()
+                .find_map(|content| match content {
+                    BodyContent::SectionProperty(section) => Some(section.clone()),
+                    _ => None,
+                })
+        })
+        .map(|mut section| {
+            // Headers and footers are not copied
+            section.header_footer_references.clear();
+            section
+        })
+        .unwrap_or_else(default_section);
+
+    let styles = match &reference {
+        Some(docx) => docx.styles.clone(),
+        None => default_styles(),
+    };
+    let mut writer = Writer::new(styles, document, options, text_width(&section));
+    let mut body = writer.blocks(&document.blocks);
+    body.push(BodyContent::SectionProperty(section));
+    let parts = writer.finish();
+
+    let mut docx = Docx::default();
+    docx.document.body.content = body;
+    docx.styles = parts.styles;
+    docx.numbering = parts.numbering;
+    docx.footnotes = parts.footnotes;
+    docx.endnotes = parts.endnotes;
+    docx.comments = parts.comments;
+
+    let (rels, note_rels): (Vec<_>, Vec<_>) = parts
+        .relationships
+        .into_iter()
+        .partition(|(part, _)| *part == Part::Document);
+    let mut rels: Vec<_> = rels.into_iter().map(|(_, rel)| rel).collect();
+    for part in [Part::Footnotes, Part::Endnotes, Part::Comments] {
+        let part_rels: Vec<_> = note_rels
+            .iter()
+            .filter(|(rel_part, _)| *rel_part == part)
+            .map(|(_, rel)| rel)
+            .collect();
+        if !part_rels.is_empty() {
+            docx.custom_xml.insert(
+                part.relationships_name().to_string(),
+                Cow::Owned(relationships_xml(&part_rels).into_bytes()),
+            );
+        }
+    }
+    for (name, bytes) in &parts.media {
+        docx.media.insert(name.clone(), (MediaType::Image, bytes));
+    }
+    if let Some(theme) = &theme {
+        rels.push(relationship(
+            "rIdTheme1",
+            "theme/theme1.xml",
+            REL_THEME,
+            false,
+        ));
+        docx.custom_xml
+            .insert(THEME_PART.to_string(), Cow::Borrowed(theme.as_slice()));
+    }
+    docx.document_rels
+        .get_or_insert_with(Default::default)
+        .relationships = rels;
+    set_content_types(&mut docx, &parts.media, theme.is_some());
+
+    let cursor = docx
+        .write(Cursor::new(Vec::new()))
+        .map_err(|e| Error::Conversion(format!("{:?}", e)))?;
+    mark_checkboxes(cursor.into_inner(), &parts.checkboxes)
+}
+
+/// Makes the content controls written for check boxes `w14:checkbox`
+/// controls, which `rs_docx` cannot write. The control with id `n` has the
+/// state `checkboxes[n - 1]`.
+fn mark_checkboxes(package: Vec<u8>, checkboxes: &[bool]) -> Result<Vec<u8>> {
+    if checkboxes.is_empty() {
+        return Ok(package);
+    }
+    let mut document = RawParts::from_reader(Cursor::new(&package))?
+        .document
+        .unwrap_or_default();
+    for (index, checked) in checkboxes.iter().enumerate() {
+        let id = format!(r#"<w:sdtPr><w:id w:val="{}"/>"#, index + 1);
+        let checkbox = format!(
+            r#"{}<w14:checkbox><w14:checked w14:val="{}"/></w14:checkbox>"#,
+            id,
+            u8::from(*checked)
+        );
+        document = document.replacen(&id, &checkbox, 1);
+    }
+    replace_part(Cursor::new(package), DOCUMENT_PART, document.as_bytes())
+}
+
+/// Relationships item of a part `rs_docx` writes no relationships for.
+fn relationships_xml(relationships: &[&Relationship<'_>]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="{}">"#,
+        NS_RELATIONSHIPS
+    );
+    for rel in relationships {
+        xml.push_str(&format!(
+            r#"<Relationship Id="{}" Type="{}" Target="{}"{}/>"#,
+            escape_html_attr(&rel.id),
+            escape_html_attr(&rel.ty),
+            escape_html_attr(&rel.target),
+            if rel.target_mode.is_some() {
+                r#" TargetMode="External""#
+            } else {
+                ""
+            }
+        ));
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+/// Reads the theme part of a reference document, which `rs_docx` does not
+/// write back faithfully.
+fn read_theme(path: &Path) -> Result<Option<Vec<u8>>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+    let Ok(mut entry) = archive.by_name(THEME_PART) else {
+        return Ok(None);
+    };
+    let mut theme = Vec::new();
+    entry.read_to_end(&mut theme)?;
+    Ok(Some(theme))
+}
+
+/// Declares the content types of the parts written besides the document
+/// and styles.
+fn set_content_types(docx: &mut Docx<'_>, media: &[(String, Vec<u8>)], theme: bool) {
+    let types = &mut docx.content_types;
+    // The package properties are not written
+    types
+        .overrides
+        .retain(|part| !part.part.starts_with("/docProps/"));
+    let mut extensions: Vec<&str> = media
+        .iter()
+        .filter_map(|(name, _)| name.rsplit_once('.').map(|(_, ext)| ext))
+        .collect();
+    extensions.sort_unstable();
+    extensions.dedup();
+    for ext in extensions {
+        types.defaults.push(DefaultContentType {
+            ext: ext.to_string().into(),
+            ty: format!("image/{}", ext).into(),
+        });
+    }
+    let mut parts = Vec::new();
+    if docx.numbering.is_some() {
+        parts.push(("/word/numbering.xml", "wordprocessingml.numbering+xml"));
+    }
+    if docx.footnotes.is_some() {
+        parts.push(("/word/footnotes.xml", "wordprocessingml.footnotes+xml"));
+    }
+    if docx.endnotes.is_some() {
+        parts.push(("/word/endnotes.xml", "wordprocessingml.endnotes+xml"));
+    }
+    if docx.comments.is_some() {
+        parts.push(("/word/comments.xml", "wordprocessingml.comments+xml"));
+    }
+    if theme {
+        parts.push(("/word/theme/theme1.xml", "theme+xml"));
+    }
+    for (part, ty) in parts {
+        types.overrides.push(OverrideContentType {
+            part: part.into(),
+            ty: format!("{}{}", CONTENT_TYPE_PREFIX, ty).into(),
+        });
+    }
+}
+
+fn relationship(id: &str, target: &str, ty: &'static str, external: bool) -> Relationship<'static> {
+    Relationship {
+        id: id.to_string().into(),
+        target: target.to_string().into(),
+        ty: ty.into(),
+        target_mode: external.then_some(TargetMode::External),
+    }
+}
+
+/// A4 portrait with one-inch margins.
+fn default_section<'a>() -> SectionProperty<'a> {
+    SectionProperty {
+        page_size: Some(PageSize {
+            weight: 11906,
+            height: 16838,
+        }),
+        page_margin: Some(PageMargin {
+            top: Some(1440),
+            right: Some(1440),
+            bottom: Some(1440),
+            left: Some(1440),
+            header: Some(720),
+            footer: Some(720),
+            gutter: Some(0),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Width between the page margins, in twentieths of a point.
+fn text_width(section: &SectionProperty<'_>) -> u64 {
+    let page = section.page_size.as_ref().map_or(11906, |size| size.weight);
+    let margins = section.page_margin.as_ref().map_or(2880, |margin| {
+        margin.left.unwrap_or(0) + margin.right.unwrap_or(0)
+    });
+    (page - margins).max(1440) as u64
+}
+
+/// Parts whose content can refer to relationships.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Document,
+    Footnotes,
+    Endnotes,
+    Comments,
+}
+
+impl Part {
+    fn relationships_name(self) -> &'static str {
+        match self {
+            Self::Document => "word/_rels/document.xml.rels",
+            Self::Footnotes => "word/_rels/footnotes.xml.rels",
+            Self::Endnotes => "word/_rels/endnotes.xml.rels",
+            Self::Comments => "word/_rels/comments.xml.rels",
+        }
+    }
+}
+
+/// Parts written next to the document body.
+struct Parts<'r> {
+    styles: Styles<'r>,
+    numbering: Option<Numbering<'static>>,
+    footnotes: Option<FootNotes<'static>>,
+    endnotes: Option<EndNotes<'static>>,
+    comments: Option<Comments<'static>>,
+    relationships: Vec<(Part, Relationship<'static>)>,
+    media: Vec<(String, Vec<u8>)>,
+    /// States of the check boxes in the document.
+    checkboxes: Vec<bool>,
+}
+
+struct Writer<'a, 'r> {
+    styles: Styles<'r>,
+    references: &'a ReferenceDefinitions,
+    options: &'a WriteOptions,
+    /// Width between the page margins, in twentieths of a point.
+    text_width: u64,
+    abstract_nums: Vec<AbstractNum<'static>>,
+    nums: Vec<Num<'static>>,
+    /// Part the content being written goes to.
+    part: Part,
+    relationships: Vec<(Part, Relationship<'static>)>,
+    media: Vec<(String, Vec<u8>)>,
+    /// Ids of the referenced comments, in order of first reference.
+    comments: Vec<String>,
+    checkboxes: Vec<bool>,
+    /// Heading slugs, bookmarked when a `#slug` link targets them and no
+    /// bookmark has the name.
+    slugs: SlugGenerator,
+    anchors: Anchors,
+    bookmarks: usize,
+    revisions: usize,
+}
+
+impl<'a, 'r> Writer<'a, 'r> {
+    fn new(
+        styles: Styles<'r>,
+        document: &'a DocumentAst,
+        options: &'a WriteOptions,
+        text_width: u64,
+    ) -> Self {
+        let references = &document.references;
+        let mut anchors = Anchors::default();
+        anchors.blocks(&document.blocks);
+        let notes = references.footnotes.iter().chain(&references.endnotes);
+        for text in notes.chain(references.comments.iter().map(|(_, text)| text)) {
+            anchors.inlines(&parse_inline(text));
+        }
+        Self {
+            styles,
+            references,
+            options,
+            text_width,
+            abstract_nums: Vec::new(),
+            nums: Vec::new(),
+            part: Part::Document,
+            relationships: Vec::new(),
+            media: Vec::new(),
+            comments: Vec::new(),
+            checkboxes: Vec::new(),
+            slugs: SlugGenerator::default(),
+            anchors,
+            bookmarks: 0,
+            revisions: 0,
+        }
+    }
+
+    fn finish(mut self) -> Parts<'r> {
+        self.part = Part::Footnotes;
+        let footnotes = (!self.references.footnotes.is_empty()).then(|| {
+            let mut notes = vec![
+                separator_footnote(-1, NoteSeparator::Separator),
+                separator_footnote(0, NoteSeparator::ContinuationSeparator),
+            ];
+            for (i, text) in self.references.footnotes.iter().enumerate() {
+                let content = self.note_content(NoteKind::Footnote, text);
+                notes.push(FootNote {
+                    ty: None,
+                    id: Some(i as isize + 1),
+                    content,
+                });
+            }
+            FootNotes { content: notes }
+        });
+        self.part = Part::Endnotes;
+        let endnotes = (!self.references.endnotes.is_empty()).then(|| {
+            let mut notes = vec![
+                separator_endnote(-1, NoteSeparator::Separator),
+                separator_endnote(0, NoteSeparator::ContinuationSeparator),
+            ];
+            for (i, text) in self.references.endnotes.iter().enumerate() {
+                let content = self.note_content(NoteKind::Endnote, text);
+                notes.push(EndNote {
+                    ty: None,
+                    id: Some(i as isize + 1),
+                    content,
+                });
+            }
+            EndNotes { content: notes }
+        });
+        self.part = Part::Comments;
+        let comments = (!self.comments.is_empty()).then(|| {
+            let ids = std::mem::take(&mut self.comments);
+            let comments = ids
+                .iter()
+                .map(|id| {
+                    let text = self.references.note_text(NoteKind::Comment, id);
+                    Comment {
+                        id: id.parse().ok(),
+                        author: "".into(),
+                        content: self.comment_paragraph(text.unwrap_or_default()),
+                    }
+                })
+                .collect();
+            Comments { comments }
+        });
+
+        let numbering = (!self.nums.is_empty()).then(|| Numbering {
+            abstract_numberings: std::mem::take(&mut self.abstract_nums),
+            numberings: std::mem::take(&mut self.nums),
+        });
+        Parts {
+            styles: self.styles,
+            numbering,
+            footnotes,
+            endnotes,
+            comments,
+            relationships: self.relationships,
+            media: self.media,
+            checkboxes: self.checkboxes,
+        }
+    }
+
+    /// Returns the id of the style named `name` (or with id `id`), adding
+    /// the built-in definition when the styles lack it.
+    fn style(&mut self, id: &str, name: &str) -> Option<String> {
+        let found = self.styles.styles.iter().find(|style| {
+            style.style_id == id
+                || style
+                    .name
+                    .as_ref()
+                    .is_some_and(|style_name| style_name.value.eq_ignore_ascii_case(name))
+        });
+        if let Some(style) = found {
+            return Some(style.style_id.to_string());
+        }
+        let style = builtin_style(id)?;
+        if let Some(base) = &style.base {
+            let base = base.value.to_string();
+            if base != id {
+                self.style(&base, &base);
+            }
+        }
+        self.styles.styles.push(style);
+        Some(id.to_string())
+    }
+
+    fn paragraph_property(&mut self, id: &str, name: &str) -> ParagraphProperty<'static> {
+        ParagraphProperty {
+            style_id: self.style(id, name).map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    fn blocks(&mut self, blocks: &[BlockNode]) -> Vec<BodyContent<'static>> {
+        let mut out = Vec::new();
+        for block in blocks {
+            self.block(block, &mut out);
+        }
+        out
+    }
+
+    fn block(&mut self, block: &BlockNode, out: &mut Vec<BodyContent<'static>>) {
+        match block {
+            BlockNode::Paragraph(paragraph) => {
+                let paragraph = self.paragraph(paragraph);
+                out.push(paragraph.into());
+            }
+            BlockNode::Heading(heading) => {
+                let heading = self.heading(heading);
+                out.push(heading.into());
+            }
+            BlockNode::TableOfContents(entries) => {
+                for entry in entries {
+                    let mut property = ParagraphProperty::default();
+                    let level = entry.level.clamp(1, 9);
+                    property.indent = Some(Indent {
+                        left: Some(240 * (level as isize - 1)),
+                        ..Default::default()
+                    });
+                    let link = InlineNode::Link {
+                        destination: entry.destination.clone(),
+                        content: vec![InlineNode::Text {
+                            text: entry.text.clone(),
+                            style: TextStyle::default(),
+                        }],
+                    };
+                    let content = self.inlines(std::slice::from_ref(&link), None);
+                    out.push(docx_paragraph(Some(property), content).into());
+                }
+            }
+            BlockNode::Table(table) => {
+                let table = self.table(table);
+                out.push(table.into());
+            }
+            // Markup of another format has no DOCX counterpart
+            BlockNode::RawHtml(_) => {}
+            BlockNode::List(list) => self.list(list, out),
+            BlockNode::ContentControl { blocks, .. } => {
+                for block in blocks {
+                    self.block(block, out);
+                }
+            }
+        }
+    }
+
+    fn paragraph(&mut self, paragraph: &Paragraph) -> rs_docx::document::Paragraph<'static> {
+        let mut property = None;
+        if let Some(style) = &paragraph.style {
+            let style_id = self.style(style, style);
+            property = style_id.map(|id| ParagraphProperty {
+                style_id: Some(id.into()),
+                ..Default::default()
+            });
+        }
+        if let Some(alignment) = paragraph.alignment {
+            let value = match alignment {
+                Alignment::Left => JustificationVal::Left,
+                Alignment::Center => JustificationVal::Center,
+                Alignment::Right => JustificationVal::Right,
+                Alignment::Justify => JustificationVal::Both,
+            };
+            property.get_or_insert_with(Default::default).justification =
+                Some(Justification { value });
+        }
+        let content = self.inlines(&paragraph.content, None);
+        docx_paragraph(property, content)
+    }
+
+    fn heading(&mut self, heading: &Heading) -> rs_docx::document::Paragraph<'static> {
+        let level = heading.level.clamp(1, 6);
+        let property =
+            self.paragraph_property(&format!("Heading{}", level), &format!("heading {}", level));
+        let mut content = Vec::new();
+        let mut names: Vec<&str> = heading
+            .anchors
+            .iter()
+            .chain(&heading.id)
+            .map(String::as_str)
+            .collect();
+        let slug = self.slugs.slug(&heading.plain_text());
+        if self.anchors.targets.contains(&slug) && !self.anchors.defined.contains(&slug) {
+            names.push(&slug);
+        }
+        for name in names {
+            self.bookmark(name, &mut content);
+        }
+        if !heading.label.is_empty() {
+            let label = format!("{} ", heading.label);
+            content.push(text_run(&label, None).into());
+        }
+        content.extend(self.inlines(&heading.content, None));
+        docx_paragraph(Some(property), content)
+    }
+
+    fn bookmark(&mut self, name: &str, out: &mut Vec<ParagraphContent<'static>>) {
+        let id = self.bookmarks.to_string();
+        self.bookmarks += 1;
+        out.push(
+            BookmarkStart {
+                id: Some(id.clone().into()),
+                name: Some(name.to_string().into()),
+            }
+            .into(),
+        );
+        out.push(
+            BookmarkEnd {
+                id: Some(id.into()),
+            }
+            .into(),
+        );
+    }
+
+    /// Writes the items of `list` as numbered paragraphs; item paragraphs
+    /// after the first are indented to the item text.
+    fn list(&mut self, list: &List, out: &mut Vec<BodyContent<'static>>) {
+        let level = list.level.min(8);
+        let num_id = self.numbering(list, level);
+        let indent = 720 * (level as isize + 1);
+        for item in &list.items {
+            let mut blocks = item.blocks.iter().peekable();
+            let mut property = self.paragraph_property("ListParagraph", "List Paragraph");
+            property.numbering = Some(NumberingProperty::from((num_id, level as isize)));
+            let mut content = Vec::new();
+            if let Some(checked) = item.checked {
+                content.push(self.checkbox(checked));
+                content.push(text_run(" ", None).into());
+            }
+            if let Some(BlockNode::Paragraph(paragraph)) = blocks.peek() {
+                content.extend(self.inlines(&paragraph.content, None));
+                blocks.next();
+            }
+            out.push(docx_paragraph(Some(property), content).into());
+
+            for block in blocks {
+                match block {
+                    BlockNode::Paragraph(paragraph) => {
+                        let mut paragraph = self.paragraph(paragraph);
+                        let property = paragraph.property.get_or_insert_with(|| {
+                            self.paragraph_property("ListParagraph", "List Paragraph")
+                        });
+                        property.indent = Some(Indent {
+                            left: Some(indent),
+                            ..Default::default()
+                        });
+                        out.push(paragraph.into());
+                    }
+                    block => self.block(block, out),
+                }
+            }
+        }
+    }
+
+    /// Returns the numbering instance of `list`. Every list gets its own
+    /// abstract numbering so that its counters start over, as readers keep
+    /// counting across instances sharing an abstract numbering.
+    fn numbering(&mut self, list: &List, level: usize) -> isize {
+        let label = list
+            .items
+            .first()
+            .map_or("", |item| item.label.as_str())
+            .trim();
+        let (format, text) = level_format(list.kind, label, level);
+        let abstract_index = self.abstract_nums.len() as isize;
+        let levels = (0..9)
+            .map(|ilvl| {
+                let (format, text) = if ilvl == level {
+                    (format.clone(), text.clone())
+                } else {
+                    default_level_format(list.kind, ilvl)
+                };
+                numbering_level(ilvl, format, text)
+            })
+            .collect();
+        self.abstract_nums.push(AbstractNum {
+            abstract_num_id: Some(abstract_index),
+            nsid: None,
+            multi_level_type: Some(MultiLevelType {
+                value: "hybridMultilevel".into(),
+            }),
+            levels,
+        });
+
+        let num_id = self.nums.len() as isize + 1;
+        let start = list
+            .start
+            .or_else(|| list.items.first().and_then(|item| item.number))
+            .filter(|_| list.kind == ListKind::Ordered);
+        self.nums.push(Num {
+            num_id: Some(num_id),
+            abstract_num_id: Some(AbstractNumId {
+                value: Some(abstract_index),
+            }),
+            level_overrides: start
+                .map(|start| LevelOverride {
+                    i_level: Some(level as isize),
+                    start_override: Some(StartOverride {
+                        value: Some(start as isize),
+                    }),
+                    level: None,
+                })
+                .into_iter()
+                .collect(),
+        });
+        num_id
+    }
+
+    fn table(&mut self, table: &Table) -> rs_docx::document::Table<'static> {
+        let mut placed = place_cells(&table.rows);
+        placed.sort_by_key(|cell| (cell.row, cell.column));
+        let columns = placed
+            .iter()
+            .map(|cell| cell.column + cell.colspan)
+            .max()
+            .unwrap_or(1);
+        let column_width = (self.text_width / columns as u64) as isize;
+
+        // Cells by row, with the continuations of row spans
+        let mut grid: Vec<Vec<(usize, DocxTableCell<'static>)>> =
+            vec![Vec::new(); table.rows.len()];
+        for cell in &placed {
+            let width = column_width * cell.colspan as isize;
+            let mut blocks = match cell.cell {
+                Some(cell) => self.cell_content(&cell.blocks),
+                None => Vec::new(),
+            };
+            if blocks.is_empty() {
+                blocks.push(TableCellContent::Paragraph(Default::default()));
+            }
+            let mut docx_cell = table_cell(width, cell.colspan, blocks);
+            if cell.rowspan > 1 {
+                docx_cell.property.v_merge = Some(VMerge {
+                    val: Some(VMergeType::Restart),
+                });
+                for row in grid
+                    .iter_mut()
+                    .take(cell.row + cell.rowspan)
+                    .skip(cell.row + 1)
+                {
+                    let mut continuation = table_cell(
+                        width,
+                        cell.colspan,
+                        vec![TableCellContent::Paragraph(Default::default())],
+                    );
+                    continuation.property.v_merge = Some(VMerge {
+                        val: Some(VMergeType::Continue),
+                    });
+                    row.push((cell.column, continuation));
+                }
+            }
+            grid[cell.row].push((cell.column, docx_cell));
+        }
+
+        let mut docx_table = rs_docx::document::Table {
+            property: TableProperty {
+                style_id: self.style("TableGrid", "Table Grid").map(Into::into),
+                width: Some(TableWidth::from((
+                    self.text_width as isize,
+                    TableWidthUnit::Dxa,
+                ))),
+                ..Default::default()
+            },
+            grids: TableGrid::from(vec![column_width; columns]),
+            rows: Vec::new(),
+        };
+        for mut cells in grid {
+            cells.sort_by_key(|(column, _)| *column);
+            let row = cells
+                .into_iter()
+                .fold(TableRow::default(), |row, (_, cell)| row.push_cell(cell));
+            docx_table.rows.push(row);
+        }
+        docx_table
+    }
+
+    fn cell_content(&mut self, blocks: &[BlockNode]) -> Vec<TableCellContent<'static>> {
+        self.blocks(blocks)
+            .into_iter()
+            .filter_map(|content| match content {
+                BodyContent::Paragraph(paragraph) => Some(TableCellContent::Paragraph(paragraph)),
+                BodyContent::Table(table) => Some(TableCellContent::Table(table)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Paragraphs of a footnote or endnote, starting with its mark.
+    fn note_content(&mut self, kind: NoteKind, text: &str) -> Vec<BodyContent<'static>> {
+        let (text_style, reference_style, mark): (_, _, RunContent<'static>) = match kind {
+            NoteKind::Endnote => (
+                ("EndnoteText", "endnote text"),
+                ("EndnoteReference", "endnote reference"),
+                EndnoteRef.into(),
+            ),
+            _ => (
+                ("FootnoteText", "footnote text"),
+                ("FootnoteReference", "footnote reference"),
+                FootnoteRef.into(),
+            ),
+        };
+        let property = self.paragraph_property(text_style.0, text_style.1);
+        let mark_style = self.style(reference_style.0, reference_style.1);
+        let mut content: Vec<ParagraphContent<'static>> = vec![
+            run(character_style(mark_style)).push(mark).into(),
+            text_run(" ", None).into(),
+        ];
+        content.extend(self.inlines(&parse_inline(text), None));
+        vec![docx_paragraph(Some(property), content).into()]
+    }
+
+    fn comment_paragraph(&mut self, text: &str) -> rs_docx::document::Paragraph<'static> {
+        let property = self.paragraph_property("CommentText", "annotation text");
+        let mark_style = self.style("CommentReference", "annotation reference");
+        let mut content: Vec<ParagraphContent<'static>> =
+            vec![run(character_style(mark_style)).push(AnnotationRef).into()];
+        content.extend(self.inlines(&parse_inline(text), None));
+        docx_paragraph(Some(property), content)
+    }
+
+    /// Writes inline nodes as paragraph content; `character_style` is the
+    /// character style of the runs, e.g. inside links.
+    fn inlines(
+        &mut self,
+        nodes: &[InlineNode],
+        character_style: Option<&str>,
+    ) -> Vec<ParagraphContent<'static>> {
+        let mut out = Vec::new();
+        for node in nodes {
+            self.inline(node, character_style, &mut out);
+        }
+        out
+    }
+
+    fn inline(
+        &mut self,
+        node: &InlineNode,
+        style_id: Option<&str>,
+        out: &mut Vec<ParagraphContent<'static>>,
+    ) {
+        match node {
+            InlineNode::Text { text, style } => {
+                let mut property = run_property(style);
+                let style_id = match style.code {
+                    true => self.style("VerbatimChar", "Verbatim Char"),
+                    false => style_id.map(str::to_string),
+                };
+                if let Some(style_id) = style_id {
+                    property.get_or_insert_with(Default::default).style_id = Some(style_id.into());
+                }
+                let mut run = run(property);
+                run.content = if style.deletion {
+                    vec![DelText::from((text.clone(), TextSpace::Preserve)).into()]
+                } else {
+                    text_content(text)
+                };
+                self.revisions += usize::from(style.insertion || style.deletion);
+                let id = Some(self.revisions.to_string().into());
+                if style.deletion {
+                    out.push(
+                        Deletion {
+                            id,
+                            author: Some("".into()),
+                            date: None,
+                            runs: vec![run],
+                        }
+                        .into(),
+                    );
+                } else if style.insertion {
+                    out.push(
+                        Insertion {
+                            id,
+                            author: Some("".into()),
+                            date: None,
+                            runs: vec![run],
+                        }
+                        .into(),
+                    );
+                } else {
+                    out.push(run.into());
+                }
+            }
+            InlineNode::Link {
+                destination,
+                content,
+            } => {
+                let link_style = self.style("Hyperlink", "Hyperlink");
+                let runs = self
+                    .inlines(content, link_style.as_deref().or(style_id))
+                    .into_iter()
+                    .filter_map(|content| match content {
+                        ParagraphContent::Run(run) => Some(run),
+                        _ => None,
+                    })
+                    .collect();
+                let mut hyperlink = Hyperlink {
+                    content: runs,
+                    ..Default::default()
+                };
+                match destination.strip_prefix('#') {
+                    Some(anchor) => hyperlink.anchor = Some(anchor.to_string().into()),
+                    None => hyperlink.id = Some(self.hyperlink_relationship(destination).into()),
+                }
+                out.push(hyperlink.into());
+            }
+            InlineNode::Anchor(name) => self.bookmark(name, out),
+            InlineNode::Math { tex, .. } => {
+                out.push(text_run(tex, character_style(style_id.map(str::to_string))).into())
+            }
+            InlineNode::Image { source, alt, width } => match self.image(source, alt, *width) {
+                Some(drawing) => out.push(run(None).push(drawing).into()),
+                None if !alt.is_empty() => out.push(text_run(alt, None).into()),
+                None => {}
+            },
+            InlineNode::NoteReference { kind, id } => {
+                let (style_id, style_name) = match kind {
+                    NoteKind::Footnote => ("FootnoteReference", "footnote reference"),
+                    NoteKind::Endnote => ("EndnoteReference", "endnote reference"),
+                    NoteKind::Comment => ("CommentReference", "annotation reference"),
+                };
+                let property = character_style(self.style(style_id, style_name));
+                let reference: RunContent<'static> = match kind {
+                    NoteKind::Footnote => FootnoteReference {
+                        supress_reference_mark: None,
+                        id: Some(id.clone().into()),
+                    }
+                    .into(),
+                    NoteKind::Endnote => EndnoteReference {
+                        supress_reference_mark: None,
+                        id: Some(id.clone().into()),
+                    }
+                    .into(),
+                    NoteKind::Comment => {
+                        if !self.comments.contains(id) {
+                            self.comments.push(id.clone());
+                        }
+                        CommentReference {
+                            id: Some(id.clone().into()),
+                        }
+                        .into()
+                    }
+                };
+                out.push(run(property).push(reference).into());
+            }
+            InlineNode::PageBreak => out.push(
+                run(None)
+                    .push_break(Break {
+                        ty: Some(BreakType::Page),
+                    })
+                    .into(),
+            ),
+            InlineNode::Raw(text) => out.push(text_run(text, None).into()),
+            InlineNode::CheckBox { checked } => out.push(self.checkbox(*checked)),
+            InlineNode::ContentControl { content, .. } => {
+                for node in content {
+                    self.inline(node, style_id, out);
+                }
+            }
+        }
+    }
+
+    /// Returns the id of the relationship to an external link target.
+    fn hyperlink_relationship(&mut self, target: &str) -> String {
+        if let Some((_, existing)) = self.relationships.iter().find(|(part, rel)| {
+            *part == self.part && rel.ty == REL_HYPERLINK && rel.target == target
+        }) {
+            return existing.id.to_string();
+        }
+        let id = format!("rIdLink{}", self.relationships.len() + 1);
+        self.relationships
+            .push((self.part, relationship(&id, target, REL_HYPERLINK, true)));
+        id
+    }
+
+    /// Writes a check box as a content control, marked as a `w14:checkbox`
+    /// once the package is written. Notes get the glyph only.
+    fn checkbox(&mut self, checked: bool) -> ParagraphContent<'static> {
+        let glyph = text_run(if checked { "☒" } else { "☐" }, None);
+        if self.part != Part::Document {
+            return glyph.into();
+        }
+        self.checkboxes.push(checked);
+        SDT {
+            property: Some(SDTProperty {
+                id: Some(STDId {
+                    id: Some(self.checkboxes.len() as isize),
+                }),
+                doc_part_obj: None,
+            }),
+            end_property: None,
+            content: Some(SDTContent {
+                content: vec![glyph.into()],
+            }),
+        }
+        .into()
+    }
+
+    /// Embeds an image from a `data:` URI or a file, sized from its header
+    /// or `width` and kept within the text width. Returns `None` when the
+    /// image cannot be read.
+    fn image(&mut self, source: &str, alt: &str, width: Option<u32>) -> Option<Drawing<'static>> {
+        let bytes = match source.strip_prefix("data:") {
+            Some(data) => {
+                let (header, payload) = data.split_once(',')?;
+                if !header.ends_with(";base64") {
+                    return None;
+                }
+                BASE64.decode(payload.trim()).ok()?
+            }
+            None if source.contains("://") => return None,
+            None => {
+                let path = match &self.options.resource_dir {
+                    Some(dir) => dir.join(source),
+                    None => source.into(),
+                };
+                std::fs::read(path).ok()?
+            }
+        };
+        let (ext, pixel_width, pixel_height) = image_size(&bytes)?;
+        let (mut cx, mut cy) = match width {
+            Some(width) if pixel_width > 0 => (
+                width as u64 * EMU_PER_PIXEL,
+                width as u64 * pixel_height * EMU_PER_PIXEL / pixel_width,
+            ),
+            _ => (pixel_width * EMU_PER_PIXEL, pixel_height * EMU_PER_PIXEL),
+        };
+        let max_width = self.text_width * EMU_PER_TWIP;
+        if cx > max_width {
+            cy = cy * max_width / cx;
+            cx = max_width;
+        }
+
+        let number = self.media.len() + 1;
+        let id = format!("rIdImage{}", number);
+        let name = format!("media/image{}.{}", number, ext);
+        self.relationships
+            .push((self.part, relationship(&id, &name, REL_IMAGE, false)));
+        self.media.push((name, bytes));
+
+        let (cx, cy) = (cx as isize, cy as isize);
+        let picture_name: Cow<'static, str> = format!("Picture {}", number).into();
+        Some(Drawing {
+            anchor: None,
+            inline: Some(Inline {
+                dist_t: Some(0),
+                dist_b: Some(0),
+                dist_l: Some(0),
+                dist_r: Some(0),
+                extent: Some(Extent { cx, cy }),
+                doc_property: DocPr {
+                    id: Some(number as isize),
+                    name: Some(picture_name.clone()),
+                    descr: Some(alt.to_string().into()),
+                },
+                graphic: Some(Graphic {
+                    a: NS_DRAWING.into(),
+                    data: GraphicData {
+                        uri: NS_PICTURE.into(),
+                        children: vec![Picture {
+                            a: NS_PICTURE.into(),
+                            nv_pic_pr: NvPicPr {
+                                c_nv_pr: Some(CNvPr {
+                                    id: Some(0),
+                                    name: Some(picture_name),
+                                    descr: None,
+                                }),
+                                c_nv_pic_pr: Some(CNvPicPr {}),
+                            },
+                            fill: BlipFill {
+                                blip: Blip {
+                                    embed: id.into(),
+                                    cstate: None,
+                                },
+                                stretch: Some(Stretch {
+                                    fill_rect: Some(FillRect {}),
+                                }),
+                            },
+                            sp_pr: SpPr {
+                                xfrm: Some(Xfrm {
+                                    offset: Some(Offset {
+                                        x: Some(0),
+                                        y: Some(0),
+                                    }),
+                                    ext: Some(rs_docx::document::Ext {
+                                        cx: Some(cx),
+                                        cy: Some(cy),
+                                    }),
+                                }),
+                                prst_geom: Some(PrstGeom {
+                                    prst: Some("rect".into()),
+                                    av_lst: Some(AvList {}),
+                                }),
+                            },
+                        }],
+                    },
+                }),
+                ..Default::default()
+            }),
+        })
+    }
+}
+
+/// In-document link targets (`#name`) and bookmark names of a document.
+#[derive(Default)]
+struct Anchors {
+    targets: HashSet<String>,
+    defined: HashSet<String>,
+}
+
+impl Anchors {
+    fn blocks(&mut self, blocks: &[BlockNode]) {
+        for block in blocks {
+            match block {
+                BlockNode::Paragraph(paragraph) => self.inlines(&paragraph.content),
+                BlockNode::Heading(heading) => {
+                    let names = heading.anchors.iter().chain(&heading.id);
+                    self.defined.extend(names.cloned());
+                    self.inlines(&heading.content);
+                }
+                BlockNode::TableOfContents(entries) => self.targets.extend(
+                    entries
+                        .iter()
+                        .filter_map(|entry| entry.destination.strip_prefix('#'))
+                        .map(str::to_string),
+                ),
+                BlockNode::Table(table) => {
+                    for cell in table.rows.iter().flatten() {
+                        self.blocks(&cell.blocks);
+                    }
+                }
+                BlockNode::List(list) => {
+                    for item in &list.items {
+                        self.blocks(&item.blocks);
+                    }
+                }
+                BlockNode::ContentControl { blocks, .. } => self.blocks(blocks),
+                BlockNode::RawHtml(_) => {}
+            }
+        }
+    }
+
+    fn inlines(&mut self, nodes: &[InlineNode]) {
+        for node in nodes {
+            match node {
+                InlineNode::Link {
+                    destination,
+                    content,
+                } => {
+                    if let Some(name) = destination.strip_prefix('#') {
+                        self.targets.insert(name.to_string());
+                    }
+                    self.inlines(content);
+                }
+                InlineNode::Anchor(name) => {
+                    self.defined.insert(name.clone());
+                }
+                InlineNode::ContentControl { content, .. } => self.inlines(content),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn docx_paragraph(
+    property: Option<ParagraphProperty<'static>>,
+    content: Vec<ParagraphContent<'static>>,
+) -> rs_docx::document::Paragraph<'static> {
+    rs_docx::document::Paragraph {
+        property,
+        content,
+        ..Default::default()
+    }
+}
+
+fn run(property: Option<CharacterProperty<'static>>) -> Run<'static> {
+    Run {
+        property,
+        ..Default::default()
+    }
+}
+
+fn text_run(text: &str, property: Option<CharacterProperty<'static>>) -> Run<'static> {
+    let mut run = run(property);
+    run.content = text_content(text);
+    run
+}
+
+fn character_style(style_id: Option<String>) -> Option<CharacterProperty<'static>> {
+    style_id.map(|id| CharacterProperty {
+        style_id: Some(id.into()),
+        ..Default::default()
+    })
+}
+
+/// Run content of text, with line breaks and tabs.
+fn text_content(text: &str) -> Vec<RunContent<'static>> {
+    let mut content = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if !matches!(c, '\n' | '\t') {
+            current.push(c);
+            continue;
+        }
+        if !current.is_empty() {
+            content.push(Text::from((std::mem::take(&mut current), TextSpace::Preserve)).into());
+        }
+        content.push(match c {
+            '\n' => Break { ty: None }.into(),
+            _ => Tab.into(),
+        });
+    }
+    if !current.is_empty() {
+        content.push(Text::from((current, TextSpace::Preserve)).into());
+    }
+    content
+}
+
+/// Direct run formatting of a text style.
+fn run_property(style: &TextStyle) -> Option<CharacterProperty<'static>> {
+    let mut property = CharacterProperty::default();
+    let mut set = false;
+    let mut flag = |on: bool| {
+        set |= on;
+        on
+    };
+    if flag(style.bold) {
+        property.bold = Some(true.into());
+    }
+    if flag(style.italic) {
+        property.italics = Some(true.into());
+    }
+    if flag(style.underline) {
+        property.underline = Some(Underline::from(UnderlineStyle::Single));
+    }
+    if flag(style.strike) {
+        property.strike = Some(true.into());
+    }
+    if flag(style.small_caps) {
+        property.small_caps = Some(true.into());
+    }
+    if flag(style.all_caps) {
+        property.caps = Some(true.into());
+    }
+    if flag(style.highlight) {
+        property.highlight = Some(Highlight::from(HighlightType::Yellow));
+    }
+    if flag(style.superscript || style.subscript) {
+        property.vertical_align = Some(VertAlign {
+            value: Some(if style.superscript {
+                VertAlignType::Superscript
+            } else {
+                VertAlignType::Subscript
+            }),
+        });
+    }
+    let color = style
+        .color
+        .as_deref()
+        .map(|color| color.trim_start_matches('#'))
+        .filter(|color| color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit()));
+    if let Some(color) = color {
+        property.color = Some(color.to_ascii_uppercase().into());
+        set = true;
+    }
+    set.then_some(property)
+}
+
+fn table_cell(
+    width: isize,
+    colspan: usize,
+    content: Vec<TableCellContent<'static>>,
+) -> DocxTableCell<'static> {
+    let mut cell = DocxTableCell {
+        content,
+        ..Default::default()
+    };
+    cell.property.wide = Some(rs_docx::formatting::TableCellWidth {
+        value: Some(width),
+        unit: Some(TableWidthUnit::Dxa),
+    });
+    if colspan > 1 {
+        cell.property.grid_span = Some(GridSpan {
+            val: colspan as isize,
+        });
+    }
+    cell
+}
+
+/// Number format and level text of a list level from the label of its
+/// first item, e.g. `(a)` gives `lowerLetter` and `(%1)`.
+fn level_format(kind: ListKind, label: &str, level: usize) -> (String, String) {
+    if kind == ListKind::Bullet {
+        return match label {
+            "" | "-" | "*" | "+" => default_level_format(kind, level),
+            glyph => ("bullet".to_string(), glyph.to_string()),
+        };
+    }
+    let Some(counter) = ordered_counter(label) else {
+        return default_level_format(kind, level);
+    };
+    let (open, parents) = match counter.prefix.strip_prefix('(') {
+        Some(parents) => ("(", parents),
+        None => ("", counter.prefix.as_str()),
+    };
+    // Parent numbers of multi-level labels refer to the levels above
+    let depth = parents.split('.').filter(|part| !part.is_empty()).count();
+    let parents = if depth > 0 && depth <= level {
+        (level - depth..level)
+            .map(|ilvl| format!("%{}.", ilvl + 1))
+            .collect()
+    } else {
+        parents.to_string()
+    };
+    (
+        counter.format.to_string(),
+        format!("{}{}%{}{}", open, parents, level + 1, counter.suffix),
+    )
+}
+
+fn default_level_format(kind: ListKind, level: usize) -> (String, String) {
+    match kind {
+        ListKind::Bullet => {
+            let glyph = ["•", "◦", "▪"][level % 3];
+            ("bullet".to_string(), glyph.to_string())
+        }
+        ListKind::Ordered => {
+            let format = ["decimal", "lowerLetter", "lowerRoman"][level % 3];
+            (format.to_string(), format!("%{}.", level + 1))
+        }
+    }
+}
+
+fn numbering_level(ilvl: usize, format: String, text: String) -> Level<'static> {
+    Level {
+        i_level: Some(ilvl as isize),
+        start: Some(LevelStart { value: Some(1) }),
+        number_format: Some(NumFmt {
+            value: format.into(),
+        }),
+        level_text: Some(LevelText {
+            value: Some(text.into()),
+        }),
+        justification: Some(LevelJustification {
+            value: JustificationVal::Left,
+        }),
+        p_pr: Some(PPr {
+            indent: Some(Indent {
+                left: Some(720 * (ilvl as isize + 1)),
+                hanging: Some(360),
+                ..Default::default()
+            }),
+        }),
+        r_pr: Vec::new(),
+    }
+}
+
+fn separator_footnote(id: isize, ty: NoteSeparator) -> FootNote<'static> {
+    FootNote {
+        content: vec![separator_paragraph(&ty).into()],
+        ty: Some(ty),
+        id: Some(id),
+    }
+}
+
+fn separator_endnote(id: isize, ty: NoteSeparator) -> EndNote<'static> {
+    EndNote {
+        content: vec![separator_paragraph(&ty).into()],
+        ty: Some(ty),
+        id: Some(id),
+    }
+}
+
+fn separator_paragraph(ty: &NoteSeparator) -> rs_docx::document::Paragraph<'static> {
+    let separator: RunContent<'static> = match ty {
+        NoteSeparator::Separator => Separator {}.into(),
+        _ => ContinuationSeparator {}.into(),
+    };
+    let property = ParagraphProperty {
+        spacing: Some(Spacing {
+            after: Some(0),
+            line: Some(240),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    docx_paragraph(Some(property), vec![run(None).push(separator).into()])
+}
+
+/// File extension and pixel size of a PNG, JPEG, GIF or BMP image.
+fn image_size(bytes: &[u8]) -> Option<(&'static str, u64, u64)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u64);
+    let le16 = |at: usize| Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?) as u64);
+    let be32 = |at: usize| Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?) as u64);
+    let le32 = |at: usize| {
+        Some(i32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?).unsigned_abs() as u64)
+    };
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(("png", be32(16)?, be32(20)?));
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some(("gif", le16(6)?, le16(8)?));
+    }
+    if bytes.starts_with(b"BM") {
+        return Some(("bmp", le32(18)?, le32(22)?));
+    }
+    if bytes.starts_with(&[0xFF, 0xD8]) {
+        // Frame header of the first start-of-frame segment
+        let mut at = 2;
+        while at + 4 <= bytes.len() {
+            if bytes[at] != 0xFF {
+                return None;
+            }
+            let marker = bytes[at + 1];
+            let length = be16(at + 2)? as usize;
+            if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+                return Some(("jpeg", be16(at + 7)?, be16(at + 5)?));
+            }
+            at += 2 + length;
+        }
+    }
+    None
+}
+
+/// Styles of a document without reference: document defaults and Normal.
+fn default_styles<'a>() -> Styles<'a> {
+    let mut normal = Style::new(StyleType::Paragraph, "Normal");
+    normal.name = Some("Normal".into());
+    normal.default = Some(true);
+    Styles {
+        default: Some(DefaultStyle {
+            character: DefaultCharacterProperty {
+                inner: Some(CharacterProperty {
+                    fonts: Some(
+                        Fonts::default()
+                            .ascii("Calibri")
+                            .h_ansi("Calibri")
+                            .east_asia("Malgun Gothic"),
+                    ),
+                    size: Some((22isize).into()),
+                    ..Default::default()
+                }),
+            },
+            paragraph: DefaultParagraphProperty {
+                inner: Some(ParagraphProperty {
+                    spacing: Some(Spacing {
+                        after: Some(160),
+                        line: Some(259),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+            },
+        }),
+        latent_styles: None,
+        styles: vec![normal],
+    }
+}
+
+/// Built-in definition of a style the writer uses.
+fn builtin_style(id: &str) -> Option<Style<'static>> {
+    let paragraph = |name: &str| {
+        let mut style = Style::new(StyleType::Paragraph, id.to_string());
+        style.name = Some(name.to_string().into());
+        style.base = Some(BasedOn {
+            value: "Normal".into(),
+        });
+        style
+    };
+    let character = |name: &str| {
+        let mut style = Style::new(StyleType::Character, id.to_string());
+        style.name = Some(name.to_string().into());
+        style
+    };
+    let superscript = || CharacterProperty {
+        vertical_align: Some(VertAlign {
+            value: Some(VertAlignType::Superscript),
+        }),
+        ..Default::default()
+    };
+    let small_text = |name: &str| {
+        let mut style = paragraph(name);
+        style.paragraph = Some(ParagraphProperty {
+            spacing: Some(Spacing {
+                after: Some(0),
+                line: Some(240),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        style.character = Some(CharacterProperty {
+            size: Some((20isize).into()),
+            ..Default::default()
+        });
+        style
+    };
+
+    let style = match id {
+        "Normal" => {
+            let mut style = Style::new(StyleType::Paragraph, "Normal");
+            style.name = Some("Normal".into());
+            style.default = Some(true);
+            style
+        }
+        "Heading1" | "Heading2" | "Heading3" | "Heading4" | "Heading5" | "Heading6" => {
+            let level: isize = id["Heading".len()..].parse().ok()?;
+            let mut style = paragraph(&format!("heading {}", level));
+            style.next = Some(Next {
+                value: "Normal".into(),
+            });
+            style.paragraph = Some(ParagraphProperty {
+                keep_next: Some(KeepNext { value: None }),
+                spacing: Some(Spacing {
+                    before: Some(if level == 1 { 360 } else { 160 }),
+                    after: Some(80),
+                    ..Default::default()
+                }),
+                outline_lvl: Some(OutlineLvl { value: level - 1 }),
+                ..Default::default()
+            });
+            style.character = Some(CharacterProperty {
+                color: Some("2F5496".into()),
+                size: Some(([32isize, 28, 26, 24, 22, 22])[level as usize - 1].into()),
+                ..Default::default()
+            });
+            style
+        }
+        "Hyperlink" => {
+            // Not underlined, as an underline would come back as `<u>` when
+            // the document is converted to Markdown again
+            let mut style = character("Hyperlink");
+            style.character = Some(CharacterProperty {
+                color: Some("0563C1".into()),
+                ..Default::default()
+            });
+            style
+        }
+        "FootnoteText" => small_text("footnote text"),
+        "EndnoteText" => small_text("endnote text"),
+        "CommentText" => small_text("annotation text"),
+        "FootnoteReference" | "EndnoteReference" => {
+            let name = if id == "FootnoteReference" {
+                "footnote reference"
+            } else {
+                "endnote reference"
+            };
+            let mut style = character(name);
+            style.character = Some(superscript());
+            style
+        }
+        "VerbatimChar" => {
+            let mut style = character("Verbatim Char");
+            style.character = Some(CharacterProperty {
+                fonts: Some(Fonts::default().ascii("Consolas").h_ansi("Consolas")),
+                ..Default::default()
+            });
+            style
+        }
+        "CommentReference" => {
+            let mut style = character("annotation reference");
+            style.character = Some(CharacterProperty {
+                size: Some((16isize).into()),
+                ..Default::default()
+            });
+            style
+        }
+        "ListParagraph" => {
+            let mut style = paragraph("List Paragraph");
+            style.paragraph = Some(ParagraphProperty {
+                indent: Some(Indent {
+                    left: Some(720),
+                    ..Default::default()
+                }),
+                contextual_spacing: Some(ContextualSpacing { value: None }),
+                ..Default::default()
+            });
+            style
+        }
+        "Quote" => {
+            let mut style = paragraph("Quote");
+            style.paragraph = Some(ParagraphProperty {
+                indent: Some(Indent {
+                    left: Some(864),
+                    right: Some(864),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+            style.character = Some(CharacterProperty {
+                italics: Some(true.into()),
+                ..Default::default()
+            });
+            style
+        }
+        "SourceCode" => {
+            let mut style = paragraph("Source Code");
+            style.paragraph = Some(ParagraphProperty {
+                spacing: Some(Spacing {
+                    after: Some(0),
+                    line: Some(240),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            });
+            style.character = Some(CharacterProperty {
+                fonts: Some(Fonts::default().ascii("Consolas").h_ansi("Consolas")),
+                size: Some((20isize).into()),
+                ..Default::default()
+            });
+            style
+        }
+        "TableGrid" => {
+            let mut style = Style::new(StyleType::Table, "TableGrid");
+            style.name = Some("Table Grid".into());
+            let (s, color, size) = (BorderStyle::Single, Some(Cow::Borrowed("auto")), Some(4));
+            style.table = Some(TableProperty {
+                borders: Some(TableBorders {
+                    top: Some(TopBorder {
+                        style: s.clone(),
+                        color: color.clone(),
+                        size,
+                        ..Default::default()
+                    }),
+                    left: Some(LeftBorder {
+                        style: s.clone(),
+                        color: color.clone(),
+                        size,
+                        ..Default::default()
+                    }),
+                    bottom: Some(BottomBorder {
+                        style: s.clone(),
+                        color: color.clone(),
+                        size,
+                        ..Default::default()
+                    }),
+                    right: Some(RightBorder {
+                        style: s.clone(),
+                        color: color.clone(),
+                        size,
+                        ..Default::default()
+                    }),
+                    inside_horizon: Some(InsideHorizonBorder {
+                        style: s.clone(),
+                        color: color.clone(),
+                        size,
+                        ..Default::default()
+                    }),
+                    inside_vertical: Some(InsideVerticalBorder {
+                        style: s,
+                        color,
+                        size,
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            });
+            style
+        }
+        _ => return None,
+    };
+    Some(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::parse_markdown;
+    use crate::{ConvertOptions, DocxToMarkdown, ListMarkerStyle};
+
+    const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+    /// Writes `markdown` and converts it back, keeping Word's list labels.
+    fn round_trip(markdown: &str) -> String {
+        let bytes = write_docx(&parse_markdown(markdown), &WriteOptions::default()).unwrap();
+        DocxToMarkdown::new(ConvertOptions {
+            list_markers: ListMarkerStyle::Original,
+            ..Default::default()
+        })
+        .convert_from_bytes(&bytes)
+        .unwrap()
+    }
+
+    /// Writes `markdown` and returns the package parts with the given names.
+    fn parts<const N: usize>(markdown: &str, names: [&str; N]) -> [Option<String>; N] {
+        let bytes = write_docx(&parse_markdown(markdown), &WriteOptions::default()).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        names.map(|name| {
+            let mut entry = archive.by_name(name).ok()?;
+            let mut xml = String::new();
+            entry.read_to_string(&mut xml).unwrap();
+            Some(xml)
+        })
+    }
+
+    #[test]
+    fn test_round_trip_headings_lists_and_notes() {
+        let markdown = round_trip(
+            "# Title\n\nSome <strong>bold</strong> and <em>italic</em> text[^1] with \
+             [a link](https://example.com).\n\n1. one\n\n2. two\n\n   a. nested\n\n\
+             - bullet\n\n---\n\n[^1]: The note.\n",
+        );
+        assert!(markdown.contains("# Title"), "{}", markdown);
+        assert!(markdown.contains("<strong>bold</strong>"), "{}", markdown);
+        assert!(markdown.contains("<em>italic</em>"), "{}", markdown);
+        assert!(
+            markdown.contains("[a link](https://example.com)"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("1. one"), "{}", markdown);
+        assert!(markdown.contains("2. two"), "{}", markdown);
+        assert!(markdown.contains("a. nested"), "{}", markdown);
+        assert!(markdown.contains("[^1]"), "{}", markdown);
+        assert!(markdown.contains("The note."), "{}", markdown);
+    }
+
+    #[test]
+    fn test_round_trip_spanned_table() {
+        let html = "<table>\n  <tr>\n    <td rowspan=\"2\" colspan=\"2\">TOP</td>\n    \
+                    <td>R1</td>\n  </tr>\n  <tr>\n    <td>R2</td>\n  </tr>\n  <tr>\n    \
+                    <td>a</td>\n    <td>b</td>\n    <td>c</td>\n  </tr>\n</table>";
+        let markdown = round_trip(html);
+        assert!(
+            markdown.contains("<td rowspan=\"2\" colspan=\"2\">TOP</td>"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("<td>R2</td>"), "{}", markdown);
+    }
+
+    #[test]
+    fn test_image_size_and_embedding() {
+        // 1x1 PNG
+        let png = BASE64.decode(PNG).unwrap();
+        assert_eq!(image_size(&png), Some(("png", 1, 1)));
+        let markdown = round_trip(&format!(
+            "<img src=\"data:image/png;base64,{}\" alt=\"dot\" />",
+            BASE64.encode(&png)
+        ));
+        assert!(markdown.contains("data:image/png;base64,"), "{}", markdown);
+    }
+
+    #[test]
+    fn test_note_relationships_belong_to_their_part() {
+        let [document_rels, footnotes, footnote_rels, comment_rels] = parts(
+            &format!(
+                "Text[^1] with [a link](https://a.example)[^c1].\n\n\
+                 [^1]: See [b](https://b.example) and ![dot](data:image/png;base64,{}).\n\
+                 [^c1]: See [c](https://c.example).\n",
+                PNG
+            ),
+            [
+                "word/_rels/document.xml.rels",
+                "word/footnotes.xml",
+                "word/_rels/footnotes.xml.rels",
+                "word/_rels/comments.xml.rels",
+            ],
+        );
+        let document_rels = document_rels.unwrap();
+        let footnote_rels = footnote_rels.unwrap();
+        assert!(document_rels.contains("https://a.example"));
+        assert!(!document_rels.contains("https://b.example"));
+        assert!(footnote_rels.contains(r#"Target="https://b.example" TargetMode="External""#));
+        assert!(footnote_rels.contains(r#"Id="rIdImage1""#));
+        assert!(footnotes.unwrap().contains("rIdImage1"));
+        assert!(comment_rels.unwrap().contains("https://c.example"));
+    }
+
+    #[test]
+    fn test_internal_links_target_heading_bookmarks() {
+        let [document] = parts(
+            "# Intro\n\nSee [two](#section-two).\n\n## Section Two\n\n\
+             <a id=\"kept\"></a>\n## Kept\n\n[kept](#kept)\n",
+            ["word/document.xml"],
+        );
+        let document = document.unwrap();
+        assert!(document.contains(r#"w:anchor="section-two""#));
+        assert!(document.contains(r#"w:name="section-two""#));
+        assert_eq!(document.matches(r#"w:name="kept""#).count(), 1);
+        // Headings nothing links to get no bookmark
+        assert!(!document.contains(r#"w:name="intro""#));
+    }
+
+    #[test]
+    fn test_task_items_and_code_spans() {
+        let markdown = "- [x] done\n- [ ] open\n\nUse `code` here.\n";
+        let [document, styles] = parts(markdown, ["word/document.xml", "word/styles.xml"]);
+        let document = document.unwrap();
+        assert!(document.contains(r#"<w14:checkbox><w14:checked w14:val="1"/></w14:checkbox>"#));
+        assert!(document.contains(r#"<w14:checkbox><w14:checked w14:val="0"/></w14:checkbox>"#));
+        assert!(document.contains(r#"<w:rStyle w:val="VerbatimChar"/>"#));
+        assert!(styles.unwrap().contains(r#"w:styleId="VerbatimChar""#));
+
+        let markdown = round_trip(markdown);
+        assert!(markdown.contains("[x] done"), "{}", markdown);
+        assert!(markdown.contains("[ ] open"), "{}", markdown);
+    }
+
+    #[test]
+    fn test_spanned_table_cells_are_merged() {
+        let [document] = parts(
+            "<table>\n  <tr>\n    <td colspan=\"2\">wide</td>\n    <td rowspan=\"2\">tall</td>\n  \
+             </tr>\n  <tr>\n    <td>a</td>\n    <td>b</td>\n  </tr>\n</table>",
+            ["word/document.xml"],
+        );
+        let document = document.unwrap();
+        assert!(
+            document.contains(r#"<w:gridSpan w:val="2"/>"#),
+            "{}",
+            document
+        );
+        assert!(
+            document.contains(r#"<w:vMerge w:val="restart"/>"#),
+            "{}",
+            document
+        );
+        assert!(
+            document.contains(r#"<w:vMerge w:val="continue"/>"#),
+            "{}",
+            document
+        );
+    }
+}
@@ -0,0 +1,1777 @@
+//! Markdown parser building the document AST the DOCX writer lays out.
+//!
+//! Reads what the Markdown renderer writes (HTML inline tags, `<a id>`
+//! anchors, HTML tables with merged cells, `[^n]` notes, list labels as Word
+//! shows them, `---` page breaks) as well as hand-written CommonMark. Line
+//! breaks inside a paragraph are kept, as the renderer writes a line break
+//! as a newline.
+
+use crate::converter::format_num;
+use crate::core::ast::{
+    Alignment, BlockNode, DocumentAst, Heading, InlineNode, List, ListItem, ListKind, NoteKind,
+    Paragraph, ReferenceDefinitions, Table, TableCell, TextStyle,
+};
+use std::collections::HashMap;
+
+/// Paragraph style given to fenced code blocks.
+pub(crate) const CODE_STYLE: &str = "SourceCode";
+/// Paragraph style given to block quotes.
+pub(crate) const QUOTE_STYLE: &str = "Quote";
+
+/// Bullet characters that start a list item.
+const BULLETS: &[char] = &[
+    '-', '*', '+', '•', '◦', '▪', '▫', '■', '□', '●', '○', '◆', '◇', '❖', '➢', '►', '▸', '➤', '✓',
+    '✔', '–', '·', 'o',
+];
+const GANADA: &str = "가나다라마바사아자차카타파하";
+const CHOSUNG: &str = "ㄱㄴㄷㄹㅁㅂㅅㅇㅈㅊㅋㅌㅍㅎ";
+
+/// Parses Markdown into a document AST.
+pub fn parse_markdown(markdown: &str) -> DocumentAst {
+    let markdown = markdown.replace("\r\n", "\n");
+    let mut lines: Vec<String> = markdown.split('\n').map(str::to_string).collect();
+    let mut notes = Notes {
+        definitions: take_note_definitions(&mut lines),
+        ..Default::default()
+    };
+
+    let blocks = Parser { notes: &mut notes }.parse_blocks(&lines, 0);
+    DocumentAst {
+        blocks,
+        references: notes.into_references(),
+        properties: Default::default(),
+    }
+}
+
+/// Parses inline Markdown, e.g. the text of a note.
+pub(crate) fn parse_inline(text: &str) -> Vec<InlineNode> {
+    let mut notes = Notes::default();
+    InlineParser::new(text, false, &mut notes).parse()
+}
+
+/// Removes `[^label]: text` definitions, with their indented continuation
+/// lines and the `---` rule placed before them, returning them by label.
+fn take_note_definitions(lines: &mut Vec<String>) -> HashMap<String, String> {
+    let mut definitions = HashMap::new();
+    let mut kept = Vec::with_capacity(lines.len());
+    let mut current: Option<String> = None;
+    let mut in_fence = None;
+    for line in lines.drain(..) {
+        if let Some(fence) = in_fence {
+            if closes_fence(&line, fence) {
+                in_fence = None;
+            }
+            kept.push(line);
+            continue;
+        }
+        if let Some((label, text)) = note_definition(&line) {
+            definitions.insert(label.to_string(), text.trim().to_string());
+            current = Some(label.to_string());
+            continue;
+        }
+        if let Some(label) = &current {
+            if line.starts_with("    ") || line.starts_with('\t') {
+                let text = definitions.entry(label.clone()).or_default();
+                text.push('\n');
+                text.push_str(line.trim());
+                continue;
+            }
+            current = None;
+        }
+        in_fence = fence_start(line.trim_start()).map(|(fence, _)| fence);
+        kept.push(line);
+    }
+    if !definitions.is_empty() {
+        while kept.last().is_some_and(|line| line.trim().is_empty()) {
+            kept.pop();
+        }
+        if kept.last().is_some_and(|line| line.trim() == "---") {
+            kept.pop();
+        }
+    }
+    *lines = kept;
+    definitions
+}
+
+/// Splits a `[^label]: text` line.
+fn note_definition(line: &str) -> Option<(&str, &str)> {
+    let rest = line.strip_prefix("[^")?;
+    let (label, text) = rest.split_once("]:")?;
+    is_note_label(label).then_some((label, text))
+}
+
+fn is_note_label(label: &str) -> bool {
+    !label.is_empty()
+        && !label
+            .chars()
+            .any(|c| c.is_whitespace() || c == '[' || c == ']')
+}
+
+/// Note definitions and the references made to them.
+#[derive(Default)]
+struct Notes {
+    definitions: HashMap<String, String>,
+    /// Kind and AST id of each referenced label.
+    ids: HashMap<String, (NoteKind, String)>,
+    /// Labels of footnotes and endnotes, in order of first reference.
+    footnotes: Vec<String>,
+    endnotes: Vec<String>,
+    /// Ids and labels of comments, in order of first reference.
+    comments: Vec<(String, String)>,
+}
+
+impl Notes {
+    /// Returns the reference to the note with `label`: `enN` labels are
+    /// endnotes, `cN` labels comments and all others footnotes, numbered in
+    /// order of first reference. Labels without a definition are not notes.
+    fn reference(&mut self, label: &str) -> Option<InlineNode> {
+        if let Some((kind, id)) = self.ids.get(label) {
+            return Some(InlineNode::NoteReference {
+                kind: *kind,
+                id: id.clone(),
+            });
+        }
+        if !self.definitions.contains_key(label) {
+            return None;
+        }
+        let digits = |prefix: &str| {
+            label
+                .strip_prefix(prefix)
+                .filter(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+        };
+        let (kind, id) = if digits("en").is_some() {
+            self.endnotes.push(label.to_string());
+            (NoteKind::Endnote, self.endnotes.len().to_string())
+        } else if let Some(id) = digits("c") {
+            self.comments.push((id.to_string(), label.to_string()));
+            (NoteKind::Comment, id.to_string())
+        } else {
+            self.footnotes.push(label.to_string());
+            (NoteKind::Footnote, self.footnotes.len().to_string())
+        };
+        self.ids.insert(label.to_string(), (kind, id.clone()));
+        Some(InlineNode::NoteReference { kind, id })
+    }
+
+    /// Texts of the referenced notes.
+    fn into_references(mut self) -> ReferenceDefinitions {
+        let mut text = |label: &String| self.definitions.remove(label).unwrap_or_default();
+        ReferenceDefinitions {
+            footnotes: self.footnotes.iter().map(&mut text).collect(),
+            endnotes: self.endnotes.iter().map(&mut text).collect(),
+            comments: self
+                .comments
+                .iter()
+                .map(|(id, label)| (id.clone(), text(label)))
+                .collect(),
+        }
+    }
+}
+
+/// List item marker at the start of a line.
+#[derive(Debug, Clone)]
+struct Marker {
+    /// Columns before the marker.
+    indent: usize,
+    label: String,
+    /// Whitespace between the label and the content.
+    separator: String,
+    kind: ListKind,
+    /// Counter value, for ordered items.
+    number: Option<i32>,
+    /// Items with the same key belong to the same list: the bullet, or the
+    /// label with its counter replaced by its class.
+    key: String,
+}
+
+impl Marker {
+    /// Column where the item content starts.
+    fn content_column(&self) -> usize {
+        self.indent + self.label.chars().count() + self.separator.chars().count()
+    }
+}
+
+struct Parser<'n> {
+    notes: &'n mut Notes,
+}
+
+impl Parser<'_> {
+    /// Parses lines into blocks; lists found at the left margin are at
+    /// nesting depth `level`.
+    fn parse_blocks(&mut self, lines: &[String], level: usize) -> Vec<BlockNode> {
+        let mut blocks = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            let line = &lines[i];
+            let text = line.trim_start();
+            if text.is_empty() {
+                i += 1;
+                continue;
+            }
+
+            if let Some((fence, _)) = fence_start(text) {
+                let indent = indentation(line);
+                let mut code = Vec::new();
+                i += 1;
+                while i < lines.len() && !closes_fence(&lines[i], fence) {
+                    code.push(strip_columns(&lines[i], indent));
+                    i += 1;
+                }
+                i += 1;
+                let code = code.join("\n");
+                blocks.push(BlockNode::Paragraph(Paragraph {
+                    content: vec![InlineNode::Text {
+                        text: code.clone(),
+                        style: TextStyle::default(),
+                    }],
+                    style: Some(CODE_STYLE.to_string()),
+                    alignment: None,
+                }));
+            } else if let Some(heading) = self.parse_heading(text) {
+                blocks.push(BlockNode::Heading(heading));
+                i += 1;
+            } else if is_thematic_break(text) {
+                blocks.push(BlockNode::Paragraph(Paragraph {
+                    content: vec![InlineNode::PageBreak],
+                    ..Default::default()
+                }));
+                i += 1;
+            } else if starts_html_table(text) {
+                let (html, next) = collect_html_table(lines, i);
+                blocks.push(BlockNode::Table(self.parse_html_table(&html)));
+                i = next;
+            } else if is_pipe_table_start(lines, i) {
+                let start = i;
+                while i < lines.len() && lines[i].trim_start().starts_with('|') {
+                    i += 1;
+                }
+                blocks.push(BlockNode::Table(self.parse_pipe_table(&lines[start..i])));
+            } else if text.starts_with('>') {
+                let mut quoted = Vec::new();
+                while i < lines.len() && !lines[i].trim().is_empty() {
+                    let text = lines[i].trim_start();
+                    let text = match text.strip_prefix('>') {
+                        Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+                        None => text,
+                    };
+                    quoted.push(text.to_string());
+                    i += 1;
+                }
+                for mut block in self.parse_blocks(&quoted, level) {
+                    if let BlockNode::Paragraph(paragraph) = &mut block {
+                        paragraph
+                            .style
+                            .get_or_insert_with(|| QUOTE_STYLE.to_string());
+                    }
+                    blocks.push(block);
+                }
+            } else if text.starts_with("<!--") {
+                while i < lines.len() && !lines[i].contains("-->") {
+                    i += 1;
+                }
+                i += 1;
+            } else if let Some(marker) = parse_marker(line) {
+                let (list, next) = self.parse_list(lines, i, marker, level);
+                blocks.push(BlockNode::List(list));
+                i = next;
+            } else {
+                let start = i;
+                i += 1;
+                while i < lines.len() && !interrupts_paragraph(&lines[i]) {
+                    i += 1;
+                }
+                let paragraph = BlockNode::Paragraph(self.parse_paragraph(&lines[start..i]));
+                // The renderer writes heading bookmarks on the line before
+                // the heading.
+                let heading = match lines.get(i) {
+                    Some(next) if paragraph.is_bookmark_only() => {
+                        self.parse_heading(next.trim_start())
+                    }
+                    _ => None,
+                };
+                match (paragraph, heading) {
+                    (BlockNode::Paragraph(paragraph), Some(mut heading)) => {
+                        heading.anchors = paragraph
+                            .content
+                            .into_iter()
+                            .filter_map(|node| match node {
+                                InlineNode::Anchor(name) => Some(name),
+                                _ => None,
+                            })
+                            .collect();
+                        blocks.push(BlockNode::Heading(heading));
+                        i += 1;
+                    }
+                    (paragraph, _) => blocks.push(paragraph),
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Parses an ATX heading with an optional `{#id}` attribute.
+    fn parse_heading(&mut self, text: &str) -> Option<Heading> {
+        let level = text.chars().take_while(|&c| c == '#').count();
+        if !(1..=6).contains(&level) {
+            return None;
+        }
+        let rest = &text[level..];
+        if !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+            return None;
+        }
+        let mut rest = rest.trim();
+        // Closing sequence of `#`s
+        let without_hashes = rest.trim_end_matches('#');
+        if without_hashes.is_empty() || without_hashes.ends_with([' ', '\t']) {
+            rest = without_hashes.trim_end();
+        }
+        let mut id = None;
+        if let Some(open) = rest.rfind("{#") {
+            if rest.ends_with('}') {
+                let attributes = &rest[open + 1..rest.len() - 1];
+                id = attributes
+                    .split_whitespace()
+                    .find_map(|attribute| attribute.strip_prefix('#'))
+                    .map(str::to_string);
+                rest = rest[..open].trim_end();
+            }
+        }
+        Some(Heading {
+            level,
+            label: String::new(),
+            content: InlineParser::new(rest, false, self.notes).parse(),
+            id,
+            anchors: Vec::new(),
+        })
+    }
+
+    /// Parses the lines of a paragraph; each line break is kept, along with
+    /// the indentation of continuation lines.
+    fn parse_paragraph(&mut self, lines: &[String]) -> Paragraph {
+        let lines: Vec<&str> = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let line = if index == 0 {
+                    line.trim()
+                } else {
+                    line.trim_end()
+                };
+                line.strip_suffix('\\')
+                    .filter(|rest| !rest.ends_with('\\'))
+                    .unwrap_or(line)
+            })
+            .collect();
+        let markdown = lines.join("\n");
+        Paragraph {
+            content: InlineParser::new(&markdown, false, self.notes).parse(),
+            style: None,
+            alignment: alignment(&markdown),
+        }
+    }
+
+    /// Parses the list starting at `lines[start]`, returning it with the
+    /// index of the line after it.
+    fn parse_list(
+        &mut self,
+        lines: &[String],
+        start: usize,
+        first: Marker,
+        level: usize,
+    ) -> (List, usize) {
+        let mut list = List {
+            kind: first.kind,
+            start: first.number,
+            level: level + first.indent / 2,
+            items: Vec::new(),
+        };
+        let mut i = start;
+        let mut marker = first.clone();
+        loop {
+            let column = marker.content_column();
+            let marker_len = marker.label.len() + marker.separator.len();
+            let first_line = lines[i].trim_start()[marker_len..].to_string();
+            let mut item_lines = vec![first_line];
+            i += 1;
+            while i < lines.len() {
+                let line = &lines[i];
+                if line.trim().is_empty() {
+                    item_lines.push(String::new());
+                } else if indentation(line) >= column {
+                    item_lines.push(strip_columns(line, column));
+                } else if parse_marker(line).is_some()
+                    || item_lines.last().map_or(true, |last| last.is_empty())
+                    || interrupts_paragraph(line)
+                {
+                    break;
+                } else {
+                    // Lazy continuation of the item's paragraph
+                    item_lines.push(line.trim_start().to_string());
+                }
+                i += 1;
+            }
+            // The first line stays, even when the item is empty
+            while item_lines.len() > 1 && item_lines.last().is_some_and(|line| line.is_empty()) {
+                item_lines.pop();
+                i -= 1;
+            }
+
+            let checked = take_task_marker(&mut item_lines[0]);
+            let number = list
+                .start
+                .map(|start| start + list.items.len() as i32)
+                .or(marker.number);
+            list.items.push(ListItem {
+                label: marker.label.clone(),
+                separator: marker.separator.clone(),
+                number,
+                checked,
+                blocks: self.parse_blocks(&item_lines, list.level + 1),
+            });
+
+            // The next item continues the list after blank lines
+            let mut next = i;
+            while next < lines.len() && lines[next].trim().is_empty() {
+                next += 1;
+            }
+            match lines.get(next).and_then(|line| parse_marker(line)) {
+                Some(next_marker)
+                    if next_marker.indent == first.indent && next_marker.key == first.key =>
+                {
+                    marker = next_marker;
+                    i = next;
+                }
+                _ => break,
+            }
+        }
+        (list, i)
+    }
+
+    /// Parses an HTML table, with `rowspan` and `colspan` attributes and
+    /// `<br>` separating the paragraphs of a cell.
+    fn parse_html_table(&mut self, html: &str) -> Table {
+        let mut rows: Vec<Vec<TableCell>> = Vec::new();
+        let mut depth = 0;
+        let mut cell: Option<(usize, usize, usize)> = None;
+        let mut pos = 0;
+        while let Some((start, tag)) = next_tag(html, pos) {
+            pos = start + tag.length;
+            match (tag.name.as_str(), tag.closing) {
+                ("table", false) => depth += 1,
+                ("table", true) => depth -= 1,
+                ("tr", false) if depth == 1 => rows.push(Vec::new()),
+                ("td" | "th", false) if depth == 1 => {
+                    let span = |name: &str| {
+                        tag.attribute(name)
+                            .and_then(|value| value.trim().parse::<usize>().ok())
+                            .unwrap_or(1)
+                            .max(1)
+                    };
+                    cell = Some((pos, span("colspan"), span("rowspan")));
+                }
+                ("td" | "th", true) if depth == 1 => {
+                    if let Some((content_start, colspan, rowspan)) = cell.take() {
+                        let blocks = self.parse_cell(&html[content_start..start]);
+                        if rows.is_empty() {
+                            rows.push(Vec::new());
+                        }
+                        if let Some(row) = rows.last_mut() {
+                            row.push(TableCell {
+                                blocks,
+                                colspan,
+                                rowspan,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Table { rows }
+    }
+
+    /// Parses the content of an HTML table cell into paragraphs and nested
+    /// tables.
+    fn parse_cell(&mut self, html: &str) -> Vec<BlockNode> {
+        let mut blocks = Vec::new();
+        let mut text = String::new();
+        let mut pos = 0;
+        let flush = |parser: &mut Self, text: &mut String, blocks: &mut Vec<BlockNode>| {
+            for part in split_breaks(text) {
+                let part = part.trim();
+                if !part.is_empty() {
+                    blocks.push(BlockNode::Paragraph(Paragraph {
+                        content: InlineParser::new(part, true, parser.notes).parse(),
+                        ..Default::default()
+                    }));
+                }
+            }
+            text.clear();
+        };
+        while let Some(offset) = html[pos..].find("<table") {
+            let start = pos + offset;
+            text.push_str(&html[pos..start]);
+            flush(self, &mut text, &mut blocks);
+            let end = matching_table_end(html, start);
+            blocks.push(BlockNode::Table(self.parse_html_table(&html[start..end])));
+            pos = end;
+        }
+        text.push_str(&html[pos..]);
+        flush(self, &mut text, &mut blocks);
+        blocks
+    }
+
+    /// Parses a pipe table; the delimiter row is skipped.
+    fn parse_pipe_table(&mut self, lines: &[String]) -> Table {
+        let rows = lines
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != 1)
+            .map(|(_, line)| {
+                split_pipe_row(line)
+                    .into_iter()
+                    .map(|cell| {
+                        let cell = cell.trim();
+                        let blocks = if cell.is_empty() {
+                            Vec::new()
+                        } else {
+                            vec![BlockNode::Paragraph(Paragraph {
+                                content: InlineParser::new(cell, false, self.notes).parse(),
+                                ..Default::default()
+                            })]
+                        };
+                        TableCell {
+                            blocks,
+                            colspan: 1,
+                            rowspan: 1,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Table { rows }
+    }
+}
+
+/// Paragraph alignment the Markdown renderer wrote as a `<div>`.
+fn alignment(markdown: &str) -> Option<Alignment> {
+    let rest = markdown.strip_prefix("<div style=\"text-align:")?;
+    let value = rest.split(';').next()?.trim();
+    match value {
+        "center" => Some(Alignment::Center),
+        "right" => Some(Alignment::Right),
+        "justify" => Some(Alignment::Justify),
+        "left" => Some(Alignment::Left),
+        _ => None,
+    }
+}
+
+/// Columns of leading whitespace, a tab counting four.
+fn indentation(line: &str) -> usize {
+    let mut columns = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => columns += 1,
+            '\t' => columns += 4 - columns % 4,
+            _ => break,
+        }
+    }
+    columns
+}
+
+/// Removes up to `columns` columns of leading whitespace.
+fn strip_columns(line: &str, columns: usize) -> String {
+    let mut removed = 0;
+    let mut chars = line.char_indices();
+    while removed < columns {
+        match chars.next() {
+            Some((_, ' ')) => removed += 1,
+            Some((_, '\t')) => removed += 4 - removed % 4,
+            Some((i, _)) => return line[i..].to_string(),
+            None => return String::new(),
+        }
+    }
+    chars.as_str().to_string()
+}
+
+/// Returns the fence character and length when `text` opens a fenced code
+/// block.
+fn fence_start(text: &str) -> Option<((char, usize), &str)> {
+    let fence = text.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let length = text.chars().take_while(|&c| c == fence).count();
+    let info = &text[length..];
+    (length >= 3 && !(fence == '`' && info.contains('`'))).then_some(((fence, length), info))
+}
+
+fn closes_fence(line: &str, (fence, length): (char, usize)) -> bool {
+    let text = line.trim();
+    text.chars().take_while(|&c| c == fence).count() >= length && text.chars().all(|c| c == fence)
+}
+
+/// Whether `text` is a thematic break (`---`, `***`, `___`), written for
+/// page breaks.
+fn is_thematic_break(text: &str) -> bool {
+    let text = text.trim_end();
+    let Some(c) = text.chars().next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    text.chars().filter(|&ch| ch == c).count() >= 3 && text.chars().all(|ch| ch == c || ch == ' ')
+}
+
+fn starts_html_table(text: &str) -> bool {
+    text.strip_prefix("<table")
+        .is_some_and(|rest| rest.starts_with(['>', ' ', '\t']) || rest.is_empty())
+}
+
+/// Collects the lines of the HTML table starting at `lines[start]`,
+/// returning its markup and the index of the line after it.
+fn collect_html_table(lines: &[String], start: usize) -> (String, usize) {
+    let mut html = String::new();
+    let mut i = start;
+    while i < lines.len() {
+        if !html.is_empty() {
+            html.push('\n');
+        }
+        html.push_str(&lines[i]);
+        i += 1;
+        let opened = html.matches("<table").count();
+        if opened <= html.matches("</table>").count() {
+            break;
+        }
+    }
+    (html, i)
+}
+
+/// Returns the end of the table starting at `start`, after its `</table>`.
+fn matching_table_end(html: &str, start: usize) -> usize {
+    let mut depth = 0;
+    let mut pos = start;
+    while let Some((tag_start, tag)) = next_tag(html, pos) {
+        pos = tag_start + tag.length;
+        if tag.name == "table" {
+            depth += if tag.closing { -1 } else { 1 };
+            if depth == 0 {
+                return pos;
+            }
+        }
+    }
+    html.len()
+}
+
+/// Splits cell text at `<br>` tags.
+fn split_breaks(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut pos = 0;
+    let mut part_start = 0;
+    while let Some((start, tag)) = next_tag(text, pos) {
+        pos = start + tag.length;
+        if tag.name == "br" {
+            parts.push(&text[part_start..start]);
+            part_start = pos;
+        }
+    }
+    parts.push(&text[part_start..]);
+    parts
+}
+
+fn is_pipe_table_start(lines: &[String], i: usize) -> bool {
+    lines[i].trim_start().starts_with('|')
+        && lines.get(i + 1).is_some_and(|line| {
+            let cells = split_pipe_row(line);
+            !cells.is_empty()
+                && cells.iter().all(|cell| {
+                    let cell = cell.trim();
+                    cell.contains('-') && cell.chars().all(|c| matches!(c, '-' | ':'))
+                })
+        })
+}
+
+/// Splits a pipe table row at unescaped `|`, without the outer pipes.
+fn split_pipe_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                chars.next();
+                cells.last_mut().unwrap().push('|');
+            }
+            '|' => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+    if cells.last().is_some_and(|cell| cell.trim().is_empty()) && line.ends_with('|') {
+        cells.pop();
+    }
+    cells
+}
+
+/// Whether `line` ends the paragraph before it.
+fn interrupts_paragraph(line: &str) -> bool {
+    let text = line.trim_start();
+    if text.is_empty() {
+        return true;
+    }
+    let starts_bullet_or_first = parse_marker(line).is_some_and(|marker| match marker.kind {
+        ListKind::Bullet => matches!(marker.label.as_str(), "-" | "*" | "+"),
+        ListKind::Ordered => marker.number == Some(1) && marker.label.starts_with('1'),
+    });
+    fence_start(text).is_some()
+        || text.starts_with('#') && text.trim_start_matches('#').starts_with([' ', '\t'])
+        || text.starts_with('#') && text.chars().all(|c| c == '#') && text.len() <= 6
+        || is_thematic_break(text)
+        || starts_html_table(text)
+        || text.starts_with('>')
+        || starts_bullet_or_first
+}
+
+/// Removes a task marker (`[ ]`, `[x]` or a ballot box) from the start of
+/// an item, returning whether it is checked.
+fn take_task_marker(line: &mut String) -> Option<bool> {
+    for (marker, checked) in [
+        ("[ ]", false),
+        ("[x]", true),
+        ("[X]", true),
+        ("☐", false),
+        ("☒", true),
+    ] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            if rest.is_empty() || rest.starts_with(' ') {
+                *line = rest.trim_start().to_string();
+                return Some(checked);
+            }
+        }
+    }
+    None
+}
+
+/// Parses the list item marker at the start of `line`: a bullet, or an
+/// ordered label such as `1.`, `a)`, `(iv)`, `가.`, `①` or `2.1.`, followed
+/// by a space or tab.
+fn parse_marker(line: &str) -> Option<Marker> {
+    let indent = indentation(line);
+    let text = line.trim_start();
+    let label_end = text.find([' ', '\t']).unwrap_or(text.len());
+    let label = &text[..label_end];
+    let rest = &text[label_end..];
+    let separator_len = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    let separator = if rest.trim().is_empty() || separator_len > 4 {
+        &rest[..rest.len().min(1)]
+    } else {
+        &rest[..separator_len]
+    };
+    if separator.is_empty() && !rest.is_empty() {
+        return None;
+    }
+    if rest.is_empty() && !label.ends_with(['.', ')']) {
+        return None;
+    }
+
+    let mut chars = label.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if BULLETS.contains(&c) {
+            // `o` is only a bullet before text
+            if c == 'o' && rest.trim().is_empty() {
+                return None;
+            }
+            return Some(Marker {
+                indent,
+                label: label.to_string(),
+                separator: separator.to_string(),
+                kind: ListKind::Bullet,
+                number: None,
+                key: label.to_string(),
+            });
+        }
+    }
+
+    let counter = ordered_counter(label)?;
+    Some(Marker {
+        indent,
+        label: label.to_string(),
+        separator: separator.to_string(),
+        kind: ListKind::Ordered,
+        number: Some(counter.value),
+        key: format!("{}{}{}", counter.prefix, counter.class, counter.suffix),
+    })
+}
+
+/// Counter of an ordered list label, between its prefix and suffix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Counter {
+    pub prefix: String,
+    pub suffix: String,
+    /// Number format (`w:numFmt`) of the counter.
+    pub format: &'static str,
+    pub value: i32,
+    /// Counter class, the same for all labels of a list.
+    class: &'static str,
+}
+
+/// Parses an ordered list label into its counter.
+pub(crate) fn ordered_counter(label: &str) -> Option<Counter> {
+    if label.is_empty() || label.chars().count() > 16 {
+        return None;
+    }
+    let (open, inner) = match label.strip_prefix('(') {
+        Some(inner) => ("(", inner),
+        None => ("", label),
+    };
+    let (body, close) = match inner.char_indices().last()? {
+        (i, c @ ('.' | ')')) => (&inner[..i], &inner[i..i + c.len_utf8()]),
+        _ => (inner, ""),
+    };
+    if open == "(" && close != ")" || body.is_empty() {
+        return None;
+    }
+
+    // Multi-level labels such as `2.1` keep the parent numbers in the prefix
+    let counter_start = body.rfind('.').map_or(0, |i| i + 1);
+    let (parents, counter) = body.split_at(counter_start);
+    if !parents.is_empty()
+        && !parents
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .all(|part| part.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+    let (format, class, value) = counter_value(counter)?;
+    if close.is_empty() && parents.is_empty() && class != "circled" {
+        return None;
+    }
+    if format_num(format, value) != counter {
+        return None;
+    }
+    Some(Counter {
+        prefix: format!("{}{}", open, parents),
+        suffix: close.to_string(),
+        format,
+        value,
+        class,
+    })
+}
+
+/// Returns the number format, class and value of a counter.
+fn counter_value(counter: &str) -> Option<(&'static str, &'static str, i32)> {
+    let first = counter.chars().next()?;
+    let count = counter.chars().count();
+    if counter.chars().all(|c| c.is_ascii_digit()) {
+        return (count <= 9)
+            .then(|| counter.parse().ok())
+            .flatten()
+            .map(|value| ("decimal", "decimal", value));
+    }
+    if count == 1 {
+        if let Some(i) = GANADA.chars().position(|c| c == first) {
+            return Some(("ganada", "ganada", i as i32 + 1));
+        }
+        if let Some(i) = CHOSUNG.chars().position(|c| c == first) {
+            return Some(("chosung", "chosung", i as i32 + 1));
+        }
+        if ('\u{2460}'..='\u{2473}').contains(&first) {
+            return Some((
+                "decimalEnclosedCircle",
+                "circled",
+                first as i32 - 0x2460 + 1,
+            ));
+        }
+    }
+    if count > 4 || !counter.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let lower = counter.chars().all(|c| c.is_ascii_lowercase());
+    let upper = counter.chars().all(|c| c.is_ascii_uppercase());
+    if !lower && !upper {
+        return None;
+    }
+    let class = if lower { "lower" } else { "upper" };
+    let roman = roman_value(counter);
+    let same_letter = counter.chars().all(|c| c == first);
+    match roman {
+        Some(value) if counter.eq_ignore_ascii_case("i") || !same_letter || count > 1 => {
+            let format = if lower { "lowerRoman" } else { "upperRoman" };
+            Some((format, class, value))
+        }
+        _ if same_letter => {
+            let format = if lower { "lowerLetter" } else { "upperLetter" };
+            let index = first.to_ascii_lowercase() as i32 - 'a' as i32 + 1;
+            Some((format, class, (count as i32 - 1) * 26 + index))
+        }
+        _ => None,
+    }
+}
+
+/// Value of a Roman numeral (either case).
+fn roman_value(numeral: &str) -> Option<i32> {
+    let digit = |c: char| match c.to_ascii_lowercase() {
+        'i' => Some(1),
+        'v' => Some(5),
+        'x' => Some(10),
+        'l' => Some(50),
+        'c' => Some(100),
+        'd' => Some(500),
+        'm' => Some(1000),
+        _ => None,
+    };
+    let digits: Vec<i32> = numeral.chars().map(digit).collect::<Option<_>>()?;
+    let mut value = 0;
+    for (i, d) in digits.iter().enumerate() {
+        if digits.get(i + 1).is_some_and(|next| next > d) {
+            value -= d;
+        } else {
+            value += d;
+        }
+    }
+    let canonical = format_num("upperRoman", value);
+    (value > 0 && canonical.eq_ignore_ascii_case(numeral)).then_some(value)
+}
+
+/// HTML tag found in text.
+struct Tag {
+    name: String,
+    closing: bool,
+    /// Length of the tag in bytes.
+    length: usize,
+    attributes: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Whether `name` is an HTML element; other names in angle brackets, e.g.
+/// `<T>` or `<name>`, are text.
+fn is_html_element(name: &str) -> bool {
+    const ELEMENTS: &[&str] = &[
+        "a", "abbr", "b", "bdi", "bdo", "big", "br", "center", "cite", "code", "dfn", "del", "div",
+        "em", "font", "i", "img", "ins", "kbd", "mark", "nobr", "p", "q", "s", "samp", "small",
+        "span", "strike", "strong", "sub", "sup", "time", "tt", "u", "var", "wbr",
+    ];
+    ELEMENTS.contains(&name)
+}
+
+/// Finds the next HTML tag at or after byte `from`, returning its start.
+fn next_tag(text: &str, from: usize) -> Option<(usize, Tag)> {
+    let mut pos = from;
+    while let Some(offset) = text[pos..].find('<') {
+        let start = pos + offset;
+        if let Some(tag) = parse_tag(&text[start..]) {
+            return Some((start, tag));
+        }
+        pos = start + 1;
+    }
+    None
+}
+
+/// Parses the HTML tag `text` starts with.
+fn parse_tag(text: &str) -> Option<Tag> {
+    let rest = text.strip_prefix('<')?;
+    let (closing, rest) = match rest.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+    let name_len = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    if name_len == 0 || !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = rest[..name_len].to_ascii_lowercase();
+    let mut attributes = Vec::new();
+    let mut pos = name_len;
+    let bytes = rest.as_bytes();
+    loop {
+        while pos < rest.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos)? {
+            b'>' => break,
+            b'/' if bytes.get(pos + 1) == Some(&b'>') => {
+                pos += 1;
+                break;
+            }
+            _ if closing => return None,
+            _ => {}
+        }
+        let key_len = rest[pos..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '=' | '>' | '/'))
+            .unwrap_or(rest.len() - pos);
+        if key_len == 0 {
+            return None;
+        }
+        let key = rest[pos..pos + key_len].to_string();
+        pos += key_len;
+        let mut value = String::new();
+        if bytes.get(pos) == Some(&b'=') {
+            pos += 1;
+            match bytes.get(pos)? {
+                quote @ (b'"' | b'\'') => {
+                    let end = rest[pos + 1..].find(*quote as char)? + pos + 1;
+                    value = decode_entities(&rest[pos + 1..end]);
+                    pos = end + 1;
+                }
+                _ => {
+                    let len = rest[pos..]
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(rest.len() - pos);
+                    value = decode_entities(&rest[pos..pos + len]);
+                    pos += len;
+                }
+            }
+        }
+        attributes.push((key, value));
+    }
+    Some(Tag {
+        name,
+        closing,
+        length: 1 + usize::from(closing) + pos + 1,
+        attributes,
+    })
+}
+
+/// Decodes the entity at the start of `text` (after `&`), returning the
+/// character and the length of the entity including `;`.
+fn decode_entity(text: &str) -> Option<(char, usize)> {
+    let end = text.get(..12.min(text.len())).unwrap_or(text).find(';')?;
+    let name = &text[..end];
+    let c = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => '\u{a0}',
+        _ => {
+            let code = match name.strip_prefix('#') {
+                Some(hex) if hex.starts_with(['x', 'X']) => u32::from_str_radix(&hex[1..], 16),
+                Some(decimal) => decimal.parse(),
+                None => return None,
+            };
+            char::from_u32(code.ok()?)?
+        }
+    };
+    Some((c, end + 1))
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        match decode_entity(&rest[i + 1..]) {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[i + 1 + len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Parser of inline Markdown and HTML over a character buffer. In HTML
+/// mode (table cells of HTML tables) backslash escapes and Markdown
+/// emphasis are literal text.
+struct InlineParser<'n> {
+    chars: Vec<char>,
+    html: bool,
+    notes: &'n mut Notes,
+}
+
+impl<'n> InlineParser<'n> {
+    fn new(text: &str, html: bool, notes: &'n mut Notes) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            html,
+            notes,
+        }
+    }
+
+    fn parse(&mut self) -> Vec<InlineNode> {
+        let mut out = Vec::new();
+        self.parse_range(0, self.chars.len(), &TextStyle::default(), &mut out);
+        trim_text(&mut out);
+        out
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.chars[start..end].iter().collect()
+    }
+
+    fn starts_with(&self, at: usize, end: usize, pattern: &str) -> bool {
+        let count = pattern.chars().count();
+        at + count <= end
+            && self.chars[at..at + count]
+                .iter()
+                .copied()
+                .eq(pattern.chars())
+    }
+
+    /// Parses `chars[start..end]` with `style` into `out`.
+    fn parse_range(
+        &mut self,
+        start: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) {
+        let mut i = start;
+        while i < end {
+            let c = self.chars[i];
+            let next = match c {
+                '\\' if !self.html => match self.chars.get(i + 1).filter(|_| i + 1 < end) {
+                    Some(&escaped) if escaped.is_ascii_punctuation() || escaped == '\n' => {
+                        push_text(out, &escaped.to_string(), style);
+                        Some(i + 2)
+                    }
+                    _ => None,
+                },
+                '&' => {
+                    let rest = self.text(i + 1, end.min(i + 13));
+                    decode_entity(&rest).map(|(decoded, len)| {
+                        push_text(out, &decoded.to_string(), style);
+                        i + 1 + len
+                    })
+                }
+                '<' => self.parse_html(i, end, style, out),
+                '[' => self.parse_bracket(i, end, style, out),
+                '!' if self.starts_with(i + 1, end, "[") => self.parse_image(i, end, out),
+                '`' if !self.html => self.parse_code(i, end, style, out),
+                '*' | '_' if !self.html => self.parse_emphasis(i, end, style, out),
+                '~' | '^' | '=' if !self.html => self.parse_marked(i, end, style, out),
+                _ => None,
+            };
+            match next {
+                Some(next) => i = next,
+                None => {
+                    push_text(out, &c.to_string(), style);
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    /// Parses an HTML tag, comment or autolink at `i`.
+    fn parse_html(
+        &mut self,
+        i: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) -> Option<usize> {
+        let rest = self.text(i, end);
+        if rest.starts_with("<!--") {
+            let close = rest.find("-->")?;
+            return Some(i + rest[..close + 3].chars().count());
+        }
+        if let Some(close) = rest.find('>') {
+            let target = &rest[1..close];
+            let is_url = target.split_once(':').is_some_and(|(scheme, _)| {
+                scheme.len() >= 2
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '+')
+            });
+            if (is_url || target.contains('@')) && !target.contains([' ', '<']) {
+                let destination = if is_url || target.starts_with("mailto:") {
+                    target.to_string()
+                } else {
+                    format!("mailto:{}", target)
+                };
+                out.push(InlineNode::Link {
+                    destination,
+                    content: vec![InlineNode::Text {
+                        text: target.to_string(),
+                        style: style.clone(),
+                    }],
+                });
+                return Some(i + rest[..close + 1].chars().count());
+            }
+        }
+
+        // Tags that are not HTML elements, e.g. `<T>`, are text
+        let tag = parse_tag(&rest).filter(|tag| is_html_element(&tag.name))?;
+        let after = i + rest[..tag.length].chars().count();
+        if tag.closing {
+            // Stray closing tags are dropped
+            return Some(after);
+        }
+        let inner_end = |parser: &Self| parser.closing_tag(after, end, &tag.name);
+        match tag.name.as_str() {
+            "br" => {
+                push_text(out, "\n", style);
+                Some(after)
+            }
+            "img" => {
+                out.push(InlineNode::Image {
+                    source: tag.attribute("src").unwrap_or_default().to_string(),
+                    alt: tag.attribute("alt").unwrap_or_default().to_string(),
+                    width: tag
+                        .attribute("width")
+                        .and_then(|width| width.trim_end_matches("px").parse().ok()),
+                });
+                Some(after)
+            }
+            "a" => {
+                let (close_start, close_end) = inner_end(self).unwrap_or((after, after));
+                if let Some(href) = tag.attribute("href") {
+                    let mut content = Vec::new();
+                    self.parse_range(after, close_start, style, &mut content);
+                    out.push(InlineNode::Link {
+                        destination: href.to_string(),
+                        content,
+                    });
+                } else {
+                    for name in ["id", "name"] {
+                        if let Some(id) = tag.attribute(name) {
+                            out.push(InlineNode::Anchor(id.to_string()));
+                            break;
+                        }
+                    }
+                    self.parse_range(after, close_start, style, out);
+                }
+                Some(close_end)
+            }
+            name => {
+                let mut inner = style.clone();
+                match name {
+                    "strong" | "b" => inner.bold = true,
+                    "em" | "i" | "cite" => inner.italic = true,
+                    "u" => inner.underline = true,
+                    "ins" => inner.insertion = true,
+                    "s" | "strike" => inner.strike = true,
+                    "del" => inner.deletion = true,
+                    "sup" => inner.superscript = true,
+                    "sub" => inner.subscript = true,
+                    "mark" => inner.highlight = true,
+                    "code" | "kbd" | "samp" | "tt" => inner.code = true,
+                    "span" => apply_css(&mut inner, tag.attribute("style").unwrap_or_default()),
+                    // Other tags (`div`, `p`, ...) keep only their content
+                    _ => return Some(after),
+                }
+                match inner_end(self) {
+                    Some((close_start, close_end)) => {
+                        self.parse_range(after, close_start, &inner, out);
+                        Some(close_end)
+                    }
+                    None => Some(after),
+                }
+            }
+        }
+    }
+
+    /// Finds the closing tag of `name` from `start`, skipping nested tags of
+    /// the same name; returns where it starts and ends.
+    fn closing_tag(&self, start: usize, end: usize, name: &str) -> Option<(usize, usize)> {
+        let mut depth = 0;
+        let mut i = start;
+        while i < end {
+            if self.chars[i] == '<' {
+                let rest = self.text(i, end.min(i + 400));
+                if let Some(tag) = parse_tag(&rest).filter(|tag| tag.name == name) {
+                    let tag_end = i + rest[..tag.length].chars().count();
+                    if tag.closing {
+                        if depth == 0 {
+                            return Some((i, tag_end));
+                        }
+                        depth -= 1;
+                    } else {
+                        depth += 1;
+                    }
+                    i = tag_end;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Finds the `]` closing the bracket at `open`.
+    fn closing_bracket(&self, open: usize, end: usize) -> Option<usize> {
+        let mut depth = 0;
+        let mut i = open;
+        while i < end {
+            match self.chars[i] {
+                '\\' if !self.html => i += 1,
+                '[' => depth += 1,
+                ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Parses `(destination "title")` at `open`, returning the destination
+    /// and the index after `)`.
+    fn parse_destination(&self, open: usize, end: usize) -> Option<(String, usize)> {
+        if self.chars.get(open) != Some(&'(') {
+            return None;
+        }
+        let mut i = open + 1;
+        let mut destination = String::new();
+        if self.chars.get(i) == Some(&'<') {
+            i += 1;
+            while i < end && self.chars[i] != '>' {
+                destination.push(self.chars[i]);
+                i += 1;
+            }
+            i += 1;
+        } else {
+            let mut depth = 0;
+            while i < end {
+                match self.chars[i] {
+                    '\\' if i + 1 < end && self.chars[i + 1].is_ascii_punctuation() => {
+                        destination.push(self.chars[i + 1]);
+                        i += 1;
+                    }
+                    '\\' if i + 1 < end && self.chars[i + 1] == ' ' => {
+                        destination.push(' ');
+                        i += 1;
+                    }
+                    '(' => {
+                        depth += 1;
+                        destination.push('(');
+                    }
+                    ')' if depth == 0 => break,
+                    ')' => {
+                        depth -= 1;
+                        destination.push(')');
+                    }
+                    c if c.is_whitespace() => break,
+                    c => destination.push(c),
+                }
+                i += 1;
+            }
+        }
+        // Optional title
+        while i < end && self.chars[i] == ' ' {
+            i += 1;
+        }
+        if i < end && matches!(self.chars[i], '"' | '\'') {
+            let quote = self.chars[i];
+            i += 1;
+            while i < end && self.chars[i] != quote {
+                i += 1;
+            }
+            i += 1;
+            while i < end && self.chars[i] == ' ' {
+                i += 1;
+            }
+        }
+        (i < end && self.chars[i] == ')').then(|| (decode_entities(&destination), i + 1))
+    }
+
+    /// Parses a note reference, link, `[]{#id}` anchor or `[text]{.class}`
+    /// span at `i`.
+    fn parse_bracket(
+        &mut self,
+        i: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) -> Option<usize> {
+        let close = self.closing_bracket(i, end)?;
+        if self.chars.get(i + 1) == Some(&'^') {
+            let label = self.text(i + 2, close);
+            if let Some(reference) = is_note_label(&label)
+                .then(|| self.notes.reference(&label))
+                .flatten()
+            {
+                out.push(reference);
+                return Some(close + 1);
+            }
+        }
+        match self.chars.get(close + 1).filter(|_| close + 1 < end) {
+            Some('(') => {
+                let (destination, after) = self.parse_destination(close + 1, end)?;
+                let mut content = Vec::new();
+                self.parse_range(i + 1, close, style, &mut content);
+                out.push(InlineNode::Link {
+                    destination,
+                    content,
+                });
+                Some(after)
+            }
+            Some('{') => {
+                let attributes_end = (close + 1..end).find(|&j| self.chars[j] == '}')?;
+                let attributes = self.text(close + 2, attributes_end);
+                let mut inner = style.clone();
+                for attribute in attributes.split_whitespace() {
+                    match attribute {
+                        ".underline" => inner.underline = true,
+                        ".smallcaps" => inner.small_caps = true,
+                        ".mark" => inner.highlight = true,
+                        _ => {
+                            if let Some(id) = attribute.strip_prefix('#') {
+                                out.push(InlineNode::Anchor(id.to_string()));
+                            }
+                        }
+                    }
+                }
+                self.parse_range(i + 1, close, &inner, out);
+                Some(attributes_end + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses `![alt](source)` at `i`.
+    fn parse_image(&mut self, i: usize, end: usize, out: &mut Vec<InlineNode>) -> Option<usize> {
+        let close = self.closing_bracket(i + 1, end)?;
+        let (source, after) = self.parse_destination(close + 1, end)?;
+        let mut alt = Vec::new();
+        self.parse_range(i + 2, close, &TextStyle::default(), &mut alt);
+        out.push(InlineNode::Image {
+            source,
+            alt: crate::core::ast::plain_text(&alt),
+            width: None,
+        });
+        Some(after)
+    }
+
+    /// Parses a code span at `i`, kept as literal text in code style.
+    fn parse_code(
+        &mut self,
+        i: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) -> Option<usize> {
+        let run = self.run_length(i, end);
+        let mut j = i + run;
+        while j < end {
+            if self.chars[j] == '`' {
+                let close = self.run_length(j, end);
+                if close == run {
+                    let mut code = self.text(i + run, j);
+                    if code.len() > 2 && code.starts_with(' ') && code.ends_with(' ') {
+                        code = code[1..code.len() - 1].to_string();
+                    }
+                    let code_style = TextStyle {
+                        code: true,
+                        ..style.clone()
+                    };
+                    push_text(out, &code, &code_style);
+                    return Some(j + run);
+                }
+                j += close;
+            } else {
+                j += 1;
+            }
+        }
+        push_text(out, &"`".repeat(run), style);
+        Some(i + run)
+    }
+
+    fn run_length(&self, i: usize, end: usize) -> usize {
+        let c = self.chars[i];
+        self.chars[i..end].iter().take_while(|&&ch| ch == c).count()
+    }
+
+    /// Parses `*`/`_` emphasis at `i`: one delimiter for italic, two for
+    /// bold, three for both.
+    fn parse_emphasis(
+        &mut self,
+        i: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) -> Option<usize> {
+        let c = self.chars[i];
+        let run = self.run_length(i, end);
+        let before = i.checked_sub(1).map(|j| self.chars[j]);
+        let after = self.chars.get(i + run).filter(|_| i + run < end).copied();
+        let left_flanking = after.is_some_and(|ch| !ch.is_whitespace());
+        if run > 3 || !left_flanking || c == '_' && before.is_some_and(char::is_alphanumeric) {
+            push_text(out, &c.to_string().repeat(run), style);
+            return Some(i + run);
+        }
+        let close = self.closing_delimiter(i + run, end, c, run)?;
+        let mut inner = style.clone();
+        inner.italic |= run != 2;
+        inner.bold |= run >= 2;
+        self.parse_range(i + run, close, &inner, out);
+        Some(close + run)
+    }
+
+    /// Finds a closing run of exactly `run` `c` characters after `start`,
+    /// preceded by a non-space character.
+    fn closing_delimiter(&self, start: usize, end: usize, c: char, run: usize) -> Option<usize> {
+        let mut j = start;
+        while j < end {
+            match self.chars[j] {
+                '\\' => j += 2,
+                '`' => j += self.run_length(j, end),
+                ch if ch == c => {
+                    let length = self.run_length(j, end);
+                    let before = self.chars[j - 1];
+                    let after = self.chars.get(j + length).filter(|_| j + length < end);
+                    if length == run
+                        && !before.is_whitespace()
+                        && (c != '_' || !after.is_some_and(|ch| ch.is_alphanumeric()))
+                    {
+                        return Some(j);
+                    }
+                    j += length;
+                }
+                _ => j += 1,
+            }
+        }
+        None
+    }
+
+    /// Parses `~~strike~~`, `~sub~`, `^sup^` and `==mark==` at `i`.
+    fn parse_marked(
+        &mut self,
+        i: usize,
+        end: usize,
+        style: &TextStyle,
+        out: &mut Vec<InlineNode>,
+    ) -> Option<usize> {
+        let c = self.chars[i];
+        let run = self.run_length(i, end);
+        let mut inner = style.clone();
+        match (c, run) {
+            ('~', 2) => inner.strike = true,
+            ('~', 1) => inner.subscript = true,
+            ('^', 1) => inner.superscript = true,
+            ('=', 2) => inner.highlight = true,
+            _ => {
+                push_text(out, &c.to_string().repeat(run), style);
+                return Some(i + run);
+            }
+        }
+        if self
+            .chars
+            .get(i + run)
+            .map_or(true, |ch| ch.is_whitespace())
+        {
+            return None;
+        }
+        let close = self.closing_delimiter(i + run, end, c, run)?;
+        // Sub- and superscripts hold no unescaped spaces
+        if run == 1 && self.chars[i + 1..close].contains(&' ') {
+            return None;
+        }
+        self.parse_range(i + run, close, &inner, out);
+        Some(close + run)
+    }
+}
+
+/// Applies the CSS of a `<span style>` to `style`.
+fn apply_css(style: &mut TextStyle, css: &str) {
+    for declaration in css.split(';') {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match property.trim().to_ascii_lowercase().as_str() {
+            "color" => style.color = Some(value.to_string()),
+            "text-transform" if value == "uppercase" => style.all_caps = true,
+            "font-variant" if value == "small-caps" => style.small_caps = true,
+            "font-weight" if value == "bold" => style.bold = true,
+            "font-style" if value == "italic" => style.italic = true,
+            "text-decoration" if value.contains("underline") => style.underline = true,
+            "text-decoration" if value.contains("line-through") => style.strike = true,
+            _ => {}
+        }
+    }
+}
+
+/// Appends text, merging it into the last node when the style is the same.
+fn push_text(out: &mut Vec<InlineNode>, text: &str, style: &TextStyle) {
+    if let Some(InlineNode::Text {
+        text: last,
+        style: last_style,
+    }) = out.last_mut()
+    {
+        if last_style == style {
+            last.push_str(text);
+            return;
+        }
+    }
+    out.push(InlineNode::Text {
+        text: text.to_string(),
+        style: style.clone(),
+    });
+}
+
+/// Trims whitespace from the start and end of the content.
+fn trim_text(nodes: &mut Vec<InlineNode>) {
+    if let Some(InlineNode::Text { text, .. }) = nodes.first_mut() {
+        *text = text.trim_start().to_string();
+    }
+    if let Some(InlineNode::Text { text, .. }) = nodes.last_mut() {
+        *text = text.trim_end().to_string();
+    }
+    nodes.retain(|node| !matches!(node, InlineNode::Text { text, .. } if text.is_empty()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> InlineNode {
+        InlineNode::Text {
+            text: text.into(),
+            style: TextStyle::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_renderer_output() {
+        let ast = parse_markdown(
+            "<a id=\"intro\"></a>\n## Intro\n\nSee <strong>this</strong>[^1] and \
+             [that](#intro).\nNext line\n\n---\n\n[^1]:  A <em>note</em>.\n",
+        );
+        assert_eq!(ast.blocks.len(), 2);
+        let BlockNode::Heading(heading) = &ast.blocks[0] else {
+            panic!("expected a heading");
+        };
+        assert_eq!(
+            (heading.level, heading.anchors.clone()),
+            (2, vec!["intro".into()])
+        );
+        let BlockNode::Paragraph(paragraph) = &ast.blocks[1] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(
+            paragraph.content,
+            vec![
+                text("See "),
+                InlineNode::Text {
+                    text: "this".into(),
+                    style: TextStyle {
+                        bold: true,
+                        ..Default::default()
+                    },
+                },
+                InlineNode::NoteReference {
+                    kind: NoteKind::Footnote,
+                    id: "1".into(),
+                },
+                text(" and "),
+                InlineNode::Link {
+                    destination: "#intro".into(),
+                    content: vec![text("that")],
+                },
+                text(".\nNext line"),
+            ]
+        );
+        assert_eq!(ast.references.footnotes, vec!["A <em>note</em>."]);
+    }
+
+    #[test]
+    fn test_parse_lists_with_word_labels() {
+        let ast =
+            parse_markdown("1. one\n\n2. two\n\n   a. a\n\n   b. b\n\n• bullet\n\n  o nested\n");
+        let BlockNode::List(list) = &ast.blocks[0] else {
+            panic!("expected a list");
+        };
+        assert_eq!(
+            (list.kind, list.start, list.items.len()),
+            (ListKind::Ordered, Some(1), 2)
+        );
+        let BlockNode::List(nested) = &list.items[1].blocks[1] else {
+            panic!("expected a nested list");
+        };
+        assert_eq!((nested.level, nested.items[1].label.as_str()), (1, "b."));
+        let BlockNode::List(bullets) = &ast.blocks[1] else {
+            panic!("expected a bullet list");
+        };
+        assert_eq!(bullets.kind, ListKind::Bullet);
+        assert!(matches!(&bullets.items[0].blocks[1], BlockNode::List(list) if list.level == 1));
+        assert_eq!(
+            ordered_counter("(iv)").map(|counter| (counter.format, counter.value)),
+            Some(("lowerRoman", 4))
+        );
+        assert_eq!(
+            ordered_counter("2.1.").map(|counter| counter.prefix),
+            Some("2.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_html_table_spans() {
+        let ast = parse_markdown(
+            "<table>\n  <tr>\n    <td rowspan=\"2\" colspan=\"2\">TOP</td>\n    <td>R</td>\n  \
+             </tr>\n  <tr>\n    <td>a &lt; b<br/>c</td>\n  </tr>\n</table>",
+        );
+        let BlockNode::Table(table) = &ast.blocks[0] else {
+            panic!("expected a table");
+        };
+        assert_eq!((table.rows[0][0].rowspan, table.rows[0][0].colspan), (2, 2));
+        assert_eq!(table.rows[1][0].blocks.len(), 2);
+        let BlockNode::Paragraph(paragraph) = &table.rows[1][0].blocks[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(paragraph.content, vec![text("a < b")]);
+    }
+
+    #[test]
+    fn test_parse_empty_and_nested_list_items() {
+        let items = |markdown: &str| match &parse_markdown(markdown).blocks[..] {
+            [BlockNode::List(list)] => list.clone(),
+            blocks => panic!("expected one list: {:?}", blocks),
+        };
+        let list = items("- a\n- \n- b");
+        assert_eq!(list.items.len(), 3);
+        assert!(list.items[1].blocks.is_empty());
+        assert!(items("* ").items[0].blocks.is_empty());
+        assert_eq!(items("3) ").start, Some(3));
+
+        let list = items("- a\n  - \n  - [x] done\n- b");
+        assert_eq!(list.items.len(), 2);
+        let BlockNode::List(nested) = &list.items[0].blocks[1] else {
+            panic!("expected a nested list");
+        };
+        assert_eq!(nested.level, 1);
+        assert!(nested.items[0].blocks.is_empty());
+        assert_eq!(nested.items[1].checked, Some(true));
+    }
+
+    #[test]
+    fn test_parse_literal_text_and_code() {
+        let ast = parse_markdown("Vec<T> in `a <b>` and <code>c</code>[^none].");
+        let BlockNode::Paragraph(paragraph) = &ast.blocks[0] else {
+            panic!("expected a paragraph");
+        };
+        let code = |text: &str| InlineNode::Text {
+            text: text.into(),
+            style: TextStyle {
+                code: true,
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            paragraph.content,
+            vec![
+                text("Vec<T> in "),
+                code("a <b>"),
+                text(" and "),
+                code("c"),
+                text("[^none]."),
+            ]
+        );
+        assert!(ast.references.footnotes.is_empty());
+    }
+}
@@ -0,0 +1,55 @@
+//! Markdown to DOCX conversion, for documents exported with this crate,
+//! edited as Markdown and handed back as Word files.
+
+mod docx;
+mod markdown;
+
+use crate::core::ast::DocumentAst;
+use crate::Result;
+use std::path::{Path, PathBuf};
+
+pub use markdown::parse_markdown;
+
+/// Options for writing DOCX files.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// DOCX file whose styles, theme and page setup are reused.
+    pub reference_docx: Option<PathBuf>,
+    /// Directory relative image paths are resolved against.
+    pub resource_dir: Option<PathBuf>,
+}
+
+/// Markdown to DOCX converter.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownToDocx {
+    options: WriteOptions,
+}
+
+impl MarkdownToDocx {
+    /// Creates a converter with the given options.
+    pub fn new(options: WriteOptions) -> Self {
+        Self { options }
+    }
+
+    /// Converts a Markdown file to DOCX bytes. Images are resolved against
+    /// the file's directory unless a resource directory is set.
+    pub fn convert<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>> {
+        let path = path.as_ref();
+        let markdown = std::fs::read_to_string(path)?;
+        let mut options = self.options.clone();
+        if options.resource_dir.is_none() {
+            options.resource_dir = path.parent().map(Path::to_path_buf);
+        }
+        docx::write_docx(&parse_markdown(&markdown), &options)
+    }
+
+    /// Converts Markdown text to DOCX bytes.
+    pub fn convert_str(&self, markdown: &str) -> Result<Vec<u8>> {
+        self.write(&parse_markdown(markdown))
+    }
+
+    /// Writes a document AST as DOCX bytes.
+    pub fn write(&self, document: &DocumentAst) -> Result<Vec<u8>> {
+        docx::write_docx(document, &self.options)
+    }
+}
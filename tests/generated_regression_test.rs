@@ -71,7 +71,7 @@ fn generated_docx_deep_list_indentation_regression() {
     let _ = std::fs::remove_file(&path);
 
     let first_line = markdown.lines().next().unwrap_or("");
-    assert_eq!(first_line, "      1. Deep Item");
+    assert_eq!(first_line, "1. Deep Item");
 }
 
 #[test]
@@ -1,3 +1,8 @@
+use dm2xcod::adapters::docx::DocxExtractor;
+use dm2xcod::render::{
+    AsciiDocRenderer, LatexRenderer, MarkdownRenderer, MediaWikiRenderer, OrgRenderer,
+    PlainTextRenderer, Renderer, RstRenderer, TypstRenderer,
+};
 use dm2xcod::{ConvertOptions, DocxToMarkdown};
 use std::fs::{create_dir_all, read_dir, File};
 use std::io::Write;
@@ -55,3 +60,59 @@ fn verify_markdown_conversion() {
     }
     println!("Successfully verified {} files", count);
 }
+
+fn convert_with<R: Renderer>(path: &str, renderer: R) -> String {
+    DocxToMarkdown::with_components(ConvertOptions::default(), DocxExtractor, renderer)
+        .convert(path)
+        .unwrap_or_else(|e| panic!("Failed to convert {}: {:?}", path, e))
+}
+
+#[test]
+fn lists_in_table_cells_keep_their_markers_in_every_format() {
+    let path = "./tests/pandoc/table_with_list_cell.docx";
+    let outputs = [
+        (
+            convert_with(path, MarkdownRenderer::default()),
+            [
+                "<td>- Cell with<br/>- A<br/>",
+                "<td>1. Cell with<br/>2. A<br/>",
+            ],
+        ),
+        (
+            convert_with(path, AsciiDocRenderer::default()),
+            ["a|* Cell with\n* A\n", "a|. Cell with\n. A\n"],
+        ),
+        (
+            convert_with(path, RstRenderer::default()),
+            ["| - Cell with    |", "| 2. A              |"],
+        ),
+        (
+            convert_with(path, LatexRenderer::default()),
+            [
+                "\\begin{itemize}\n\\item Cell with\n",
+                "\\begin{enumerate}\n\\item Cell with\n\\item A\n",
+            ],
+        ),
+        (
+            convert_with(path, TypstRenderer::default()),
+            ["[\n    - Cell with\n    - A\n", "[#enum[Cell with][A]"],
+        ),
+        (
+            convert_with(path, OrgRenderer::default()),
+            ["| - Cell with - A -", "| 1. Cell with 2. A 3."],
+        ),
+        (
+            convert_with(path, MediaWikiRenderer::default()),
+            ["|\n* Cell with\n* A\n", "|\n# Cell with\n# A\n"],
+        ),
+        (
+            convert_with(path, PlainTextRenderer::default()),
+            ["• Cell with • A •", "1. Cell with 2. A 3."],
+        ),
+    ];
+    for (output, expected) in outputs {
+        for text in expected {
+            assert!(output.contains(text), "{:?} not in:\n{}", text, output);
+        }
+    }
+}
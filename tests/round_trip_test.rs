@@ -0,0 +1,105 @@
+use dm2xcod::{ConvertOptions, DocxToMarkdown, MarkdownToDocx, WriteOptions};
+use std::io::{Cursor, Read};
+
+fn to_markdown(docx: &[u8]) -> String {
+    DocxToMarkdown::new(ConvertOptions::default())
+        .convert_from_bytes(docx)
+        .expect("DOCX converts to Markdown")
+}
+
+fn to_docx(markdown: &str, options: &WriteOptions) -> Vec<u8> {
+    MarkdownToDocx::new(options.clone())
+        .convert_str(markdown)
+        .expect("Markdown converts to DOCX")
+}
+
+/// Converts `docx` to Markdown, writes that back to DOCX and converts it
+/// again, returning both Markdown conversions and the written package.
+fn round_trip(docx: &[u8], options: &WriteOptions) -> (String, String, Vec<u8>) {
+    let first = to_markdown(docx);
+    let written = to_docx(&first, options);
+    let second = to_markdown(&written);
+    (first, second, written)
+}
+
+fn read_fixture(path: &str) -> Vec<u8> {
+    std::fs::read(path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+}
+
+fn read_part(docx: &[u8], name: &str) -> String {
+    let mut archive = zip::ZipArchive::new(Cursor::new(docx)).expect("package is a zip archive");
+    let mut xml = String::new();
+    archive
+        .by_name(name)
+        .unwrap_or_else(|e| panic!("{} is missing: {}", name, e))
+        .read_to_string(&mut xml)
+        .expect("part is UTF-8");
+    xml
+}
+
+#[test]
+fn nested_lists_survive_a_round_trip() {
+    let docx = read_fixture("./tests/pandoc/lists.docx");
+    let (first, second, _) = round_trip(&docx, &WriteOptions::default());
+
+    assert!(first.contains("2. two\n\n   1. a\n\n   2. b"), "{}", first);
+    assert!(first.contains("  - three\n\n    - four"), "{}", first);
+    assert_eq!(second, first);
+}
+
+#[test]
+fn html_tables_with_spans_survive_a_round_trip() {
+    let docx = to_docx(
+        "<table>\n  <tr>\n    <td rowspan=\"2\" colspan=\"2\">Top</td>\n    <td>R1</td>\n  \
+         </tr>\n  <tr>\n    <td>R2</td>\n  </tr>\n  <tr>\n    <td>a</td>\n    <td>b</td>\n    \
+         <td>c</td>\n  </tr>\n</table>\n",
+        &WriteOptions::default(),
+    );
+    let (first, second, _) = round_trip(&docx, &WriteOptions::default());
+
+    assert!(
+        first.contains("<td rowspan=\"2\" colspan=\"2\">Top</td>"),
+        "{}",
+        first
+    );
+    assert_eq!(second, first);
+}
+
+#[test]
+fn footnotes_and_endnotes_survive_a_round_trip() {
+    let docx = read_fixture("./tests/pandoc/notes.docx");
+    let (first, second, _) = round_trip(&docx, &WriteOptions::default());
+
+    assert!(first.contains("[^1]:  My note."), "{}", first);
+    assert!(
+        first.contains("[^2]:  This is an endnote at the end of the document."),
+        "{}",
+        first
+    );
+    assert_eq!(second, first);
+}
+
+#[test]
+fn reference_doc_styles_survive_a_round_trip() {
+    let reference = "./tests/pandoc/custom_style_reference.docx";
+    let docx = read_fixture(reference);
+    let options = WriteOptions {
+        reference_docx: Some(reference.into()),
+        ..Default::default()
+    };
+    let (first, second, written) = round_trip(&docx, &options);
+
+    let styles = read_part(&written, "word/styles.xml");
+    for style in read_part(&docx, "word/styles.xml")
+        .split("w:styleId=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+    {
+        assert!(
+            styles.contains(&format!("w:styleId=\"{}\"", style)),
+            "style {} of the reference document is missing",
+            style
+        );
+    }
+    assert_eq!(second, first);
+}